@@ -2,15 +2,19 @@
 // Tauri CLI may merge features in dev mode and pass both `cuda-workers` and
 // `wgpu-backend` to Cargo. In that case we intentionally prioritize CUDA below.
 
+use burn::backend::ndarray::NdArrayDevice;
+use burn::backend::{Autodiff, NdArray};
+use serde::{Deserialize, Serialize};
+
 #[cfg(not(feature = "cuda-workers"))]
-use burn::backend::wgpu::WgpuDevice;
+use burn::backend::Wgpu;
 #[cfg(not(feature = "cuda-workers"))]
-use burn::backend::{Autodiff, Wgpu};
+use burn::backend::wgpu::WgpuDevice;
 
 #[cfg(feature = "cuda-workers")]
-use burn::backend::cuda::CudaDevice;
+use burn::backend::Cuda;
 #[cfg(feature = "cuda-workers")]
-use burn::backend::{Autodiff, Cuda};
+use burn::backend::cuda::CudaDevice;
 
 #[cfg(not(feature = "cuda-workers"))]
 pub type TrainBackend = Autodiff<Wgpu>;
@@ -22,10 +26,18 @@ pub type TrainDevice = WgpuDevice;
 #[cfg(feature = "cuda-workers")]
 pub type TrainDevice = CudaDevice;
 
+// The CPU backend is always compiled in and selected at runtime via `ExecutionBackend`.
+pub type CpuBackend = Autodiff<NdArray>;
+pub type CpuDevice = NdArrayDevice;
+
 pub fn create_device() -> TrainDevice {
     TrainDevice::default()
 }
 
+pub fn create_cpu_device() -> CpuDevice {
+    CpuDevice::default()
+}
+
 pub fn backend_name() -> &'static str {
     #[cfg(feature = "cuda-workers")]
     {
@@ -37,3 +49,55 @@ pub fn backend_name() -> &'static str {
         "wgpu"
     }
 }
+
+/// Backend a training run executes on, chosen per request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionBackend {
+    /// The compile-time GPU backend (`wgpu` or `cuda`).
+    #[default]
+    Gpu,
+    /// NdArray on the CPU; genomes are trained in parallel on the rayon pool.
+    Cpu,
+}
+
+impl ExecutionBackend {
+    /// Parses a user-facing backend name. `None` selects the GPU backend.
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("gpu") | Some("default") => Ok(ExecutionBackend::Gpu),
+            Some(name) if name == backend_name() => Ok(ExecutionBackend::Gpu),
+            Some("cpu") | Some("ndarray") => Ok(ExecutionBackend::Cpu),
+            Some(other) => Err(format!(
+                "Unknown execution backend '{}'. Expected 'gpu', '{}' or 'cpu'",
+                other,
+                backend_name()
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExecutionBackend::Gpu => backend_name(),
+            ExecutionBackend::Cpu => "ndarray",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_execution_backend_names() {
+        assert_eq!(ExecutionBackend::parse(None).unwrap(), ExecutionBackend::Gpu);
+        assert_eq!(ExecutionBackend::parse(Some(backend_name())).unwrap(), ExecutionBackend::Gpu);
+        assert_eq!(ExecutionBackend::parse(Some("CPU")).unwrap(), ExecutionBackend::Cpu);
+        assert_eq!(ExecutionBackend::parse(Some("ndarray")).unwrap(), ExecutionBackend::Cpu);
+        assert!(ExecutionBackend::parse(Some("tpu")).is_err());
+
+        assert_eq!(ExecutionBackend::Cpu.name(), "ndarray");
+        let json = serde_json::to_string(&ExecutionBackend::Cpu).unwrap();
+        assert_eq!(json, "\"cpu\"");
+    }
+}
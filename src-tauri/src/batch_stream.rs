@@ -0,0 +1,361 @@
+/// Batch Pipeline
+///
+/// Splits are either preloaded onto the device once per evaluation (fast when the
+/// dataset fits) or streamed: every pass decodes samples on background threads and
+/// hands finished batches over through a bounded queue, so memory stays at
+/// `prefetch_batches` batches regardless of dataset size.
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, SyncSender, sync_channel};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use burn::tensor::backend::Backend;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{DynamicBatch, DynamicTensor, concat_dynamic_tensors};
+use crate::evaluation::PreparedDataset;
+use crate::sampling::EpochSampler;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchLoading {
+    /// Preload when the estimated dataset size fits `preload_budget_mb`, otherwise stream.
+    #[default]
+    Auto,
+    Preload,
+    Stream,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatchPipelineConfig {
+    pub loading: BatchLoading,
+    pub preload_budget_mb: u64,
+    /// Background decode threads per stream; 0 picks half the available cores.
+    pub decode_workers: usize,
+    /// Bound of the queue between decode threads and the training loop.
+    pub prefetch_batches: usize,
+}
+
+impl BatchLoading {
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("auto") => Ok(BatchLoading::Auto),
+            Some("preload") => Ok(BatchLoading::Preload),
+            Some("stream") => Ok(BatchLoading::Stream),
+            Some(other) => Err(format!(
+                "Unknown batch loading mode '{}'. Expected 'auto', 'preload' or 'stream'",
+                other
+            )),
+        }
+    }
+}
+
+impl Default for BatchPipelineConfig {
+    fn default() -> Self {
+        BatchPipelineConfig {
+            loading: BatchLoading::Auto,
+            preload_budget_mb: 2048,
+            decode_workers: 0,
+            prefetch_batches: 4,
+        }
+    }
+}
+
+impl BatchPipelineConfig {
+    fn worker_count(&self) -> usize {
+        if self.decode_workers > 0 {
+            return self.decode_workers;
+        }
+        std::thread::available_parallelism()
+            .map(|n| (n.get() / 2).max(1))
+            .unwrap_or(2)
+    }
+}
+
+/// Loads the samples of one chunk and concatenates them into a batch.
+///
+/// Samples that fail to load are dropped; `Ok(None)` means no complete batch could be
+/// built from the chunk. A panic while loading aborts with `Err`, since the backend
+/// state can no longer be trusted.
+pub fn load_batch<B: Backend>(
+    prepared: &PreparedDataset,
+    chunk: &[String],
+    device: &B::Device,
+    is_cancelled: &dyn Fn() -> bool,
+) -> Result<Option<DynamicBatch<B>>, String> {
+    let mut batch_inputs: Vec<Vec<DynamicTensor<B>>> = vec![Vec::new(); prepared.input_stream_indices.len()];
+    let mut batch_targets: Vec<Vec<DynamicTensor<B>>> = vec![Vec::new(); prepared.target_stream_indices.len()];
+
+    for id in chunk {
+        if is_cancelled() {
+            return Err("Evolution cancelled during batch assembly".to_string());
+        }
+
+        let load_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            prepared.loader.load_sample::<B>(id, device)
+        }));
+
+        match load_result {
+            Ok(Ok(sample)) => {
+                for (i, &stream_idx) in prepared.input_stream_indices.iter().enumerate() {
+                    if let Some(t) = sample.stream_tensors.get(&stream_idx) {
+                        batch_inputs[i].push(t.clone());
+                    }
+                }
+                for (i, &stream_idx) in prepared.target_stream_indices.iter().enumerate() {
+                    if let Some(t) = sample.stream_tensors.get(&stream_idx) {
+                        batch_targets[i].push(t.clone());
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                eprintln!("[ERROR] Sample '{}' dropped during load: {}", id, e);
+            }
+            Err(_) => {
+                eprintln!("[ERROR] Sample '{}' panicked during load; dropping sample", id);
+                return Err(format!(
+                    "Sample '{}' panicked during load (Burn/WGPU state became invalid). Aborting evaluation.",
+                    id
+                ));
+            }
+        }
+    }
+
+    let complete =
+        batch_inputs.iter().all(|list| !list.is_empty()) && batch_targets.iter().all(|list| !list.is_empty());
+    if !complete {
+        eprintln!(
+            "[DEBUG] Skipping batch. Input lens: {:?}, Target lens: {:?}",
+            batch_inputs.iter().map(|l| l.len()).collect::<Vec<_>>(),
+            batch_targets.iter().map(|l| l.len()).collect::<Vec<_>>()
+        );
+        return Ok(None);
+    }
+
+    let assembled = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let inputs: Vec<DynamicTensor<B>> = batch_inputs.into_iter().map(concat_dynamic_tensors::<B>).collect();
+        let targets: Vec<DynamicTensor<B>> = batch_targets.into_iter().map(concat_dynamic_tensors::<B>).collect();
+        (inputs, targets)
+    }));
+
+    match assembled {
+        Ok((inputs, targets)) => Ok(Some(DynamicBatch { inputs, targets })),
+        Err(_) => Err("Batch concatenation panicked (Burn/WGPU state invalid). Aborting evaluation.".to_string()),
+    }
+}
+
+fn tensor_bytes<B: Backend>(tensor: &DynamicTensor<B>) -> u64 {
+    let elements = match tensor {
+        DynamicTensor::Dim2(t) => t.shape().num_elements(),
+        DynamicTensor::Dim3(t) => t.shape().num_elements(),
+        DynamicTensor::Dim4(t) => t.shape().num_elements(),
+    };
+    (elements * std::mem::size_of::<f32>()) as u64
+}
+
+/// Estimated device memory needed to preload `sample_count` samples, extrapolated
+/// from the first loadable sample among `probe_ids`.
+pub fn estimate_preload_mb<B: Backend>(
+    prepared: &PreparedDataset,
+    probe_ids: &[String],
+    sample_count: usize,
+    device: &B::Device,
+) -> Option<u64> {
+    probe_ids.iter().take(8).find_map(|id| {
+        let sample = prepared.loader.load_sample::<B>(id, device).ok()?;
+        let bytes: u64 = sample.stream_tensors.values().map(tensor_bytes::<B>).sum();
+        Some((bytes * sample_count as u64).div_ceil(1024 * 1024))
+    })
+}
+
+/// Streams the batches of one split, decoding a fresh pass each time `iter` is called.
+pub struct StreamingBatches<B: Backend> {
+    prepared: Arc<PreparedDataset>,
+    ids: Arc<Vec<String>>,
+    batch_size: usize,
+    device: B::Device,
+    workers: usize,
+    prefetch: usize,
+    /// Draws the ids of every training epoch; `ids` then holds the first epoch's.
+    sampler: Option<Arc<EpochSampler>>,
+}
+
+impl<B: Backend> StreamingBatches<B> {
+    pub fn new(
+        prepared: Arc<PreparedDataset>,
+        ids: Vec<String>,
+        batch_size: usize,
+        device: B::Device,
+        config: &BatchPipelineConfig,
+    ) -> Self {
+        StreamingBatches {
+            prepared,
+            ids: Arc::new(ids),
+            batch_size: batch_size.max(1),
+            device,
+            workers: config.worker_count(),
+            prefetch: config.prefetch_batches.max(1),
+            sampler: None,
+        }
+    }
+
+    /// Streams a freshly drawn id list every epoch (see `epoch`).
+    pub fn resampled(mut self, sampler: EpochSampler) -> Self {
+        self.sampler = Some(Arc::new(sampler));
+        self
+    }
+
+    /// Upper bound: chunks that yield no complete batch are skipped during the pass.
+    pub fn num_batches(&self) -> usize {
+        self.ids.len().div_ceil(self.batch_size)
+    }
+
+    /// Starts decode threads for one pass. Batches arrive in completion order.
+    pub fn iter(&self) -> BatchStream<B> {
+        self.pass(self.ids.clone())
+    }
+
+    /// One training pass of epoch `epoch`: the sampler's ids for it, or the fixed ones.
+    pub fn epoch(&self, epoch: usize) -> BatchStream<B> {
+        match &self.sampler {
+            Some(sampler) if epoch > 0 => self.pass(Arc::new(sampler.epoch_ids(epoch))),
+            _ => self.iter(),
+        }
+    }
+
+    fn pass(&self, ids: Arc<Vec<String>>) -> BatchStream<B> {
+        let total_chunks = ids.len().div_ceil(self.batch_size);
+        let (sender, receiver) = sync_channel(self.prefetch);
+        let next_chunk = Arc::new(AtomicUsize::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handles = (0..self.workers.min(total_chunks.max(1)))
+            .map(|_| {
+                let worker = DecodeWorker {
+                    prepared: self.prepared.clone(),
+                    ids: ids.clone(),
+                    batch_size: self.batch_size,
+                    device: self.device.clone(),
+                    next_chunk: next_chunk.clone(),
+                    stop: stop.clone(),
+                    sender: sender.clone(),
+                };
+                std::thread::spawn(move || worker.run())
+            })
+            .collect();
+
+        BatchStream {
+            receiver: Some(receiver),
+            stop,
+            handles,
+        }
+    }
+}
+
+struct DecodeWorker<B: Backend> {
+    prepared: Arc<PreparedDataset>,
+    ids: Arc<Vec<String>>,
+    batch_size: usize,
+    device: B::Device,
+    next_chunk: Arc<AtomicUsize>,
+    stop: Arc<AtomicBool>,
+    sender: SyncSender<Result<DynamicBatch<B>, String>>,
+}
+
+impl<B: Backend> DecodeWorker<B> {
+    fn run(self) {
+        let is_stopped = || self.stop.load(Ordering::SeqCst);
+        loop {
+            if is_stopped() {
+                return;
+            }
+            let chunk = self.next_chunk.fetch_add(1, Ordering::SeqCst);
+            let start = chunk * self.batch_size;
+            if start >= self.ids.len() {
+                return;
+            }
+            let end = (start + self.batch_size).min(self.ids.len());
+
+            let message = match load_batch::<B>(&self.prepared, &self.ids[start..end], &self.device, &is_stopped) {
+                Ok(Some(batch)) => Ok(batch),
+                Ok(None) => continue,
+                Err(_) if is_stopped() => return,
+                Err(e) => Err(e),
+            };
+            let failed = message.is_err();
+            // A closed queue means the consumer is gone
+            if self.sender.send(message).is_err() || failed {
+                return;
+            }
+        }
+    }
+}
+
+/// One streaming pass. Dropping it stops the decode threads.
+pub struct BatchStream<B: Backend> {
+    receiver: Option<Receiver<Result<DynamicBatch<B>, String>>>,
+    stop: Arc<AtomicBool>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl<B: Backend> Iterator for BatchStream<B> {
+    type Item = Result<DynamicBatch<B>, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // A decode error arrives once and stops its worker; the training loop fails
+        // the genome with it
+        self.receiver.as_ref()?.recv().ok()
+    }
+}
+
+impl<B: Backend> Drop for BatchStream<B> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Unblocks workers waiting on a full queue
+        self.receiver.take();
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Batches of one split, either resident on the device or streamed per pass.
+pub enum BatchSource<B: Backend> {
+    Preloaded(Vec<DynamicBatch<B>>),
+    Streaming(StreamingBatches<B>),
+}
+
+impl<B: Backend> BatchSource<B> {
+    pub fn len(&self) -> usize {
+        match self {
+            BatchSource::Preloaded(batches) => batches.len(),
+            BatchSource::Streaming(stream) => stream.num_batches(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        matches!(self, BatchSource::Streaming(_))
+    }
+
+    /// One pass over the split. Only streamed batches can fail to load here; preloaded
+    /// ones reported their errors before training started.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = Result<DynamicBatch<B>, String>> + '_> {
+        match self {
+            BatchSource::Preloaded(batches) => Box::new(batches.iter().cloned().map(Ok)),
+            BatchSource::Streaming(stream) => Box::new(stream.iter()),
+        }
+    }
+
+    /// The training pass of epoch `epoch`; differs from `iter` only for resampled splits.
+    pub fn epoch(&self, epoch: usize) -> Box<dyn Iterator<Item = Result<DynamicBatch<B>, String>> + '_> {
+        match self {
+            BatchSource::Streaming(stream) => Box::new(stream.epoch(epoch)),
+            preloaded => preloaded.iter(),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(err) = neural_evo_tauri_app_lib::cli::run(&args) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
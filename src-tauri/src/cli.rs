@@ -0,0 +1,332 @@
+/// Headless Command Line Interface
+///
+/// Runs the evolution pipeline without the Tauri GUI (servers, CI, scripts):
+/// - `evolve`   run the Rust evolution driver from a JSON config file
+/// - `evaluate` train and score a single genome file against a dataset profile
+/// - `scan`     scan a dataset root with stream locator configs
+/// - `validate` validate a dataset profile for evolution readiness
+/// - `export`   export cached weights + metadata for a genome
+///
+/// Results are printed as JSON on the last stdout line; `--output <file>` also
+/// writes them to a file, since training logs share stdout.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use burn::tensor::backend::AutodiffBackend;
+use serde::Serialize;
+
+use crate::backend::ExecutionBackend;
+use crate::batch_stream::{BatchLoading, BatchPipelineConfig};
+use crate::evaluation::{EvaluationContext, GenomeEvaluation, GenomeJob, PreparedDataset, TrainingParams};
+use crate::progress::NoopProgressSink;
+
+const USAGE: &str = "Usage: neural-evo-cli <command> [options]
+
+Commands:
+  evolve    --config <evolution.json> [--backend gpu|cpu] [--loading auto|preload|stream]
+  evaluate  --genome <genome.txt> --profile <id> [--profiles <dataset_profiles.json>]
+            [--genome-id <id>] [--epochs 1] [--batch-size 32] [--dataset-percent 100]
+            [--split 80/10/10] [--app-data-dir <dir>] [--backend gpu|cpu]
+            [--loading auto|preload|stream]
+  scan      --root <dir> --streams <stream_configs.json>
+  validate  (--profile-json <profile.json> | --profile <id> [--profiles <dataset_profiles.json>])
+            [--split 80/10/10]
+  export    --genome-id <id> --out <dir>
+
+Every command accepts --output <file> to also write its JSON result to a file.";
+
+/// `--key value` pairs following the subcommand.
+struct CliArgs {
+    values: HashMap<String, String>,
+}
+
+impl CliArgs {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut values = HashMap::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let key = arg
+                .strip_prefix("--")
+                .ok_or_else(|| format!("Unexpected argument '{}'", arg))?;
+            let value = iter
+                .next()
+                .ok_or_else(|| format!("Missing value for --{}", key))?;
+            values.insert(key.to_string(), value.clone());
+        }
+        Ok(CliArgs { values })
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    fn required(&self, key: &str) -> Result<&str, String> {
+        self.get(key).ok_or_else(|| format!("Missing required option --{}", key))
+    }
+
+    fn usize_or(&self, key: &str, default: usize) -> Result<usize, String> {
+        match self.get(key) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("--{} expects a non-negative integer, got '{}'", key, value)),
+            None => Ok(default),
+        }
+    }
+}
+
+/// Parses `train/val/test` percentages such as `80/10/10`.
+fn parse_split(value: &str) -> Result<(usize, usize, usize), String> {
+    let parts: Vec<usize> = value
+        .split(['/', ','])
+        .map(|p| p.trim().parse::<usize>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid split '{}', expected e.g. 80/10/10", value))?;
+    match parts.as_slice() {
+        [train, val, test] if train + val + test > 0 => Ok((*train, *val, *test)),
+        _ => Err(format!("Invalid split '{}', expected e.g. 80/10/10", value)),
+    }
+}
+
+fn emit_result<T: Serialize>(args: &CliArgs, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    if let Some(path) = args.get("output") {
+        std::fs::write(path, &json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    println!("{}", json);
+    Ok(())
+}
+
+fn block_on<F: std::future::Future>(future: F) -> Result<F::Output, String> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to start tokio runtime: {}", e))?;
+    Ok(runtime.block_on(future))
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+fn cmd_evolve(args: &CliArgs) -> Result<(), String> {
+    let config_path = args.required("config")?;
+    let config: crate::evolution::EvolutionConfig = serde_json::from_str(&read_file(config_path)?)
+        .map_err(|e| format!("Invalid evolution config {}: {}", config_path, e))?;
+
+    let on_generation = |summary: &crate::evolution::GenerationSummary| {
+        eprintln!(
+            ">>> Generation {} done: best_fitness={:.4}, best_acc={:.2}%, avg_fitness={:.4} ({} evaluated, {} cached)",
+            summary.generation,
+            summary.best_fitness,
+            summary.best_accuracy,
+            summary.avg_fitness,
+            summary.evaluated,
+            summary.cache_hits
+        );
+    };
+
+    let mut config = config;
+    if let Some(backend) = args.get("backend") {
+        config.execution_backend = ExecutionBackend::parse(Some(backend))?;
+    }
+    if let Some(loading) = args.get("loading") {
+        config.batch_pipeline.loading = BatchLoading::parse(Some(loading))?;
+    }
+
+    let outcome = crate::evolution::run_evolution(&config, &NoopProgressSink, &on_generation)?;
+    emit_result(args, &outcome)
+}
+
+fn evaluate_on<B: AutodiffBackend>(
+    prepared: &Arc<PreparedDataset>,
+    params: TrainingParams,
+    pipeline: &BatchPipelineConfig,
+    device: B::Device,
+    job: &GenomeJob,
+) -> Result<GenomeEvaluation, String> {
+    let session_snapshot = crate::EVOLUTION_SESSION.load(std::sync::atomic::Ordering::SeqCst);
+    let ctx = EvaluationContext::<B>::prepare(
+        prepared,
+        params,
+        pipeline,
+        device,
+        crate::profiler::MemoryMode::Hybrid,
+        session_snapshot,
+        &NoopProgressSink,
+    )?;
+    crate::evaluation::evaluate_genome(&ctx, job, &NoopProgressSink)
+}
+
+fn cmd_evaluate(args: &CliArgs) -> Result<(), String> {
+    let genome_path = args.required("genome")?;
+    let genome_str = read_file(genome_path)?;
+    crate::evolution::GenomeGraph::parse(&genome_str)?;
+
+    let dataset_profile = args.required("profile")?.to_string();
+    let epochs = args.usize_or("epochs", 1)?;
+    let batch_size = args.usize_or("batch-size", 32)?.max(1);
+    let dataset_percent = args.usize_or("dataset-percent", 100)?;
+    let (train_split, val_split, test_split) = parse_split(args.get("split").unwrap_or("80/10/10"))?;
+    let genome_id = args.get("genome-id").map(str::to_string).unwrap_or_else(|| {
+        Path::new(genome_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "genome_0".to_string())
+    });
+
+    let profiles_json = crate::evaluation::read_dataset_profiles(args.get("profiles").map(Path::new))?;
+    let profile = crate::evaluation::find_dataset_profile(&profiles_json, &dataset_profile)?;
+    let prepared = Arc::new(crate::evaluation::prepare_dataset(
+        profile,
+        args.get("app-data-dir").map(PathBuf::from),
+        dataset_percent,
+        train_split,
+        val_split,
+        test_split,
+    )?);
+    let pipeline = BatchPipelineConfig {
+        loading: BatchLoading::parse(args.get("loading"))?,
+        ..BatchPipelineConfig::default()
+    };
+
+    let params = TrainingParams {
+        dataset_profile,
+        batch_size,
+        dataset_percent,
+        train_split,
+        val_split,
+        test_split,
+    };
+    let job = GenomeJob {
+        index: 0,
+        genome_id,
+        genome_str,
+        epochs,
+        queue_wait_ms: 0,
+    };
+
+    let evaluation = match ExecutionBackend::parse(args.get("backend"))? {
+        ExecutionBackend::Gpu => evaluate_on::<crate::backend::TrainBackend>(
+            &prepared,
+            params,
+            &pipeline,
+            crate::backend::create_device(),
+            &job,
+        )?,
+        ExecutionBackend::Cpu => evaluate_on::<crate::backend::CpuBackend>(
+            &prepared,
+            params,
+            &pipeline,
+            crate::backend::create_cpu_device(),
+            &job,
+        )?,
+    };
+    if evaluation.source == crate::evaluation::EvaluationSource::CompileFailed {
+        return Err(format!("Genome '{}' failed to compile against the dataset shapes", job.genome_id));
+    }
+    emit_result(args, &evaluation.result)
+}
+
+fn cmd_scan(args: &CliArgs) -> Result<(), String> {
+    let root = args.required("root")?.to_string();
+    let streams_path = args.required("streams")?;
+    let stream_configs: Vec<crate::StreamLocatorConfig> = serde_json::from_str(&read_file(streams_path)?)
+        .map_err(|e| format!("Invalid stream configs {}: {}", streams_path, e))?;
+
+    let result = block_on(crate::scan_dataset(root, stream_configs))??;
+    emit_result(args, &result)
+}
+
+fn cmd_validate(args: &CliArgs) -> Result<(), String> {
+    let profile_json = if let Some(path) = args.get("profile-json") {
+        read_file(path)?
+    } else {
+        let profile_id = args.required("profile")?;
+        let profiles_json = crate::evaluation::read_dataset_profiles(args.get("profiles").map(Path::new))?;
+        let root: serde_json::Value = serde_json::from_str(&profiles_json)
+            .map_err(|e| format!("Failed to parse dataset_profiles.json: {}", e))?;
+        root.pointer("/state/profiles")
+            .and_then(|p| p.as_array())
+            .and_then(|profiles| {
+                profiles
+                    .iter()
+                    .find(|p| p.get("id").and_then(|id| id.as_str()) == Some(profile_id))
+            })
+            .map(|p| p.to_string())
+            .ok_or_else(|| format!("Dataset profile '{}' not found in profiles JSON", profile_id))?
+    };
+
+    let (train_split, val_split, test_split) = parse_split(args.get("split").unwrap_or("80/10/10"))?;
+    let report = block_on(crate::validate_dataset_profile(
+        profile_json,
+        Some(train_split),
+        Some(val_split),
+        Some(test_split),
+    ))??;
+    emit_result(args, &report)
+}
+
+fn cmd_export(args: &CliArgs) -> Result<(), String> {
+    let genome_id = args.required("genome-id")?.to_string();
+    let out = args.required("out")?.to_string();
+
+    let response = block_on(crate::export_genome_with_weights(genome_id, out))??;
+    emit_result(args, &response)
+}
+
+/// Entry point for the `neural-evo-cli` binary. `args` excludes the program name.
+pub fn run(args: &[String]) -> Result<(), String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(USAGE.to_string());
+    };
+    if command == "help" || command == "--help" || command == "-h" {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let parsed = CliArgs::parse(rest)?;
+    let backend = ExecutionBackend::parse(parsed.get("backend"))?;
+    eprintln!(">>> neural-evo-cli '{}' (backend='{}')", command, backend.name());
+
+    match command.as_str() {
+        "evolve" => cmd_evolve(&parsed),
+        "evaluate" => cmd_evaluate(&parsed),
+        "scan" => cmd_scan(&parsed),
+        "validate" => cmd_validate(&parsed),
+        "export" => cmd_export(&parsed),
+        other => Err(format!("Unknown command '{}'\n\n{}", other, USAGE)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_key_value_options() {
+        let parsed = CliArgs::parse(&args(&["--genome", "g.txt", "--epochs", "3"])).unwrap();
+        assert_eq!(parsed.get("genome"), Some("g.txt"));
+        assert_eq!(parsed.usize_or("epochs", 1).unwrap(), 3);
+        assert_eq!(parsed.usize_or("batch-size", 32).unwrap(), 32);
+        assert!(parsed.required("profile").is_err());
+
+        assert!(CliArgs::parse(&args(&["--genome"])).is_err());
+        assert!(CliArgs::parse(&args(&["genome"])).is_err());
+    }
+
+    #[test]
+    fn parses_split_triplets() {
+        assert_eq!(parse_split("70/20/10").unwrap(), (70, 20, 10));
+        assert_eq!(parse_split("8,1,1").unwrap(), (8, 1, 1));
+        assert!(parse_split("80/20").is_err());
+        assert!(parse_split("0/0/0").is_err());
+    }
+
+    #[test]
+    fn unknown_command_is_rejected() {
+        let err = run(&args(&["train"])).unwrap_err();
+        assert!(err.contains("Unknown command"));
+    }
+}
@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// Annotation format of a `CompanionFile` stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompanionParser {
+    /// One `class x_center y_center width height` line per object, normalized to [0, 1].
+    Yolo,
+    /// COCO `images` / `annotations` / `categories` JSON, either one file per sample or
+    /// a single shared file (template without `{id}`) from which each sample's subset is taken.
+    CocoSubset,
+    /// Raw text; used as a class label or as a list of numbers.
+    Text,
+}
+
+impl CompanionParser {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "yolo" => Ok(CompanionParser::Yolo),
+            "coco" | "coco_subset" => Ok(CompanionParser::CocoSubset),
+            "text" | "txt" => Ok(CompanionParser::Text),
+            other => Err(format!(
+                "Unknown companion parser '{}'. Expected 'YOLO', 'COCO_Subset' or 'Text'",
+                other
+            )),
+        }
+    }
+}
+
+/// One annotated object. Coordinates are normalized to [0, 1] of the image size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectAnnotation {
+    pub class: String,
+    pub x_center: f32,
+    pub y_center: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Normalized outline polygons (COCO segmentation); the box is used when empty.
+    pub polygons: Vec<Vec<(f32, f32)>>,
+}
+
+impl ObjectAnnotation {
+    fn area(&self) -> f32 {
+        self.width * self.height
+    }
+
+    fn contains(&self, x: f32, y: f32) -> bool {
+        if self.polygons.is_empty() {
+            let (half_w, half_h) = (self.width / 2.0, self.height / 2.0);
+            return (x - self.x_center).abs() <= half_w && (y - self.y_center).abs() <= half_h;
+        }
+        self.polygons.iter().any(|polygon| point_in_polygon(polygon, x, y))
+    }
+}
+
+/// Even-odd rule.
+fn point_in_polygon(polygon: &[(f32, f32)], x: f32, y: f32) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for (i, &(xi, yi)) in polygon.iter().enumerate() {
+        let (xj, yj) = polygon[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Parsed companion file of one sample.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompanionAnnotation {
+    Objects(Vec<ObjectAnnotation>),
+    Text(String),
+}
+
+impl CompanionAnnotation {
+    /// Classification target: the most frequent class, ties broken by total area.
+    /// For text annotations, the first non-empty line.
+    pub fn dominant_class(&self) -> Option<String> {
+        match self {
+            CompanionAnnotation::Text(text) => text
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(str::to_string),
+            CompanionAnnotation::Objects(objects) => {
+                let mut totals: HashMap<&str, (usize, f32)> = HashMap::new();
+                for object in objects {
+                    let entry = totals.entry(object.class.as_str()).or_insert((0, 0.0));
+                    entry.0 += 1;
+                    entry.1 += object.area();
+                }
+                totals
+                    .into_iter()
+                    .max_by(|a, b| {
+                        (a.1.0, a.1.1)
+                            .partial_cmp(&(b.1.0, b.1.1))
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                            .then_with(|| b.0.cmp(a.0))
+                    })
+                    .map(|(class, _)| class.to_string())
+            }
+        }
+    }
+
+    /// Number of objects per class (one per sample for text labels).
+    pub fn class_counts(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        match self {
+            CompanionAnnotation::Objects(objects) => {
+                for object in objects {
+                    *counts.entry(object.class.clone()).or_insert(0) += 1;
+                }
+            }
+            CompanionAnnotation::Text(_) => {
+                if let Some(label) = self.dominant_class() {
+                    counts.insert(label, 1);
+                }
+            }
+        }
+        counts
+    }
+
+    /// Regression target of exactly `len` values. Objects contribute
+    /// `[x_center, y_center, width, height]`, largest first, zero-padded; text
+    /// annotations contribute the numbers they contain.
+    pub fn regression_targets(&self, len: usize) -> Vec<f32> {
+        let mut values: Vec<f32> = match self {
+            CompanionAnnotation::Objects(objects) => {
+                let mut sorted: Vec<&ObjectAnnotation> = objects.iter().collect();
+                sorted.sort_by(|a, b| b.area().partial_cmp(&a.area()).unwrap_or(std::cmp::Ordering::Equal));
+                sorted
+                    .into_iter()
+                    .flat_map(|o| [o.x_center, o.y_center, o.width, o.height])
+                    .collect()
+            }
+            CompanionAnnotation::Text(text) => text
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter_map(|s| s.parse::<f32>().ok())
+                .collect(),
+        };
+        values.resize(len, 0.0);
+        values
+    }
+
+    /// Rasterizes objects into a CHW mask sampled at pixel centers. With one channel
+    /// the mask is binary; otherwise each object is drawn into the channel of its class.
+    pub fn rasterize_mask(&self, width: usize, height: usize, channels: usize, classes: &[String]) -> Vec<f32> {
+        let channels = channels.max(1);
+        let mut mask = vec![0.0_f32; channels * width * height];
+        let CompanionAnnotation::Objects(objects) = self else {
+            return mask;
+        };
+
+        for object in objects {
+            let channel = if channels == 1 {
+                0
+            } else {
+                match classes.iter().position(|c| c == &object.class) {
+                    Some(index) if index < channels => index,
+                    _ => continue,
+                }
+            };
+            let plane = &mut mask[channel * width * height..(channel + 1) * width * height];
+            for y in 0..height {
+                let py = (y as f32 + 0.5) / height as f32;
+                for x in 0..width {
+                    let px = (x as f32 + 0.5) / width as f32;
+                    if object.contains(px, py) {
+                        plane[y * width + x] = 1.0;
+                    }
+                }
+            }
+        }
+        mask
+    }
+}
+
+pub fn parse_yolo(contents: &str) -> Result<Vec<ObjectAnnotation>, String> {
+    let mut objects = Vec::new();
+    for (line_no, line) in contents.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() {
+            continue;
+        }
+        if fields.len() < 5 {
+            return Err(format!(
+                "line {}: expected 'class x_center y_center width height', got '{}'",
+                line_no + 1,
+                line.trim()
+            ));
+        }
+        let mut coords = [0.0_f32; 4];
+        for (slot, raw) in coords.iter_mut().zip(&fields[1..5]) {
+            *slot = raw
+                .parse::<f32>()
+                .map_err(|_| format!("line {}: invalid coordinate '{}'", line_no + 1, raw))?;
+        }
+        objects.push(ObjectAnnotation {
+            class: fields[0].to_string(),
+            x_center: coords[0],
+            y_center: coords[1],
+            width: coords[2],
+            height: coords[3],
+            polygons: Vec::new(),
+        });
+    }
+    Ok(objects)
+}
+
+#[derive(Deserialize)]
+struct CocoFile {
+    #[serde(default)]
+    images: Vec<CocoImage>,
+    #[serde(default)]
+    annotations: Vec<CocoAnnotation>,
+    #[serde(default)]
+    categories: Vec<CocoCategory>,
+}
+
+#[derive(Deserialize)]
+struct CocoImage {
+    id: u64,
+    file_name: String,
+    width: f32,
+    height: f32,
+}
+
+#[derive(Deserialize)]
+struct CocoAnnotation {
+    image_id: u64,
+    category_id: u64,
+    #[serde(default)]
+    bbox: Vec<f32>,
+    /// Polygon lists; RLE masks are ignored in favour of the box.
+    #[serde(default)]
+    segmentation: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct CocoCategory {
+    id: u64,
+    name: String,
+}
+
+impl CocoFile {
+    fn objects_for(&self, image: &CocoImage) -> Vec<ObjectAnnotation> {
+        let (w, h) = (image.width.max(1.0), image.height.max(1.0));
+        self.annotations
+            .iter()
+            .filter(|a| a.image_id == image.id && a.bbox.len() == 4)
+            .map(|a| {
+                let class = self
+                    .categories
+                    .iter()
+                    .find(|c| c.id == a.category_id)
+                    .map(|c| c.name.clone())
+                    .unwrap_or_else(|| a.category_id.to_string());
+                let polygons = a
+                    .segmentation
+                    .as_array()
+                    .map(|polys| {
+                        polys
+                            .iter()
+                            .filter_map(|poly| poly.as_array())
+                            .map(|coords| {
+                                let flat: Vec<f32> = coords.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect();
+                                flat.chunks_exact(2).map(|p| (p[0] / w, p[1] / h)).collect::<Vec<_>>()
+                            })
+                            .filter(|poly| poly.len() >= 3)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                ObjectAnnotation {
+                    class,
+                    x_center: (a.bbox[0] + a.bbox[2] / 2.0) / w,
+                    y_center: (a.bbox[1] + a.bbox[3] / 2.0) / h,
+                    width: a.bbox[2] / w,
+                    height: a.bbox[3] / h,
+                    polygons,
+                }
+            })
+            .collect()
+    }
+
+    /// Image entry for `sample_id`: matched on the file name without extension, either
+    /// as the full relative path or as its last component.
+    fn image_for(&self, sample_id: &str) -> Option<&CocoImage> {
+        let stem = |name: &str| {
+            let normalized = name.replace('\\', "/");
+            Path::new(&normalized).with_extension("").to_string_lossy().to_string()
+        };
+        let last = |id: &str| id.rsplit('/').next().unwrap_or(id).to_string();
+        self.images
+            .iter()
+            .find(|img| stem(&img.file_name) == sample_id)
+            .or_else(|| self.images.iter().find(|img| last(&stem(&img.file_name)) == last(sample_id)))
+    }
+}
+
+fn parse_coco(contents: &str) -> Result<CocoFile, String> {
+    serde_json::from_str(contents).map_err(|e| format!("invalid COCO JSON: {}", e))
+}
+
+/// Annotations of one `CompanionFile` stream, keyed by sample id.
+#[derive(Debug, Default)]
+pub struct CompanionAnnotations {
+    pub by_sample: HashMap<String, CompanionAnnotation>,
+    /// Files that exist but could not be parsed, as `"<sample id>: <reason>"`.
+    pub errors: Vec<String>,
+}
+
+impl CompanionAnnotations {
+    /// Object counts per class over all samples.
+    pub fn class_counts(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for annotation in self.by_sample.values() {
+            for (class, n) in annotation.class_counts() {
+                *counts.entry(class).or_insert(0) += n;
+            }
+        }
+        counts
+    }
+
+    /// Sorted class vocabulary; numeric labels (YOLO ids) sort numerically.
+    pub fn classes(&self) -> Vec<String> {
+        let mut classes: Vec<String> = self.class_counts().into_keys().collect();
+        classes.sort_by(|a, b| match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => a.cmp(b),
+        });
+        classes
+    }
+}
+
+/// Resolves `path_template` (relative to `root`, `{id}` replaced by the sample id).
+pub fn companion_path(root: &Path, path_template: &str, sample_id: &str) -> PathBuf {
+    root.join(path_template.replace("{id}", sample_id))
+}
+
+/// Parses the companion files of `sample_ids`. Samples without a companion file (or
+/// without an entry in a shared COCO file) are left out.
+pub fn load_companion_annotations<'a>(
+    root: &Path,
+    path_template: &str,
+    parser: CompanionParser,
+    sample_ids: impl IntoIterator<Item = &'a String>,
+) -> CompanionAnnotations {
+    let mut loaded = CompanionAnnotations::default();
+
+    // A template without `{id}` names one COCO file shared by all samples
+    if parser == CompanionParser::CocoSubset && !path_template.contains("{id}") {
+        let path = root.join(path_template);
+        let shared = std::fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))
+            .and_then(|raw| parse_coco(&raw));
+        match shared {
+            Ok(coco) => {
+                for id in sample_ids {
+                    if let Some(image) = coco.image_for(id) {
+                        loaded
+                            .by_sample
+                            .insert(id.clone(), CompanionAnnotation::Objects(coco.objects_for(image)));
+                    }
+                }
+            }
+            Err(e) => loaded.errors.push(format!("{}: {}", path_template, e)),
+        }
+        return loaded;
+    }
+
+    for id in sample_ids {
+        let path = companion_path(root, path_template, id);
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let parsed = match parser {
+            CompanionParser::Yolo => parse_yolo(&contents).map(CompanionAnnotation::Objects),
+            CompanionParser::Text => Ok(CompanionAnnotation::Text(contents)),
+            CompanionParser::CocoSubset => parse_coco(&contents).map(|coco| {
+                let objects = coco
+                    .image_for(id)
+                    .or(coco.images.first())
+                    .map(|image| coco.objects_for(image))
+                    .unwrap_or_default();
+                CompanionAnnotation::Objects(objects)
+            }),
+        };
+        match parsed {
+            Ok(annotation) => {
+                loaded.by_sample.insert(id.clone(), annotation);
+            }
+            Err(e) => loaded.errors.push(format!("{}: {}", id, e)),
+        }
+    }
+    loaded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}-{}", prefix, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn yolo_labels_yield_dominant_class_boxes_and_masks() {
+        let objects = parse_yolo("2 0.25 0.25 0.5 0.5\n\n0 0.9 0.9 0.1 0.1\n2 0.75 0.75 0.2 0.2\n").expect("valid yolo");
+        assert_eq!(objects.len(), 3);
+        assert!(parse_yolo("1 0.5 0.5").is_err());
+
+        let annotation = CompanionAnnotation::Objects(objects);
+        assert_eq!(annotation.dominant_class().as_deref(), Some("2"));
+        assert_eq!(
+            annotation.regression_targets(6),
+            vec![0.25, 0.25, 0.5, 0.5, 0.75, 0.75]
+        );
+
+        let mask = annotation.rasterize_mask(4, 4, 1, &[]);
+        // Top-left quadrant is covered by the first box
+        assert_eq!(&mask[0..2], &[1.0, 1.0]);
+        assert_eq!(mask[3], 0.0);
+
+        let classes = vec!["0".to_string(), "2".to_string()];
+        let per_class = annotation.rasterize_mask(4, 4, 2, &classes);
+        assert_eq!(per_class[0], 0.0);
+        assert_eq!(per_class[16], 1.0);
+    }
+
+    #[test]
+    fn shared_coco_file_is_split_per_sample() {
+        let root = temp_dir("companion-coco");
+        let coco = serde_json::json!({
+            "images": [
+                {"id": 1, "file_name": "images/cat_01.jpg", "width": 100, "height": 50},
+                {"id": 2, "file_name": "images/dog_01.jpg", "width": 100, "height": 100}
+            ],
+            "annotations": [
+                {"image_id": 1, "category_id": 7, "bbox": [0, 0, 50, 25]},
+                {"image_id": 2, "category_id": 8, "bbox": [10, 10, 20, 20],
+                 "segmentation": [[10, 10, 30, 10, 30, 30, 10, 30]]}
+            ],
+            "categories": [{"id": 7, "name": "cat"}, {"id": 8, "name": "dog"}]
+        });
+        std::fs::write(root.join("instances.json"), coco.to_string()).expect("write coco");
+
+        let ids = vec!["images/cat_01".to_string(), "dog_01".to_string(), "bird_01".to_string()];
+        let loaded = load_companion_annotations(&root, "instances.json", CompanionParser::CocoSubset, &ids);
+        assert!(loaded.errors.is_empty());
+        assert_eq!(loaded.by_sample.len(), 2);
+
+        let CompanionAnnotation::Objects(cat) = &loaded.by_sample["images/cat_01"] else {
+            panic!("expected objects");
+        };
+        assert_eq!((cat[0].x_center, cat[0].y_center, cat[0].width, cat[0].height), (0.25, 0.25, 0.5, 0.5));
+        assert_eq!(loaded.by_sample["dog_01"].dominant_class().as_deref(), Some("dog"));
+        assert_eq!(loaded.classes(), vec!["cat".to_string(), "dog".to_string()]);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn text_companions_are_labels_and_unparseable_files_are_reported() {
+        let root = temp_dir("companion-text");
+        std::fs::create_dir_all(root.join("labels")).expect("create labels dir");
+        std::fs::write(root.join("labels/a.txt"), "\n  cat \n").expect("write label");
+        std::fs::write(root.join("labels/b.txt"), "0 0.5").expect("write broken yolo");
+
+        let ids = vec!["a".to_string(), "b".to_string(), "missing".to_string()];
+        let text = load_companion_annotations(&root, "labels/{id}.txt", CompanionParser::Text, &ids);
+        assert_eq!(text.by_sample.len(), 2);
+        assert_eq!(text.by_sample["a"].dominant_class().as_deref(), Some("cat"));
+
+        let yolo = load_companion_annotations(&root, "labels/{id}.txt", CompanionParser::Yolo, &ids);
+        assert_eq!(yolo.errors.len(), 2);
+        assert!(CompanionParser::from_name("COCO_Subset").is_ok());
+        assert!(CompanionParser::from_name("pascal").is_err());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
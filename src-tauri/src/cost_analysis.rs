@@ -0,0 +1,272 @@
+/// Static Cost Analysis
+///
+/// Walks a built `GraphModel`'s execution plan and counts, for one sample (batch 1):
+/// - MACs of every node: Dense and Conv1D/Conv2D per output element, LSTM/GRU per step
+///   and gate, attention for its four projections plus the score and value products;
+///   norms count one per element for their affine, pooling and shape nodes none
+/// - parameters, taken from the layer modules themselves
+/// - activation memory: the node's f32 output
+///
+/// Peak memory replays `run_plan`'s buffer lifetimes: a node's output stays live until
+/// its last consumer (`use_counts`) has run, model outputs until the pass returns, and
+/// an operation's inputs and output are live at the same time.
+use burn::module::Module;
+use burn::tensor::backend::Backend;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{GraphModel, Instruction, Operation};
+
+/// Bytes of one f32 element.
+pub const BYTES_PER_ELEMENT: u64 = 4;
+
+/// Cost of one node of the execution plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerCost {
+    pub node_id: usize,
+    pub op: String,
+    pub output_shape: Vec<usize>,
+    pub macs: u64,
+    pub params: u64,
+    /// Output size at batch 1; outputs alias their input and take none
+    pub activation_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelCost {
+    pub layers: Vec<LayerCost>,
+    pub total_macs: u64,
+    pub total_params: u64,
+    /// f32 weights, what the model occupies in flash
+    pub parameter_bytes: u64,
+    /// Sum of every node's output
+    pub activation_bytes: u64,
+    /// Largest set of node outputs live at once during a forward pass
+    pub peak_activation_bytes: u64,
+}
+
+impl ModelCost {
+    /// Two FLOPs per MAC.
+    pub fn flops(&self) -> u64 {
+        self.total_macs.saturating_mul(2)
+    }
+}
+
+pub fn op_name(op: &Operation) -> &'static str {
+    match op {
+        Operation::Input(_) => "Input",
+        Operation::Dense { .. } => "Dense",
+        Operation::Conv1D { .. } => "Conv1D",
+        Operation::Conv2D { .. } => "Conv2D",
+        Operation::LSTM { .. } => "LSTM",
+        Operation::GRU { .. } => "GRU",
+        Operation::MultiHeadAttention { .. } => "MultiHeadAttention",
+        Operation::TransformerEncoderBlock { .. } => "TransformerEncoderBlock",
+        Operation::MaxPool { .. } => "MaxPool",
+        Operation::AvgPool { .. } => "AvgPool",
+        Operation::Flatten => "Flatten",
+        Operation::Add => "Add",
+        Operation::Concat => "Concat",
+        Operation::Dropout { .. } => "Dropout",
+        Operation::BatchNorm { .. } => "BatchNorm",
+        Operation::LayerNorm { .. } => "LayerNorm",
+        Operation::Dropout2D { .. } => "Dropout2D",
+        Operation::GaussianNoise { .. } => "GaussianNoise",
+        Operation::Output(_) => "Output",
+    }
+}
+
+fn elements(shape: &[usize]) -> u64 {
+    shape.iter().map(|&d| d as u64).product()
+}
+
+/// MACs and parameters of one node, given its first input's and its output's shape.
+fn node_cost<B: Backend>(model: &GraphModel<B>, op: &Operation, input: &[usize], output: &[usize]) -> (u64, u64) {
+    let out = elements(output);
+    let dim = |i: usize| input.get(i).copied().unwrap_or(1) as u64;
+    match op {
+        Operation::Dense { dense_idx, .. } => (elements(input) * out, model.denses[*dense_idx].num_params() as u64),
+        Operation::Conv2D { conv2d_idx, .. } => {
+            let conv = &model.conv2ds[*conv2d_idx];
+            let per_output = dim(0) / conv.groups.max(1) as u64 * (conv.kernel_size[0] * conv.kernel_size[1]) as u64;
+            (out * per_output, conv.num_params() as u64)
+        }
+        Operation::Conv1D { conv1d_idx, .. } => {
+            let conv = &model.conv1ds[*conv1d_idx];
+            let per_output = dim(1) / conv.groups.max(1) as u64 * conv.kernel_size as u64;
+            (out * per_output, conv.num_params() as u64)
+        }
+        Operation::LSTM { lstm_idx } => {
+            let hidden = model.lstm_hidden_sizes[*lstm_idx] as u64;
+            (dim(0) * 4 * hidden * (dim(1) + hidden), model.lstms[*lstm_idx].num_params() as u64)
+        }
+        Operation::GRU { gru_idx } => {
+            let hidden = model.gru_hidden_sizes[*gru_idx] as u64;
+            (dim(0) * 3 * hidden * (dim(1) + hidden), model.grus[*gru_idx].num_params() as u64)
+        }
+        Operation::MultiHeadAttention { mha_idx: idx } | Operation::TransformerEncoderBlock { transformer_idx: idx } => {
+            let mha = &model.mha_layers[*idx];
+            let d_model = mha.d_model.max(1) as u64;
+            let seq_len = elements(input) / d_model;
+            (
+                4 * seq_len * d_model * d_model + 2 * seq_len * seq_len * d_model,
+                mha.num_params() as u64,
+            )
+        }
+        Operation::BatchNorm { batch_norm_idx } => {
+            let norm = if input.len() == 3 {
+                &model.batch_norms_4d[*batch_norm_idx]
+            } else {
+                &model.batch_norms_2d[*batch_norm_idx]
+            };
+            (out, norm.num_params() as u64)
+        }
+        Operation::LayerNorm { layer_norm_idx } => (out, model.layer_norms[*layer_norm_idx].num_params() as u64),
+        _ => (0, 0),
+    }
+}
+
+/// Per-node costs and totals of a built model.
+pub fn analyze<B: Backend>(model: &GraphModel<B>) -> ModelCost {
+    let shapes = &model.node_output_shapes.0;
+    let shape_of = |id: usize| shapes.get(id).map(Vec::as_slice).unwrap_or(&[]);
+
+    let layers: Vec<LayerCost> = model
+        .execution_plan
+        .0
+        .iter()
+        .map(|instr| {
+            let output = shape_of(instr.node_id);
+            let input = instr.input_ids.first().map_or(&[][..], |&id| shape_of(id));
+            let (macs, params) = node_cost(model, &instr.op, input, output);
+            let activation_bytes = match instr.op {
+                Operation::Output(_) => 0,
+                _ => elements(output) * BYTES_PER_ELEMENT,
+            };
+            LayerCost {
+                node_id: instr.node_id,
+                op: op_name(&instr.op).to_string(),
+                output_shape: output.to_vec(),
+                macs,
+                params,
+                activation_bytes,
+            }
+        })
+        .collect();
+
+    let total_params: u64 = layers.iter().map(|l| l.params).sum();
+    ModelCost {
+        total_macs: layers.iter().map(|l| l.macs).sum(),
+        total_params,
+        parameter_bytes: total_params * BYTES_PER_ELEMENT,
+        activation_bytes: layers.iter().map(|l| l.activation_bytes).sum(),
+        peak_activation_bytes: peak_live_bytes(&model.execution_plan.0, &model.use_counts, &layers),
+        layers,
+    }
+}
+
+/// Replays the plan's buffer lifetimes; `layers` follows the plan's order.
+fn peak_live_bytes(plan: &[Instruction], use_counts: &[usize], layers: &[LayerCost]) -> u64 {
+    let mut bytes = vec![0u64; use_counts.len()];
+    let mut kept = vec![false; use_counts.len()];
+    for (instr, layer) in plan.iter().zip(layers) {
+        bytes[instr.node_id] = layer.activation_bytes;
+        if let Operation::Output(_) = instr.op {
+            for &id in &instr.input_ids {
+                kept[id] = true;
+            }
+        }
+    }
+
+    let mut remaining = use_counts.to_vec();
+    let (mut live, mut peak) = (0u64, 0u64);
+    for instr in plan {
+        if !matches!(instr.op, Operation::Output(_)) {
+            peak = peak.max(live + bytes[instr.node_id]);
+        }
+        for &id in &instr.input_ids {
+            remaining[id] = remaining[id].saturating_sub(1);
+            if remaining[id] == 0 && !kept[id] {
+                live -= bytes[id];
+            }
+        }
+        if remaining[instr.node_id] > 0 {
+            live += bytes[instr.node_id];
+        }
+    }
+    peak
+}
+
+/// Builds a genome on the CPU backend and analyses it. The build initialises the
+/// weights as training would; only their counts and the shapes are read.
+pub fn analyze_genome(
+    genome_str: &str,
+    input_overrides: Option<&[Vec<usize>]>,
+    output_overrides: Option<&[Vec<usize>]>,
+) -> Result<ModelCost, String> {
+    let device = crate::backend::create_cpu_device();
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let model = GraphModel::<crate::backend::CpuBackend>::build(genome_str, &device, input_overrides, output_overrides);
+        analyze(&model)
+    }))
+    .map_err(|err| format!("Genome failed to compile: {}", crate::evaluation::panic_message(err.as_ref())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conv_net_costs_match_hand_counts() {
+        let genome = [
+            r#"{"node":"Input","params":{"output_shape":[8,8,1]}}"#,
+            r#"{"node":"Conv2D","params":{"filters":4,"kernel_size":{"h":3,"w":3},"stride":1,"padding":1,"dilation":1,"use_bias":true,"activation":"relu"}}"#,
+            r#"{"node":"Pooling","params":{"pool_type":"max","kernel_size":{"h":2,"w":2},"stride":2,"padding":0}}"#,
+            r#"{"node":"Flatten","params":{}}"#,
+            r#"{"node":"Dense","params":{"units":3,"activation":"linear","use_bias":true}}"#,
+            r#"{"node":"Output","params":{"input_shape":[3]}}"#,
+            "CONNECTIONS",
+            "0 1",
+            "1 2",
+            "2 3",
+            "3 4",
+            "4 5",
+        ]
+        .join("\n");
+        let cost = analyze_genome(&genome, None, None).unwrap();
+
+        let conv = &cost.layers[1];
+        assert_eq!((conv.op.as_str(), conv.output_shape.as_slice()), ("Conv2D", &[4, 8, 8][..]));
+        assert_eq!((conv.macs, conv.params), (256 * 9, 4 * 9 + 4));
+        let dense = &cost.layers[4];
+        assert_eq!((dense.macs, dense.params), (64 * 3, 64 * 3 + 3));
+        assert_eq!(cost.total_macs, 2304 + 192);
+        assert_eq!(cost.flops(), 2 * 2496);
+        assert_eq!(cost.parameter_bytes, (40 + 195) * 4);
+        // Conv output and its input are live together: (64 + 256) floats
+        assert_eq!(cost.peak_activation_bytes, 320 * 4);
+    }
+
+    #[test]
+    fn skip_connections_keep_their_source_alive() {
+        let genome = [
+            r#"{"node":"Input","params":{"output_shape":[4]}}"#,
+            r#"{"node":"Dense","params":{"units":8,"activation":"relu","use_bias":false}}"#,
+            r#"{"node":"Dense","params":{"units":8,"activation":"relu","use_bias":false}}"#,
+            r#"{"node":"Add","params":{}}"#,
+            r#"{"node":"Output","params":{"input_shape":[8]}}"#,
+            "CONNECTIONS",
+            "0 1",
+            "1 2",
+            "1 3",
+            "2 3",
+            "3 4",
+        ]
+        .join("\n");
+        let cost = analyze_genome(&genome, None, None).unwrap();
+
+        assert_eq!(cost.total_macs, 4 * 8 + 8 * 8);
+        assert_eq!(cost.activation_bytes, (4 + 8 + 8 + 8) * 4);
+        // Both Dense outputs feed Add while its result is written
+        assert_eq!(cost.peak_activation_bytes, 3 * 8 * 4);
+    }
+}
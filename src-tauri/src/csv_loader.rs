@@ -1,11 +1,10 @@
 use burn::tensor::Tensor;
+use burn::tensor::backend::Backend;
 use std::path::{Path, PathBuf};
 
 use crate::dtos::CsvDatasetDef;
 use crate::entities::DynamicTensor;
-
-type Backend = crate::backend::TrainBackend;
-type TrainDevice = crate::backend::TrainDevice;
+use crate::tabular::{self, MissingStrategy, TabularStats};
 
 /// Loads CSV-based datasets in both row-wise and temporal-window modes
 #[allow(dead_code)]
@@ -15,10 +14,17 @@ pub struct CsvDatasetLoader {
     config: CsvDatasetDef,
     
     // All raw data loaded into memory
-    rows: Vec<Vec<f32>>,           // [num_rows, num_features]
+    cells: Vec<Vec<String>>,       // [num_rows, num_features] raw feature cells
+    rows: Vec<Vec<f32>>,           // [num_rows, feature_width] encoded features
     labels: Vec<String>,           // [num_rows]
+    targets: Vec<Vec<f32>>,        // [num_rows, num_target_columns], NaN where not numeric
     feature_indices: Vec<usize>,   // Which CSV columns are features
+    feature_names: Vec<String>,
+    feature_width: usize,          // Values per row after one-hot encoding
     target_index: usize,           // Which CSV column is the target
+    target_indices: Vec<usize>,    // All target columns (comma-separated target_column)
+    forecast_offset: usize,        // Rows from a sample's first row to its first forecast row
+    row_stride: usize,             // Rows between the first rows of consecutive samples
     
     // Computed metadata
     pub num_samples: usize,
@@ -60,7 +66,15 @@ impl CsvDatasetLoader {
         };
 
         // Find target column index (required if target_column is not empty, optional otherwise)
-        let target_index = if config.target_column.is_empty() {
+        let target_indices = if config.target_column.contains(',') {
+            let specs: Vec<String> = config.target_column.split(',').map(|s| s.trim().to_string()).collect();
+            map_column_specs_to_indices(&specs, &headers)?
+        } else {
+            vec![]
+        };
+        let target_index = if let Some(&first) = target_indices.first() {
+            first
+        } else if config.target_column.is_empty() {
             // Input stream: no target column needed
             0 // dummy value, won't be used
         } else if config.has_headers {
@@ -84,26 +98,33 @@ impl CsvDatasetLoader {
             }
         }
 
-        // Load all data from CSV
-        let mut rows = vec![];
+        let feature_names: Vec<String> = feature_indices
+            .iter()
+            .map(|&i| headers.get(i).cloned().unwrap_or_else(|| i.to_string()))
+            .collect();
+
+        // Load all data from CSV. Features stay raw strings until tabular statistics
+        // are applied, so missing and categorical cells can be handled per column.
+        let target_indices = if target_indices.is_empty() && !config.target_column.is_empty() {
+            vec![target_index]
+        } else {
+            target_indices
+        };
+
+        let mut cells = vec![];
         let mut labels = vec![];
+        let mut targets = vec![];
         let mut class_set = std::collections::HashSet::new();
 
         for (row_idx, result) in reader.records().enumerate() {
             let record = result.map_err(|e| format!("Error reading row {}: {}", row_idx, e))?;
 
             // Extract feature values
-            let mut feature_values = vec![];
-            for &col_idx in &feature_indices {
-                let val_str = record.get(col_idx).unwrap_or("0");
-                let val = val_str.trim().parse::<f32>()
-                    .map_err(|_| format!(
-                        "Failed to parse float at row {} col {}: '{}'",
-                        row_idx, col_idx, val_str
-                    ))?;
-                feature_values.push(val);
-            }
-            rows.push(feature_values);
+            let feature_values: Vec<String> = feature_indices
+                .iter()
+                .map(|&col_idx| record.get(col_idx).unwrap_or("").trim().to_string())
+                .collect();
+            cells.push(feature_values);
 
             // Extract label (required if target_column is specified, dummy "unlabeled" for Input streams)
             let label = if config.target_column.is_empty() {
@@ -117,25 +138,35 @@ impl CsvDatasetLoader {
             };
             labels.push(label.clone());
             class_set.insert(label);
+            targets.push(
+                target_indices
+                    .iter()
+                    .map(|&col_idx| record.get(col_idx).and_then(|v| v.trim().parse::<f32>().ok()).unwrap_or(f32::NAN))
+                    .collect::<Vec<f32>>(),
+            );
         }
 
-        if rows.is_empty() {
+        if cells.is_empty() {
             return Err("CSV contains no data rows".to_string());
         }
 
         // Compute number of samples based on sample mode
+        let row_stride = match config.sample_mode.as_str() {
+            "temporal_window" => config.window_stride.unwrap_or(1).max(1),
+            _ => 1,
+        };
         let num_samples = match config.sample_mode.as_str() {
-            "row" => rows.len(),
+            "row" => cells.len(),
             "temporal_window" => {
                 let ws = config.window_size.ok_or("window_size required for temporal_window mode")?;
-                if rows.len() < ws {
+                if cells.len() < ws {
                     return Err(format!(
                         "CSV has {} rows but window_size is {}",
-                        rows.len(),
+                        cells.len(),
                         ws
                     ));
                 }
-                rows.len().saturating_sub(ws - 1)
+                (cells.len() - ws) / row_stride + 1
             }
             _ => return Err(format!("Unknown sample_mode: {}", config.sample_mode)),
         };
@@ -143,53 +174,165 @@ impl CsvDatasetLoader {
         let mut discovered_classes: Vec<String> = class_set.into_iter().collect();
         discovered_classes.sort();
 
+        // Until statistics are fitted, numeric cells are used as-is and anything else is NaN
+        let rows = cells
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.parse::<f32>().unwrap_or(f32::NAN)).collect())
+            .collect();
+        let feature_width = feature_indices.len();
+
         Ok(CsvDatasetLoader {
             csv_path,
             config,
+            cells,
             rows,
             labels,
+            targets,
             feature_indices,
+            feature_names,
+            feature_width,
             target_index,
+            target_indices,
+            forecast_offset: 1,
+            row_stride,
             num_samples,
             discovered_classes,
         })
     }
 
+    /// Applies the row-level part of a missing-value strategy: interpolation fills
+    /// interior gaps in place. Returns, per row, whether it still has missing features
+    /// (those rows are dropped under `MissingStrategy::Drop`).
+    pub fn resolve_missing(&mut self, strategy: MissingStrategy) -> Vec<bool> {
+        if strategy == MissingStrategy::Interpolate {
+            tabular::interpolate_columns(&mut self.cells);
+        }
+        self.cells.iter().map(|row| tabular::row_has_missing(row)).collect()
+    }
+
+    /// First CSV row of a sample: windows advance by `window_stride` rows.
+    fn first_row(&self, sample_idx: usize) -> usize {
+        sample_idx * self.row_stride
+    }
+
+    /// CSV rows covered by a sample (one row, or a whole temporal window).
+    pub fn sample_rows(&self, sample_idx: usize) -> std::ops::Range<usize> {
+        let len = match self.config.sample_mode.as_str() {
+            "temporal_window" => self.config.window_size.unwrap_or(1),
+            _ => 1,
+        };
+        let start = self.first_row(sample_idx);
+        start..(start + len).min(self.cells.len())
+    }
+
+    /// Rows from a sample's first row to the last row it reads (features or targets),
+    /// and the rows between the first rows of consecutive samples.
+    pub fn sample_span(&self) -> (usize, usize) {
+        let mut span = self.sample_rows(0).len().max(1);
+        if !self.target_indices.is_empty() {
+            span = span.max(self.target_rows(0).end);
+        }
+        (span, self.row_stride)
+    }
+
+    pub fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
+
+    pub fn feature_cells(&self, row_idx: usize) -> Option<&[String]> {
+        self.cells.get(row_idx).map(Vec::as_slice)
+    }
+
+    /// Re-encodes every row with statistics fitted on the training split.
+    pub fn apply_tabular(&mut self, stats: &TabularStats) {
+        self.rows = self.cells.iter().map(|row| stats.transform(row)).collect();
+        self.feature_width = stats.width();
+    }
+
+    /// Aligns the samples of this stream with the windows of the temporal input
+    /// stream: row-mode samples advance by the input's stride, and forecasting targets
+    /// start right after the input window (the next row without a temporal input).
+    pub fn align_to_windows(&mut self, window_size: usize, stride: usize) {
+        self.forecast_offset = window_size.max(1);
+        if self.config.sample_mode != "temporal_window" {
+            self.row_stride = stride.max(1);
+        }
+    }
+
+    /// Rows a sample's targets are read from: its own row, or with a forecasting
+    /// horizon the `horizon` rows that follow the input window.
+    pub fn target_rows(&self, sample_idx: usize) -> std::ops::Range<usize> {
+        match self.config.horizon.filter(|&h| h > 0) {
+            Some(horizon) => {
+                let start = self.first_row(sample_idx) + self.forecast_offset;
+                start..start + horizon
+            }
+            None => self.first_row(sample_idx)..self.first_row(sample_idx) + 1,
+        }
+    }
+
+    /// Whether every row a sample reads exists (forecasts near the end of the series
+    /// run past the last row).
+    pub fn has_sample(&self, sample_idx: usize) -> bool {
+        self.first_row(sample_idx) < self.cells.len()
+            && (self.target_indices.is_empty() || self.target_rows(sample_idx).end <= self.targets.len())
+    }
+
+    /// Values per sample: target columns times forecast steps.
+    pub fn target_width(&self) -> usize {
+        self.target_indices.len() * self.config.horizon.unwrap_or(1).max(1)
+    }
+
+    /// Numeric target values of a sample, row by row (`[horizon, target columns]`).
+    pub fn target_values(&self, sample_idx: usize) -> Result<Vec<f32>, String> {
+        let rows = self.target_rows(sample_idx);
+        if rows.end > self.targets.len() {
+            return Err(format!(
+                "Targets of sample {} need rows up to {} but the CSV has {} rows",
+                sample_idx,
+                rows.end,
+                self.targets.len()
+            ));
+        }
+        let values: Vec<f32> = self.targets[rows].iter().flatten().copied().collect();
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(format!("Sample {} has missing or non-numeric target values", sample_idx));
+        }
+        Ok(values)
+    }
+
     /// Retrieve raw label string for stratification
     pub fn get_label(&self, sample_idx: usize) -> Option<String> {
-        if sample_idx < self.labels.len() {
-            Some(self.labels[sample_idx].clone())
-        } else {
-            None
-        }
+        self.labels.get(self.first_row(sample_idx)).cloned()
     }
 
     /// Load a single sample by index
-    pub fn load_sample(
+    pub fn load_sample<B: Backend>(
         &self,
         sample_idx: usize,
-        device: &TrainDevice,
-    ) -> Result<(DynamicTensor<Backend>, String), String> {
+        device: &B::Device,
+    ) -> Result<(DynamicTensor<B>, String), String> {
         match self.config.sample_mode.as_str() {
             "row" => {
                 // Each row = one sample
-                if sample_idx >= self.rows.len() {
+                let row = self.first_row(sample_idx);
+                if row >= self.rows.len() {
                     return Err(format!("Sample index {} out of bounds", sample_idx));
                 }
 
-                let features = &self.rows[sample_idx];
-                let label = &self.labels[sample_idx];
+                let features = &self.rows[row];
+                let label = &self.labels[row];
 
                 // If no features (e.g., Target stream), return a dummy tensor
                 let tensor_2d = if self.feature_indices.is_empty() {
-                    let tensor = Tensor::<Backend, 2>::from_data([[0.0]], device);
+                    let tensor = Tensor::<B, 2>::from_data([[0.0]], device);
                     tensor // Dummy tensor
                 } else {
                     // Normalize if configured
                     let features = self.normalize_row(features)?;
 
                     // Create tensor [1, num_features]
-                    let tensor = Tensor::<Backend, 1>::from_floats(features.as_slice(), device);
+                    let tensor = Tensor::<B, 1>::from_floats(features.as_slice(), device);
                     tensor.reshape([1, features.len()])
                 };
 
@@ -207,8 +350,8 @@ impl CsvDatasetLoader {
                     ));
                 }
 
-                let start_row = sample_idx;
-                let end_row = sample_idx + ws;
+                let start_row = self.first_row(sample_idx);
+                let end_row = start_row + ws;
 
                 // Stack rows[start:end] into [T, C] format
                 let mut window_data = vec![];
@@ -218,15 +361,15 @@ impl CsvDatasetLoader {
 
                 // If no features (e.g., Target stream), return a dummy 3D tensor
                 let tensor_3d = if self.feature_indices.is_empty() {
-                    let tensor = Tensor::<Backend, 3>::from_data([[[0.0]]], device);
+                    let tensor = Tensor::<B, 3>::from_data([[[0.0]]], device);
                     tensor // Dummy tensor
                 } else {
                     // Apply normalization
                     let window_data = self.normalize_temporal(&window_data, ws)?;
 
                     // Create tensor [1, ws, num_features]
-                    let tensor = Tensor::<Backend, 1>::from_floats(window_data.as_slice(), device);
-                    tensor.reshape([1, ws, self.feature_indices.len()])
+                    let tensor = Tensor::<B, 1>::from_floats(window_data.as_slice(), device);
+                    tensor.reshape([1, ws, self.feature_width])
                 };
 
                 // Use label from first row of window
@@ -245,7 +388,7 @@ impl CsvDatasetLoader {
             "none" => Ok(row.to_vec()),
             
             "global" => {
-                // Not applicable for single row
+                // Already scaled with training-split statistics by apply_tabular
                 Ok(row.to_vec())
             }
             
@@ -278,7 +421,7 @@ impl CsvDatasetLoader {
         window: &[f32],
         window_size: usize,
     ) -> Result<Vec<f32>, String> {
-        let num_channels = self.feature_indices.len();
+        let num_channels = self.feature_width;
         
         if window.len() != window_size * num_channels {
             return Err(format!(
@@ -289,7 +432,8 @@ impl CsvDatasetLoader {
         }
 
         match self.config.preprocessing.normalization.as_str() {
-            "none" => Ok(window.to_vec()),
+            // "global" is applied by apply_tabular with training-split statistics
+            "none" | "global" => Ok(window.to_vec()),
 
             "per-channel" => {
                 let mut normalized = window.to_vec();
@@ -343,7 +487,7 @@ impl CsvDatasetLoader {
     }
 
     pub fn get_num_features(&self) -> usize {
-        self.feature_indices.len()
+        self.feature_width
     }
 
     pub fn get_window_size(&self) -> Option<usize> {
@@ -1,13 +1,23 @@
+use burn::tensor::backend::Backend;
 use burn::tensor::{Tensor, TensorData};
+use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::dtos::{DataLocatorDef, DataType, DatasetProfile};
+use crate::companion_loader::{CompanionAnnotation, CompanionAnnotations, CompanionParser};
+use crate::dtos::{DataLocatorDef, DataStream, DataType, DatasetProfile};
 use crate::entities::DynamicTensor;
+use crate::image_cache::{
+    self, CacheOutcome, ChannelAccumulator, ChannelStats, EntryState, ImageCacheOptions, ImageCacheSpec,
+    VisionNormalization, VisionNormalizer,
+};
+use crate::tabular::{self, MissingStrategy, TabularConfig, TabularStats};
+use crate::targets::{TargetScaler, TargetSpec, TargetTask};
+use crate::weight_io::{InputPreprocessing, VisionPreprocessing};
 use crate::csv_loader::CsvDatasetLoader;
-
-type Backend = crate::backend::TrainBackend;
-type TrainDevice = crate::backend::TrainDevice;
+use crate::tensor_files::TensorTable;
 
 #[derive(serde::Serialize)]
 pub struct CacheResult {
@@ -15,15 +25,47 @@ pub struct CacheResult {
     pub total_dropped: usize,
     pub dropped_sample_ids: Vec<String>,
     pub class_counts: HashMap<String, usize>,
+    /// Entries (re)decoded by this build vs. kept from a previous one, over all Image streams.
+    pub decoded: usize,
+    pub reused: usize,
+}
+
+/// Emitted as `dataset-cache-progress` while `build_image_cache` runs.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CacheBuildProgress {
+    pub stream_id: String,
+    pub processed: usize,
+    pub total: usize,
+    pub decoded: usize,
+    pub reused: usize,
+    pub failed: usize,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct StreamCacheReport {
+    pub stream_id: String,
+    pub checked: usize,
+    pub fresh: usize,
+    pub missing: Vec<String>,
+    /// `"<sample id>: <reason>"`
+    pub stale: Vec<String>,
+    pub corrupt: Vec<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct CacheVerifyReport {
+    pub ok: bool,
+    pub streams: Vec<StreamCacheReport>,
 }
 
 // Struct to hold loaded data for a single specific sample
-pub struct SampleData {
+pub struct SampleData<B: Backend> {
     pub id: String,
     // Each index corresponds to the stream index in the DatasetProfile
-    pub stream_tensors: HashMap<usize, DynamicTensor<Backend>>,
+    pub stream_tensors: HashMap<usize, DynamicTensor<B>>,
 }
 
+#[derive(Clone)]
 pub struct DataLoader {
     profile: DatasetProfile,
     root_path: PathBuf,
@@ -35,10 +77,39 @@ pub struct DataLoader {
     // Cache for CsvDatasetLoader instances (Stream ID -> Loader)
     #[allow(dead_code)]
     csv_loaders: HashMap<String, CsvDatasetLoader>,
+    // NumPy/Parquet samples (Stream ID -> table), addressed by "tensor:<row>" locators
+    tensor_tables: HashMap<String, Arc<TensorTable>>,
+    // Parsed annotations of CompanionFile Image streams, rasterized into masks on load
+    companion_masks: HashMap<String, Arc<CompanionMasks>>,
+    // Stream ID -> preprocessing fitted on the training split (see fit_tabular)
+    tabular: HashMap<String, TabularStats>,
+    // Stream ID -> per-channel statistics of "dataset"-normalized Image streams (see fit_vision)
+    vision_stats: HashMap<String, ChannelStats>,
+    // Target stream ID -> declared task
+    target_tasks: HashMap<String, TargetTask>,
+    // Target stream ID -> task, output width and scaling fitted on the training split (see fit_targets)
+    targets: HashMap<String, TargetSpec>,
+    // Whether fitted statistics are written next to the profile (not for walk-forward folds)
+    persist_fitted: bool,
+}
+
+struct CompanionMasks {
+    annotations: CompanionAnnotations,
+    classes: Vec<String>,
 }
 
 impl DataLoader {
+    /// Loader for reading samples: once the image cache was built, only samples with a
+    /// cache entry are valid, which removes images that failed to decode.
     pub fn new(profile: DatasetProfile, app_data_dir: Option<PathBuf>) -> Result<Self, String> {
+        let mut loader = Self::with_all_samples(profile, app_data_dir)?;
+        loader.retain_cached_samples();
+        Ok(loader)
+    }
+
+    /// Loader over every sample the locators find, cached or not. The image cache is
+    /// built and verified over these, so images added after a build get cached.
+    pub fn with_all_samples(profile: DatasetProfile, app_data_dir: Option<PathBuf>) -> Result<Self, String> {
         let root_path_str = profile
             .source_path
             .clone()
@@ -48,6 +119,11 @@ impl DataLoader {
             return Err(format!("Root path does not exist: {}", root_path_str));
         }
 
+        let mut target_tasks = HashMap::new();
+        for stream in profile.streams.iter().filter(|s| s.role == "Target") {
+            target_tasks.insert(stream.id.clone(), TargetTask::for_stream(stream)?);
+        }
+
         let mut loader = DataLoader {
             profile: profile.clone(),
             root_path,
@@ -56,40 +132,45 @@ impl DataLoader {
             stream_classes: HashMap::new(),
             app_data_dir,
             csv_loaders: HashMap::new(),
+            tensor_tables: HashMap::new(),
+            companion_masks: HashMap::new(),
+            tabular: HashMap::new(),
+            vision_stats: HashMap::new(),
+            target_tasks,
+            targets: HashMap::new(),
+            persist_fitted: true,
         };
 
         loader.init_locators()?;
+        Ok(loader)
+    }
 
-        // If a cache was built, filter valid_sample_ids to only include cached samples.
-        // This removes corrupt/unreadable images discovered during cache building.
-        if let Some(ref app_data) = loader.app_data_dir {
-            let cache_base = app_data.join("datasets_cache").join(&loader.profile.id);
-            // Find any image stream whose cache dir exists
-            for stream in &loader.profile.streams {
-                if let DataType::Image = stream.data_type {
-                    let stream_cache_dir = cache_base.join(&stream.id);
-                    if stream_cache_dir.exists() {
-                        let before = loader.valid_sample_ids.len();
-                        loader.valid_sample_ids.retain(|sample_id| {
-                            let cache_path = stream_cache_dir
-                                .join(format!("{}.bin", sample_id.replace("/", "_")));
-                            cache_path.exists()
-                        });
-                        let dropped = before - loader.valid_sample_ids.len();
-                        if dropped > 0 {
-                            println!(
-                                "  Cache filter: removed {} uncached samples ({} -> {} valid)",
-                                dropped,
-                                before,
-                                loader.valid_sample_ids.len()
-                            );
-                        }
+    /// If a cache was built, filter valid_sample_ids to only include cached samples.
+    /// This removes corrupt/unreadable images discovered during cache building.
+    fn retain_cached_samples(&mut self) {
+        let Some(app_data) = self.app_data_dir.as_ref() else {
+            return;
+        };
+        // Find any image stream whose cache dir exists
+        for stream in &self.profile.streams {
+            if let DataType::Image = stream.data_type {
+                let stream_cache_dir = image_cache::stream_cache_dir(app_data, &self.profile.id, &stream.id);
+                if stream_cache_dir.exists() {
+                    let before = self.valid_sample_ids.len();
+                    self.valid_sample_ids
+                        .retain(|sample_id| image_cache::cache_file_path(&stream_cache_dir, sample_id).exists());
+                    let dropped = before - self.valid_sample_ids.len();
+                    if dropped > 0 {
+                        println!(
+                            "  Cache filter: removed {} uncached samples ({} -> {} valid)",
+                            dropped,
+                            before,
+                            self.valid_sample_ids.len()
+                        );
                     }
                 }
             }
         }
-
-        Ok(loader)
     }
 
     fn init_locators(&mut self) -> Result<(), String> {
@@ -101,6 +182,10 @@ impl DataLoader {
 
         // First pass: Pre-load all CSV datasets to determine temporal window alignment
         let mut temporal_window_count: Option<usize> = None;
+        let mut temporal_window_size: Option<usize> = None;
+        let mut temporal_window_stride: Option<usize> = None;
+        // Stream ID -> per-row "has missing features" for streams that drop such rows
+        let mut csv_missing_rows: HashMap<String, Vec<bool>> = HashMap::new();
         
         for stream in &self.profile.streams {
             if let DataLocatorDef::CsvDataset(csv_def) = &stream.locator {
@@ -117,13 +202,29 @@ impl DataLoader {
                 }
                 
                 match CsvDatasetLoader::init(&self.root_path, adjusted_def.clone()) {
-                    Ok(loader) => {
+                    Ok(mut loader) => {
                         eprintln!(">>> Pre-loaded CSV for stream {}: {} (found {} samples)", stream.id, adjusted_def.csv_path, loader.num_samples);
+                        if let Some(config) = TabularConfig::for_stream(stream)? {
+                            let missing_rows = loader.resolve_missing(config.missing);
+                            let missing_count = missing_rows.iter().filter(|m| **m).count();
+                            if missing_count > 0 {
+                                eprintln!(
+                                    ">>> Stream {}: {} rows with missing features ({:?})",
+                                    stream.id, missing_count, config.missing
+                                );
+                            }
+                            if config.missing == MissingStrategy::Drop && missing_count > 0 {
+                                csv_missing_rows.insert(stream.id.clone(), missing_rows);
+                            }
+                        }
                         if stream.role == "Input" && adjusted_def.sample_mode == "temporal_window" {
                             temporal_window_count = Some(loader.num_samples);
+                            temporal_window_size = adjusted_def.window_size;
+                            temporal_window_stride = adjusted_def.window_stride;
                             eprintln!(
-                                ">>> Detected temporal_window Input stream: window_size={}, sample count={}",
+                                ">>> Detected temporal_window Input stream: window_size={}, stride={}, sample count={}",
                                 adjusted_def.window_size.unwrap_or(1),
+                                adjusted_def.window_stride.unwrap_or(1),
                                 loader.num_samples
                             );
                         }
@@ -138,7 +239,20 @@ impl DataLoader {
             }
         }
 
-        for stream in &self.profile.streams {
+        // Samples follow the input windows; forecasting targets start right after the
+        // input window (or the input row)
+        for csv_loader in self.csv_loaders.values_mut() {
+            csv_loader.align_to_windows(temporal_window_size.unwrap_or(1), temporal_window_stride.unwrap_or(1));
+        }
+
+        // Companion files are resolved against the sample ids found by the other streams
+        let (anchored, companions): (Vec<&DataStream>, Vec<&DataStream>) = self
+            .profile
+            .streams
+            .iter()
+            .partition(|s| !matches!(s.locator, DataLocatorDef::CompanionFile { .. }));
+
+        for stream in anchored.into_iter().chain(companions) {
             let mut stream_map = HashMap::new();
             println!(
                 "  Processing stream: ID={}, DataType={:?}",
@@ -289,6 +403,71 @@ impl DataLoader {
                     }
                     println!("    MasterIndex found {} samples", stream_map.len());
                 }
+                DataLocatorDef::CompanionFile { path_template, parser } => {
+                    println!(
+                        "    Locator: CompanionFile, template: {}, parser: {}",
+                        path_template, parser
+                    );
+                    let parser = CompanionParser::from_name(parser)?;
+                    let anchor_ids = all_sample_ids.clone().unwrap_or_default();
+                    if anchor_ids.is_empty() {
+                        eprintln!(
+                            ">>> Warning: CompanionFile stream {} has no sample ids to resolve; add a file-based stream",
+                            stream.id
+                        );
+                    }
+
+                    let annotations = crate::companion_loader::load_companion_annotations(
+                        &self.root_path,
+                        path_template,
+                        parser,
+                        &anchor_ids,
+                    );
+                    for error in annotations.errors.iter().take(5) {
+                        eprintln!(">>> Failed to parse companion file {}", error);
+                    }
+
+                    let target_len = stream.tensor_shape.iter().product::<usize>().max(1);
+                    for (id, annotation) in &annotations.by_sample {
+                        let value = match stream.data_type {
+                            // Dominant class; mapped to a class index below like other categorical streams
+                            DataType::Categorical => annotation.dominant_class(),
+                            DataType::Vector => Some(
+                                annotation
+                                    .regression_targets(target_len)
+                                    .iter()
+                                    .map(f32::to_string)
+                                    .collect::<Vec<_>>()
+                                    .join(","),
+                            ),
+                            _ => match annotation {
+                                CompanionAnnotation::Text(text) if stream.data_type == DataType::Text => {
+                                    Some(text.clone())
+                                }
+                                _ => Some(
+                                    crate::companion_loader::companion_path(&self.root_path, path_template, id)
+                                        .to_string_lossy()
+                                        .to_string(),
+                                ),
+                            },
+                        };
+                        if let Some(value) = value {
+                            stream_map.insert(id.clone(), value);
+                        }
+                    }
+
+                    let classes = annotations.classes();
+                    println!(
+                        "    CompanionFile parsed {} samples ({} unreadable), {} classes",
+                        annotations.by_sample.len(),
+                        annotations.errors.len(),
+                        classes.len()
+                    );
+                    if stream.data_type == DataType::Image {
+                        self.companion_masks
+                            .insert(stream.id.clone(), Arc::new(CompanionMasks { annotations, classes }));
+                    }
+                }
                 DataLocatorDef::CsvDataset(csv_def) => {
                     eprintln!("    Locator: CsvDataset for stream {}", stream.id);
                     
@@ -316,7 +495,16 @@ impl DataLoader {
                             csv_loader.num_samples
                         };
                         
+                        let missing_rows = csv_missing_rows.get(&stream.id);
                         for sample_idx in 0..sample_count {
+                            if let Some(missing) = missing_rows
+                                && csv_loader.sample_rows(sample_idx).any(|row| missing[row])
+                            {
+                                continue;
+                            }
+                            if !csv_loader.has_sample(sample_idx) {
+                                continue;
+                            }
                             let sample_id = sample_idx.to_string();
                             stream_map.insert(sample_id, format!("csv:{}", sample_idx));
                         }
@@ -348,11 +536,54 @@ impl DataLoader {
                         return Err(format!("CSV dataset not pre-loaded: {}", csv_def.csv_path));
                     }
                 }
+                DataLocatorDef::NumpyArray { path, .. } | DataLocatorDef::ParquetTable { path, .. } => {
+                    println!("    Locator: {:?}", stream.locator);
+                    if stream.data_type == DataType::Image {
+                        return Err(format!(
+                            "Stream {}: Image streams cannot be read from NumPy/Parquet files ({})",
+                            stream.id, path
+                        ));
+                    }
+                    let table = crate::tensor_files::load_table(&self.root_path, &stream.locator)?;
+                    for (row, id) in table.ids.iter().enumerate() {
+                        // Class labels are mapped to indices below like other categorical streams
+                        let value = if stream.data_type == DataType::Categorical {
+                            table.label(row)
+                        } else {
+                            Some(format!("tensor:{}", row))
+                        };
+                        if let Some(value) = value {
+                            stream_map.insert(id.clone(), value);
+                        }
+                    }
+                    println!(
+                        "    {} found {} samples of shape {:?}",
+                        path,
+                        table.len(),
+                        table.sample_shape
+                    );
+                    self.tensor_tables.insert(stream.id.clone(), Arc::new(table));
+                }
                 _ => {
                     println!("    Locator: Other/Unknown");
                 }
             }
 
+            if stream.data_type == DataType::Vector
+                && !matches!(stream.locator, DataLocatorDef::CsvDataset(_))
+                && let Some(config) = TabularConfig::for_stream(stream)?
+                && config.missing == MissingStrategy::Drop
+            {
+                let before = stream_map.len();
+                stream_map.retain(|_, value| match self.tensor_row(&stream.id, value) {
+                    Some(row) => row.iter().all(|v| v.is_finite()),
+                    None => !tabular::row_has_missing(&vector_cells(value)),
+                });
+                if stream_map.len() < before {
+                    println!("    Dropped {} samples with missing values", before - stream_map.len());
+                }
+            }
+
             let current_ids: HashSet<String> = stream_map.keys().cloned().collect();
             println!("  Stream mapped {} distinct SampleIDs", current_ids.len());
 
@@ -424,99 +655,525 @@ impl DataLoader {
         Ok(())
     }
 
-    pub fn build_image_cache(&self) -> Result<CacheResult, String> {
-        let app_data = self
-            .app_data_dir
-            .as_ref()
-            .ok_or("No AppData directory available to store cache")?;
-        let cache_dir = app_data.join("datasets_cache").join(&self.profile.id);
+    /// A copy without fitted statistics, for fitting a walk-forward fold on its own
+    /// training window. Whatever it fits is not persisted.
+    pub fn unfitted_copy(&self) -> DataLoader {
+        let mut loader = self.clone();
+        loader.persist_fitted = false;
+        loader.tabular.clear();
+        loader.vision_stats.clear();
+        loader.targets.clear();
+        loader
+    }
 
-        let mut cached_ids: Vec<String> = Vec::new();
-        let mut dropped_ids: Vec<String> = Vec::new();
+    /// Fits tabular preprocessing of every Vector/CSV stream on the training split and
+    /// applies it to all samples. The fitted statistics are persisted with the profile
+    /// when an app data directory is available.
+    pub fn fit_tabular(&mut self, train_ids: &[String]) -> Result<(), String> {
+        let fingerprint = tabular::train_fingerprint(train_ids);
+        let mut fitted = Vec::new();
 
         for stream in &self.profile.streams {
-            if let DataType::Image = stream.data_type {
-                let stream_cache_dir = cache_dir.join(&stream.id);
-                std::fs::create_dir_all(&stream_cache_dir).map_err(|e| e.to_string())?;
-
-                let mut target_w = 64;
-                let mut target_h = 64;
-                let mut grayscale = false;
-
-                if let Some(prep) = &stream.preprocessing {
-                    if let Some(vision) = &prep.vision {
-                        if vision.resize.len() == 2 {
-                            target_w = vision.resize[0];
-                            target_h = vision.resize[1];
-                        }
-                        grayscale = vision.grayscale;
+            // Value targets are standardized by fit_targets instead
+            if self.reads_target_values(stream) {
+                continue;
+            }
+            let Some(config) = TabularConfig::for_stream(stream)? else {
+                continue;
+            };
+            let locators = self.stream_files.get(&stream.id);
+            let stats = if let Some(csv_loader) = self.csv_loaders.get(&stream.id) {
+                if csv_loader.feature_names().is_empty() {
+                    continue;
+                }
+                let mut rows = std::collections::BTreeSet::new();
+                for id in train_ids {
+                    if let Some(sample_idx) = locators
+                        .and_then(|m| m.get(id))
+                        .and_then(|v| v.strip_prefix("csv:"))
+                        .and_then(|i| i.parse::<usize>().ok())
+                    {
+                        rows.extend(csv_loader.sample_rows(sample_idx));
                     }
                 }
-                let channels = if grayscale { 1 } else { 3 };
+                TabularStats::fit(
+                    &stream.id,
+                    csv_loader.feature_names(),
+                    rows.into_iter().filter_map(|row| csv_loader.feature_cells(row)),
+                    &config,
+                    fingerprint.clone(),
+                )
+            } else if stream.data_type == DataType::Vector
+                && let Some(locators) = locators
+            {
+                let rows: Vec<Vec<String>> = train_ids
+                    .iter()
+                    .filter_map(|id| locators.get(id))
+                    .map(|value| match self.tensor_row(&stream.id, value) {
+                        Some(row) => row.iter().map(f32::to_string).collect(),
+                        None => vector_cells(value),
+                    })
+                    .collect();
+                let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+                let names: Vec<String> = (0..width).map(|i| format!("col{}", i)).collect();
+                TabularStats::fit(&stream.id, &names, rows.iter().map(Vec::as_slice), &config, fingerprint.clone())
+            } else {
+                continue;
+            };
+
+            println!(
+                "  Tabular stream {}: fitted on {} training rows, {} columns -> {} features",
+                stream.id,
+                stats.fitted_rows,
+                stats.columns.len(),
+                stats.width()
+            );
+            for (column, missing) in stats.missing_report() {
+                println!("    Column '{}': {} missing values ({:?})", column, missing, config.missing);
+            }
+            fitted.push(stats);
+        }
+
+        for stats in fitted {
+            if let Some(csv_loader) = self.csv_loaders.get_mut(&stats.stream_id) {
+                csv_loader.apply_tabular(&stats);
+            }
+            self.tabular.insert(stats.stream_id.clone(), stats);
+        }
+
+        if let Some(app_data) = &self.app_data_dir
+            && self.persist_fitted
+            && !self.tabular.is_empty()
+        {
+            let path = tabular::stats_path(app_data, &self.profile.id);
+            let mut persisted = tabular::load_persisted(&path);
+            persisted.extend(self.tabular.iter().map(|(id, stats)| (id.clone(), stats.clone())));
+            if let Err(e) = tabular::persist(&path, &persisted) {
+                eprintln!(">>> Warning: could not persist tabular statistics: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Target streams whose tensors are built from numeric values (regression,
+    /// multi-output, multi-label) rather than a class index or a dense mask.
+    fn reads_target_values(&self, stream: &DataStream) -> bool {
+        stream.data_type != DataType::Image
+            && self
+                .target_tasks
+                .get(&stream.id)
+                .is_some_and(|&task| task != TargetTask::Classification)
+    }
+
+    /// Unscaled values of a target stream for one sample.
+    pub fn raw_target(&self, stream: &DataStream, sample_id: &str) -> Result<Vec<f32>, String> {
+        let locator_val = self
+            .stream_files
+            .get(&stream.id)
+            .and_then(|m| m.get(sample_id))
+            .ok_or_else(|| format!("Sample '{}' has no value in stream {}", sample_id, stream.id))?;
+
+        if let Some(sample_idx) = locator_val.strip_prefix("csv:") {
+            let sample_idx = sample_idx
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid CSV sample index: {}", sample_idx))?;
+            let csv_loader = self
+                .csv_loaders
+                .get(&stream.id)
+                .ok_or_else(|| format!("No CSV loader found for stream {}", stream.id))?;
+            return csv_loader.target_values(sample_idx);
+        }
+        if let Some(row) = self.tensor_row(&stream.id, locator_val) {
+            if row.iter().any(|v| !v.is_finite()) {
+                return Err(format!("Sample '{}' has missing target values in stream {}", sample_id, stream.id));
+            }
+            return Ok(row.to_vec());
+        }
+
+        vector_cells(locator_val)
+            .iter()
+            .map(|cell| {
+                cell.parse::<f32>()
+                    .ok()
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| format!("Non-numeric target value '{}' in stream {}", cell, stream.id))
+            })
+            .collect()
+    }
+
+    /// Values of a sample of a NumPy/Parquet stream.
+    fn tensor_row(&self, stream_id: &str, locator_val: &str) -> Option<&[f32]> {
+        let row = locator_val.strip_prefix("tensor:")?.parse::<usize>().ok()?;
+        self.tensor_tables.get(stream_id)?.row(row)
+    }
+
+    /// Raw content of a sample of a table-backed stream (the CSV cells of its rows, or
+    /// its NumPy/Parquet values), for duplicate detection. `None` for file streams.
+    pub fn table_content(&self, stream_id: &str, sample_id: &str) -> Option<String> {
+        let locator_val = self.stream_files.get(stream_id)?.get(sample_id)?;
+        if let Some(values) = self.tensor_row(stream_id, locator_val) {
+            return Some(values.iter().map(|v| v.to_bits().to_string()).collect::<Vec<_>>().join(","));
+        }
+        let sample_idx = locator_val.strip_prefix("csv:")?.parse::<usize>().ok()?;
+        let csv_loader = self.csv_loaders.get(stream_id)?;
+        let rows: Vec<String> = csv_loader
+            .sample_rows(sample_idx)
+            .filter_map(|row| csv_loader.feature_cells(row).map(|cells| cells.join("\u{1f}")))
+            .collect();
+        Some(rows.join("\n"))
+    }
+
+    pub fn csv_loader(&self, stream_id: &str) -> Option<&CsvDatasetLoader> {
+        self.csv_loaders.get(stream_id)
+    }
+
+    /// First target value of every sample in `ids`, for quantile-stratified splits of
+    /// continuous targets. Samples without a readable value are left out.
+    pub fn continuous_target_values(&self, stream: &DataStream, ids: &[String]) -> Vec<(String, f32)> {
+        ids.iter()
+            .filter_map(|id| {
+                let values = self.raw_target(stream, id).ok()?;
+                Some((id.clone(), *values.first()?))
+            })
+            .collect()
+    }
+
+    /// Neighbouring samples that share CSV rows with a sample through overlapping
+    /// windows or forecast rows. Chronological splits leave this many samples out
+    /// between segments so no row is seen on both sides.
+    pub fn sample_overlap(&self) -> usize {
+        self.csv_loaders
+            .values()
+            .map(|csv_loader| {
+                let (span, stride) = csv_loader.sample_span();
+                span.div_ceil(stride).saturating_sub(1)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn target_task(&self, stream_id: &str) -> Option<TargetTask> {
+        self.target_tasks.get(stream_id).copied()
+    }
+
+    /// Fits the output width and, for continuous targets, the standardization of every
+    /// Target stream on the training split.
+    pub fn fit_targets(&mut self, train_ids: &[String]) -> Result<(), String> {
+        let mut fitted = Vec::new();
+        for (idx, stream) in self.profile.streams.iter().enumerate() {
+            let Some(&task) = self.target_tasks.get(&stream.id) else {
+                continue;
+            };
+
+            let (width, scaler) = if !self.reads_target_values(stream) {
+                let width = match task {
+                    TargetTask::Classification => self.stream_classes.get(&idx).copied().unwrap_or(1),
+                    _ => stream.tensor_shape.first().copied().unwrap_or(1),
+                };
+                (width, None)
+            } else {
+                let rows: Vec<Vec<f32>> = train_ids
+                    .iter()
+                    .filter_map(|id| self.raw_target(stream, id).ok())
+                    .collect();
+                let width = rows
+                    .iter()
+                    .map(Vec::len)
+                    .max()
+                    .or_else(|| self.csv_loaders.get(&stream.id).map(CsvDatasetLoader::target_width))
+                    .unwrap_or_else(|| stream.tensor_shape.iter().product::<usize>().max(1));
+                if rows.is_empty() {
+                    eprintln!(">>> Warning: target stream {} has no numeric values in the training split", stream.id);
+                }
+                let scaler = if task.is_continuous() {
+                    TargetScaler::fit(rows.iter().map(Vec::as_slice))
+                } else {
+                    None
+                };
+                (width, scaler)
+            };
+
+            println!(
+                "  Target stream {}: {:?}, {} outputs{}",
+                stream.id,
+                task,
+                width,
+                if scaler.is_some() { ", standardized on the training split" } else { "" }
+            );
+            fitted.push(TargetSpec {
+                stream_id: stream.id.clone(),
+                task,
+                width,
+                scaler,
+            });
+        }
+
+        self.targets = fitted.into_iter().map(|spec| (spec.stream_id.clone(), spec)).collect();
+        Ok(())
+    }
+
+    pub fn target_spec(&self, stream_id: &str) -> Option<&TargetSpec> {
+        self.targets.get(stream_id)
+    }
+
+    pub fn tabular_stats(&self) -> &HashMap<String, TabularStats> {
+        &self.tabular
+    }
+
+    /// Features per sample of a fitted tabular stream (grows with one-hot encoding).
+    pub fn tabular_width(&self, stream_id: &str) -> Option<usize> {
+        self.tabular.get(stream_id).map(TabularStats::width)
+    }
+
+    /// Preprocessing of the given streams as recorded in exported model metadata.
+    pub fn input_preprocessing(&self, stream_indices: &[usize]) -> Result<Vec<InputPreprocessing>, String> {
+        stream_indices
+            .iter()
+            .filter_map(|&idx| self.profile.streams.get(idx))
+            .map(|stream| {
+                let vision = if stream.data_type == DataType::Image {
+                    let spec = ImageCacheSpec::for_stream(stream);
+                    Some(VisionPreprocessing {
+                        width: spec.width,
+                        height: spec.height,
+                        grayscale: spec.grayscale,
+                        normalizer: self.vision_normalizer(stream)?,
+                    })
+                } else {
+                    None
+                };
+                Ok(InputPreprocessing {
+                    stream_id: stream.id.clone(),
+                    data_type: format!("{:?}", stream.data_type),
+                    vision,
+                    tabular: self.tabular.get(&stream.id).cloned(),
+                })
+            })
+            .collect()
+    }
+
+    /// 0..1 scaled CHW pixels of one sample, from the image cache when it was built.
+    fn image_pixels(
+        &self,
+        stream: &DataStream,
+        spec: &ImageCacheSpec,
+        sample_id: &str,
+        locator_val: &str,
+    ) -> Result<Vec<f32>, String> {
+        // CHECK CACHE FIRST
+        let stream_cache_dir = self
+            .app_data_dir
+            .as_ref()
+            .map(|app_data| image_cache::stream_cache_dir(app_data, &self.profile.id, &stream.id))
+            .filter(|dir| dir.exists());
+        if let Some(dir) = &stream_cache_dir {
+            match image_cache::read_pixels(&image_cache::cache_file_path(dir, sample_id), spec) {
+                Ok(Some(pixels)) => return Ok(pixels),
+                // Cache was built but this sample has no entry -> it was corrupt
+                Ok(None) => {
+                    return Err(format!(
+                        "Sample '{}' was not cached (corrupt/unreadable during cache build)",
+                        sample_id
+                    ));
+                }
+                Err(reason) => {
+                    eprintln!(
+                        "[WARN] Cache entry for '{}' ({}) is unusable, decoding the source: {}",
+                        sample_id, stream.id, reason
+                    );
+                }
+            }
+        }
 
-                for sample_id in &self.valid_sample_ids {
-                    let locator_val = self
-                        .stream_files
-                        .get(&stream.id)
-                        .and_then(|m| m.get(sample_id))
-                        .cloned()
-                        .unwrap_or_default();
+        let img_path = image_cache::source_path(locator_val);
+        let raw_pixels = image_cache::decode_image_file(&img_path, spec).map_err(|e| {
+            format!(
+                "Failed to load image for stream {} at path '{}': {}",
+                stream.id,
+                img_path.display(),
+                e
+            )
+        })?;
+        Ok(image_cache::pixels_to_f32(&raw_pixels))
+    }
+
+    /// Normalization applied to an Image stream, including fitted "dataset" statistics.
+    pub fn vision_normalizer(&self, stream: &DataStream) -> Result<VisionNormalizer, String> {
+        VisionNormalizer::new(&ImageCacheSpec::for_stream(stream), self.vision_stats.get(&stream.id))
+    }
+
+    fn uses_dataset_normalization(stream: &DataStream) -> bool {
+        VisionNormalization::from_name(&ImageCacheSpec::for_stream(stream).normalization)
+            == Ok(VisionNormalization::Dataset)
+    }
 
-                    let sanitized_path = locator_val.trim_matches(|c| c == '"' || c == '\'');
-                    let img_path = PathBuf::from(sanitized_path);
+    /// Per-channel mean/std of `ids` on the rayon pool; unloadable samples are skipped.
+    fn compute_channel_stats(&self, stream: &DataStream, ids: &[String]) -> ChannelStats {
+        let spec = ImageCacheSpec::for_stream(stream);
+        let channels = spec.channels();
+        ids.par_iter()
+            .fold(ChannelAccumulator::default, |mut acc, sample_id| {
+                let locator_val = self
+                    .stream_files
+                    .get(&stream.id)
+                    .and_then(|m| m.get(sample_id))
+                    .map(String::as_str)
+                    .unwrap_or_default();
+                if let Ok(pixels) = self.image_pixels(stream, &spec, sample_id, locator_val) {
+                    acc.add(&pixels, channels);
+                }
+                acc
+            })
+            .reduce(ChannelAccumulator::default, ChannelAccumulator::merge)
+            .finish(tabular::train_fingerprint(ids))
+    }
 
-                    let cache_path =
-                        stream_cache_dir.join(format!("{}.bin", sample_id.replace("/", "_")));
-                    if cache_path.exists() {
-                        cached_ids.push(sample_id.clone());
-                        continue; // Already processed
+    /// Fits per-channel statistics for Image streams with "dataset" normalization on the
+    /// training split. Statistics stored with the image cache are reused when they were
+    /// computed on the same samples.
+    pub fn fit_vision(&mut self, train_ids: &[String]) -> Result<(), String> {
+        let fingerprint = tabular::train_fingerprint(train_ids);
+        let mut fitted = Vec::new();
+
+        for stream in self.image_streams() {
+            VisionNormalization::from_name(&ImageCacheSpec::for_stream(stream).normalization)?;
+            if !Self::uses_dataset_normalization(stream) {
+                continue;
+            }
+            let stats_path = self
+                .app_data_dir
+                .as_ref()
+                .map(|app_data| image_cache::channel_stats_path(&image_cache::stream_cache_dir(app_data, &self.profile.id, &stream.id)));
+
+            let stored = stats_path
+                .as_deref()
+                .and_then(image_cache::load_channel_stats)
+                .filter(|stats| stats.train_fingerprint == fingerprint);
+            let stats = match stored {
+                Some(stats) => stats,
+                None => {
+                    let stats = self.compute_channel_stats(stream, train_ids);
+                    if let Some(path) = &stats_path
+                        && self.persist_fitted
+                        && path.parent().is_some_and(Path::exists)
+                        && let Err(e) = image_cache::save_channel_stats(path, &stats)
+                    {
+                        eprintln!(">>> Warning: could not store channel statistics: {}", e);
                     }
+                    stats
+                }
+            };
+            println!(
+                "  Image stream {}: dataset normalization mean={:?} std={:?} ({} training samples)",
+                stream.id, stats.mean, stats.std, stats.samples
+            );
+            fitted.push((stream.id.clone(), stats));
+        }
 
-                    if let Ok(img) = image::open(&img_path) {
-                        let img = img.resize_exact(
-                            target_w,
-                            target_h,
-                            image::imageops::FilterType::Triangle,
-                        );
-                        let mut pixels: Vec<f32> =
-                            Vec::with_capacity((target_w * target_h) as usize * channels);
-
-                        if grayscale {
-                            let img_gray = img.to_luma8();
-                            for y in 0..target_h {
-                                for x in 0..target_w {
-                                    pixels.push(img_gray.get_pixel(x, y)[0] as f32 / 255.0);
-                                }
-                            }
-                        } else {
-                            let img_rgb = img.to_rgb8();
-                            for c in 0..3 {
-                                for y in 0..target_h {
-                                    for x in 0..target_w {
-                                        pixels.push(img_rgb.get_pixel(x, y)[c] as f32 / 255.0);
-                                    }
-                                }
-                            }
-                        }
+        self.vision_stats.extend(fitted);
+        Ok(())
+    }
 
-                        let bytes: &[u8] = bytemuck::cast_slice(&pixels);
-                        if let Err(e) = std::fs::write(&cache_path, bytes) {
-                            eprintln!("Failed to write cache for {}: {}", sample_id, e);
-                            dropped_ids.push(sample_id.clone());
-                        } else {
-                            cached_ids.push(sample_id.clone());
-                        }
-                    } else {
-                        eprintln!(
-                            "AoT Cache Builder failed to decode image for {}: {:?}",
-                            sample_id, img_path
-                        );
-                        dropped_ids.push(sample_id.clone());
+    fn image_streams(&self) -> impl Iterator<Item = &DataStream> {
+        self.profile
+            .streams
+            .iter()
+            .filter(|stream| matches!(stream.data_type, DataType::Image) && !self.companion_masks.contains_key(&stream.id))
+    }
+
+    fn image_source(&self, stream_id: &str, sample_id: &str) -> PathBuf {
+        let locator_val = self
+            .stream_files
+            .get(stream_id)
+            .and_then(|m| m.get(sample_id))
+            .map(String::as_str)
+            .unwrap_or_default();
+        image_cache::source_path(locator_val)
+    }
+
+    /// Decodes every Image stream into the on-disk cache on the rayon pool. Entries whose
+    /// source and settings are unchanged are kept, so rebuilds only touch new or
+    /// modified images. A sample counts as cached once every Image stream has it.
+    pub fn build_image_cache(
+        &self,
+        options: &ImageCacheOptions,
+        on_progress: &(dyn Fn(&CacheBuildProgress) + Sync),
+    ) -> Result<CacheResult, String> {
+        let app_data = self
+            .app_data_dir
+            .as_ref()
+            .ok_or("No AppData directory available to store cache")?;
+
+        let mut dropped: HashSet<String> = HashSet::new();
+        let mut decoded = 0;
+        let mut reused = 0;
+
+        for stream in self.image_streams() {
+            let stream_cache_dir = image_cache::stream_cache_dir(app_data, &self.profile.id, &stream.id);
+            std::fs::create_dir_all(&stream_cache_dir).map_err(|e| e.to_string())?;
+            let spec = ImageCacheSpec::for_stream(stream);
+
+            let total = self.valid_sample_ids.len();
+            let report_every = (total / 100).max(1);
+            let processed = AtomicUsize::new(0);
+            let counters = [AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0)];
+
+            let outcomes: Vec<(&String, CacheOutcome)> = self
+                .valid_sample_ids
+                .par_iter()
+                .map(|sample_id| {
+                    let outcome = image_cache::cache_image(
+                        &image_cache::cache_file_path(&stream_cache_dir, sample_id),
+                        &self.image_source(&stream.id, sample_id),
+                        &spec,
+                        options,
+                    );
+                    let slot = match outcome {
+                        CacheOutcome::Decoded => 0,
+                        CacheOutcome::Reused => 1,
+                        CacheOutcome::Failed(_) => 2,
+                    };
+                    counters[slot].fetch_add(1, Ordering::Relaxed);
+                    let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
+                    if done.is_multiple_of(report_every) || done == total {
+                        on_progress(&CacheBuildProgress {
+                            stream_id: stream.id.clone(),
+                            processed: done,
+                            total,
+                            decoded: counters[0].load(Ordering::Relaxed),
+                            reused: counters[1].load(Ordering::Relaxed),
+                            failed: counters[2].load(Ordering::Relaxed),
+                        });
+                    }
+                    (sample_id, outcome)
+                })
+                .collect();
+
+            for (sample_id, outcome) in outcomes {
+                match outcome {
+                    CacheOutcome::Decoded => decoded += 1,
+                    CacheOutcome::Reused => reused += 1,
+                    CacheOutcome::Failed(e) => {
+                        eprintln!("AoT Cache Builder failed to cache {} ({}): {}", sample_id, stream.id, e);
+                        dropped.insert(sample_id.clone());
                     }
                 }
             }
+
+            if Self::uses_dataset_normalization(stream) {
+                let stats_ids: Vec<String> = match &options.train_ids {
+                    Some(ids) => ids.clone(),
+                    None => self.valid_sample_ids.iter().filter(|id| !dropped.contains(*id)).cloned().collect(),
+                };
+                let stats = self.compute_channel_stats(stream, &stats_ids);
+                image_cache::save_channel_stats(&image_cache::channel_stats_path(&stream_cache_dir), &stats)?;
+            }
         }
 
+        let cached_ids: Vec<&String> = self.valid_sample_ids.iter().filter(|id| !dropped.contains(*id)).collect();
+        let mut dropped_ids: Vec<String> = dropped.into_iter().collect();
+        dropped_ids.sort();
+
         // Build per-class counts from sample IDs (e.g. "Cat/1234" -> class "Cat")
         let mut class_counts: HashMap<String, usize> = HashMap::new();
         for id in &cached_ids {
@@ -529,6 +1186,64 @@ impl DataLoader {
             total_dropped: dropped_ids.len(),
             dropped_sample_ids: dropped_ids,
             class_counts,
+            decoded,
+            reused,
+        })
+    }
+
+    /// Checks every cache entry against its source image and the stream settings.
+    /// `deep` hashes each source instead of trusting unchanged size/mtime.
+    pub fn verify_image_cache(&self, deep: bool) -> Result<CacheVerifyReport, String> {
+        let app_data = self
+            .app_data_dir
+            .as_ref()
+            .ok_or("No AppData directory available to store cache")?;
+
+        let streams = self
+            .image_streams()
+            .map(|stream| {
+                let stream_cache_dir = image_cache::stream_cache_dir(app_data, &self.profile.id, &stream.id);
+                let spec = ImageCacheSpec::for_stream(stream);
+                let states: Vec<(&String, EntryState)> = self
+                    .valid_sample_ids
+                    .par_iter()
+                    .map(|sample_id| {
+                        let state = image_cache::entry_state(
+                            &image_cache::cache_file_path(&stream_cache_dir, sample_id),
+                            &self.image_source(&stream.id, sample_id),
+                            &spec,
+                            None,
+                            deep,
+                        );
+                        (sample_id, state)
+                    })
+                    .collect();
+
+                let mut report = StreamCacheReport {
+                    stream_id: stream.id.clone(),
+                    checked: states.len(),
+                    fresh: 0,
+                    missing: Vec::new(),
+                    stale: Vec::new(),
+                    corrupt: Vec::new(),
+                };
+                for (sample_id, state) in states {
+                    match state {
+                        EntryState::Fresh => report.fresh += 1,
+                        EntryState::Missing => report.missing.push(sample_id.clone()),
+                        EntryState::Stale(reason) => report.stale.push(format!("{}: {}", sample_id, reason)),
+                        EntryState::Corrupt(reason) => report.corrupt.push(format!("{}: {}", sample_id, reason)),
+                    }
+                }
+                report
+            })
+            .collect::<Vec<_>>();
+
+        Ok(CacheVerifyReport {
+            ok: streams
+                .iter()
+                .all(|s| s.missing.is_empty() && s.stale.is_empty() && s.corrupt.is_empty()),
+            streams,
         })
     }
 
@@ -545,7 +1260,7 @@ impl DataLoader {
         Some(locator_val.clone())
     }
 
-    pub fn load_sample(&self, sample_id: &str, device: &TrainDevice) -> Result<SampleData, String> {
+    pub fn load_sample<B: Backend>(&self, sample_id: &str, device: &B::Device) -> Result<SampleData<B>, String> {
         let mut tensors = HashMap::new();
 
         for (idx, stream) in self.profile.streams.iter().enumerate() {
@@ -556,109 +1271,31 @@ impl DataLoader {
                 .cloned()
                 .unwrap_or_default();
 
+            if let Some(masks) = self.companion_masks.get(&stream.id) {
+                tensors.insert(idx, Self::companion_mask::<B>(stream, masks, sample_id, device)?);
+                continue;
+            }
+
+            if self.reads_target_values(stream) {
+                let mut values = self.raw_target(stream, sample_id)?;
+                if let Some(scaler) = self.targets.get(&stream.id).and_then(|spec| spec.scaler.as_ref()) {
+                    scaler.transform(&mut values);
+                }
+                let len = values.len();
+                let tensor = Tensor::<B, 2>::from_data(TensorData::new(values, [1, len]), device);
+                tensors.insert(idx, DynamicTensor::Dim2(tensor));
+                continue;
+            }
+
             match stream.data_type {
                 DataType::Image => {
-                    let mut target_w = 64;
-                    let mut target_h = 64;
-                    let mut grayscale = false;
-
-                    if let Some(prep) = &stream.preprocessing {
-                        if let Some(vision) = &prep.vision {
-                            if vision.resize.len() == 2 {
-                                target_w = vision.resize[0];
-                                target_h = vision.resize[1];
-                            }
-                            grayscale = vision.grayscale;
-                        }
-                    }
-                    let channels = if grayscale { 1 } else { 3 };
-                    let expected_len = (target_w * target_h) as usize * channels;
-
-                    // CHECK CACHE FIRST
-                    let mut cached_pixels = None;
-                    let mut cache_dir_exists = false;
-                    if let Some(app_data) = &self.app_data_dir {
-                        let stream_cache_dir = app_data
-                            .join("datasets_cache")
-                            .join(&self.profile.id)
-                            .join(&stream.id);
-                        cache_dir_exists = stream_cache_dir.exists();
-
-                        let cache_path =
-                            stream_cache_dir.join(format!("{}.bin", sample_id.replace("/", "_")));
-
-                        if let Ok(bytes) = std::fs::read(&cache_path) {
-                            let floats: &[f32] = bytemuck::cast_slice(&bytes);
-                            if floats.len() == expected_len {
-                                cached_pixels = Some(floats.to_vec());
-                            }
-                        }
-                    }
-
-                    let pixels = if let Some(p) = cached_pixels {
-                        p
-                    } else if cache_dir_exists {
-                        // Cache was built but this sample has no .bin -> it was corrupt
-                        return Err(format!(
-                            "Sample '{}' was not cached (corrupt/unreadable during cache build)",
-                            sample_id
-                        ));
-                    } else {
-                        let sanitized_path = locator_val.trim_matches(|c| c == '"' || c == '\'');
-                        let img_path = PathBuf::from(sanitized_path);
-
-                        match image::open(&img_path) {
-                            Ok(img) => {
-                                let img = img.resize_exact(
-                                    target_w,
-                                    target_h,
-                                    image::imageops::FilterType::Triangle,
-                                );
-                                let mut raw_pixels = Vec::with_capacity(expected_len);
-
-                                if grayscale {
-                                    let img_gray = img.to_luma8();
-                                    for y in 0..target_h {
-                                        for x in 0..target_w {
-                                            raw_pixels
-                                                .push(img_gray.get_pixel(x, y)[0] as f32 / 255.0);
-                                        }
-                                    }
-                                } else {
-                                    let img_rgb = img.to_rgb8();
-                                    for c in 0..3 {
-                                        for y in 0..target_h {
-                                            for x in 0..target_w {
-                                                raw_pixels.push(
-                                                    img_rgb.get_pixel(x, y)[c] as f32 / 255.0,
-                                                );
-                                            }
-                                        }
-                                    }
-                                }
-                                raw_pixels
-                            }
-                            Err(e) => {
-                                return Err(format!(
-                                    "Failed to load image for stream {} at path '{}': {:?}",
-                                    stream.id,
-                                    img_path.display(),
-                                    e
-                                ));
-                            }
-                        }
-                    };
+                    let spec = ImageCacheSpec::for_stream(stream);
+                    let mut pixels = self.image_pixels(stream, &spec, sample_id, &locator_val)?;
+                    self.vision_normalizer(stream)?.apply(&mut pixels);
 
-                    let tensor_4d = Tensor::<Backend, 4>::from_data(
-                        TensorData::new(
-                            pixels,
-                            [
-                                1,
-                                channels as usize,
-                                target_h as usize,
-                                target_w as usize,
-                            ],
-                        ),
+                    let [channels, height, width] = spec.shape();
+                    let tensor_4d = Tensor::<B, 4>::from_data(
+                        TensorData::new(pixels, [1, channels, height, width]),
                         device,
                     );
                     tensors.insert(idx, DynamicTensor::Dim4(tensor_4d));
@@ -673,14 +1310,22 @@ impl DataLoader {
                             return Err(format!("No CSV loader found for stream {}", stream.id));
                         }
                     } else {
-                        let vals: Vec<f32> = locator_val
-                            .split(',')
-                            .filter_map(|s| s.trim().parse::<f32>().ok())
-                            .collect();
+                        let tensor_row = self.tensor_row(&stream.id, &locator_val);
+                        let vals: Vec<f32> = match (self.tabular.get(&stream.id), tensor_row) {
+                            (Some(stats), Some(row)) => {
+                                stats.transform(&row.iter().map(f32::to_string).collect::<Vec<_>>())
+                            }
+                            (None, Some(row)) => row.to_vec(),
+                            (Some(stats), None) => stats.transform(&vector_cells(&locator_val)),
+                            (None, None) => locator_val
+                                .split(',')
+                                .filter_map(|s| s.trim().parse::<f32>().ok())
+                                .collect(),
+                        };
 
                         if !vals.is_empty() {
                             let vals_len = vals.len();
-                            let tensor_2d = Tensor::<Backend, 2>::from_data(
+                            let tensor_2d = Tensor::<B, 2>::from_data(
                                 TensorData::new(vals, [1, vals_len]),
                                 device,
                             );
@@ -697,12 +1342,12 @@ impl DataLoader {
                     if locator_val.starts_with("csv:") {
                         let sample_idx = locator_val[4..].parse::<usize>().unwrap_or(0);
                         if let Some(csv_loader) = self.csv_loaders.get(&stream.id) {
-                            match csv_loader.load_sample(sample_idx, device) {
+                            match csv_loader.load_sample::<B>(sample_idx, device) {
                                 Ok((_, label_str)) => {
                                     let class_idx = csv_loader.discovered_classes.iter()
                                         .position(|c| c == &label_str)
                                         .unwrap_or(0) as f32;
-                                    let tensor = Tensor::<Backend, 2>::from_data([[class_idx]], device);
+                                    let tensor = Tensor::<B, 2>::from_data([[class_idx]], device);
                                     tensors.insert(idx, DynamicTensor::Dim2(tensor));
                                 }
                                 Err(e) => return Err(format!("Failed to load CSV label {}: {}", sample_idx, e)),
@@ -713,7 +1358,7 @@ impl DataLoader {
                     } else {
                         match locator_val.parse::<f32>() {
                             Ok(val) => {
-                                let tensor = Tensor::<Backend, 2>::from_data([[val]], device);
+                                let tensor = Tensor::<B, 2>::from_data([[val]], device);
                                 tensors.insert(idx, DynamicTensor::Dim2(tensor));
                             }
                             Err(e) => {
@@ -748,6 +1393,15 @@ impl DataLoader {
                         } else {
                             return Err(format!("No CSV loader found for stream {}", stream.id));
                         }
+                    } else if let Some(row) = self.tensor_row(&stream.id, &locator_val) {
+                        // [T, C] samples; 1-D samples are a single-channel sequence
+                        let shape = &self.tensor_tables[&stream.id].sample_shape;
+                        let (steps, channels) = match shape[..] {
+                            [steps, channels] => (steps, channels),
+                            _ => (row.len(), 1),
+                        };
+                        let tensor = Tensor::<B, 3>::from_data(TensorData::new(row.to_vec(), [1, steps, channels]), device);
+                        tensors.insert(idx, DynamicTensor::Dim3(tensor));
                     } else {
                         return Err(format!("Invalid temporal sequence locator format: {}", locator_val));
                     }
@@ -760,6 +1414,42 @@ impl DataLoader {
             stream_tensors: tensors,
         })
     }
+
+    /// Rasterizes a sample's companion annotations into a `[1, C, H, W]` mask. `C` comes
+    /// from the stream's tensor shape (one channel per class when > 1), `H`/`W` from
+    /// the vision resize setting or the tensor shape.
+    fn companion_mask<B: Backend>(
+        stream: &DataStream,
+        masks: &CompanionMasks,
+        sample_id: &str,
+        device: &B::Device,
+    ) -> Result<DynamicTensor<B>, String> {
+        let annotation = masks
+            .annotations
+            .by_sample
+            .get(sample_id)
+            .ok_or_else(|| format!("No companion annotation for sample '{}' in stream {}", sample_id, stream.id))?;
+
+        let (mut channels, mut height, mut width) = (1, 64, 64);
+        if let [c, h, w] = crate::normalize_image_shape_to_internal_chw(&stream.tensor_shape)[..] {
+            (channels, height, width) = (c, h, w);
+        }
+        if let Some(vision) = stream.preprocessing.as_ref().and_then(|p| p.vision.as_ref())
+            && vision.resize.len() == 2
+        {
+            width = vision.resize[0] as usize;
+            height = vision.resize[1] as usize;
+        }
+
+        let mask = annotation.rasterize_mask(width, height, channels, &masks.classes);
+        let tensor = Tensor::<B, 4>::from_data(TensorData::new(mask, [1, channels, height, width]), device);
+        Ok(DynamicTensor::Dim4(tensor))
+    }
+}
+
+/// Cells of a comma-joined Vector locator value (MasterIndex columns).
+fn vector_cells(value: &str) -> Vec<String> {
+    value.split(',').map(|cell| cell.trim().to_string()).collect()
 }
 
 // Helpers reused from scan_dataset
@@ -823,6 +1513,7 @@ mod tests {
                         pattern: "*.csv".to_string(),
                     },
                     preprocessing: None,
+                    task_type: None,
                 },
                 crate::dtos::DataStream {
                     id: "target_stream".to_string(),
@@ -835,8 +1526,11 @@ mod tests {
                         pattern: "*.csv".to_string(),
                     },
                     preprocessing: None,
+                    task_type: None,
                 },
             ],
+            split: None,
+            sampling: None,
         }
     }
 
@@ -900,6 +1594,8 @@ mod tests {
             total_dropped: 2,
             dropped_sample_ids: vec!["corrupt1".to_string()],
             class_counts,
+            decoded: 12,
+            reused: 3,
         };
         
         // Should be serializable to JSON via serde
@@ -916,7 +1612,7 @@ mod tests {
     fn test_sample_data_construction() {
         let tensors = HashMap::new();
         
-        let sample = SampleData {
+        let sample: SampleData<crate::backend::CpuBackend> = SampleData {
             id: "sample_001".to_string(),
             stream_tensors: tensors,
         };
@@ -974,6 +1670,300 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_companion_yolo_labels_become_targets() {
+        let root = std::env::temp_dir().join(format!("test_companion_yolo-{}", uuid::Uuid::new_v4()));
+        let images = root.join("images");
+        fs::create_dir_all(&images).expect("create images dir");
+        fs::create_dir_all(root.join("labels")).expect("create labels dir");
+        for (id, label) in [("a", "1 0.5 0.5 0.5 0.5\n"), ("b", "0 0.1 0.1 0.1 0.1\n0 0.3 0.3 0.1 0.1\n")] {
+            fs::write(images.join(format!("{}.jpg", id)), "not an image").expect("write image");
+            fs::write(root.join("labels").join(format!("{}.txt", id)), label).expect("write label");
+        }
+        // Image without a label file is dropped from the dataset
+        fs::write(images.join("c.jpg"), "not an image").expect("write image");
+
+        let companion = |id: &str, data_type: crate::dtos::DataType, tensor_shape: Vec<usize>| crate::dtos::DataStream {
+            id: id.to_string(),
+            alias: id.to_string(),
+            role: "Target".to_string(),
+            data_type,
+            tensor_shape,
+            num_classes: None,
+            locator: DataLocatorDef::CompanionFile {
+                path_template: "../labels/{id}.txt".to_string(),
+                parser: "YOLO".to_string(),
+            },
+            preprocessing: None,
+            task_type: None,
+        };
+        let mut profile = make_test_profile("companion".to_string(), "Companion".to_string(), images.to_string_lossy().to_string());
+        profile.streams = vec![
+            companion("class", crate::dtos::DataType::Categorical, vec![1]),
+            companion("mask", crate::dtos::DataType::Image, vec![4, 4, 1]),
+            crate::dtos::DataStream {
+                id: "image".to_string(),
+                alias: "Image".to_string(),
+                role: "Input".to_string(),
+                data_type: crate::dtos::DataType::Image,
+                tensor_shape: vec![8, 8, 3],
+                num_classes: None,
+                locator: DataLocatorDef::GlobPattern {
+                    pattern: "*.jpg".to_string(),
+                },
+                preprocessing: None,
+                task_type: None,
+            },
+        ];
+
+        let loader = DataLoader::new(profile, None).expect("loader builds");
+        let ids = loader.valid_sample_ids.clone();
+        assert_eq!(ids, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(loader.stream_classes.get(&0), Some(&2));
+        assert_eq!(loader.stream_files["class"]["a"], "1");
+        assert_eq!(loader.stream_files["class"]["b"], "0");
+
+        let masks = &loader.companion_masks["mask"];
+        let stream = &loader.profile.streams[1];
+        let device = Default::default();
+        let DynamicTensor::Dim4(mask) =
+            DataLoader::companion_mask::<crate::backend::CpuBackend>(stream, masks, "a", &device).expect("mask")
+        else {
+            panic!("expected a 4D mask");
+        };
+        assert_eq!(mask.dims(), [1, 1, 4, 4]);
+        let values = mask.into_data().to_vec::<f32>().expect("f32 mask");
+        assert_eq!(values.iter().filter(|v| **v == 1.0).count(), 4);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_image_cache_rebuild_is_incremental() {
+        let root = std::env::temp_dir().join(format!("test_image_cache-{}", uuid::Uuid::new_v4()));
+        let images = root.join("images");
+        let app_data = root.join("app_data");
+        fs::create_dir_all(&images).expect("create images dir");
+        for (id, shade) in [("a", 10u8), ("b", 120), ("c", 240)] {
+            image::RgbImage::from_pixel(6, 6, image::Rgb([shade, shade, 0]))
+                .save(images.join(format!("{}.png", id)))
+                .expect("write png");
+        }
+        fs::write(images.join("broken.png"), "not an image").expect("write broken image");
+
+        let mut profile = make_test_profile("image_cache".to_string(), "Images".to_string(), images.to_string_lossy().to_string());
+        profile.streams = vec![crate::dtos::DataStream {
+            id: "image".to_string(),
+            alias: "Image".to_string(),
+            role: "Input".to_string(),
+            data_type: crate::dtos::DataType::Image,
+            tensor_shape: vec![4, 4, 3],
+            num_classes: None,
+            locator: DataLocatorDef::GlobPattern {
+                pattern: "*.png".to_string(),
+            },
+            preprocessing: None,
+            task_type: None,
+        }];
+
+        let options = ImageCacheOptions {
+            storage: image_cache::CacheDtype::U8,
+            rebuild: false,
+            train_ids: None,
+        };
+        let progress = std::sync::Mutex::new(Vec::new());
+        let on_progress = |p: &CacheBuildProgress| progress.lock().unwrap().push(p.processed);
+
+        let loader = DataLoader::new(profile.clone(), Some(app_data.clone())).expect("loader builds");
+        let first = loader.build_image_cache(&options, &on_progress).expect("first build");
+        assert_eq!((first.total_cached, first.decoded, first.reused), (3, 3, 0));
+        assert_eq!(first.dropped_sample_ids, vec!["broken".to_string()]);
+        assert_eq!(progress.lock().unwrap().iter().max(), Some(&4));
+
+        // A rebuilt loader drops the undecodable sample and only re-decodes the changed image
+        image::RgbImage::from_pixel(8, 8, image::Rgb([0, 0, 255]))
+            .save(images.join("b.png"))
+            .expect("rewrite png");
+        let loader = DataLoader::new(profile.clone(), Some(app_data.clone())).expect("loader rebuilds");
+        assert_eq!(loader.valid_sample_ids.len(), 3);
+        let second = loader.build_image_cache(&options, &|_| {}).expect("second build");
+        assert_eq!((second.decoded, second.reused), (1, 2));
+        assert!(loader.verify_image_cache(true).expect("verify").ok);
+
+        let device = Default::default();
+        let sample = loader.load_sample::<crate::backend::CpuBackend>("b", &device).expect("cached sample loads");
+        let DynamicTensor::Dim4(tensor) = &sample.stream_tensors[&0] else {
+            panic!("expected a 4D image");
+        };
+        assert_eq!(tensor.dims(), [1, 3, 4, 4]);
+
+        // An image added after the build is not readable until it is cached, and the
+        // cache build picks it up
+        image::RgbImage::from_pixel(6, 6, image::Rgb([60, 60, 60]))
+            .save(images.join("d.png"))
+            .expect("write new png");
+        assert!(!DataLoader::new(profile.clone(), Some(app_data.clone())).unwrap().valid_sample_ids.contains(&"d".to_string()));
+        let builder = DataLoader::with_all_samples(profile.clone(), Some(app_data.clone())).expect("builder loads");
+        let third = builder.build_image_cache(&options, &|_| {}).expect("third build");
+        assert_eq!((third.total_cached, third.decoded, third.reused), (4, 1, 3));
+        let loader = DataLoader::new(profile, Some(app_data)).expect("loader reloads");
+        assert_eq!(loader.valid_sample_ids, vec!["a", "b", "c", "d"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_tabular_stats_are_fitted_on_train_ids() {
+        let root = std::env::temp_dir().join(format!("test_tabular-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create root");
+        fs::write(
+            root.join("data.csv"),
+            "x,color,label\n1,red,a\n3,blue,b\nNA,red,a\n100,green,b\n,blue,a\n",
+        )
+        .expect("write csv");
+
+        let csv_stream = |id: &str, role: &str, features: Vec<String>, target: &str, handle_missing: &str| {
+            crate::dtos::DataStream {
+                id: id.to_string(),
+                alias: id.to_string(),
+                role: role.to_string(),
+                data_type: if role == "Input" {
+                    crate::dtos::DataType::Vector
+                } else {
+                    crate::dtos::DataType::Categorical
+                },
+                tensor_shape: vec![2],
+                num_classes: None,
+                locator: DataLocatorDef::CsvDataset(crate::dtos::CsvDatasetDef {
+                    csv_path: "data.csv".to_string(),
+                    has_headers: true,
+                    sample_mode: "row".to_string(),
+                    feature_columns: features,
+                    target_column: target.to_string(),
+                    window_size: None,
+                    window_stride: None,
+                    horizon: None,
+                    preprocessing: crate::dtos::CsvPreprocessingConfig {
+                        normalization: "none".to_string(),
+                        handle_missing: handle_missing.to_string(),
+                    },
+                }),
+                preprocessing: None,
+                task_type: None,
+            }
+        };
+        let mut profile = make_test_profile("tabular".to_string(), "Tabular".to_string(), root.to_string_lossy().to_string());
+        let mut features = csv_stream("features", "Input", vec!["x".to_string(), "color".to_string()], "", "skip");
+        profile.streams = vec![features.clone(), csv_stream("label", "Target", vec![], "label", "skip")];
+
+        // "skip" drops the rows with a missing x
+        let loader = DataLoader::new(profile.clone(), None).expect("loader builds");
+        assert_eq!(loader.valid_sample_ids, vec!["0", "1", "3"]);
+
+        features.preprocessing = Some(crate::dtos::PreprocessingSettings {
+            vision: None,
+            tabular: Some(crate::dtos::TabularSettings {
+                normalization: "min-max".to_string(),
+                one_hot: true,
+                fill_missing: "mean".to_string(),
+            }),
+        });
+        profile.streams[0] = features;
+        let mut loader = DataLoader::new(profile, None).expect("loader builds");
+        assert_eq!(loader.valid_sample_ids.len(), 5);
+
+        // Row 3 (x = 100, "green") is held out: it must not move min/max or add a category
+        let train_ids: Vec<String> = ["0", "1", "2", "4"].iter().map(|s| s.to_string()).collect();
+        loader.fit_tabular(&train_ids).expect("fit");
+        assert_eq!(loader.tabular_width("features"), Some(3));
+        let stats = &loader.tabular_stats()["features"];
+        assert_eq!(stats.missing_report(), vec![("x", 2)]);
+
+        let device = Default::default();
+        let features_of = |id: &str| {
+            let sample = loader.load_sample::<crate::backend::CpuBackend>(id, &device).expect("sample loads");
+            let DynamicTensor::Dim2(tensor) = &sample.stream_tensors[&0] else {
+                panic!("expected a 2D vector");
+            };
+            tensor.clone().into_data().to_vec::<f32>().expect("f32 features")
+        };
+        assert_eq!(features_of("1"), vec![1.0, 0.0, 1.0]);
+        assert_eq!(features_of("2"), vec![0.5, 1.0, 0.0]);
+        assert_eq!(features_of("3"), vec![49.5, 0.0, 0.0]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_forecast_targets_are_standardized_on_train_ids() {
+        let root = std::env::temp_dir().join(format!("test_forecast-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create root");
+        let rows: String = (0..10).map(|i| format!("{},{}\n", i, i * 10)).collect();
+        fs::write(root.join("series.csv"), format!("x,y\n{}", rows)).expect("write csv");
+
+        let csv_def = |sample_mode: &str, features: Vec<String>, target: &str, horizon: Option<usize>| {
+            DataLocatorDef::CsvDataset(crate::dtos::CsvDatasetDef {
+                csv_path: "series.csv".to_string(),
+                has_headers: true,
+                sample_mode: sample_mode.to_string(),
+                feature_columns: features,
+                target_column: target.to_string(),
+                window_size: (sample_mode == "temporal_window").then_some(3),
+                window_stride: None,
+                horizon,
+                preprocessing: crate::dtos::CsvPreprocessingConfig {
+                    normalization: "none".to_string(),
+                    handle_missing: "skip".to_string(),
+                },
+            })
+        };
+        let mut profile = make_test_profile("forecast".to_string(), "Forecast".to_string(), root.to_string_lossy().to_string());
+        profile.streams = vec![
+            crate::dtos::DataStream {
+                id: "window".to_string(),
+                alias: "Window".to_string(),
+                role: "Input".to_string(),
+                data_type: crate::dtos::DataType::TemporalSequence,
+                tensor_shape: vec![3, 1],
+                num_classes: None,
+                locator: csv_def("temporal_window", vec!["x".to_string()], "", None),
+                preprocessing: None,
+                task_type: None,
+            },
+            crate::dtos::DataStream {
+                id: "next".to_string(),
+                alias: "Next".to_string(),
+                role: "Target".to_string(),
+                data_type: crate::dtos::DataType::Vector,
+                tensor_shape: vec![2],
+                num_classes: None,
+                locator: csv_def("row", vec![], "y", Some(2)),
+                preprocessing: None,
+                task_type: Some("multi_output".to_string()),
+            },
+        ];
+
+        // 8 windows of 3 rows, but only the first 6 have two rows left to forecast
+        let mut loader = DataLoader::new(profile.clone(), None).expect("loader builds");
+        assert_eq!(loader.valid_sample_ids.len(), 6);
+        assert_eq!(loader.raw_target(&profile.streams[1], "0").expect("target"), vec![30.0, 40.0]);
+
+        loader.fit_targets(&["0".to_string(), "1".to_string()]).expect("fit");
+        let spec = loader.target_spec("next").expect("fitted spec");
+        assert_eq!((spec.task, spec.width), (TargetTask::MultiOutput, 2));
+        assert_eq!(spec.scaler.as_ref().map(|s| s.mean.clone()), Some(vec![35.0, 45.0]));
+
+        let device = Default::default();
+        let sample = loader.load_sample::<crate::backend::CpuBackend>("5", &device).expect("sample loads");
+        let DynamicTensor::Dim2(target) = &sample.stream_tensors[&1] else {
+            panic!("expected a 2D target");
+        };
+        // Rows 8 and 9 (y = 80, 90) in standard deviations of the training targets
+        assert_eq!(target.clone().into_data().to_vec::<f32>().expect("f32 target"), vec![9.0, 9.0]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn test_vector_parsing() {
         // Test that vector CSV values are properly parsed
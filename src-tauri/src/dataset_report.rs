@@ -0,0 +1,632 @@
+/// Dataset Health Report
+///
+/// Where `validate_dataset_profile` checks the profile, the report looks at the data
+/// itself. Over every sample of a train/val/test split it collects:
+/// - per-class counts of each split (first classification target)
+/// - width/height and aspect-ratio histograms of source images, before resize
+/// - image files that cannot be read or decoded
+/// - duplicate samples, by a content hash over every Input stream
+/// - per-column statistics of CSV files (missing %, min/max/mean, constant columns)
+/// - label-noise hints: identical samples with different labels, labels that differ
+///   only in case or spacing, and classes too small to be anything but a typo
+///
+/// Reports are cached in `datasets_cache/<profile>/dataset_report.json` and reused
+/// while the profile, the split percentages and the files behind the samples are
+/// unchanged.
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data_loader::DataLoader;
+use crate::dtos::{ClassSplitCounts, DataLocatorDef, DataType, DatasetProfile};
+use crate::eval_cache::{StableHasher, hash_file_metadata};
+use crate::evaluation::DatasetSplit;
+use crate::image_cache;
+
+const REPORT_FILE: &str = "dataset_report.json";
+/// Longest lists kept in a report; the totals next to them are always complete.
+const MAX_LISTED: usize = 100;
+/// Distinct values counted per CSV column before counting stops.
+const MAX_DISTINCT: usize = 1000;
+/// A class this small is flagged when the largest class is `RARE_CLASS_RATIO` times bigger.
+const RARE_CLASS_SAMPLES: usize = 3;
+const RARE_CLASS_RATIO: usize = 20;
+/// Longer image side, in pixels.
+const SIZE_EDGES: [f32; 6] = [64.0, 128.0, 256.0, 512.0, 1024.0, 2048.0];
+/// Width / height.
+const ASPECT_EDGES: [f32; 6] = [0.5, 0.75, 0.9, 1.1, 1.33, 2.0];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetReport {
+    pub profile_id: String,
+    /// Digest of the profile, split percentages and data files the report was built from.
+    pub key: String,
+    pub generated_at_ms: u64,
+    pub total_samples: usize,
+    pub train_samples: usize,
+    pub val_samples: usize,
+    pub test_samples: usize,
+    pub class_counts: Vec<ClassSplitCounts>,
+    pub images: Vec<ImageStreamStats>,
+    pub unreadable_files: usize,
+    pub unreadable: Vec<UnreadableFile>,
+    /// Samples that repeat an earlier sample's content.
+    pub duplicate_samples: usize,
+    pub duplicates: Vec<DuplicateGroup>,
+    pub columns: Vec<ColumnStats>,
+    pub label_hints: Vec<LabelNoiseHint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBin {
+    pub label: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageStreamStats {
+    pub stream_id: String,
+    pub decoded: usize,
+    pub min_width: u32,
+    pub max_width: u32,
+    pub min_height: u32,
+    pub max_height: u32,
+    /// By the longer side.
+    pub size_histogram: Vec<HistogramBin>,
+    /// By width / height.
+    pub aspect_histogram: Vec<HistogramBin>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadableFile {
+    pub stream_id: String,
+    pub sample_id: String,
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub sample_ids: Vec<String>,
+    /// Splits the copies ended up in; more than one means test data leaks into training.
+    pub splits: Vec<String>,
+    /// Distinct class labels of the copies.
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnStats {
+    pub file: String,
+    pub column: String,
+    pub rows: usize,
+    pub missing_pct: f32,
+    /// Every present value parses as a number.
+    pub numeric: bool,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub mean: Option<f32>,
+    /// Distinct present values, counted up to 1000.
+    pub distinct: usize,
+    pub constant: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelNoiseHint {
+    pub kind: String, // "conflicting_duplicates" | "similar_labels" | "rare_class"
+    pub message: String,
+    pub sample_ids: Vec<String>,
+}
+
+pub fn report_path(app_data: &Path, profile_id: &str) -> PathBuf {
+    app_data.join("datasets_cache").join(profile_id).join(REPORT_FILE)
+}
+
+/// The cached report, if it was built from the data `key` describes.
+pub fn load_cached(path: &Path, key: &str) -> Option<DatasetReport> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str::<DatasetReport>(&raw).ok().filter(|report| report.key == key)
+}
+
+pub fn persist(path: &Path, report: &DatasetReport) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn locator_path(root: &Path, locator_val: &str) -> PathBuf {
+    let path = image_cache::source_path(locator_val);
+    if path.is_absolute() { path } else { root.join(path) }
+}
+
+/// Digest of the profile, the split percentages, the table files and every located
+/// sample with the size/mtime of the file behind it.
+pub fn report_key(profile: &DatasetProfile, loader: &DataLoader, splits: (usize, usize, usize)) -> String {
+    let mut hasher = StableHasher::default();
+    let root = PathBuf::from(profile.source_path.clone().unwrap_or_default());
+    hasher
+        .field(format!("{:?}", profile))
+        .number(splits.0 as u64)
+        .number(splits.1 as u64)
+        .number(splits.2 as u64);
+
+    for stream in &profile.streams {
+        match &stream.locator {
+            DataLocatorDef::CsvDataset(def) => hash_file_metadata(&mut hasher, &root.join(&def.csv_path)),
+            DataLocatorDef::MasterIndex { index_path, .. } => hash_file_metadata(&mut hasher, &root.join(index_path)),
+            DataLocatorDef::NumpyArray { path, .. } | DataLocatorDef::ParquetTable { path, .. } => {
+                hash_file_metadata(&mut hasher, &root.join(path))
+            }
+            _ => {}
+        }
+    }
+
+    let mut ids = loader.valid_sample_ids.clone();
+    ids.sort();
+    let mut stream_ids: Vec<&String> = loader.stream_files.keys().collect();
+    stream_ids.sort();
+    for stream_id in stream_ids {
+        let files = &loader.stream_files[stream_id];
+        hasher.field(stream_id);
+        for id in &ids {
+            let Some(locator) = files.get(id) else {
+                continue;
+            };
+            hasher.field(id).field(locator);
+            hash_file_metadata(&mut hasher, &locator_path(&root, locator));
+        }
+    }
+    hasher.finish_hex()
+}
+
+/// Builds the report over every sample of `split`.
+pub fn build_report(
+    profile: &DatasetProfile,
+    loader: &DataLoader,
+    split: &DatasetSplit,
+    key: String,
+) -> Result<DatasetReport, String> {
+    let root = PathBuf::from(profile.source_path.clone().unwrap_or_default());
+    let mut split_of: HashMap<&str, &'static str> = HashMap::new();
+    for (name, ids) in [("train", &split.train_ids), ("val", &split.val_ids), ("test", &split.test_ids)] {
+        for id in ids {
+            split_of.insert(id.as_str(), name);
+        }
+    }
+    let mut ids: Vec<&String> = split.train_ids.iter().chain(&split.val_ids).chain(&split.test_ids).collect();
+    ids.sort();
+
+    // Images: decode every source once for its size, hash and readability
+    let mut images = Vec::new();
+    let mut unreadable = Vec::new();
+    let mut content_hashes: HashMap<(&str, &str), String> = HashMap::new();
+    for stream in &profile.streams {
+        if stream.data_type != DataType::Image || matches!(stream.locator, DataLocatorDef::CompanionFile { .. }) {
+            continue;
+        }
+        let Some(files) = loader.stream_files.get(&stream.id) else {
+            continue;
+        };
+        let inspected: Vec<_> = ids
+            .par_iter()
+            .filter_map(|&id| {
+                let path = locator_path(&root, files.get(id)?);
+                let inspected = inspect_image(&path);
+                Some((id, path, inspected))
+            })
+            .collect();
+
+        let mut sizes = Vec::new();
+        let mut aspects = Vec::new();
+        let mut stats = ImageStreamStats {
+            stream_id: stream.id.clone(),
+            decoded: 0,
+            min_width: u32::MAX,
+            max_width: 0,
+            min_height: u32::MAX,
+            max_height: 0,
+            size_histogram: Vec::new(),
+            aspect_histogram: Vec::new(),
+        };
+        for (id, path, inspected) in inspected {
+            match inspected {
+                Ok((width, height, hash)) => {
+                    stats.decoded += 1;
+                    stats.min_width = stats.min_width.min(width);
+                    stats.max_width = stats.max_width.max(width);
+                    stats.min_height = stats.min_height.min(height);
+                    stats.max_height = stats.max_height.max(height);
+                    sizes.push(width.max(height) as f32);
+                    aspects.push(width as f32 / height.max(1) as f32);
+                    content_hashes.insert((stream.id.as_str(), id.as_str()), hash);
+                }
+                Err(error) => unreadable.push(UnreadableFile {
+                    stream_id: stream.id.clone(),
+                    sample_id: id.clone(),
+                    path: path.display().to_string(),
+                    error,
+                }),
+            }
+        }
+        if stats.decoded == 0 {
+            (stats.min_width, stats.min_height) = (0, 0);
+        }
+        stats.size_histogram = histogram(&sizes, &SIZE_EDGES);
+        stats.aspect_histogram = histogram(&aspects, &ASPECT_EDGES);
+        images.push(stats);
+    }
+    let unreadable_ids: HashSet<String> = unreadable.iter().map(|file| file.sample_id.clone()).collect();
+
+    // Duplicates: one content hash over every Input stream of a sample
+    let inputs: Vec<&crate::dtos::DataStream> = profile.streams.iter().filter(|s| s.role == "Input").collect();
+    let sample_hashes: Vec<(String, &String)> = ids
+        .par_iter()
+        .filter(|id| !unreadable_ids.contains(id.as_str()))
+        .map(|&id| {
+            let mut hasher = StableHasher::default();
+            for stream in &inputs {
+                hasher.field(&stream.id);
+                if let Some(hash) = content_hashes.get(&(stream.id.as_str(), id.as_str())) {
+                    hasher.field(hash);
+                } else if let Some(content) = loader.table_content(&stream.id, id) {
+                    hasher.field(content);
+                } else if let Some(locator) = loader.stream_files.get(&stream.id).and_then(|files| files.get(id)) {
+                    match std::fs::read(locator_path(&root, locator)) {
+                        Ok(bytes) => hasher.field(bytes),
+                        Err(_) => hasher.field(locator),
+                    };
+                }
+            }
+            (hasher.finish_hex(), id)
+        })
+        .collect();
+    let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+    for (hash, id) in sample_hashes {
+        by_hash.entry(hash).or_default().push(id.clone());
+    }
+    let mut duplicate_ids: Vec<Vec<String>> = by_hash.into_values().filter(|ids| ids.len() > 1).collect();
+    for group in &mut duplicate_ids {
+        group.sort();
+    }
+    duplicate_ids.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+    let labels: HashMap<String, String> = match crate::evaluation::class_stream(profile, loader) {
+        Some(stream) => ids
+            .iter()
+            .filter_map(|id| Some(((*id).clone(), loader.get_class_label(&stream.id, id)?)))
+            .collect(),
+        None => HashMap::new(),
+    };
+    let duplicates = duplicate_ids
+        .iter()
+        .take(MAX_LISTED)
+        .map(|group| {
+            let splits: BTreeSet<&str> = group.iter().filter_map(|id| split_of.get(id.as_str()).copied()).collect();
+            let group_labels: BTreeSet<&String> = group.iter().filter_map(|id| labels.get(id)).collect();
+            DuplicateGroup {
+                sample_ids: group.clone(),
+                splits: splits.into_iter().map(str::to_string).collect(),
+                labels: group_labels.into_iter().cloned().collect(),
+            }
+        })
+        .collect();
+
+    let unreadable_files = unreadable.len();
+    unreadable.truncate(MAX_LISTED);
+    Ok(DatasetReport {
+        profile_id: profile.id.clone(),
+        key,
+        generated_at_ms: crate::current_unix_ms(),
+        total_samples: ids.len(),
+        train_samples: split.train_ids.len(),
+        val_samples: split.val_ids.len(),
+        test_samples: split.test_ids.len(),
+        class_counts: crate::evaluation::class_split_counts(profile, loader, split)?,
+        images,
+        unreadable_files,
+        unreadable,
+        duplicate_samples: duplicate_ids.iter().map(|group| group.len() - 1).sum(),
+        duplicates,
+        columns: csv_column_stats(profile, loader, &root, &ids)?,
+        label_hints: label_hints(&labels, &duplicate_ids),
+    })
+}
+
+/// Size and content hash of an image file, or why it cannot be used.
+fn inspect_image(path: &Path) -> Result<(u32, u32, String), String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Cannot read: {}", e))?;
+    let image = image::ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .decode()
+        .map_err(|e| format!("Cannot decode: {}", e))?;
+    Ok((image.width(), image.height(), image_cache::hash_bytes(&bytes)))
+}
+
+/// Counts of `values` below the first edge, between consecutive edges and from the
+/// last edge on.
+fn histogram(values: &[f32], edges: &[f32]) -> Vec<HistogramBin> {
+    let mut counts = vec![0; edges.len() + 1];
+    for &value in values {
+        counts[edges.partition_point(|&edge| edge <= value)] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(bin, count)| {
+            let label = if bin == 0 {
+                format!("<{}", edges[0])
+            } else if bin == edges.len() {
+                format!(">={}", edges[bin - 1])
+            } else {
+                format!("{}-{}", edges[bin - 1], edges[bin])
+            };
+            HistogramBin { label, count }
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct ColumnAccumulator {
+    rows: usize,
+    missing: usize,
+    numbers: usize,
+    min: f32,
+    max: f32,
+    sum: f64,
+    distinct: HashSet<String>,
+}
+
+impl ColumnAccumulator {
+    fn add(&mut self, cell: &str) {
+        self.rows += 1;
+        if crate::tabular::is_missing(cell) {
+            self.missing += 1;
+            return;
+        }
+        let cell = cell.trim();
+        if let Ok(value) = cell.parse::<f32>()
+            && value.is_finite()
+        {
+            if self.numbers == 0 {
+                (self.min, self.max) = (value, value);
+            }
+            self.numbers += 1;
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+            self.sum += value as f64;
+        }
+        if self.distinct.len() < MAX_DISTINCT {
+            self.distinct.insert(cell.to_string());
+        }
+    }
+
+    fn finish(self, file: &str, column: String) -> ColumnStats {
+        let present = self.rows - self.missing;
+        let numeric = self.numbers > 0 && self.numbers == present;
+        ColumnStats {
+            file: file.to_string(),
+            column,
+            rows: self.rows,
+            missing_pct: if self.rows > 0 { self.missing as f32 * 100.0 / self.rows as f32 } else { 0.0 },
+            numeric,
+            min: numeric.then_some(self.min),
+            max: numeric.then_some(self.max),
+            mean: numeric.then(|| (self.sum / self.numbers as f64) as f32),
+            distinct: self.distinct.len(),
+            constant: self.distinct.len() <= 1,
+        }
+    }
+}
+
+/// Statistics of every column of every CSV file, over the rows the split's samples
+/// read (features of Input streams, target rows of Target streams). Cells are read
+/// raw, before missing values are filled.
+fn csv_column_stats(
+    profile: &DatasetProfile,
+    loader: &DataLoader,
+    root: &Path,
+    ids: &[&String],
+) -> Result<Vec<ColumnStats>, String> {
+    let mut files: Vec<(&crate::dtos::CsvDatasetDef, BTreeSet<usize>)> = Vec::new();
+    for stream in &profile.streams {
+        let DataLocatorDef::CsvDataset(def) = &stream.locator else {
+            continue;
+        };
+        let (Some(csv_loader), Some(locators)) = (loader.csv_loader(&stream.id), loader.stream_files.get(&stream.id))
+        else {
+            continue;
+        };
+        let idx = match files.iter().position(|(known, _)| known.csv_path == def.csv_path) {
+            Some(idx) => idx,
+            None => {
+                files.push((def, BTreeSet::new()));
+                files.len() - 1
+            }
+        };
+        for id in ids {
+            let Some(sample_idx) = locators
+                .get(*id)
+                .and_then(|locator| locator.strip_prefix("csv:"))
+                .and_then(|idx| idx.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            if stream.role == "Target" {
+                files[idx].1.extend(csv_loader.target_rows(sample_idx));
+            } else {
+                files[idx].1.extend(csv_loader.sample_rows(sample_idx));
+            }
+        }
+    }
+
+    let mut columns = Vec::new();
+    for (def, rows) in files {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(def.has_headers)
+            .flexible(true)
+            .from_path(root.join(&def.csv_path))
+            .map_err(|e| format!("Failed to read CSV {}: {}", def.csv_path, e))?;
+        let headers: Vec<String> = if def.has_headers {
+            reader
+                .headers()
+                .map_err(|e| format!("Failed to read headers of {}: {}", def.csv_path, e))?
+                .iter()
+                .map(str::to_string)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut accumulators: Vec<ColumnAccumulator> = Vec::new();
+        for (row_idx, record) in reader.records().enumerate() {
+            let record = record.map_err(|e| format!("Error reading row {} of {}: {}", row_idx, def.csv_path, e))?;
+            if !rows.contains(&row_idx) {
+                continue;
+            }
+            if accumulators.len() < record.len() {
+                accumulators.resize_with(record.len(), ColumnAccumulator::default);
+            }
+            for (accumulator, cell) in accumulators.iter_mut().zip(record.iter()) {
+                accumulator.add(cell);
+            }
+        }
+        columns.extend(accumulators.into_iter().enumerate().map(|(idx, accumulator)| {
+            let name = headers.get(idx).cloned().unwrap_or_else(|| idx.to_string());
+            accumulator.finish(&def.csv_path, name)
+        }));
+    }
+    Ok(columns)
+}
+
+/// Hints at mislabelled samples: identical samples with different labels, labels that
+/// only differ in case or spacing, and classes that are tiny next to the largest one.
+fn label_hints(labels: &HashMap<String, String>, duplicate_groups: &[Vec<String>]) -> Vec<LabelNoiseHint> {
+    let mut hints = Vec::new();
+    if labels.is_empty() {
+        return hints;
+    }
+
+    for group in duplicate_groups {
+        let distinct: BTreeSet<&String> = group.iter().filter_map(|id| labels.get(id)).collect();
+        if distinct.len() > 1 {
+            hints.push(LabelNoiseHint {
+                kind: "conflicting_duplicates".to_string(),
+                message: format!(
+                    "{} identical samples are labelled {}",
+                    group.len(),
+                    distinct.iter().map(|l| format!("'{}'", l)).collect::<Vec<_>>().join(", ")
+                ),
+                sample_ids: group.clone(),
+            });
+        }
+    }
+
+    let mut members: HashMap<&String, Vec<String>> = HashMap::new();
+    for (id, label) in labels {
+        members.entry(label).or_default().push(id.clone());
+    }
+    let mut normalized: HashMap<String, Vec<&String>> = HashMap::new();
+    for label in members.keys() {
+        let key: String = label
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '_' && *c != '-')
+            .flat_map(char::to_lowercase)
+            .collect();
+        normalized.entry(key).or_default().push(label);
+    }
+    let mut similar: Vec<Vec<&String>> = normalized.into_values().filter(|labels| labels.len() > 1).collect();
+    similar.sort();
+    for mut group in similar {
+        group.sort();
+        hints.push(LabelNoiseHint {
+            kind: "similar_labels".to_string(),
+            message: format!(
+                "Labels {} differ only in case or spacing",
+                group.iter().map(|l| format!("'{}'", l)).collect::<Vec<_>>().join(", ")
+            ),
+            sample_ids: Vec::new(),
+        });
+    }
+
+    let largest = members.values().map(Vec::len).max().unwrap_or(0);
+    let mut rare: Vec<(&String, Vec<String>)> = members
+        .into_iter()
+        .filter(|(_, ids)| ids.len() < RARE_CLASS_SAMPLES && largest >= ids.len() * RARE_CLASS_RATIO)
+        .collect();
+    rare.sort();
+    for (label, mut ids) in rare {
+        ids.sort();
+        hints.push(LabelNoiseHint {
+            kind: "rare_class".to_string(),
+            message: format!(
+                "Class '{}' has only {} sample(s) (largest class: {}); check for a typo or a mislabelled sample",
+                label,
+                ids.len(),
+                largest
+            ),
+            sample_ids: ids,
+        });
+    }
+
+    hints.truncate(MAX_LISTED);
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_bins_are_half_open() {
+        let bins = histogram(&[10.0, 64.0, 127.0, 128.0, 4096.0], &[64.0, 128.0]);
+        let counts: Vec<(&str, usize)> = bins.iter().map(|b| (b.label.as_str(), b.count)).collect();
+        assert_eq!(counts, vec![("<64", 1), ("64-128", 2), (">=128", 2)]);
+    }
+
+    #[test]
+    fn column_stats_count_missing_and_constant_columns() {
+        let mut numbers = ColumnAccumulator::default();
+        for cell in ["1", "NA", "3", ""] {
+            numbers.add(cell);
+        }
+        let stats = numbers.finish("data.csv", "x".to_string());
+        assert_eq!(stats.missing_pct, 50.0);
+        assert!(stats.numeric && !stats.constant);
+        assert_eq!((stats.min, stats.max, stats.mean), (Some(1.0), Some(3.0), Some(2.0)));
+
+        let mut text = ColumnAccumulator::default();
+        for cell in ["a", "a ", "a"] {
+            text.add(cell);
+        }
+        let stats = text.finish("data.csv", "y".to_string());
+        assert!(!stats.numeric && stats.constant);
+        assert_eq!(stats.mean, None);
+    }
+
+    #[test]
+    fn label_hints_flag_conflicts_typos_and_rare_classes() {
+        let mut labels: HashMap<String, String> = (0..40).map(|i| (format!("c{}", i), "Cat".to_string())).collect();
+        labels.insert("d0".to_string(), "dog".to_string());
+        labels.insert("d1".to_string(), "Dog ".to_string());
+        labels.insert("c40".to_string(), "dog".to_string());
+        let duplicates = vec![vec!["c0".to_string(), "c40".to_string()]];
+
+        let hints = label_hints(&labels, &duplicates);
+        let kinds: Vec<&str> = hints.iter().map(|h| h.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["conflicting_duplicates", "similar_labels", "rare_class", "rare_class"]);
+        assert_eq!(hints[0].sample_ids, vec!["c0".to_string(), "c40".to_string()]);
+        assert!(hints[1].message.contains("'Dog '") && hints[1].message.contains("'dog'"));
+    }
+}
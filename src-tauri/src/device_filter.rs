@@ -0,0 +1,322 @@
+/// Pre-Training Device Filter
+///
+/// `validate_genome_for_device` judges genomes by objectives measured after training.
+/// With a `DeviceFilterConfig`, `evaluate_population` checks every genome of a new run
+/// against the device before anything is trained:
+/// - MOPS, flash (f32 weights) and RAM (peak activations) from the static cost analysis
+/// - latency from the device's latency table; devices without one are not checked on
+///   latency until the genome has been trained
+///
+/// Genomes that do not fit finish right away, rejected with the untrained sentinel
+/// result or penalized with the penalty fitness of their violation. With `repair`,
+/// such genomes are first shrunk: every Dense, Conv and recurrent layer not feeding an
+/// Output keeps `REPAIR_FACTOR` of its units/filters per step, until the genome fits,
+/// nothing shrinks any further or a step no longer compiles.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::device_profiles::{self, DeviceResourceConstraints, DeviceValidationResult};
+use crate::dtos::GenomeObjectives;
+use crate::evolution::GenomeGraph;
+use crate::latency_lut::LatencyLut;
+
+/// Share of units/filters a layer keeps per repair step.
+pub const REPAIR_FACTOR: f64 = 0.75;
+/// Repair steps tried before a genome is given up on.
+const MAX_REPAIR_STEPS: usize = 12;
+
+fn default_penalty_alpha() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InfeasibleAction {
+    Reject,
+    #[default]
+    Penalize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceFilterConfig {
+    /// Built-in profile id or device template id
+    pub device: String,
+    /// Constraints in place of the device's own; its latency table is still used
+    #[serde(default)]
+    pub constraints: Option<DeviceResourceConstraints>,
+    #[serde(default)]
+    pub action: InfeasibleAction,
+    #[serde(default = "default_penalty_alpha")]
+    pub penalty_alpha: f32,
+    #[serde(default)]
+    pub repair: bool,
+}
+
+/// Constraints and latency table of the device a run is filtered for.
+#[derive(Debug, Clone)]
+pub struct DeviceTarget {
+    pub name: String,
+    pub constraints: DeviceResourceConstraints,
+    pub latency_lut: Option<LatencyLut>,
+}
+
+impl DeviceTarget {
+    pub fn resolve(config: &DeviceFilterConfig) -> Result<Self, String> {
+        let mut target = match device_profiles::built_in_profiles()
+            .into_iter()
+            .find(|profile| profile.profile_id == config.device)
+        {
+            Some(profile) => DeviceTarget {
+                name: profile.profile_name,
+                constraints: profile.constraints,
+                latency_lut: None,
+            },
+            None => {
+                let template = crate::device_library::get_device_template(&config.device)
+                    .map_err(|_| format!("Unknown device profile or template '{}'", config.device))?;
+                DeviceTarget {
+                    name: template.name,
+                    constraints: template.constraints,
+                    latency_lut: template.latency_lut,
+                }
+            }
+        };
+        if let Some(constraints) = &config.constraints {
+            device_profiles::validate_constraints(constraints)?;
+            target.constraints = constraints.clone();
+        }
+        Ok(target)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceDecision {
+    Train,
+    Rejected,
+    Penalized,
+}
+
+/// Outcome of the pre-training check of one genome.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceCheck {
+    pub device: String,
+    pub validation: DeviceValidationResult,
+    /// Latency the check used, when the device has a latency table
+    pub predicted_latency_ms: Option<f32>,
+    /// Repair steps applied to the genome that is trained
+    pub repair_steps: usize,
+    /// The shrunk genome that replaces the original, after a successful repair
+    pub repaired_genome: Option<String>,
+    pub decision: DeviceDecision,
+    /// Fitness given instead of training, for penalized genomes
+    pub penalty_fitness: Option<f32>,
+}
+
+/// Validation of a genome's static costs on the device.
+fn validate(
+    genome_str: &str,
+    input_overrides: Option<&[Vec<usize>]>,
+    output_overrides: Option<&[Vec<usize>]>,
+    target: &DeviceTarget,
+) -> Result<(DeviceValidationResult, Option<f32>), String> {
+    let cost = crate::cost_analysis::analyze_genome(genome_str, input_overrides, output_overrides)?;
+    let untrained = GenomeObjectives {
+        genome_id: String::new(),
+        accuracy: 0.0,
+        inference_latency_ms: 0.0,
+        model_size_mb: cost.parameter_bytes as f32 / (1024.0 * 1024.0),
+        training_time_ms: 0,
+        is_dominated: false,
+        domination_count: 0,
+        flops: None,
+        parameter_count: None,
+        peak_activation_bytes: None,
+        constraint_violation_score: None,
+        custom_metrics: Default::default(),
+    };
+    let objectives = device_profiles::objectives_on_device(&untrained, &cost, target.latency_lut.as_ref())?;
+    let predicted_latency_ms = target.latency_lut.as_ref().map(|_| objectives.inference_latency_ms);
+    Ok((device_profiles::validate_genome_for_device(&objectives, &target.constraints), predicted_latency_ms))
+}
+
+/// One repair step: scales the units/filters of every shrinkable layer by
+/// `REPAIR_FACTOR`. `None` when no layer can shrink any further.
+pub fn shrink(genome_str: &str) -> Result<Option<String>, String> {
+    let mut graph = GenomeGraph::parse(genome_str)?;
+    let feeds_output = graph.output_feeders();
+
+    let mut changed = false;
+    for (idx, node) in graph.nodes.iter_mut().enumerate() {
+        let key = match node.get("node").and_then(Value::as_str) {
+            Some("Dense") => "units",
+            Some("Conv2D" | "Conv1D") => "filters",
+            Some("LSTM" | "GRU") => "hidden_units",
+            _ => continue,
+        };
+        if feeds_output.contains(&idx) {
+            continue;
+        }
+        let Some(params) = node.get_mut("params").and_then(Value::as_object_mut) else {
+            continue;
+        };
+        let Some(size) = params.get(key).and_then(Value::as_u64) else {
+            continue;
+        };
+        let shrunk = ((size as f64 * REPAIR_FACTOR).floor() as u64).max(1);
+        if shrunk < size {
+            params.insert(key.into(), Value::from(shrunk));
+            changed = true;
+        }
+    }
+    Ok(changed.then(|| graph.to_genome_string()))
+}
+
+/// Checks a genome against the device, repairing it first when configured. Returns the
+/// genome to train (the repaired one, if repair made it fit) and the check.
+pub fn filter_genome(
+    genome_str: &str,
+    input_overrides: Option<&[Vec<usize>]>,
+    output_overrides: Option<&[Vec<usize>]>,
+    target: &DeviceTarget,
+    config: &DeviceFilterConfig,
+) -> Result<(String, DeviceCheck), String> {
+    let (mut validation, mut predicted_latency_ms) = validate(genome_str, input_overrides, output_overrides, target)?;
+    let mut genome = genome_str.to_string();
+    let mut repair_steps = 0;
+
+    if config.repair && !validation.is_feasible {
+        let mut candidate = genome.clone();
+        for step in 1..=MAX_REPAIR_STEPS {
+            let Ok(Some(shrunk)) = shrink(&candidate) else {
+                break;
+            };
+            let Ok((shrunk_validation, shrunk_latency)) = validate(&shrunk, input_overrides, output_overrides, target)
+            else {
+                break;
+            };
+            candidate = shrunk;
+            if shrunk_validation.is_feasible {
+                genome = candidate.clone();
+                (validation, predicted_latency_ms, repair_steps) = (shrunk_validation, shrunk_latency, step);
+                break;
+            }
+        }
+    }
+
+    let (decision, penalty_fitness) = match (validation.is_feasible, config.action) {
+        (true, _) => (DeviceDecision::Train, None),
+        (false, InfeasibleAction::Reject) => (DeviceDecision::Rejected, None),
+        (false, InfeasibleAction::Penalize) => (
+            DeviceDecision::Penalized,
+            Some(device_profiles::apply_device_penalty(0.0, validation.violation_score, config.penalty_alpha)),
+        ),
+    };
+    let repaired_genome = (repair_steps > 0).then(|| genome.clone());
+    Ok((
+        genome,
+        DeviceCheck {
+            device: target.name.clone(),
+            validation,
+            predicted_latency_ms,
+            repair_steps,
+            repaired_genome,
+            decision,
+            penalty_fitness,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn genome(hidden: usize) -> String {
+        [
+            r#"{"node":"Input","params":{"output_shape":[64]}}"#.to_string(),
+            format!(r#"{{"node":"Dense","params":{{"units":{},"activation":"relu","use_bias":true}}}}"#, hidden),
+            r#"{"node":"Dense","params":{"units":10,"activation":"linear","use_bias":true}}"#.to_string(),
+            r#"{"node":"Output","params":{"input_shape":[10]}}"#.to_string(),
+            "CONNECTIONS".to_string(),
+            "0 1".to_string(),
+            "1 2".to_string(),
+            "2 3".to_string(),
+        ]
+        .join("\n")
+    }
+
+    fn target(flash_budget_mb: f32) -> DeviceTarget {
+        DeviceTarget {
+            name: "Tiny".to_string(),
+            constraints: DeviceResourceConstraints {
+                mops_budget: 100.0,
+                ram_budget_mb: 1.0,
+                flash_budget_mb,
+                max_latency_ms: 10.0,
+            },
+            latency_lut: None,
+        }
+    }
+
+    fn check(genome_str: &str, action: InfeasibleAction, repair: bool) -> (String, DeviceCheck) {
+        let config = DeviceFilterConfig {
+            device: "tiny".to_string(),
+            constraints: None,
+            action,
+            penalty_alpha: 1.0,
+            repair,
+        };
+        // 100 KB of flash
+        filter_genome(genome_str, None, None, &target(100.0 / 1024.0), &config).unwrap()
+    }
+
+    #[test]
+    fn shrink_scales_hidden_layers_and_keeps_the_output_layer() {
+        let shrunk = shrink(&genome(64)).unwrap().unwrap();
+        let graph = GenomeGraph::parse(&shrunk).unwrap();
+        assert_eq!(graph.nodes[1]["params"]["units"], 48);
+        assert_eq!(graph.nodes[2]["params"]["units"], 10);
+
+        assert_eq!(shrink(&genome(1)).unwrap(), None);
+    }
+
+    #[test]
+    fn repair_keeps_the_output_layer_behind_dropout() {
+        let chained = genome(512)
+            .replace("CONNECTIONS", "{\"node\":\"Dropout\",\"params\":{\"prob\":0.2}}\nCONNECTIONS")
+            .replace("2 3", "2 4\n4 3");
+        let shrunk = GenomeGraph::parse(&shrink(&chained).unwrap().unwrap()).unwrap();
+        assert_eq!(shrunk.nodes[2]["params"]["units"], 10);
+
+        let (repaired, fitted) = check(&chained, InfeasibleAction::Reject, true);
+        assert_eq!((fitted.decision, fitted.repair_steps), (DeviceDecision::Train, 2));
+        let cost = crate::cost_analysis::analyze_genome(&repaired, None, None).unwrap();
+        let output = cost.layers.iter().find(|layer| layer.op == "Output").unwrap();
+        assert_eq!(output.output_shape, vec![10]);
+    }
+
+    #[test]
+    fn infeasible_genomes_are_rejected_penalized_or_repaired() {
+        // 64*512 + 512 + 512*10 + 10 weights, ~150 KB
+        let big = genome(512);
+
+        let (kept, rejected) = check(&big, InfeasibleAction::Reject, false);
+        assert_eq!(kept, big);
+        assert_eq!(rejected.decision, DeviceDecision::Rejected);
+        assert!(rejected.validation.flash_ratio > 1.0);
+
+        let (_, penalized) = check(&big, InfeasibleAction::Penalize, false);
+        assert_eq!(penalized.decision, DeviceDecision::Penalized);
+        assert_eq!(penalized.penalty_fitness, Some(-penalized.validation.violation_score));
+
+        // 512 -> 384 -> 288 units, 21610 weights
+        let (repaired, fitted) = check(&big, InfeasibleAction::Reject, true);
+        assert_eq!((fitted.decision, fitted.repair_steps), (DeviceDecision::Train, 2));
+        assert!(fitted.validation.is_feasible);
+        assert_eq!(GenomeGraph::parse(&repaired).unwrap().nodes[1]["params"]["units"], 288);
+        assert_eq!(fitted.repaired_genome.as_deref(), Some(repaired.as_str()));
+
+        let (_, fits) = check(&genome(16), InfeasibleAction::Reject, false);
+        assert_eq!((fits.decision, fits.repair_steps), (DeviceDecision::Train, 0));
+    }
+}
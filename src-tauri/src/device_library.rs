@@ -2,6 +2,7 @@ use crate::device_profiles;
 use crate::dtos::{
     CreateDeviceTemplateInput, DeviceLibraryImportMode, DeviceTemplateDto, UpdateDeviceTemplatePatch,
 };
+use crate::latency_lut::LatencyLut;
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -121,6 +122,9 @@ fn normalize_import_template(mut template: DeviceTemplateDto) -> Result<DeviceTe
     template.name = normalize_name(&template.name);
     validate_template_name(&template.name)?;
     device_profiles::validate_constraints(&template.constraints)?;
+    if let Some(lut) = &template.latency_lut {
+        lut.validate()?;
+    }
     template.notes = normalize_notes(template.notes);
     template.tags = normalize_tags(template.tags);
 
@@ -178,6 +182,7 @@ pub fn create_device_template(input: CreateDeviceTemplateInput) -> Result<Device
         tags: normalize_tags(input.tags),
         created_at_unix_ms: now,
         updated_at_unix_ms: now,
+        latency_lut: None,
     };
 
     entries.push(created.clone());
@@ -255,6 +260,7 @@ pub fn duplicate_device_template(id: String, new_name: String) -> Result<DeviceT
         tags: source.tags,
         created_at_unix_ms: now,
         updated_at_unix_ms: now,
+        latency_lut: source.latency_lut,
     };
 
     entries.push(cloned.clone());
@@ -263,6 +269,59 @@ pub fn duplicate_device_template(id: String, new_name: String) -> Result<DeviceT
     Ok(cloned)
 }
 
+pub fn get_device_template(id: &str) -> Result<DeviceTemplateDto, String> {
+    read_device_templates()?
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("Template '{}' not found", id))
+}
+
+fn set_latency_lut(id: &str, lut: LatencyLut) -> Result<DeviceTemplateDto, String> {
+    lut.validate()?;
+    let mut entries = read_device_templates()?;
+    let template = entries
+        .iter_mut()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("Template '{}' not found", id))?;
+    template.latency_lut = Some(lut);
+    template.updated_at_unix_ms = current_unix_ms();
+    let updated = template.clone();
+    write_device_templates(&entries)?;
+
+    Ok(updated)
+}
+
+/// Attaches a latency table read from its JSON file, replacing the template's table.
+pub fn attach_latency_lut(id: String, path: String) -> Result<DeviceTemplateDto, String> {
+    let lut = LatencyLut::load(Path::new(&path))?;
+    set_latency_lut(&id, lut)
+}
+
+/// Calibrates the template's latency table from a benchmark CSV. Merged rows replace
+/// entries of the same layer type, output shape and MACs.
+pub fn import_latency_benchmarks(
+    id: String,
+    path: String,
+    mode: DeviceLibraryImportMode,
+) -> Result<DeviceTemplateDto, String> {
+    let imported = LatencyLut::read_csv(Path::new(&path))?;
+    let mut lut = get_device_template(&id)?.latency_lut.unwrap_or_default();
+
+    match mode {
+        DeviceLibraryImportMode::Replace => lut.entries = imported,
+        DeviceLibraryImportMode::Merge => {
+            lut.entries.retain(|existing| {
+                !imported
+                    .iter()
+                    .any(|entry| entry.measures(&existing.op, &existing.output_shape, existing.macs))
+            });
+            lut.entries.extend(imported);
+        }
+    }
+
+    set_latency_lut(&id, lut)
+}
+
 pub fn export_device_library(path: String) -> Result<usize, String> {
     let entries = read_device_templates()?;
 
@@ -486,4 +545,47 @@ mod tests {
         let _ = fs::remove_dir_all(source_storage);
         let _ = fs::remove_dir_all(target_storage);
     }
+
+    #[test]
+    fn latency_benchmarks_calibrate_the_template_table() {
+        let _guard = TEST_LOCK.lock().expect("test lock");
+        let temp = with_test_storage("device-library-latency");
+
+        let created = create_device_template(CreateDeviceTemplateInput {
+            name: "Cortex-M7".to_string(),
+            constraints: constraints(),
+            notes: None,
+            tags: vec![],
+        })
+        .expect("create template");
+        assert!(created.latency_lut.is_none());
+
+        let first = temp.join("first.csv");
+        fs::write(&first, "op,output_shape,macs,latency_ms\nConv2D,4x8x8,2304,2.0\nDense,10,640,0.4\n").unwrap();
+        let second = temp.join("second.csv");
+        // Same Dense shape with other MACs is a new measurement, not a replacement
+        fs::write(&second, "op,output_shape,macs,latency_ms\nConv2D,4x8x8,2304,1.5\nDense,10,1280,0.7\n").unwrap();
+
+        import_latency_benchmarks(created.id.clone(), first.to_string_lossy().to_string(), DeviceLibraryImportMode::Merge)
+            .expect("import first benchmarks");
+        let merged = import_latency_benchmarks(
+            created.id.clone(),
+            second.to_string_lossy().to_string(),
+            DeviceLibraryImportMode::Merge,
+        )
+        .expect("merge second benchmarks");
+        let lut = merged.latency_lut.expect("latency table");
+        assert_eq!(lut.entries.len(), 3);
+        assert!(lut.entries.iter().any(|e| e.op == "Conv2D" && e.latency_ms == 1.5));
+        assert_eq!(lut.entries.iter().filter(|e| e.op == "Dense").count(), 2);
+
+        let copy = duplicate_device_template(created.id.clone(), "Cortex-M7 Copy".to_string()).expect("duplicate");
+        assert_eq!(copy.latency_lut, Some(lut));
+
+        let replaced = import_latency_benchmarks(created.id, second.to_string_lossy().to_string(), DeviceLibraryImportMode::Replace)
+            .expect("replace benchmarks");
+        assert_eq!(replaced.latency_lut.expect("latency table").entries.len(), 2);
+
+        let _ = fs::remove_dir_all(temp);
+    }
 }
@@ -1,4 +1,6 @@
+use crate::cost_analysis::ModelCost;
 use crate::dtos::GenomeObjectives;
+use crate::latency_lut::LatencyLut;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -52,19 +54,31 @@ fn safe_ratio(usage: f32, budget: f32) -> f32 {
     usage / budget
 }
 
+const BYTES_PER_MB: f32 = 1024.0 * 1024.0;
+
 fn estimate_mops(objectives: &GenomeObjectives) -> f32 {
-    // Until dedicated MACs/FLOPs stats are added to GenomeObjectives,
-    // use a coarse proxy from latency and model size to keep constraint
-    // validation monotonic and deterministic.
+    if let Some(flops) = objectives.flops {
+        return flops as f32 / 1e6;
+    }
+    // Without static cost analysis (see `cost_analysis`), use a coarse proxy from
+    // latency and model size to keep constraint validation monotonic and deterministic.
     (objectives.inference_latency_ms.max(0.0) * 1.5) + (objectives.model_size_mb.max(0.0) * 12.0)
 }
 
+/// Peak activations only: the weights stay in flash (see `estimate_flash_mb`) and are
+/// read from there, as on microcontrollers, so they do not count against RAM.
 fn estimate_ram_mb(objectives: &GenomeObjectives) -> f32 {
+    if let Some(peak) = objectives.peak_activation_bytes {
+        return peak as f32 / BYTES_PER_MB;
+    }
     // Runtime RAM is usually > model size due to activations/intermediate buffers.
     objectives.model_size_mb.max(0.0) * 2.5
 }
 
 fn estimate_flash_mb(objectives: &GenomeObjectives) -> f32 {
+    if let Some(params) = objectives.parameter_count {
+        return (params * crate::cost_analysis::BYTES_PER_ELEMENT) as f32 / BYTES_PER_MB;
+    }
     // Persisted model footprint approximation.
     objectives.model_size_mb.max(0.0)
 }
@@ -198,6 +212,24 @@ pub fn validate_genome_for_device(
     }
 }
 
+/// Objectives of a genome on the target device: MOPS, flash and RAM from the static cost
+/// analysis and, when the device has a latency table, its predicted latency in place of
+/// the host measurement.
+pub fn objectives_on_device(
+    objectives: &GenomeObjectives,
+    cost: &ModelCost,
+    latency_lut: Option<&LatencyLut>,
+) -> Result<GenomeObjectives, String> {
+    let mut on_device = objectives.clone();
+    on_device.flops = Some(cost.flops());
+    on_device.parameter_count = Some(cost.total_params);
+    on_device.peak_activation_bytes = Some(cost.peak_activation_bytes);
+    if let Some(lut) = latency_lut {
+        on_device.inference_latency_ms = lut.predict(cost)?.total_ms;
+    }
+    Ok(on_device)
+}
+
 pub fn apply_device_penalty(base_fitness: f32, violation_score: f32, alpha: f32) -> f32 {
     base_fitness - alpha.max(0.0) * violation_score.max(0.0)
 }
@@ -226,6 +258,11 @@ mod tests {
             training_time_ms: 1200,
             is_dominated: false,
             domination_count: 0,
+            flops: None,
+            parameter_count: None,
+            peak_activation_bytes: None,
+            constraint_violation_score: None,
+            custom_metrics: Default::default(),
         }
     }
 
@@ -289,6 +326,75 @@ mod tests {
         assert!(ratio > 8.5 && ratio < 9.5);
     }
 
+    #[test]
+    fn analysed_costs_replace_the_heuristics() {
+        let constraints = DeviceResourceConstraints {
+            mops_budget: 100.0,
+            ram_budget_mb: 0.5,
+            flash_budget_mb: 2.0,
+            max_latency_ms: 80.0,
+        };
+        // The heuristics would reject 1.5 MB of weights for 3.75 MB of RAM
+        let mut genome = objectives(10.0, 1.5);
+        assert!(!validate_genome_for_device(&genome, &constraints).is_feasible);
+
+        genome.flops = Some(60_000_000);
+        genome.parameter_count = Some(393_216);
+        genome.peak_activation_bytes = Some(256 * 1024);
+        let validation = validate_genome_for_device(&genome, &constraints);
+        assert!(validation.is_feasible);
+        assert!((validation.mops_ratio - 0.6).abs() < 1e-6);
+        assert!((validation.ram_ratio - 0.5).abs() < 1e-6);
+        assert!((validation.flash_ratio - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn device_latency_table_replaces_host_latency() {
+        let constraints = DeviceResourceConstraints {
+            mops_budget: 120.0,
+            ram_budget_mb: 0.5,
+            flash_budget_mb: 2.0,
+            max_latency_ms: 80.0,
+        };
+        let cost = ModelCost {
+            layers: vec![crate::cost_analysis::LayerCost {
+                node_id: 1,
+                op: "Dense".to_string(),
+                output_shape: vec![64],
+                macs: 50_000,
+                params: 50_064,
+                activation_bytes: 256,
+            }],
+            total_macs: 50_000,
+            total_params: 50_064,
+            parameter_bytes: 200_256,
+            activation_bytes: 256,
+            peak_activation_bytes: 2_304,
+        };
+        // Fast on the host, 0.002 ms per MAC on the device
+        let host = objectives(0.5, 0.2);
+        let lut = LatencyLut {
+            entries: vec![crate::latency_lut::LatencyLutEntry {
+                op: "Dense".to_string(),
+                output_shape: vec![32],
+                macs: 25_000,
+                latency_ms: 50.0,
+            }],
+            overhead_ms: 2.0,
+        };
+
+        let without_table = objectives_on_device(&host, &cost, None).unwrap();
+        assert_eq!(without_table.inference_latency_ms, 0.5);
+        assert_eq!(without_table.parameter_count, Some(50_064));
+        assert!(validate_genome_for_device(&without_table, &constraints).is_feasible);
+
+        let on_device = objectives_on_device(&host, &cost, Some(&lut)).unwrap();
+        assert!((on_device.inference_latency_ms - 102.0).abs() < 1e-3);
+        let validation = validate_genome_for_device(&on_device, &constraints);
+        assert!(!validation.is_feasible);
+        assert!(validation.latency_ratio > 1.0);
+    }
+
     #[test]
     fn serde_roundtrip_for_api_payloads() {
         let payload = DeviceResourceConstraints {
@@ -112,16 +112,34 @@ pub struct DatasetProfileState {
     pub profiles: Vec<DatasetProfile>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct DatasetProfile {
     pub id: String,
     pub name: String,
     pub source_path: Option<String>,
     pub streams: Vec<DataStream>,
+    #[serde(default)]
+    pub split: Option<SplitSettings>,
+    /// Training-set sampling: "uniform" (default) | "oversample" | "undersample" | "weighted"
+    #[serde(default)]
+    pub sampling: Option<String>,
+}
+
+/// How samples are divided into train/validation/test.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SplitSettings {
+    pub strategy: String, // "stratified" (default) | "random" | "chronological"
+    /// Chronological only: samples left out between segments. Defaults to the number
+    /// of neighbouring samples that share rows with a sample (overlapping windows).
+    pub gap: Option<usize>,
+    /// Chronological only: score genomes as the mean over this many walk-forward
+    /// folds, the regular split being the last one. 0 or 1 disables it.
+    pub walk_forward_folds: usize,
 }
 
-#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum DataType {
     Image,
     Vector,
@@ -130,7 +148,7 @@ pub enum DataType {
     TemporalSequence,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct DataStream {
     pub id: String,
@@ -142,9 +160,12 @@ pub struct DataStream {
     pub num_classes: Option<usize>,
     pub locator: DataLocatorDef,
     pub preprocessing: Option<PreprocessingSettings>,
+    /// Target streams only: "classification" | "regression" | "multi_label" | "multi_output"
+    #[serde(default)]
+    pub task_type: Option<String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "type")]
 pub enum DataLocatorDef {
     GlobPattern {
@@ -167,12 +188,28 @@ pub enum DataLocatorDef {
         has_headers: bool,
     },
     CsvDataset(CsvDatasetDef),
+    /// `.npy` array or one array (`key`, default: the first) of an `.npz` archive,
+    /// indexed by its first axis.
+    NumpyArray {
+        path: String,
+        #[serde(default)]
+        key: Option<String>,
+    },
+    /// Parquet table with one row per sample; `columns` selects the value columns
+    /// (default: every numeric one).
+    ParquetTable {
+        path: String,
+        #[serde(default)]
+        columns: Vec<String>,
+        #[serde(rename = "idColumn", default)]
+        id_column: Option<String>,
+    },
     None,
     #[serde(other)]
     Other,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CsvDatasetDef {
     pub csv_path: String,
@@ -183,31 +220,35 @@ pub struct CsvDatasetDef {
     pub window_size: Option<usize>,
     pub window_stride: Option<usize>,
     pub preprocessing: CsvPreprocessingConfig,
+    /// Target streams: predict the target columns of the next `horizon` rows after the
+    /// input window instead of the row the sample starts at.
+    #[serde(default)]
+    pub horizon: Option<usize>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CsvPreprocessingConfig {
     pub normalization: String, // "none" | "global" | "per-sample" | "per-channel"
     pub handle_missing: String, // "skip" | "interpolate" | "mean"
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PreprocessingSettings {
     pub vision: Option<VisionSettings>,
     pub tabular: Option<TabularSettings>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct VisionSettings {
     pub resize: Vec<u32>, // e.g. [256, 256]
     pub grayscale: bool,
-    pub normalization: String, // "0-1" | "imagenet" | "none"
+    pub normalization: String, // "0-1" | "imagenet" | "none" | "dataset"
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TabularSettings {
     pub normalization: String, // "min-max" | "z-score" | "none"
@@ -246,6 +287,19 @@ pub struct DatasetValidationReport {
     pub output_shape: Option<Vec<usize>>,
     pub total_valid_samples: usize,
     pub can_start_evolution: bool,
+    /// Per-class sample counts of each split, for the first classification target.
+    pub class_counts: Vec<ClassSplitCounts>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassSplitCounts {
+    pub class_name: String,
+    pub train: usize,
+    pub val: usize,
+    pub test: usize,
+    /// Training samples per epoch after the profile's sampling strategy.
+    pub train_sampled: usize,
 }
 
 // ---------------------------------------------------------------------------
@@ -331,6 +385,20 @@ pub struct GenomeObjectives {
     pub training_time_ms: u64,
     pub is_dominated: bool,
     pub domination_count: u32,
+    /// Floating point operations of one forward pass (two per multiply-accumulate), when known
+    #[serde(default)]
+    pub flops: Option<u64>,
+    #[serde(default)]
+    pub parameter_count: Option<u64>,
+    /// Peak live activation memory of one forward pass at batch 1, when known
+    #[serde(default)]
+    pub peak_activation_bytes: Option<u64>,
+    /// Device constraint violation (0 when the genome fits its target device)
+    #[serde(default)]
+    pub constraint_violation_score: Option<f32>,
+    /// Further objectives by name, e.g. energy per inference
+    #[serde(default)]
+    pub custom_metrics: HashMap<String, f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -379,6 +447,9 @@ pub struct DeviceTemplateDto {
     pub tags: Vec<String>,
     pub created_at_unix_ms: u64,
     pub updated_at_unix_ms: u64,
+    /// Per-layer latencies measured on the device
+    #[serde(default)]
+    pub latency_lut: Option<crate::latency_lut::LatencyLut>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -597,6 +668,14 @@ pub struct WorkerTrainRequest {
     pub val_split: usize,
     pub test_split: usize,
     pub queue_entered_ms: u64,
+    #[serde(default)]
+    pub execution_backend: crate::backend::ExecutionBackend,
+    #[serde(default)]
+    pub batch_pipeline: crate::batch_stream::BatchPipelineConfig,
+    #[serde(default)]
+    pub split: Option<crate::evaluation::DatasetSplit>,
+    #[serde(default)]
+    pub epoch_checkpoint_dir: Option<std::path::PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -740,6 +819,11 @@ mod tests {
             training_time_ms: 5000,
             is_dominated: false,
             domination_count: 0,
+            flops: None,
+            parameter_count: None,
+            peak_activation_bytes: None,
+            constraint_violation_score: None,
+            custom_metrics: HashMap::new(),
         };
 
         let json = serde_json::to_string(&obj).unwrap();
@@ -760,6 +844,11 @@ mod tests {
                 training_time_ms: 5000,
                 is_dominated: false,
                 domination_count: 0,
+                flops: None,
+                parameter_count: None,
+                peak_activation_bytes: None,
+                constraint_violation_score: None,
+                custom_metrics: HashMap::new(),
             },
             GenomeObjectives {
                 genome_id: "g2".to_string(),
@@ -769,6 +858,11 @@ mod tests {
                 training_time_ms: 4000,
                 is_dominated: false,
                 domination_count: 0,
+                flops: None,
+                parameter_count: None,
+                peak_activation_bytes: None,
+                constraint_violation_score: None,
+                custom_metrics: HashMap::new(),
             },
         ];
 
@@ -1003,6 +1097,11 @@ mod tests {
                 training_time_ms: 8000,
                 is_dominated: false,
                 domination_count: 0,
+                flops: None,
+                parameter_count: None,
+                peak_activation_bytes: None,
+                constraint_violation_score: None,
+                custom_metrics: HashMap::new(),
             }),
             profiler_data: None,
             model_weights: Some("weights".to_string()),
@@ -13,7 +13,7 @@ use burn::{
         BatchNorm, BatchNormConfig, Dropout, DropoutConfig, LayerNorm, LayerNormConfig, Linear,
         LinearConfig, PaddingConfig1d, PaddingConfig2d,
         conv::{Conv1d, Conv1dConfig, Conv2d, Conv2dConfig},
-        loss::{CrossEntropyLossConfig, MseLoss},
+        loss::{BinaryCrossEntropyLossConfig, CrossEntropyLossConfig, MseLoss},
         pool::{AvgPool2d, AvgPool2dConfig, MaxPool2d, MaxPool2dConfig},
         lstm::{Lstm, LstmConfig},
         gru::{Gru, GruConfig},
@@ -28,8 +28,11 @@ use burn::{
     },
 };
 
+use crate::batch_stream::BatchSource;
 use crate::dtos::NodeDtoJSON;
 use crate::profiler::ProfilerCollector;
+use crate::progress::ProgressSink;
+use crate::targets::{TargetMetrics, TargetSpec, TargetTask};
 
 // ---------------------------------------------------------------------------
 // Тензорные типы
@@ -84,6 +87,17 @@ pub fn concat_dynamic_tensors<B: Backend>(tensors: Vec<DynamicTensor<B>>) -> Dyn
 // Слои (обёртки для burn-слоёв)
 // ---------------------------------------------------------------------------
 
+/// BatchNorm with its running statistics. Burn's `BatchNorm::forward` switches to batch
+/// statistics whenever the backend tracks gradients.
+fn batch_norm_eval<B: Backend, const D: usize>(bn: &BatchNorm<B>, x: Tensor<B, D>) -> Tensor<B, D> {
+    let mut shape = [1; D];
+    shape[1] = x.dims()[1];
+    let device = x.device();
+    let mean = bn.running_mean.value().to_device(&device).reshape(shape);
+    let std = bn.running_var.value().to_device(&device).reshape(shape).add_scalar(bn.epsilon).sqrt();
+    x.sub(mean).div(std).mul(bn.gamma.val().reshape(shape)).add(bn.beta.val().reshape(shape))
+}
+
 // Removed Layer enum to avoid hiding parameters from Burn
 
 // ---------------------------------------------------------------------------
@@ -179,6 +193,9 @@ pub struct GraphModel<B: Backend> {
     pub output_shapes: Vec<Vec<usize>>,
     pub node_output_shapes: Ignored<Vec<Vec<usize>>>,
     pub estimated_parameter_elements: usize,
+    /// Task of each output (see `with_target_specs`); empty means the loss is picked from
+    /// the output width alone.
+    pub target_specs: Ignored<Vec<TargetSpec>>,
 }
 
 // ---------------------------------------------------------------------------
@@ -884,9 +901,16 @@ impl<B: Backend> GraphModel<B> {
             output_shapes,
             node_output_shapes: Ignored(shape_cache),
             estimated_parameter_elements,
+            target_specs: Ignored(Vec::new()),
         }
     }
 
+    /// Trains and scores each output according to the task of its target stream.
+    pub fn with_target_specs(mut self, specs: Vec<TargetSpec>) -> Self {
+        self.target_specs = Ignored(specs);
+        self
+    }
+
     // -----------------------------------------------------------------------
     // Прямой проход
     // -----------------------------------------------------------------------
@@ -900,6 +924,34 @@ impl<B: Backend> GraphModel<B> {
         inputs: &[DynamicTensor<B>],
         is_training: bool,
         debug: bool,
+    ) -> Vec<DynamicTensor<B>> {
+        self.run_plan(inputs, is_training, debug, false, None)
+    }
+
+    /// Forward pass that behaves like inference even on an autodiff backend: BatchNorm
+    /// normalizes with its running statistics and Dropout is skipped.
+    pub fn forward_eval_mode(&self, inputs: &[DynamicTensor<B>]) -> Vec<DynamicTensor<B>> {
+        self.run_plan(inputs, false, false, true, None)
+    }
+
+    /// Inference forward pass that also returns the output of every parametric layer
+    /// (Dense, Conv, recurrent and attention nodes) in execution order.
+    pub fn forward_with_activations(
+        &self,
+        inputs: &[DynamicTensor<B>],
+    ) -> (Vec<DynamicTensor<B>>, Vec<DynamicTensor<B>>) {
+        let mut activations = Vec::new();
+        let outputs = self.run_plan(inputs, false, false, false, Some(&mut activations));
+        (outputs, activations)
+    }
+
+    fn run_plan(
+        &self,
+        inputs: &[DynamicTensor<B>],
+        is_training: bool,
+        debug: bool,
+        eval_mode: bool,
+        mut activations: Option<&mut Vec<DynamicTensor<B>>>,
     ) -> Vec<DynamicTensor<B>> {
         assert_eq!(inputs.len(), self.num_inputs);
 
@@ -1153,6 +1205,7 @@ impl<B: Backend> GraphModel<B> {
                     let input = consume!(instr.input_ids[0]);
                     let dropout = &self.dropouts[*dropout_idx];
                     match input {
+                        _ if eval_mode => input,
                         DynamicTensor::Dim2(t) => DynamicTensor::Dim2(dropout.forward(t)),
                         DynamicTensor::Dim3(t) => DynamicTensor::Dim3(dropout.forward(t)),
                         DynamicTensor::Dim4(t) => DynamicTensor::Dim4(dropout.forward(t)),
@@ -1164,7 +1217,7 @@ impl<B: Backend> GraphModel<B> {
                     match input {
                         DynamicTensor::Dim2(t) => {
                             let bn = &self.batch_norms_2d[*batch_norm_idx];
-                            DynamicTensor::Dim2(bn.forward(t))
+                            DynamicTensor::Dim2(if eval_mode { batch_norm_eval(bn, t) } else { bn.forward(t) })
                         }
                         // BatchNorm for Dim3 is 1D in PyTorch/Burn terminology, usually batch_norms_1d
                         // But since we only have 2d and 4d in entities rn, fallback or handle:
@@ -1173,7 +1226,7 @@ impl<B: Backend> GraphModel<B> {
                         }
                         DynamicTensor::Dim4(t) => {
                             let bn = &self.batch_norms_4d[*batch_norm_idx];
-                            DynamicTensor::Dim4(bn.forward(t))
+                            DynamicTensor::Dim4(if eval_mode { batch_norm_eval(bn, t) } else { bn.forward(t) })
                         }
                     }
                 }
@@ -1249,6 +1302,21 @@ impl<B: Backend> GraphModel<B> {
                 );
             }
 
+            if let Some(activations) = activations.as_deref_mut()
+                && matches!(
+                    instr.op,
+                    Operation::Dense { .. }
+                        | Operation::Conv1D { .. }
+                        | Operation::Conv2D { .. }
+                        | Operation::LSTM { .. }
+                        | Operation::GRU { .. }
+                        | Operation::MultiHeadAttention { .. }
+                        | Operation::TransformerEncoderBlock { .. }
+                )
+            {
+                activations.push(out_tensor.clone());
+            }
+
             if remaining_uses[instr.node_id] > 0 {
                 memory[instr.node_id] = Some(out_tensor);
             }
@@ -1279,8 +1347,17 @@ impl<B: Backend> GraphModel<B> {
                 (DynamicTensor::Dim2(p), DynamicTensor::Dim2(t)) => {
                     let batch_size = p.dims()[0];
                     let out_features = p.dims()[1];
+                    let task = self.target_specs.0.get(i).map(|spec| spec.task);
 
-                    if out_features == 1 {
+                    if matches!(task, Some(TargetTask::Regression | TargetTask::MultiOutput)) {
+                        // Standardized continuous targets
+                        let loss_func = MseLoss::new();
+                        loss_func.forward(p.clone(), t.clone(), burn::nn::loss::Reduction::Mean)
+                    } else if task == Some(TargetTask::MultiLabel) {
+                        // Independent 0/1 labels, the outputs are logits
+                        let loss_func = BinaryCrossEntropyLossConfig::new().with_logits(true).init(&p.device());
+                        loss_func.forward(p.clone(), t.clone().int())
+                    } else if out_features == 1 {
                         // For a final node with 1 unit (binary classification or regression),
                         // interpret floats and use MSE
                         let loss_func = MseLoss::new();
@@ -1511,6 +1588,23 @@ pub fn compute_accuracy<B: AutodiffBackend>(
     (0, 0)
 }
 
+/// Adds a batch to `metrics`, scored by the model's target specs, or by
+/// `compute_accuracy` for models built without them.
+pub fn score_batch<B: AutodiffBackend>(
+    model: &GraphModel<B>,
+    metrics: &mut TargetMetrics,
+    predictions: &[DynamicTensor<B>],
+    targets: &[DynamicTensor<B>],
+    is_classification: bool,
+) {
+    if model.target_specs.0.is_empty() {
+        let (correct, total) = compute_accuracy(predictions, targets, is_classification);
+        metrics.add_counts(correct, total);
+    } else {
+        metrics.add_batch(&model.target_specs.0, predictions, targets);
+    }
+}
+
 /// Простой тренировочный цикл «вручную» — без SupervisedTraining.
 ///
 /// Полезен для быстрого прототипирования и когда не нужны все
@@ -1663,12 +1757,19 @@ pub fn estimate_activations_mb(batch_shapes: &[Vec<usize>], batch_size: usize) -
     crate::profiler::estimate_mb_from_elements(elements, 4)
 }
 
+/// Resumable training for `run_eval_pass`: epochs before `start_epoch` are skipped
+/// and `on_epoch_end` receives the model after every completed epoch (1-based count).
+pub struct EpochCheckpointing<'a, B: AutodiffBackend> {
+    pub start_epoch: usize,
+    pub on_epoch_end: &'a mut dyn FnMut(usize, &GraphModel<B>),
+}
+
 #[allow(clippy::type_complexity)]
 pub fn run_eval_pass<B: AutodiffBackend>(
-    app_handle: &tauri::AppHandle,
+    progress: &dyn ProgressSink,
     genome_index: usize,
     model: &mut GraphModel<B>,
-    batches: &[DynamicBatch<B>],
+    batches: &BatchSource<B>,
     num_epochs: usize,
     learning_rate: f64,
     is_classification: bool,
@@ -1676,8 +1777,13 @@ pub fn run_eval_pass<B: AutodiffBackend>(
     session_snapshot: u64,
     queue_wait_ms: u64,
     profiler: Option<&mut ProfilerCollector>,
-) -> (f32, f32) {
+    checkpointing: Option<EpochCheckpointing<'_, B>>,
+) -> Result<(f32, f32), String> {
     let train_started_at = std::time::Instant::now();
+    let (start_epoch, mut on_epoch_end) = match checkpointing {
+        Some(c) => (c.start_epoch.min(num_epochs), Some(c.on_epoch_end)),
+        None => (0, None),
+    };
     let mut profiler = profiler;
     if let Some(p) = profiler.as_mut() {
         p.mark_train_start();
@@ -1702,7 +1808,11 @@ pub fn run_eval_pass<B: AutodiffBackend>(
     let log_interval = (total_batches / 10).max(1);
     let mut prev_acc = 0.0;
 
-    for epoch in 0..num_epochs {
+    if start_epoch > 0 {
+        println!("  >>> Resuming training from epoch checkpoint at epoch {}/{}.", start_epoch, num_epochs);
+    }
+
+    for epoch in start_epoch..num_epochs {
         // Check cancellation at the start of each epoch
         if session_counter.load(std::sync::atomic::Ordering::SeqCst) != session_snapshot {
             println!("  >>> Training cancelled at epoch {}.", epoch + 1);
@@ -1710,10 +1820,11 @@ pub fn run_eval_pass<B: AutodiffBackend>(
         }
 
         let mut train_loss_sum = 0.0f32;
-        let mut train_correct = 0;
-        let mut train_total = 0;
+        let mut train_metrics = TargetMetrics::default();
+        let mut trained_batches = 0usize;
 
-        for (batch_idx, batch) in batches.iter().enumerate() {
+        for (batch_idx, batch) in batches.epoch(epoch).enumerate() {
+            let batch = batch?;
             // Check cancellation every 50 batches
             if batch_idx % 50 == 0
                 && session_counter.load(std::sync::atomic::Ordering::SeqCst) != session_snapshot
@@ -1723,7 +1834,7 @@ pub fn run_eval_pass<B: AutodiffBackend>(
                     epoch + 1,
                     batch_idx + 1
                 );
-                return (final_loss, final_acc);
+                return Ok((final_loss, final_acc));
             }
 
             // Clone inputs/targets so the autodiff graph is fresh each pass
@@ -1732,10 +1843,11 @@ pub fn run_eval_pass<B: AutodiffBackend>(
             let cloned_targets: Vec<DynamicTensor<B>> =
                 batch.targets.iter().map(|t| t.clone()).collect();
 
-            if epoch == 0 && batch_idx == 0 {
-                if let Some(p) = profiler.as_mut() {
-                    p.mark_first_batch();
-                }
+            if epoch == start_epoch
+                && batch_idx == 0
+                && let Some(p) = profiler.as_mut()
+            {
+                p.mark_first_batch();
             }
 
             let batch_size = match &batch.inputs[0] {
@@ -1761,19 +1873,13 @@ pub fn run_eval_pass<B: AutodiffBackend>(
 
             let loss_val = loss.into_data().to_vec::<f32>().unwrap()[0];
             train_loss_sum += loss_val;
+            trained_batches += 1;
 
-            let (batch_correct, batch_total) =
-                compute_accuracy(&predictions, &cloned_targets, is_classification);
-            train_correct += batch_correct;
-            train_total += batch_total;
+            score_batch(model, &mut train_metrics, &predictions, &cloned_targets, is_classification);
 
             if batch_idx % log_interval == 0 || batch_idx == total_batches - 1 {
                 let current_avg_loss = train_loss_sum / (batch_idx + 1) as f32;
-                let current_acc = if train_total > 0 {
-                    (train_correct as f32 / train_total as f32) * 100.0
-                } else {
-                    0.0
-                };
+                let current_acc = train_metrics.accuracy();
                 println!(
                     "  [Epoch {}/{}] Batch {}/{}: Loss = {:.4}, Acc = {:>5.2}%",
                     epoch + 1,
@@ -1784,28 +1890,24 @@ pub fn run_eval_pass<B: AutodiffBackend>(
                     current_acc
                 );
 
-                use tauri::Emitter;
-                let _ = app_handle.emit(
-                    "evaluating-batch-metrics",
-                    BatchMetrics {
-                        genome_index,
-                        epoch: epoch + 1,
-                        batch: batch_idx + 1,
-                        total_batches,
-                        step: epoch * total_batches + batch_idx + 1,
-                        total_steps,
-                        elapsed_train_ms: train_started_at.elapsed().as_millis() as u64,
-                        queue_wait_ms,
-                        gpu_active_ms: train_started_at.elapsed().as_millis() as u64,
-                        step_time_ms: if batch_idx + 1 > 0 {
-                            train_started_at.elapsed().as_millis() as f32 / (batch_idx + 1) as f32
-                        } else {
-                            0.0
-                        },
-                        loss: current_avg_loss,
-                        accuracy: current_acc,
+                progress.batch_metrics(&BatchMetrics {
+                    genome_index,
+                    epoch: epoch + 1,
+                    batch: batch_idx + 1,
+                    total_batches,
+                    step: epoch * total_batches + batch_idx + 1,
+                    total_steps,
+                    elapsed_train_ms: train_started_at.elapsed().as_millis() as u64,
+                    queue_wait_ms,
+                    gpu_active_ms: train_started_at.elapsed().as_millis() as u64,
+                    step_time_ms: if batch_idx + 1 > 0 {
+                        train_started_at.elapsed().as_millis() as f32 / (batch_idx + 1) as f32
+                    } else {
+                        0.0
                     },
-                );
+                    loss: current_avg_loss,
+                    accuracy: current_acc,
+                });
             }
 
             // Yield to avoid blocking the executor and let the GPU/OS breathe
@@ -1814,15 +1916,15 @@ pub fn run_eval_pass<B: AutodiffBackend>(
             std::thread::sleep(std::time::Duration::from_millis(1));
         }
 
-        final_loss = train_loss_sum / total_batches.max(1) as f32;
-        final_acc = if train_total > 0 {
-            (train_correct as f32 / train_total as f32) * 100.0
-        } else {
-            0.0
-        };
+        final_loss = train_loss_sum / trained_batches.max(1) as f32;
+        final_acc = train_metrics.accuracy();
+
+        if let Some(on_epoch_end) = on_epoch_end.as_mut() {
+            on_epoch_end(epoch + 1, model);
+        }
 
         if is_classification {
-            if epoch > 0 {
+            if epoch > start_epoch {
                 let improvement = final_acc - prev_acc;
                 if improvement < 1.0 && final_acc < 75.0 {
                     println!(
@@ -1843,18 +1945,19 @@ pub fn run_eval_pass<B: AutodiffBackend>(
         p.mark_train_end();
     }
 
-    (final_loss, final_acc)
+    Ok((final_loss, final_acc))
 }
 
 /// Inference-only pass on validation/test batches. No gradients, no weight updates.
 #[allow(clippy::type_complexity)]
 pub fn run_validation_pass<B: AutodiffBackend>(
     model: &GraphModel<B>,
-    batches: &[DynamicBatch<B>],
+    batches: &BatchSource<B>,
     split_name: &str,
     is_classification: bool,
     profiler: Option<&mut ProfilerCollector>,
-) -> (f32, f32) {
+    progress: &dyn ProgressSink,
+) -> Result<(f32, f32), String> {
     let mut profiler = profiler;
     if split_name.eq_ignore_ascii_case("validation") {
         if let Some(p) = profiler.as_mut() {
@@ -1870,13 +1973,14 @@ pub fn run_validation_pass<B: AutodiffBackend>(
 
     println!(">>> [{}] starting with {} batches...", split_name, batches.len());
     let mut val_loss_sum = 0.0f32;
-    let mut val_correct = 0usize;
-    let mut val_total = 0usize;
+    let mut val_metrics = TargetMetrics::default();
+    let mut evaluated_batches = 0usize;
 
     let total_batches = batches.len();
     let log_interval = (total_batches / 10).max(1);
 
     for (batch_idx, batch) in batches.iter().enumerate() {
+        let batch = batch?;
         if batch_idx % log_interval == 0 || batch_idx == total_batches - 1 {
             println!("  [{}] Batch {}/{}", split_name, batch_idx + 1, total_batches);
         }
@@ -1889,17 +1993,16 @@ pub fn run_validation_pass<B: AutodiffBackend>(
         let loss = model.compute_loss(&predictions, &cloned_targets);
 
         val_loss_sum += loss.into_data().to_vec::<f32>().unwrap()[0];
-        let (correct, count) = compute_accuracy(&predictions, &cloned_targets, is_classification);
-        val_correct += correct;
-        val_total += count;
+        evaluated_batches += 1;
+        score_batch(model, &mut val_metrics, &predictions, &cloned_targets, is_classification);
 
         if let Some(p) = profiler.as_mut() {
-            p.record_inference_samples(count);
             let batch_size = match &batch.inputs[0] {
                 DynamicTensor::Dim2(t) => t.dims()[0],
                 DynamicTensor::Dim3(t) => t.dims()[0],
                 DynamicTensor::Dim4(t) => t.dims()[0],
             };
+            p.record_inference_samples(batch_size);
             p.set_activation_mb(estimate_activations_mb(
                 &model.node_output_shapes.0,
                 batch_size,
@@ -1912,18 +2015,24 @@ pub fn run_validation_pass<B: AutodiffBackend>(
         std::thread::sleep(std::time::Duration::from_millis(1));
     }
 
-    let avg_loss = val_loss_sum / batches.len().max(1) as f32;
-    let acc = if val_total > 0 {
-        (val_correct as f32 / val_total as f32) * 100.0
-    } else {
-        0.0
-    };
+    let avg_loss = val_loss_sum / evaluated_batches.max(1) as f32;
+    let acc = val_metrics.accuracy();
 
     eprintln!(
         "  [{}] Loss = {:.4}, Acc = {:.2}%",
         split_name, avg_loss, acc
     );
+    if let (Some(mae), Some(rmse)) = (val_metrics.mae(), val_metrics.rmse()) {
+        eprintln!(
+            "  [{}] Targets: MAE = {:.4}, RMSE = {:.4} (original units), R2 = {:.4}",
+            split_name,
+            mae,
+            rmse,
+            val_metrics.r2().unwrap_or(0.0)
+        );
+    }
     println!(">>> [{}] finished with loss={:.4}, acc={:.2}%", split_name, avg_loss, acc);
+    progress.split_evaluated(split_name, avg_loss, acc);
 
     if split_name.eq_ignore_ascii_case("validation") {
         if let Some(p) = profiler.as_mut() {
@@ -1935,7 +2044,7 @@ pub fn run_validation_pass<B: AutodiffBackend>(
         }
     }
 
-    (avg_loss, acc)
+    Ok((avg_loss, acc))
 }
 
 #[cfg(test)]
@@ -0,0 +1,556 @@
+/// Persistent Evaluation Cache
+///
+/// Trained genome results are stored on disk as `<cache dir>/entries/<key>.json`, one
+/// file per evaluation. The key is a SHA-256 digest of the canonicalised genome, the
+/// training parameters, the dataset fingerprint (profile definition plus size/mtime of
+/// every file behind the split's samples) and the split manifest, so it is stable
+/// across processes and Rust versions and changes whenever the data does.
+///
+/// The cache is bounded by entry count and total bytes; the least recently used
+/// entries are evicted first. Limits persist in `limits.json`.
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::EvaluationResult;
+use crate::dtos::DataLocatorDef;
+use crate::evaluation::PreparedDataset;
+
+const ENTRIES_DIR: &str = "entries";
+const LIMITS_FILE: &str = "limits.json";
+
+pub const DEFAULT_MAX_ENTRIES: usize = 10_000;
+pub const DEFAULT_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Length-prefixed field hashing, so adjacent fields cannot run into each other.
+#[derive(Default)]
+pub struct StableHasher(Sha256);
+
+impl StableHasher {
+    pub fn field(&mut self, value: impl AsRef<[u8]>) -> &mut Self {
+        let bytes = value.as_ref();
+        self.0.update((bytes.len() as u64).to_le_bytes());
+        self.0.update(bytes);
+        self
+    }
+
+    pub fn number(&mut self, value: u64) -> &mut Self {
+        self.0.update(value.to_le_bytes());
+        self
+    }
+
+    pub fn finish_hex(self) -> String {
+        format!("{:x}", self.0.finalize())
+    }
+}
+
+fn canonical_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|key| format!("{}:{}", serde_json::Value::String(key.clone()), canonical_json(&map[key])))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        serde_json::Value::Array(items) => {
+            format!("[{}]", items.iter().map(canonical_json).collect::<Vec<_>>().join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Genome text with JSON node lines re-serialised with sorted keys and all other
+/// lines whitespace-normalised. Formatting-only edits keep the same cache key.
+pub fn canonical_genome(genome_str: &str) -> String {
+    genome_str
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(value) => canonical_json(&value),
+            Err(_) => line.split_whitespace().collect::<Vec<_>>().join(" "),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Size and mtime of a regular file; missing files and directories add nothing.
+pub fn hash_file_metadata(hasher: &mut StableHasher, path: &Path) {
+    let Ok(meta) = fs::metadata(path) else {
+        return;
+    };
+    if !meta.is_file() {
+        return;
+    }
+    let modified_ms = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    hasher.number(meta.len()).number(modified_ms);
+}
+
+/// Sample ids hashed in sorted order: the split sets matter, not their shuffle order.
+fn hash_id_set(hasher: &mut StableHasher, ids: &[String]) {
+    let mut sorted: Vec<&String> = ids.iter().collect();
+    sorted.sort();
+    hasher.number(sorted.len() as u64);
+    for id in sorted {
+        hasher.field(id);
+    }
+}
+
+/// Fingerprint of the data an evaluation trains on: the profile definition, the split
+/// manifest and, for every sample in the split, its locator value and the size/mtime
+/// of the file behind it. Table-backed streams contribute their CSV/index files.
+pub fn dataset_fingerprint(prepared: &PreparedDataset) -> String {
+    let mut hasher = StableHasher::default();
+    let root = PathBuf::from(prepared.profile.source_path.clone().unwrap_or_default());
+    let profile = serde_json::to_value(&prepared.profile).map(|value| canonical_json(&value)).unwrap_or_default();
+    hasher.field(profile);
+
+    for stream in &prepared.profile.streams {
+        match &stream.locator {
+            DataLocatorDef::CsvDataset(def) => hash_file_metadata(&mut hasher, &root.join(&def.csv_path)),
+            DataLocatorDef::MasterIndex { index_path, .. } => hash_file_metadata(&mut hasher, &root.join(index_path)),
+            DataLocatorDef::NumpyArray { path, .. } | DataLocatorDef::ParquetTable { path, .. } => {
+                hash_file_metadata(&mut hasher, &root.join(path))
+            }
+            _ => {}
+        }
+    }
+
+    let split = &prepared.split;
+    for (name, ids) in [("train", &split.train_ids), ("val", &split.val_ids), ("test", &split.test_ids)] {
+        hasher.field(name);
+        hash_id_set(&mut hasher, ids);
+    }
+    for fold in &split.folds {
+        hasher.field("fold");
+        hash_id_set(&mut hasher, &fold.train_ids);
+        hash_id_set(&mut hasher, &fold.test_ids);
+    }
+
+    let mut sample_ids: Vec<&String> = split.train_ids.iter().chain(&split.val_ids).chain(&split.test_ids).collect();
+    sample_ids.sort();
+    let mut stream_ids: Vec<&String> = prepared.loader.stream_files.keys().collect();
+    stream_ids.sort();
+    for stream_id in stream_ids {
+        let files = &prepared.loader.stream_files[stream_id];
+        hasher.field(stream_id);
+        for &id in &sample_ids {
+            let Some(locator) = files.get(id) else {
+                continue;
+            };
+            hasher.field(locator);
+            let path = Path::new(locator);
+            if path.is_absolute() {
+                hash_file_metadata(&mut hasher, path);
+            } else {
+                hash_file_metadata(&mut hasher, &root.join(path));
+            }
+        }
+    }
+
+    hasher.finish_hex()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvalCacheLimits {
+    pub max_entries: usize,
+    pub max_bytes: u64,
+}
+
+impl Default for EvalCacheLimits {
+    fn default() -> Self {
+        EvalCacheLimits {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedEvaluation {
+    pub key: String,
+    pub dataset_profile: String,
+    pub epochs: usize,
+    pub result: EvaluationResult,
+    pub created_at_ms: u64,
+    pub last_used_ms: u64,
+}
+
+/// The part of an entry file needed to rebuild the index.
+#[derive(Deserialize)]
+struct EntryHeader {
+    dataset_profile: String,
+    last_used_ms: u64,
+}
+
+struct IndexEntry {
+    dataset_profile: String,
+    bytes: u64,
+    last_used_ms: u64,
+}
+
+/// Counters since process start plus the current size of the cache.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalCacheStats {
+    pub entries: usize,
+    pub total_bytes: u64,
+    pub max_entries: usize,
+    pub max_bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub writes: u64,
+    pub evictions: u64,
+    pub hit_rate: f32,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// Loaded from disk on first use.
+    index: Option<HashMap<String, IndexEntry>>,
+    limits: EvalCacheLimits,
+    hits: u64,
+    misses: u64,
+    writes: u64,
+    evictions: u64,
+}
+
+pub struct EvalCache {
+    dir: PathBuf,
+    state: Mutex<CacheState>,
+}
+
+fn write_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+impl EvalCache {
+    /// Opens the cache rooted at `dir`. Nothing is read until the first lookup.
+    pub fn open(dir: PathBuf) -> Self {
+        let limits = fs::read_to_string(dir.join(LIMITS_FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        EvalCache {
+            dir,
+            state: Mutex::new(CacheState {
+                limits,
+                ..CacheState::default()
+            }),
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(ENTRIES_DIR).join(format!("{}.json", key))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.index.is_none() {
+            state.index = Some(self.load_index());
+        }
+        state
+    }
+
+    fn load_index(&self) -> HashMap<String, IndexEntry> {
+        let mut index = HashMap::new();
+        let Ok(entries) = fs::read_dir(self.dir.join(ENTRIES_DIR)) else {
+            return index;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(key) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+                continue;
+            };
+            let header = fs::read_to_string(&path)
+                .ok()
+                .and_then(|raw| serde_json::from_str::<EntryHeader>(&raw).ok());
+            match header {
+                Some(header) => {
+                    let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    index.insert(
+                        key,
+                        IndexEntry {
+                            dataset_profile: header.dataset_profile,
+                            bytes,
+                            last_used_ms: header.last_used_ms,
+                        },
+                    );
+                }
+                None => {
+                    eprintln!("[eval_cache] dropping unreadable entry {}", path.display());
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+        index
+    }
+
+    fn remove_entry(&self, state: &mut CacheState, key: &str) {
+        if let Some(index) = state.index.as_mut() {
+            index.remove(key);
+        }
+        let _ = fs::remove_file(self.entry_path(key));
+    }
+
+    /// Evicts least recently used entries until the cache fits its limits.
+    fn enforce_limits(&self, state: &mut CacheState) {
+        let limits = state.limits;
+        let Some(index) = state.index.as_ref() else {
+            return;
+        };
+        let mut total_bytes: u64 = index.values().map(|e| e.bytes).sum();
+        let mut count = index.len();
+        if count <= limits.max_entries && total_bytes <= limits.max_bytes {
+            return;
+        }
+
+        let mut by_age: Vec<(u64, u64, String)> = index
+            .iter()
+            .map(|(key, e)| (e.last_used_ms, e.bytes, key.clone()))
+            .collect();
+        by_age.sort();
+        for (_, bytes, key) in by_age {
+            if count <= limits.max_entries && total_bytes <= limits.max_bytes {
+                break;
+            }
+            self.remove_entry(state, &key);
+            count -= 1;
+            total_bytes = total_bytes.saturating_sub(bytes);
+            state.evictions += 1;
+        }
+    }
+
+    /// Looks up `key`, counting a hit or a miss. Corrupt entries are dropped.
+    pub fn get(&self, key: &str) -> Option<EvaluationResult> {
+        let mut state = self.lock();
+        let known = state.index.as_ref().is_some_and(|index| index.contains_key(key));
+        let cached = if known {
+            fs::read_to_string(self.entry_path(key))
+                .ok()
+                .and_then(|raw| serde_json::from_str::<CachedEvaluation>(&raw).ok())
+        } else {
+            None
+        };
+
+        let Some(mut cached) = cached else {
+            if known {
+                eprintln!("[eval_cache] dropping unreadable entry {}", key);
+                self.remove_entry(&mut state, key);
+            }
+            state.misses += 1;
+            return None;
+        };
+
+        state.hits += 1;
+        cached.last_used_ms = crate::current_unix_ms();
+        if let Some(entry) = state.index.as_mut().and_then(|index| index.get_mut(key)) {
+            entry.last_used_ms = cached.last_used_ms;
+        }
+        if let Ok(json) = serde_json::to_string(&cached)
+            && let Err(e) = write_atomically(&self.entry_path(key), &json)
+        {
+            eprintln!("[eval_cache] failed to refresh entry {}: {}", key, e);
+        }
+        Some(cached.result)
+    }
+
+    pub fn insert(&self, key: &str, dataset_profile: &str, epochs: usize, result: &EvaluationResult) -> Result<(), String> {
+        let now = crate::current_unix_ms();
+        let entry = CachedEvaluation {
+            key: key.to_string(),
+            dataset_profile: dataset_profile.to_string(),
+            epochs,
+            result: result.clone(),
+            created_at_ms: now,
+            last_used_ms: now,
+        };
+        let json = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+
+        let mut state = self.lock();
+        let entries_dir = self.dir.join(ENTRIES_DIR);
+        fs::create_dir_all(&entries_dir)
+            .map_err(|e| format!("Failed to create cache dir {}: {}", entries_dir.display(), e))?;
+        write_atomically(&self.entry_path(key), &json)?;
+        if let Some(index) = state.index.as_mut() {
+            index.insert(
+                key.to_string(),
+                IndexEntry {
+                    dataset_profile: dataset_profile.to_string(),
+                    bytes: json.len() as u64,
+                    last_used_ms: now,
+                },
+            );
+        }
+        state.writes += 1;
+        self.enforce_limits(&mut state);
+        Ok(())
+    }
+
+    /// Drops every entry trained on `dataset_profile`. Returns the number removed.
+    pub fn invalidate_profile(&self, dataset_profile: &str) -> usize {
+        let mut state = self.lock();
+        let keys: Vec<String> = state
+            .index
+            .as_ref()
+            .map(|index| {
+                index
+                    .iter()
+                    .filter(|(_, e)| e.dataset_profile == dataset_profile)
+                    .map(|(key, _)| key.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        for key in &keys {
+            self.remove_entry(&mut state, key);
+        }
+        keys.len()
+    }
+
+    /// Drops every entry. Returns the number removed.
+    pub fn clear(&self) -> usize {
+        let mut state = self.lock();
+        let keys: Vec<String> = state
+            .index
+            .as_ref()
+            .map(|index| index.keys().cloned().collect())
+            .unwrap_or_default();
+        for key in &keys {
+            self.remove_entry(&mut state, key);
+        }
+        keys.len()
+    }
+
+    /// Persists new limits and evicts immediately if the cache no longer fits.
+    pub fn set_limits(&self, limits: EvalCacheLimits) -> Result<(), String> {
+        let mut state = self.lock();
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create cache dir {}: {}", self.dir.display(), e))?;
+        let json = serde_json::to_string_pretty(&limits).map_err(|e| e.to_string())?;
+        write_atomically(&self.dir.join(LIMITS_FILE), &json)?;
+        state.limits = limits;
+        self.enforce_limits(&mut state);
+        Ok(())
+    }
+
+    pub fn stats(&self) -> EvalCacheStats {
+        let state = self.lock();
+        let (entries, total_bytes) = state
+            .index
+            .as_ref()
+            .map(|index| (index.len(), index.values().map(|e| e.bytes).sum()))
+            .unwrap_or((0, 0));
+        let lookups = state.hits + state.misses;
+        EvalCacheStats {
+            entries,
+            total_bytes,
+            max_entries: state.limits.max_entries,
+            max_bytes: state.limits.max_bytes,
+            hits: state.hits,
+            misses: state.misses,
+            writes: state.writes,
+            evictions: state.evictions,
+            hit_rate: if lookups > 0 {
+                state.hits as f32 / lookups as f32
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache() -> (PathBuf, EvalCache) {
+        let dir = std::env::temp_dir().join(format!("eval-cache-test-{}", uuid::Uuid::new_v4()));
+        (dir.clone(), EvalCache::open(dir))
+    }
+
+    fn result(accuracy: f32) -> EvaluationResult {
+        EvaluationResult {
+            genome_id: "g".to_string(),
+            loss: 0.25,
+            accuracy,
+            profiler: None,
+            budget: None,
+            cost: None,
+            latency: None,
+            device_check: None,
+        }
+    }
+
+    #[test]
+    fn canonical_genome_ignores_key_order_and_whitespace() {
+        let a = "{\"node\":\"Dense\",\"params\":{\"units\":4,\"use_bias\":true}}\nCONNECTIONS\n0 1\n";
+        let b = "  {\"params\": {\"use_bias\": true, \"units\": 4}, \"node\": \"Dense\"}\n\nCONNECTIONS\n0   1";
+        assert_eq!(canonical_genome(a), canonical_genome(b));
+        assert_ne!(canonical_genome(a), canonical_genome("CONNECTIONS\n0 1"));
+    }
+
+    #[test]
+    fn entries_survive_reopen_and_track_hits() {
+        let (dir, cache) = temp_cache();
+        assert!(cache.get("k1").is_none());
+        cache.insert("k1", "profile-a", 3, &result(88.0)).expect("insert");
+        assert_eq!(cache.get("k1").map(|r| r.accuracy), Some(88.0));
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.writes, stats.entries), (1, 1, 1, 1));
+
+        let reopened = EvalCache::open(dir.clone());
+        assert_eq!(reopened.get("k1").map(|r| r.loss), Some(0.25));
+        assert_eq!(reopened.invalidate_profile("profile-b"), 0);
+        assert_eq!(reopened.invalidate_profile("profile-a"), 1);
+        assert!(reopened.get("k1").is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted() {
+        let (dir, cache) = temp_cache();
+        cache
+            .set_limits(EvalCacheLimits {
+                max_entries: 2,
+                max_bytes: DEFAULT_MAX_BYTES,
+            })
+            .expect("set limits");
+
+        cache.insert("old", "p", 1, &result(70.0)).expect("insert");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        cache.insert("mid", "p", 1, &result(71.0)).expect("insert");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert!(cache.get("old").is_some());
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        cache.insert("new", "p", 1, &result(72.0)).expect("insert");
+
+        assert!(cache.get("mid").is_none());
+        assert!(cache.get("old").is_some());
+        assert!(cache.get("new").is_some());
+        assert_eq!(cache.stats().evictions, 1);
+
+        // Limits persist with the cache
+        assert_eq!(EvalCache::open(dir.clone()).stats().max_entries, 2);
+        assert_eq!(cache.clear(), 2);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,533 @@
+/// Evaluation Run Checkpoints
+///
+/// Every `evaluate_population` call persists its progress to `<runs root>/<run_id>/`:
+/// - `run.json`: request configuration plus per-genome status and results, rewritten
+///   as soon as a genome starts or finishes
+/// - `split.json`: the train/val/test sample ids, written once
+/// - `epochs/`: per-epoch weight checkpoints of long-running genomes
+///
+/// `resume_evaluation(run_id)` reopens a run, reuses its split, keeps finished genomes
+/// and continues the rest (long jobs from their last completed epoch).
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::EvaluationResult;
+use crate::backend::ExecutionBackend;
+use crate::batch_stream::BatchPipelineConfig;
+use crate::device_filter::{DeviceCheck, DeviceDecision, DeviceFilterConfig};
+use crate::evaluation::{DatasetSplit, EvaluationSource};
+use crate::latency_bench::LatencyBenchmark;
+use crate::profiler::MemoryMode;
+use crate::zero_cost_proxies::{TrainingBudget, ZeroCostConfig};
+
+const MANIFEST_FILE: &str = "run.json";
+const SPLIT_FILE: &str = "split.json";
+const EPOCHS_DIR: &str = "epochs";
+
+/// The `evaluate_population` arguments a run was started with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationRunConfig {
+    pub dataset_profile: String,
+    pub batch_size: usize,
+    pub dataset_percent: usize,
+    pub train_split: usize,
+    pub val_split: usize,
+    pub test_split: usize,
+    pub source_generation: Option<u32>,
+    pub memory_mode: Option<MemoryMode>,
+    pub max_parallel_jobs: Option<usize>,
+    pub execution_mode: Option<String>,
+    pub memory_safety_margin_mb: Option<u64>,
+    pub execution_backend: ExecutionBackend,
+    #[serde(default)]
+    pub batch_pipeline: BatchPipelineConfig,
+    /// Zero-cost scoring before training; decisions are stored per genome
+    #[serde(default)]
+    pub zero_cost: Option<ZeroCostConfig>,
+    /// Inference latency benchmark of every genome before training
+    #[serde(default)]
+    pub latency_benchmark: Option<crate::latency_bench::LatencyBenchConfig>,
+    /// Device check of every genome before training; checks are stored per genome
+    #[serde(default)]
+    pub device_filter: Option<DeviceFilterConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenomeRunStatus {
+    Pending,
+    Running,
+    Completed,
+    CompileFailed,
+    Failed,
+}
+
+impl GenomeRunStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, GenomeRunStatus::Completed | GenomeRunStatus::CompileFailed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// In progress, or the process died before the run could finish.
+    Running,
+    /// Stopped through `stop_evolution` or an error; resumable.
+    Interrupted,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunGenomeEntry {
+    pub index: usize,
+    pub genome_id: String,
+    pub genome_str: String,
+    pub epochs: usize,
+    pub status: GenomeRunStatus,
+    pub result: Option<EvaluationResult>,
+    pub error: Option<String>,
+    pub updated_at_ms: u64,
+    #[serde(default)]
+    pub budget: Option<TrainingBudget>,
+    #[serde(default)]
+    pub device_check: Option<DeviceCheck>,
+    #[serde(default)]
+    pub latency: Option<LatencyBenchmark>,
+}
+
+impl RunGenomeEntry {
+    pub fn pending(index: usize, genome_id: String, genome_str: String, epochs: usize) -> Self {
+        RunGenomeEntry {
+            index,
+            genome_id,
+            genome_str,
+            epochs,
+            status: GenomeRunStatus::Pending,
+            result: None,
+            error: None,
+            updated_at_ms: crate::current_unix_ms(),
+            budget: None,
+            device_check: None,
+            latency: None,
+        }
+    }
+
+    /// Trains the genome for its zero-cost budget. Skipped genomes finish right away
+    /// with the untrained sentinel result.
+    pub fn apply_budget(&mut self, budget: TrainingBudget) {
+        self.epochs = budget.epochs;
+        if budget.is_skipped() {
+            self.status = GenomeRunStatus::Completed;
+            self.result = Some(EvaluationResult {
+                genome_id: self.genome_id.clone(),
+                loss: 999.0,
+                accuracy: 0.0,
+                profiler: None,
+                budget: Some(budget.clone()),
+                cost: None,
+                latency: None,
+                device_check: None,
+            });
+        }
+        self.budget = Some(budget);
+    }
+
+    /// Trains the genome the device check settled on. Genomes that do not fit the
+    /// device finish right away with the untrained sentinel result; penalized ones
+    /// carry their penalty fitness in the attached check, not in `accuracy`.
+    pub fn apply_device_check(&mut self, genome_str: String, check: DeviceCheck) {
+        self.genome_str = genome_str;
+        if check.decision != DeviceDecision::Train {
+            self.status = GenomeRunStatus::Completed;
+            self.result = Some(EvaluationResult {
+                genome_id: self.genome_id.clone(),
+                loss: 999.0,
+                accuracy: 0.0,
+                profiler: None,
+                budget: None,
+                cost: None,
+                latency: None,
+                device_check: Some(check.clone()),
+            });
+        }
+        self.device_check = Some(check);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationRunManifest {
+    pub run_id: String,
+    pub status: RunStatus,
+    pub created_at_ms: u64,
+    pub updated_at_ms: u64,
+    pub config: EvaluationRunConfig,
+    pub genomes: Vec<RunGenomeEntry>,
+}
+
+/// Listing entry for `list_evaluation_runs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluationRunSummary {
+    pub run_id: String,
+    pub status: RunStatus,
+    pub created_at_ms: u64,
+    pub updated_at_ms: u64,
+    pub dataset_profile: String,
+    pub total_genomes: usize,
+    pub finished_genomes: usize,
+}
+
+impl From<&EvaluationRunManifest> for EvaluationRunSummary {
+    fn from(manifest: &EvaluationRunManifest) -> Self {
+        EvaluationRunSummary {
+            run_id: manifest.run_id.clone(),
+            status: manifest.status,
+            created_at_ms: manifest.created_at_ms,
+            updated_at_ms: manifest.updated_at_ms,
+            dataset_profile: manifest.config.dataset_profile.clone(),
+            total_genomes: manifest.genomes.len(),
+            finished_genomes: manifest.genomes.iter().filter(|g| g.status.is_finished()).count(),
+        }
+    }
+}
+
+/// Handle to a run directory. Updates are serialized through an internal lock, so
+/// the handle can be shared across evaluation tasks.
+pub struct RunCheckpoint {
+    dir: PathBuf,
+    manifest: Mutex<EvaluationRunManifest>,
+}
+
+fn write_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+fn read_manifest(dir: &Path) -> Result<EvaluationRunManifest, String> {
+    let path = dir.join(MANIFEST_FILE);
+    let raw = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&raw).map_err(|e| format!("Invalid run manifest {}: {}", path.display(), e))
+}
+
+impl RunCheckpoint {
+    /// Creates a new run directory under `root` and writes its split and manifest.
+    pub fn create(
+        root: &Path,
+        config: EvaluationRunConfig,
+        split: &DatasetSplit,
+        genomes: Vec<RunGenomeEntry>,
+    ) -> Result<Self, String> {
+        let now = crate::current_unix_ms();
+        let run_id = format!("run-{}-{}", now, &uuid::Uuid::new_v4().simple().to_string()[..8]);
+        let dir = root.join(&run_id);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create run dir {}: {}", dir.display(), e))?;
+
+        let split_json = serde_json::to_string(split).map_err(|e| e.to_string())?;
+        write_atomically(&dir.join(SPLIT_FILE), &split_json)?;
+
+        let checkpoint = RunCheckpoint {
+            dir,
+            manifest: Mutex::new(EvaluationRunManifest {
+                run_id,
+                status: RunStatus::Running,
+                created_at_ms: now,
+                updated_at_ms: now,
+                config,
+                genomes,
+            }),
+        };
+        checkpoint.update(|_| {})?;
+        Ok(checkpoint)
+    }
+
+    /// Reopens an existing run. Genomes that were running when it stopped go back to pending.
+    pub fn open(root: &Path, run_id: &str) -> Result<Self, String> {
+        let dir = run_dir(root, run_id)?;
+        let mut manifest = read_manifest(&dir)?;
+        for entry in &mut manifest.genomes {
+            if matches!(entry.status, GenomeRunStatus::Running | GenomeRunStatus::Failed) {
+                entry.status = GenomeRunStatus::Pending;
+            }
+        }
+        manifest.status = RunStatus::Running;
+
+        let checkpoint = RunCheckpoint {
+            dir,
+            manifest: Mutex::new(manifest),
+        };
+        checkpoint.update(|_| {})?;
+        Ok(checkpoint)
+    }
+
+    pub fn run_id(&self) -> String {
+        self.snapshot().run_id
+    }
+
+    pub fn snapshot(&self) -> EvaluationRunManifest {
+        self.manifest.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn load_split(&self) -> Result<DatasetSplit, String> {
+        let path = self.dir.join(SPLIT_FILE);
+        let raw = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&raw).map_err(|e| format!("Invalid split {}: {}", path.display(), e))
+    }
+
+    pub fn epoch_checkpoint_dir(&self) -> PathBuf {
+        self.dir.join(EPOCHS_DIR)
+    }
+
+    /// Result of genome `index` if it already finished in an earlier session.
+    pub fn finished_result(&self, index: usize) -> Option<EvaluationResult> {
+        let manifest = self.manifest.lock().unwrap_or_else(|e| e.into_inner());
+        manifest
+            .genomes
+            .get(index)
+            .filter(|entry| entry.status.is_finished())
+            .and_then(|entry| entry.result.clone())
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut EvaluationRunManifest)) -> Result<(), String> {
+        let mut manifest = self.manifest.lock().unwrap_or_else(|e| e.into_inner());
+        apply(&mut manifest);
+        manifest.updated_at_ms = crate::current_unix_ms();
+        let json = serde_json::to_string_pretty(&*manifest).map_err(|e| e.to_string())?;
+        write_atomically(&self.dir.join(MANIFEST_FILE), &json)
+    }
+
+    fn update_genome(&self, index: usize, apply: impl FnOnce(&mut RunGenomeEntry)) {
+        let persisted = self.update(|manifest| {
+            if let Some(entry) = manifest.genomes.get_mut(index) {
+                apply(entry);
+                entry.updated_at_ms = crate::current_unix_ms();
+            }
+        });
+        if let Err(e) = persisted {
+            eprintln!("[eval_runs] failed to persist genome {} status: {}", index, e);
+        }
+    }
+
+    pub fn mark_running(&self, index: usize) {
+        self.update_genome(index, |entry| entry.status = GenomeRunStatus::Running);
+    }
+
+    /// Returns a genome that was interrupted before finishing to the pending state.
+    pub fn mark_pending(&self, index: usize) {
+        self.update_genome(index, |entry| entry.status = GenomeRunStatus::Pending);
+    }
+
+    pub fn record_result(&self, index: usize, result: &EvaluationResult, source: EvaluationSource) {
+        self.update_genome(index, |entry| {
+            entry.status = if source == EvaluationSource::CompileFailed {
+                GenomeRunStatus::CompileFailed
+            } else {
+                GenomeRunStatus::Completed
+            };
+            entry.result = Some(result.clone());
+            entry.error = None;
+        });
+    }
+
+    pub fn record_failure(&self, index: usize, error: &str) {
+        self.update_genome(index, |entry| {
+            entry.status = GenomeRunStatus::Failed;
+            entry.error = Some(error.to_string());
+        });
+    }
+
+    pub fn finish(&self, status: RunStatus) {
+        if let Err(e) = self.update(|manifest| manifest.status = status) {
+            eprintln!("[eval_runs] failed to persist run status: {}", e);
+        }
+    }
+}
+
+/// All runs under `root`, newest first. Unreadable run directories are skipped.
+pub fn list_runs(root: &Path) -> Vec<EvaluationRunSummary> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut runs: Vec<EvaluationRunSummary> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| read_manifest(&entry.path()).ok())
+        .map(|manifest| EvaluationRunSummary::from(&manifest))
+        .collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.created_at_ms));
+    runs
+}
+
+/// Directory of a run. Only ids of the generated `run-<millis>-<hex>` form are accepted,
+/// so an id from the frontend cannot point outside `root`.
+fn run_dir(root: &Path, run_id: &str) -> Result<PathBuf, String> {
+    let valid = run_id
+        .strip_prefix("run-")
+        .and_then(|rest| rest.split_once('-'))
+        .is_some_and(|(millis, hex)| {
+            !millis.is_empty()
+                && millis.bytes().all(|b| b.is_ascii_digit())
+                && !hex.is_empty()
+                && hex.bytes().all(|b| b.is_ascii_hexdigit())
+        });
+    if !valid {
+        return Err(format!("Invalid evaluation run id '{}'", run_id));
+    }
+    Ok(root.join(run_id))
+}
+
+pub fn delete_run(root: &Path, run_id: &str) -> Result<(), String> {
+    let dir = run_dir(root, run_id)?;
+    if !dir.join(MANIFEST_FILE).exists() {
+        return Err(format!("Evaluation run '{}' not found", run_id));
+    }
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete run {}: {}", dir.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("eval-runs-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn config() -> EvaluationRunConfig {
+        EvaluationRunConfig {
+            dataset_profile: "profile-a".to_string(),
+            batch_size: 32,
+            dataset_percent: 100,
+            train_split: 80,
+            val_split: 10,
+            test_split: 10,
+            source_generation: Some(3),
+            memory_mode: None,
+            max_parallel_jobs: None,
+            execution_mode: None,
+            memory_safety_margin_mb: None,
+            execution_backend: ExecutionBackend::Cpu,
+            batch_pipeline: BatchPipelineConfig::default(),
+            zero_cost: None,
+            latency_benchmark: None,
+            device_filter: None,
+        }
+    }
+
+    fn result(genome_id: &str, accuracy: f32) -> EvaluationResult {
+        EvaluationResult {
+            genome_id: genome_id.to_string(),
+            loss: 0.5,
+            accuracy,
+            profiler: None,
+            budget: None,
+            cost: None,
+            latency: None,
+            device_check: None,
+        }
+    }
+
+    #[test]
+    fn finished_genomes_survive_reopen_and_running_ones_become_pending() {
+        let root = temp_root();
+        let split = DatasetSplit {
+            train_ids: vec!["a".to_string(), "b".to_string()],
+            val_ids: vec!["c".to_string()],
+            test_ids: vec!["d".to_string()],
+            folds: Vec::new(),
+        };
+        let genomes = (0..3)
+            .map(|i| RunGenomeEntry::pending(i, format!("g{}", i), format!("genome {}", i), 2))
+            .collect();
+
+        let checkpoint = RunCheckpoint::create(&root, config(), &split, genomes).expect("create run");
+        let run_id = checkpoint.run_id();
+        checkpoint.record_result(0, &result("g0", 91.0), EvaluationSource::Trained);
+        checkpoint.record_result(1, &result("g1", 0.0), EvaluationSource::CompileFailed);
+        checkpoint.mark_running(2);
+        drop(checkpoint);
+
+        let reopened = RunCheckpoint::open(&root, &run_id).expect("reopen run");
+        let manifest = reopened.snapshot();
+        assert_eq!(manifest.status, RunStatus::Running);
+        assert_eq!(manifest.config.source_generation, Some(3));
+        assert_eq!(manifest.genomes[0].status, GenomeRunStatus::Completed);
+        assert_eq!(manifest.genomes[1].status, GenomeRunStatus::CompileFailed);
+        assert_eq!(manifest.genomes[2].status, GenomeRunStatus::Pending);
+
+        assert_eq!(reopened.finished_result(0).map(|r| r.accuracy), Some(91.0));
+        assert!(reopened.finished_result(2).is_none());
+        assert_eq!(reopened.load_split().expect("split").train_ids.len(), 2);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn skipped_genomes_are_finished_with_their_budget() {
+        let root = temp_root();
+        let zero_cost = ZeroCostConfig { enabled: true, partial_training_epochs: 1, ..ZeroCostConfig::default() };
+        let decided = |decision: &str| crate::zero_cost_proxies::ZeroCostMetrics {
+            synflow: 1.0,
+            normalized_score: 0.2,
+            strategy_decision: decision.to_string(),
+            proxies: Vec::new(),
+        };
+        let mut genomes: Vec<RunGenomeEntry> = (0..2)
+            .map(|i| RunGenomeEntry::pending(i, format!("g{}", i), format!("genome {}", i), 4))
+            .collect();
+        genomes[0].apply_budget(TrainingBudget::plan(decided("skip"), 4, &zero_cost));
+        genomes[1].apply_budget(TrainingBudget::plan(decided("partial_train"), 4, &zero_cost));
+
+        let checkpoint = RunCheckpoint::create(&root, config(), &DatasetSplit::default(), genomes).expect("create run");
+        let run_id = checkpoint.run_id();
+        drop(checkpoint);
+
+        let reopened = RunCheckpoint::open(&root, &run_id).expect("reopen run");
+        let skipped = reopened.finished_result(0).expect("skipped genome is finished");
+        assert_eq!(skipped.budget.map(|b| b.epochs), Some(0));
+        assert!(reopened.finished_result(1).is_none());
+        let manifest = reopened.snapshot();
+        assert_eq!(manifest.genomes[1].epochs, 1);
+        assert_eq!(manifest.genomes[1].budget.as_ref().map(|b| b.requested_epochs), Some(4));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn list_and_delete_runs() {
+        let root = temp_root();
+        let checkpoint = RunCheckpoint::create(
+            &root,
+            config(),
+            &DatasetSplit::default(),
+            vec![RunGenomeEntry::pending(0, "g0".to_string(), "genome".to_string(), 1)],
+        )
+        .expect("create run");
+        checkpoint.record_result(0, &result("g0", 70.0), EvaluationSource::Cache);
+        checkpoint.finish(RunStatus::Completed);
+
+        let runs = list_runs(&root);
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, RunStatus::Completed);
+        assert_eq!(runs[0].finished_genomes, 1);
+
+        delete_run(&root, &runs[0].run_id).expect("delete run");
+        assert!(list_runs(&root).is_empty());
+        assert!(delete_run(&root, "missing").is_err());
+        assert!(delete_run(&root, "run-1-abc").unwrap_err().contains("not found"));
+
+        // Ids that are not generated run ids never reach the filesystem
+        fs::write(root.join(MANIFEST_FILE), "{}").expect("write manifest");
+        for run_id in ["..", ".", "", "run-1-../..", "run-1/../..", "../run-1-ab"] {
+            assert!(delete_run(&root, run_id).unwrap_err().contains("Invalid"));
+            assert!(RunCheckpoint::open(&root, run_id).is_err());
+        }
+        assert!(root.join(MANIFEST_FILE).exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,1492 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use burn::tensor::backend::{AutodiffBackend, Backend};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data_loader::DataLoader;
+use crate::dtos::{ClassSplitCounts, DataStream, DataType, DatasetProfile, DatasetProfilesRoot, SplitSettings, TrainingProfiler};
+use crate::batch_stream::{BatchLoading, BatchPipelineConfig, BatchSource, StreamingBatches};
+use crate::entities::{DynamicBatch, GraphModel};
+use crate::profiler::{MemoryMode, ProfilerCollector};
+use crate::progress::ProgressSink;
+use crate::sampling::{EpochSampler, SamplingStrategy};
+use crate::targets::{TargetSpec, TargetTask};
+use crate::weight_io::InputPreprocessing;
+use crate::zero_cost_proxies::{PROXY_BATCH_SIZE, ZeroCostConfig, ZeroCostMetrics, compute_proxies};
+use crate::{EVAL_CACHE, EVOLUTION_SESSION, EvaluationResult};
+
+/// Number of fresh-weight attempts a genome gets before its best result is kept.
+pub const MAX_RETRIES: usize = 3;
+/// Accuracy (in %) above which an attempt is considered to have learned something.
+pub const RANDOM_CHANCE_THRESHOLD: f32 = 55.0;
+const LEARNING_RATE: f64 = 0.001;
+/// Jobs with at least this many epochs write a checkpoint after every epoch when
+/// `EvaluationContext::epoch_checkpoint_dir` is set.
+pub const MIN_EPOCHS_FOR_CHECKPOINT: usize = 2;
+
+/// Reads `dataset_profiles.json`, either from `path` or from the default location
+/// next to the executable.
+pub fn read_dataset_profiles(path: Option<&Path>) -> Result<String, String> {
+    let path = path.map(Path::to_path_buf).unwrap_or_else(crate::get_dataset_profiles_path);
+    std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read dataset profiles {}: {}", path.display(), e))
+}
+
+/// Finds a profile by id in the persisted `dataset_profiles.json` payload.
+pub fn find_dataset_profile(profiles_json: &str, dataset_profile: &str) -> Result<DatasetProfile, String> {
+    let root: DatasetProfilesRoot = serde_json::from_str(profiles_json)
+        .map_err(|e| format!("Failed to parse dataset_profiles.json: {}", e))?;
+
+    root.state
+        .profiles
+        .into_iter()
+        .find(|p| p.id == dataset_profile)
+        .ok_or_else(|| format!("Dataset profile '{}' not found in profiles JSON", dataset_profile))
+}
+
+/// Sample ids assigned to each split. Persisted with evaluation runs so a resumed
+/// run trains and scores on exactly the same samples.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DatasetSplit {
+    pub train_ids: Vec<String>,
+    pub val_ids: Vec<String>,
+    pub test_ids: Vec<String>,
+    /// Earlier walk-forward folds of a chronological split, oldest first.
+    #[serde(default)]
+    pub folds: Vec<SplitFold>,
+}
+
+/// One walk-forward fold: a test window and every sample before it (minus the gap).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SplitFold {
+    pub train_ids: Vec<String>,
+    pub test_ids: Vec<String>,
+}
+
+/// A dataset that has been located, split and mapped onto model input/output shapes.
+pub struct PreparedDataset {
+    pub profile: DatasetProfile,
+    pub loader: DataLoader,
+    pub split: DatasetSplit,
+    pub input_stream_indices: Vec<usize>,
+    pub target_stream_indices: Vec<usize>,
+    pub input_overrides: Vec<Vec<usize>>,
+    pub output_overrides: Vec<Vec<usize>>,
+    pub is_classification: bool,
+    /// One per target stream, in model output order.
+    pub target_specs: Vec<TargetSpec>,
+}
+
+pub fn prepare_dataset(
+    profile: DatasetProfile,
+    app_data_dir: Option<PathBuf>,
+    dataset_percent: usize,
+    train_split: usize,
+    val_split: usize,
+    test_split: usize,
+) -> Result<PreparedDataset, String> {
+    let loader = create_loader(&profile, app_data_dir)?;
+    let split = split_samples(&profile, &loader, dataset_percent, train_split, val_split, test_split)?;
+    finish_prepared(profile, loader, split)
+}
+
+/// Like `prepare_dataset`, but reuses a previously persisted split. Samples that are
+/// no longer available in the dataset are dropped.
+pub fn prepare_dataset_with_split(
+    profile: DatasetProfile,
+    app_data_dir: Option<PathBuf>,
+    split: DatasetSplit,
+) -> Result<PreparedDataset, String> {
+    let loader = create_loader(&profile, app_data_dir)?;
+    let available: HashSet<&str> = loader.valid_sample_ids.iter().map(String::as_str).collect();
+    let keep_available = |ids: Vec<String>| -> Vec<String> {
+        ids.into_iter().filter(|id| available.contains(id.as_str())).collect()
+    };
+
+    let requested = split.train_ids.len() + split.val_ids.len() + split.test_ids.len();
+    let split = DatasetSplit {
+        train_ids: keep_available(split.train_ids),
+        val_ids: keep_available(split.val_ids),
+        test_ids: keep_available(split.test_ids),
+        folds: split
+            .folds
+            .into_iter()
+            .map(|fold| SplitFold {
+                train_ids: keep_available(fold.train_ids),
+                test_ids: keep_available(fold.test_ids),
+            })
+            .collect(),
+    };
+    let kept = split.train_ids.len() + split.val_ids.len() + split.test_ids.len();
+    if kept < requested {
+        eprintln!(
+            ">>> Persisted split: {} of {} samples are no longer available and were dropped",
+            requested - kept,
+            requested
+        );
+    }
+
+    finish_prepared(profile, loader, split)
+}
+
+fn create_loader(profile: &DatasetProfile, app_data_dir: Option<PathBuf>) -> Result<DataLoader, String> {
+    let source_path_str = profile
+        .source_path
+        .clone()
+        .ok_or_else(|| format!("Profile '{}' has no sourcePath defined", profile.name))?;
+
+    if !std::path::Path::new(&source_path_str).exists() {
+        return Err(format!("Source directory does not exist: {}", source_path_str));
+    }
+
+    println!(">>> Starting DataLoader creation...");
+    let loader = match DataLoader::new(profile.clone(), app_data_dir) {
+        Ok(l) => {
+            println!(">>> DataLoader created successfully!");
+            l
+        }
+        Err(e) => {
+            println!(">>> DataLoader creation FAILED: {}", e);
+            return Err(e);
+        }
+    };
+
+    if loader.valid_sample_ids.is_empty() {
+        return Err(format!(
+            "No valid samples found matching all streams for dataset: {}",
+            source_path_str
+        ));
+    }
+    Ok(loader)
+}
+
+/// Split shuffles are seeded by the profile id, so every generation and every run over
+/// a profile trains on the same samples and hits the same evaluation cache entries.
+fn split_seed(profile: &DatasetProfile) -> u64 {
+    let mut hasher = crate::eval_cache::StableHasher::default();
+    hasher.field("split").field(&profile.id);
+    let digest = hasher.finish_hex();
+    u64::from_str_radix(&digest[..16], 16).unwrap_or_default()
+}
+
+fn split_rng(profile: &DatasetProfile) -> rand::rngs::StdRng {
+    use rand::SeedableRng;
+    rand::rngs::StdRng::seed_from_u64(split_seed(profile))
+}
+
+/// Shuffles the valid samples, keeps `dataset_percent` of them and splits the rest,
+/// stratified by the first categorical target stream when there is one, otherwise by
+/// quantile bins of the first continuous target. Profiles can ask for a plain random
+/// or a chronological split instead.
+fn split_samples(
+    profile: &DatasetProfile,
+    loader: &DataLoader,
+    dataset_percent: usize,
+    train_split: usize,
+    val_split: usize,
+    test_split: usize,
+) -> Result<DatasetSplit, String> {
+    let settings = profile.split.clone().unwrap_or_default();
+    match settings.strategy.as_str() {
+        "chronological" => {
+            return Ok(chronological_split(loader, &settings, dataset_percent, train_split, val_split, test_split));
+        }
+        "" | "stratified" | "random" => {}
+        other => {
+            return Err(format!(
+                "Unknown split strategy '{}'. Expected 'stratified', 'random' or 'chronological'",
+                other
+            ));
+        }
+    }
+    let stratify = settings.strategy != "random";
+
+    let mut valid_ids = loader.valid_sample_ids.clone();
+    let mut rng = split_rng(profile);
+
+    {
+        use rand::seq::SliceRandom;
+        valid_ids.shuffle(&mut rng);
+    }
+
+    // Apply pct
+    let pct = dataset_percent.clamp(1, 100);
+    let use_count = (valid_ids.len() * pct) / 100;
+    let use_count = use_count.max(1);
+    valid_ids.truncate(use_count);
+
+    let total_split = (train_split + val_split + test_split).max(1) as f32;
+    let train_ratio = train_split as f32 / total_split;
+    let val_ratio = val_split as f32 / total_split;
+
+    let mut train_ids = Vec::new();
+    let mut val_ids = Vec::new();
+    let mut test_ids = Vec::new();
+
+    // Find a categorical target stream for stratification
+    let class_stream = class_stream(profile, loader).filter(|_| stratify);
+    let continuous_stream = profile
+        .streams
+        .iter()
+        .find(|s| stratify && s.role == "Target" && loader.target_task(&s.id).is_some_and(TargetTask::is_continuous));
+
+    let groups: Option<Vec<(String, Vec<String>)>> = if let Some(stream) = class_stream {
+        println!(">>> Stratifying split based on categorical stream '{}'...", stream.alias);
+        Some(group_by_class(loader, &stream.id, &valid_ids))
+    } else if let Some(stream) = continuous_stream {
+        println!(">>> Stratifying split based on quantiles of target stream '{}'...", stream.alias);
+        let values = loader.continuous_target_values(stream, &valid_ids);
+        let binned: HashSet<&String> = values.iter().map(|(id, _)| id).collect();
+        let unreadable: Vec<String> = valid_ids.iter().filter(|id| !binned.contains(id)).cloned().collect();
+        let mut groups = crate::targets::quantile_bins(values);
+        if !unreadable.is_empty() {
+            groups.push(("unknown".to_string(), unreadable));
+        }
+        Some(groups)
+    } else {
+        None
+    };
+
+    if let Some(groups) = groups {
+        for (label, mut members) in groups {
+            {
+                use rand::seq::SliceRandom;
+                members.shuffle(&mut rng);
+            }
+            let n = members.len();
+            let t_count = ((n as f32) * train_ratio).round() as usize;
+            let v_count = ((n as f32) * val_ratio).round() as usize;
+
+            let t_count = t_count.min(n);
+            let v_count = v_count.min(n - t_count);
+
+            train_ids.extend(members.iter().take(t_count).cloned());
+            val_ids.extend(members.iter().skip(t_count).take(v_count).cloned());
+            test_ids.extend(members.iter().skip(t_count + v_count).cloned());
+
+            println!("  Group '{}': Total={}, Train={}, Val={}, Test={}", label, n, t_count, v_count, n - t_count - v_count);
+        }
+
+        // Final shuffle of the split sets
+        {
+            use rand::seq::SliceRandom;
+            train_ids.shuffle(&mut rng);
+            val_ids.shuffle(&mut rng);
+            test_ids.shuffle(&mut rng);
+        }
+    } else {
+        // Fallback to random split
+        let train_count = ((valid_ids.len() as f32) * train_ratio).round() as usize;
+        let val_count = ((valid_ids.len() as f32) * val_ratio).round() as usize;
+
+        let train_count = train_count.min(valid_ids.len());
+        let val_count = val_count.min(valid_ids.len() - train_count);
+
+        train_ids = valid_ids.iter().take(train_count).cloned().collect();
+        val_ids = valid_ids.iter().skip(train_count).take(val_count).cloned().collect();
+        test_ids = valid_ids.iter().skip(train_count + val_count).cloned().collect();
+    }
+
+    Ok(DatasetSplit {
+        train_ids,
+        val_ids,
+        test_ids,
+        folds: Vec::new(),
+    })
+}
+
+/// The first Categorical target stream trained as a classification task.
+pub fn class_stream<'a>(profile: &'a DatasetProfile, loader: &DataLoader) -> Option<&'a DataStream> {
+    profile.streams.iter().find(|s| {
+        s.role == "Target"
+            && matches!(s.data_type, DataType::Categorical)
+            && loader.target_task(&s.id) == Some(TargetTask::Classification)
+    })
+}
+
+/// `ids` grouped by their class label in `stream_id`, classes in name order.
+pub fn group_by_class(loader: &DataLoader, stream_id: &str, ids: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for id in ids {
+        let label = loader.get_class_label(stream_id, id).unwrap_or_else(|| "unknown".to_string());
+        groups.entry(label).or_default().push(id.clone());
+    }
+    let mut groups: Vec<(String, Vec<String>)> = groups.into_iter().collect();
+    groups.sort_by(|a, b| a.0.cmp(&b.0));
+    groups
+}
+
+/// Per-epoch resampling of the training ids under the profile's sampling strategy,
+/// seeded by the split seed; `None` for `uniform`. Other strategies need a
+/// classification target.
+fn train_sampler(profile: &DatasetProfile, loader: &DataLoader, ids: &[String]) -> Result<Option<EpochSampler>, String> {
+    let strategy = SamplingStrategy::from_name(profile.sampling.as_deref().unwrap_or(""))?;
+    if strategy == SamplingStrategy::Uniform {
+        return Ok(None);
+    }
+    let Some(stream) = class_stream(profile, loader) else {
+        eprintln!(
+            ">>> Sampling '{}' needs a classification target; using every training sample once",
+            strategy.name()
+        );
+        return Ok(None);
+    };
+
+    let groups = group_by_class(loader, &stream.id, ids);
+    let sampler = EpochSampler::new(strategy, groups, split_seed(profile));
+    eprintln!(
+        ">>> Sampling '{}' over classes of '{}', drawn afresh every epoch: {} training samples -> {}",
+        strategy.name(),
+        stream.alias,
+        ids.len(),
+        sampler.epoch_ids(0).len()
+    );
+    Ok(Some(sampler))
+}
+
+/// Locates the samples and splits all of them the way an evaluation run would, without
+/// fitting any preprocessing. Used by dataset validation and reports.
+pub fn load_and_split(
+    profile: &DatasetProfile,
+    app_data_dir: Option<PathBuf>,
+    train_split: usize,
+    val_split: usize,
+    test_split: usize,
+) -> Result<(DataLoader, DatasetSplit), String> {
+    let loader = create_loader(profile, app_data_dir)?;
+    let split = split_samples(profile, &loader, 100, train_split, val_split, test_split)?;
+    Ok((loader, split))
+}
+
+/// Samples of every class in each split and in the first epoch's sampled training set,
+/// for the first classification target (empty without one).
+pub fn class_split_counts(
+    profile: &DatasetProfile,
+    loader: &DataLoader,
+    split: &DatasetSplit,
+) -> Result<Vec<ClassSplitCounts>, String> {
+    let Some(stream) = class_stream(profile, loader) else {
+        return Ok(Vec::new());
+    };
+
+    let sampled = match train_sampler(profile, loader, &split.train_ids)? {
+        Some(sampler) => sampler.epoch_ids(0),
+        None => split.train_ids.clone(),
+    };
+    let mut counts: Vec<ClassSplitCounts> = Vec::new();
+    let parts = [&split.train_ids, &split.val_ids, &split.test_ids, &sampled];
+    for (part, ids) in parts.into_iter().enumerate() {
+        for (class_name, members) in group_by_class(loader, &stream.id, ids) {
+            let idx = match counts.iter().position(|c| c.class_name == class_name) {
+                Some(idx) => idx,
+                None => {
+                    counts.push(ClassSplitCounts {
+                        class_name,
+                        train: 0,
+                        val: 0,
+                        test: 0,
+                        train_sampled: 0,
+                    });
+                    counts.len() - 1
+                }
+            };
+            let entry = &mut counts[idx];
+            match part {
+                0 => entry.train = members.len(),
+                1 => entry.val = members.len(),
+                2 => entry.test = members.len(),
+                _ => entry.train_sampled = members.len(),
+            }
+        }
+    }
+    counts.sort_by(|a, b| a.class_name.cmp(&b.class_name));
+    Ok(counts)
+}
+
+/// Sample ids in series order: numerically when they are row or window indices.
+fn chronological_order(ids: &[String]) -> Vec<String> {
+    let mut ids = ids.to_vec();
+    ids.sort_by_cached_key(|id| {
+        let index = id.parse::<u64>().ok();
+        (index.is_none(), index.unwrap_or(0), id.clone())
+    });
+    ids
+}
+
+/// Keeps the first `dataset_percent` of the samples in series order and cuts them into
+/// consecutive train, validation and test segments with `gap` samples left out between
+/// segments, so overlapping windows never straddle a boundary. Walk-forward folds
+/// move a test window of the same size back in time, training on everything before it.
+fn chronological_split(
+    loader: &DataLoader,
+    settings: &SplitSettings,
+    dataset_percent: usize,
+    train_split: usize,
+    val_split: usize,
+    test_split: usize,
+) -> DatasetSplit {
+    let mut ids = chronological_order(&loader.valid_sample_ids);
+    let pct = dataset_percent.clamp(1, 100);
+    ids.truncate((ids.len() * pct / 100).max(1));
+
+    let gap = settings.gap.unwrap_or_else(|| loader.sample_overlap());
+    let segments = [train_split, val_split, test_split].iter().filter(|&&s| s > 0).count();
+    let usable = ids.len().saturating_sub(gap * segments.saturating_sub(1));
+
+    let total_split = (train_split + val_split + test_split).max(1) as f32;
+    let train_count = ((usable as f32) * train_split as f32 / total_split).round() as usize;
+    let val_count = ((usable as f32) * val_split as f32 / total_split).round() as usize;
+    let train_count = train_count.min(usable);
+    let val_count = val_count.min(usable - train_count);
+
+    let val_start = train_count + if val_count > 0 { gap } else { 0 };
+    let test_start = (val_start + val_count + gap).min(ids.len());
+    let test_len = ids.len() - test_start;
+
+    let mut folds = Vec::new();
+    if settings.walk_forward_folds > 1 && test_len > 0 {
+        for back in (1..settings.walk_forward_folds).rev() {
+            let Some(fold_test_start) = test_start.checked_sub(back * test_len) else {
+                continue;
+            };
+            let fold_train_end = fold_test_start.saturating_sub(gap);
+            if fold_train_end == 0 {
+                continue;
+            }
+            folds.push(SplitFold {
+                train_ids: ids[..fold_train_end].to_vec(),
+                test_ids: ids[fold_test_start..fold_test_start + test_len].to_vec(),
+            });
+        }
+    }
+
+    println!(
+        ">>> Chronological split: gap={} samples, Train={}, Val={}, Test={}, walk-forward folds={}",
+        gap,
+        train_count,
+        val_count,
+        test_len,
+        folds.len() + 1
+    );
+
+    DatasetSplit {
+        train_ids: ids[..train_count].to_vec(),
+        val_ids: ids[val_start..val_start + val_count].to_vec(),
+        test_ids: ids[test_start..].to_vec(),
+        folds,
+    }
+}
+
+fn finish_prepared(profile: DatasetProfile, mut loader: DataLoader, split: DatasetSplit) -> Result<PreparedDataset, String> {
+    eprintln!(
+        ">>> Split: {} train samples, {} val samples, {} test samples",
+        split.train_ids.len(),
+        split.val_ids.len(),
+        split.test_ids.len()
+    );
+    for (idx, fold) in split.folds.iter().enumerate() {
+        eprintln!(
+            ">>> Walk-forward fold {}: {} train samples, {} test samples",
+            idx + 1,
+            fold.train_ids.len(),
+            fold.test_ids.len()
+        );
+    }
+    // Scaling/encoding statistics must never see validation or test samples
+    loader.fit_tabular(&split.train_ids)?;
+    loader.fit_vision(&split.train_ids)?;
+    loader.fit_targets(&split.train_ids)?;
+
+    // Filter streams by role
+    let input_stream_indices: Vec<usize> = profile
+        .streams
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.role == "Input")
+        .map(|(i, _)| i)
+        .collect();
+    let target_stream_indices: Vec<usize> = profile
+        .streams
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.role == "Target")
+        .map(|(i, _)| i)
+        .collect();
+
+    if input_stream_indices.is_empty() || target_stream_indices.is_empty() {
+        return Err("Dataset Profile must define at least one Input and one Target stream".to_string());
+    }
+
+    // Determine expected input and output overrides based on the profile
+    let mut input_overrides = Vec::new();
+    for &idx in &input_stream_indices {
+        let stream = &profile.streams[idx];
+        match stream.data_type {
+            DataType::Image => {
+                let mut h = 64;
+                let mut w = 64;
+                let mut channels = 3;
+                if let Some(prep) = &stream.preprocessing
+                    && let Some(vision) = &prep.vision
+                {
+                    if vision.resize.len() == 2 {
+                        w = vision.resize[0] as usize;
+                        h = vision.resize[1] as usize;
+                    }
+                    if vision.grayscale {
+                        channels = 1;
+                    }
+                }
+
+                let external_shape = if stream.tensor_shape.len() == 3 {
+                    stream.tensor_shape.clone()
+                } else {
+                    vec![h, w, channels]
+                };
+
+                input_overrides.push(crate::normalize_image_shape_to_internal_chw(&external_shape));
+            }
+            DataType::Vector => {
+                let dim = stream.tensor_shape.first().cloned().unwrap_or(1);
+                input_overrides.push(vec![loader.tabular_width(&stream.id).unwrap_or(dim)]);
+            }
+            DataType::TemporalSequence => {
+                let mut shape = stream.tensor_shape.clone();
+                if let (Some(width), Some(channels)) = (loader.tabular_width(&stream.id), shape.last_mut()) {
+                    *channels = width;
+                }
+                input_overrides.push(shape);
+            }
+            _ => input_overrides.push(vec![1]),
+        }
+    }
+
+    let mut output_overrides = Vec::new();
+    let mut target_specs = Vec::new();
+    for &idx in &target_stream_indices {
+        let stream = &profile.streams[idx];
+        let spec = loader
+            .target_spec(&stream.id)
+            .cloned()
+            .ok_or_else(|| format!("Target stream {} was not fitted", stream.id))?;
+        if spec.task != TargetTask::Classification && stream.data_type == DataType::Image {
+            let dim = stream.tensor_shape.first().cloned().unwrap_or(1);
+            output_overrides.push(vec![loader.tabular_width(&stream.id).unwrap_or(dim)]);
+        } else {
+            // Class count discovered by the loader, or the fitted number of target values
+            output_overrides.push(vec![spec.width]);
+        }
+        target_specs.push(spec);
+    }
+    let is_classification = target_specs.iter().any(|spec| spec.task == TargetTask::Classification);
+
+    Ok(PreparedDataset {
+        profile,
+        loader,
+        split,
+        input_stream_indices,
+        target_stream_indices,
+        input_overrides,
+        output_overrides,
+        is_classification,
+        target_specs,
+    })
+}
+
+/// Loads `ids` through the dataset loader and concatenates them into device batches.
+pub fn assemble_batches<B: Backend>(
+    prepared: &PreparedDataset,
+    ids: &[String],
+    split_name: &str,
+    batch_size: usize,
+    device: &B::Device,
+    is_cancelled: &dyn Fn() -> bool,
+    progress: &dyn ProgressSink,
+) -> Result<Vec<DynamicBatch<B>>, String> {
+    let mut assembled_batches: Vec<DynamicBatch<B>> = Vec::new();
+    let total_chunks = ids.len().div_ceil(batch_size.max(1));
+    for (idx, chunk) in ids.chunks(batch_size.max(1)).enumerate() {
+        // Check cancellation every 10 chunks during assembly
+        if idx % 10 == 0 && is_cancelled() {
+            return Err("Evolution cancelled during batch assembly".to_string());
+        }
+
+        if let Some(batch) = crate::batch_stream::load_batch::<B>(prepared, chunk, device, is_cancelled)? {
+            assembled_batches.push(batch);
+        }
+        progress.batch_assembly(split_name, idx + 1, total_chunks);
+    }
+    Ok(assembled_batches)
+}
+
+/// Train/validation/test batches shared by every genome of an evaluation run.
+pub struct SplitBatches<B: Backend> {
+    pub train: BatchSource<B>,
+    pub val: BatchSource<B>,
+    pub test: BatchSource<B>,
+}
+
+/// Preloads the splits onto `device`, or sets up streaming when `pipeline` asks for it
+/// or (in `Auto` mode) the estimated dataset size exceeds the preload budget.
+pub fn assemble_split_batches<B: Backend>(
+    prepared: &Arc<PreparedDataset>,
+    batch_size: usize,
+    pipeline: &BatchPipelineConfig,
+    device: &B::Device,
+    is_cancelled: &dyn Fn() -> bool,
+    progress: &dyn ProgressSink,
+) -> Result<SplitBatches<B>, String> {
+    assemble_batches_of_split(prepared, &prepared.split, batch_size, pipeline, device, is_cancelled, progress)
+}
+
+/// Batches of every walk-forward fold of the prepared split, oldest first.
+pub fn assemble_fold_batches<B: Backend>(
+    prepared: &Arc<PreparedDataset>,
+    batch_size: usize,
+    pipeline: &BatchPipelineConfig,
+    device: &B::Device,
+    is_cancelled: &dyn Fn() -> bool,
+    progress: &dyn ProgressSink,
+) -> Result<Vec<SplitBatches<B>>, String> {
+    let mut folds = Vec::new();
+    for (idx, fold) in prepared.split.folds.iter().enumerate() {
+        let Some(fold_prepared) = fold_dataset(prepared, fold)? else {
+            eprintln!(
+                ">>> Warning: walk-forward fold {} skipped, its training window encodes to different input/output widths",
+                idx + 1
+            );
+            continue;
+        };
+        let fold_prepared = Arc::new(fold_prepared);
+        folds.push(assemble_batches_of_split(
+            &fold_prepared,
+            &fold_prepared.split,
+            batch_size,
+            pipeline,
+            device,
+            is_cancelled,
+            progress,
+        )?);
+    }
+    Ok(folds)
+}
+
+/// A walk-forward fold as a dataset of its own, with scaling and encoding statistics
+/// fitted on the fold's training window only. `None` when those statistics give other
+/// input or output widths than the final split, since every fold trains the same model.
+fn fold_dataset(prepared: &PreparedDataset, fold: &SplitFold) -> Result<Option<PreparedDataset>, String> {
+    let split = DatasetSplit {
+        train_ids: fold.train_ids.clone(),
+        val_ids: Vec::new(),
+        test_ids: fold.test_ids.clone(),
+        folds: Vec::new(),
+    };
+    let fold_prepared = finish_prepared(prepared.profile.clone(), prepared.loader.unfitted_copy(), split)?;
+    let same_widths = fold_prepared.input_overrides == prepared.input_overrides
+        && fold_prepared.output_overrides == prepared.output_overrides;
+    Ok(same_widths.then_some(fold_prepared))
+}
+
+fn assemble_batches_of_split<B: Backend>(
+    prepared: &Arc<PreparedDataset>,
+    split: &DatasetSplit,
+    batch_size: usize,
+    pipeline: &BatchPipelineConfig,
+    device: &B::Device,
+    is_cancelled: &dyn Fn() -> bool,
+    progress: &dyn ProgressSink,
+) -> Result<SplitBatches<B>, String> {
+    let sampler = train_sampler(&prepared.profile, &prepared.loader, &split.train_ids)?;
+    let train_ids = match &sampler {
+        Some(sampler) => sampler.epoch_ids(0),
+        None => split.train_ids.clone(),
+    };
+    let sample_count = train_ids.len() + split.val_ids.len() + split.test_ids.len();
+    let stream = match pipeline.loading {
+        BatchLoading::Preload => false,
+        BatchLoading::Stream => true,
+        BatchLoading::Auto => {
+            match crate::batch_stream::estimate_preload_mb::<B>(prepared, &train_ids, sample_count, device) {
+                Some(estimated_mb) => {
+                    let stream = estimated_mb > pipeline.preload_budget_mb;
+                    eprintln!(
+                        ">>> Estimated preload size: {} MB (budget {} MB) -> {}",
+                        estimated_mb,
+                        pipeline.preload_budget_mb,
+                        if stream { "streaming batches" } else { "preloading batches" }
+                    );
+                    stream
+                }
+                None => false,
+            }
+        }
+    };
+
+    let source = |ids: &[String]| {
+        StreamingBatches::new(prepared.clone(), ids.to_vec(), batch_size, device.clone(), pipeline)
+    };
+    if train_ids.is_empty() {
+        return Err("No training batches could be assembled. Aborting.".to_string());
+    }
+    // A resampled training split changes every epoch, so it is always streamed
+    let train = match sampler {
+        Some(sampler) => BatchSource::Streaming(source(&train_ids).resampled(sampler)),
+        None if stream => BatchSource::Streaming(source(&train_ids)),
+        None => BatchSource::Preloaded(assemble_batches(
+            prepared,
+            &train_ids,
+            "train",
+            batch_size,
+            device,
+            is_cancelled,
+            progress,
+        )?),
+    };
+    if stream {
+        return Ok(SplitBatches {
+            train,
+            val: BatchSource::Streaming(source(&split.val_ids)),
+            test: BatchSource::Streaming(source(&split.test_ids)),
+        });
+    }
+
+    let val = assemble_batches(prepared, &split.val_ids, "val", batch_size, device, is_cancelled, progress)?;
+    let test = assemble_batches(prepared, &split.test_ids, "test", batch_size, device, is_cancelled, progress)?;
+
+    if train.is_empty() {
+        return Err("No training batches could be assembled. Aborting.".to_string());
+    }
+
+    Ok(SplitBatches {
+        train,
+        val: BatchSource::Preloaded(val),
+        test: BatchSource::Preloaded(test),
+    })
+}
+
+/// Parameters that, together with the genome text and epoch count, identify an evaluation.
+#[derive(Debug, Clone)]
+pub struct TrainingParams {
+    pub dataset_profile: String,
+    pub batch_size: usize,
+    pub dataset_percent: usize,
+    pub train_split: usize,
+    pub val_split: usize,
+    pub test_split: usize,
+}
+
+impl TrainingParams {
+    /// Stable digest of the canonicalised genome and the training parameters.
+    pub fn cache_key(&self, genome_str: &str, epochs: usize) -> String {
+        let mut hasher = crate::eval_cache::StableHasher::default();
+        hasher
+            .field(crate::eval_cache::canonical_genome(genome_str))
+            .field(&self.dataset_profile)
+            .number(self.batch_size as u64)
+            .number(epochs as u64)
+            .number(self.dataset_percent as u64)
+            .number(self.train_split as u64)
+            .number(self.val_split as u64)
+            .number(self.test_split as u64);
+        hasher.finish_hex()
+    }
+}
+
+/// Everything a genome evaluation needs besides the genome itself. Cheap to clone,
+/// so it can be moved into blocking tasks.
+#[derive(Clone)]
+pub struct EvaluationContext<B: Backend> {
+    pub device: B::Device,
+    pub params: TrainingParams,
+    pub input_overrides: Vec<Vec<usize>>,
+    pub output_overrides: Vec<Vec<usize>>,
+    pub is_classification: bool,
+    pub target_specs: Vec<TargetSpec>,
+    pub batches: Arc<SplitBatches<B>>,
+    /// Earlier walk-forward folds; empty unless the chronological split asks for them.
+    pub walk_forward: Arc<Vec<SplitBatches<B>>>,
+    pub memory_mode: MemoryMode,
+    pub session_snapshot: u64,
+    /// Where per-epoch checkpoints of long jobs are kept, so interrupted training can resume.
+    pub epoch_checkpoint_dir: Option<PathBuf>,
+    /// `eval_cache::dataset_fingerprint` of the dataset and split the batches came from.
+    pub dataset_fingerprint: String,
+    /// Stored next to trained weights and exported with them.
+    pub input_preprocessing: Vec<InputPreprocessing>,
+}
+
+impl<B: Backend> EvaluationContext<B> {
+    /// Assembles (or sets up streaming of) the shared split batches on `device` and
+    /// captures the dataset's model input/output shapes. Assembly stops when the
+    /// session is cancelled.
+    pub fn prepare(
+        prepared: &Arc<PreparedDataset>,
+        params: TrainingParams,
+        pipeline: &BatchPipelineConfig,
+        device: B::Device,
+        memory_mode: MemoryMode,
+        session_snapshot: u64,
+        progress: &dyn ProgressSink,
+    ) -> Result<Self, String> {
+        let is_cancelled = || EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot;
+        let batches =
+            assemble_split_batches::<B>(prepared, params.batch_size, pipeline, &device, &is_cancelled, progress)?;
+        let walk_forward =
+            assemble_fold_batches::<B>(prepared, params.batch_size, pipeline, &device, &is_cancelled, progress)?;
+
+        Ok(EvaluationContext {
+            device,
+            params,
+            input_overrides: prepared.input_overrides.clone(),
+            output_overrides: prepared.output_overrides.clone(),
+            is_classification: prepared.is_classification,
+            target_specs: prepared.target_specs.clone(),
+            batches: Arc::new(batches),
+            walk_forward: Arc::new(walk_forward),
+            memory_mode,
+            session_snapshot,
+            epoch_checkpoint_dir: None,
+            dataset_fingerprint: crate::eval_cache::dataset_fingerprint(prepared),
+            input_preprocessing: prepared.loader.input_preprocessing(&prepared.input_stream_indices)?,
+        })
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        EVOLUTION_SESSION.load(Ordering::SeqCst) != self.session_snapshot
+    }
+
+    /// Persistent cache key: genome and training parameters plus the dataset fingerprint.
+    pub fn cache_key(&self, genome_str: &str, epochs: usize) -> String {
+        let mut hasher = crate::eval_cache::StableHasher::default();
+        hasher
+            .field(self.params.cache_key(genome_str, epochs))
+            .field(&self.dataset_fingerprint);
+        hasher.finish_hex()
+    }
+}
+
+#[derive(Clone)]
+pub struct GenomeJob {
+    pub index: usize,
+    pub genome_id: String,
+    pub genome_str: String,
+    pub epochs: usize,
+    pub queue_wait_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvaluationSource {
+    Cache,
+    Trained,
+    CompileFailed,
+}
+
+#[derive(Clone)]
+pub struct GenomeEvaluation {
+    pub result: EvaluationResult,
+    pub source: EvaluationSource,
+}
+
+fn failed_result(genome_id: String) -> EvaluationResult {
+    EvaluationResult {
+        genome_id,
+        loss: 999.0,
+        accuracy: 0.0,
+        profiler: None,
+        budget: None,
+        cost: None,
+        latency: None,
+        device_check: None,
+    }
+}
+
+pub(crate) fn panic_message(err: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(s) = err.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = err.downcast_ref::<String>() {
+        s.as_str()
+    } else {
+        "Unknown panic"
+    }
+}
+
+/// Loads the genome's epoch checkpoint into `model` when one exists, returning the
+/// number of epochs it already completed.
+fn resume_from_epoch_checkpoint<B: AutodiffBackend>(
+    ctx: &EvaluationContext<B>,
+    job: &GenomeJob,
+    model: GraphModel<B>,
+) -> (GraphModel<B>, usize) {
+    let Some(dir) = ctx.epoch_checkpoint_dir.as_deref() else {
+        return (model, 0);
+    };
+    if job.epochs < MIN_EPOCHS_FOR_CHECKPOINT {
+        return (model, 0);
+    }
+
+    let fallback = model.clone();
+    match crate::weight_io::load_epoch_checkpoint(&job.genome_id, model, dir, &ctx.device) {
+        Ok(Some((model, meta))) if meta.total_epochs == job.epochs => {
+            eprintln!(
+                ">>> Genome {} resumes from epoch checkpoint ({}/{} epochs done)",
+                job.index, meta.completed_epochs, meta.total_epochs
+            );
+            (model, meta.completed_epochs)
+        }
+        Ok(_) => (fallback, 0),
+        Err(e) => {
+            eprintln!(">>> Genome {}: ignoring unreadable epoch checkpoint: {}", job.index, e);
+            (fallback, 0)
+        }
+    }
+}
+
+/// Trains a model once, starting after `start_epoch` completed epochs, and scores it
+/// on the held-out split, or on walk-forward fold `fold` (never checkpointed).
+fn train_and_score<B: AutodiffBackend>(
+    ctx: &EvaluationContext<B>,
+    job: &GenomeJob,
+    model: GraphModel<B>,
+    start_epoch: usize,
+    fold: Option<usize>,
+    progress: &dyn ProgressSink,
+) -> Result<(f32, f32, TrainingProfiler, GraphModel<B>), String> {
+    let batches = match fold {
+        Some(fold) => &ctx.walk_forward[fold],
+        None => &*ctx.batches,
+    };
+    let mut model_local = model;
+    let mut profiler = ProfilerCollector::new();
+    profiler.set_memory_mode(ctx.memory_mode);
+    profiler.set_queue_wait_ms(job.queue_wait_ms);
+
+    let checkpoint_dir = ctx
+        .epoch_checkpoint_dir
+        .as_deref()
+        .filter(|_| job.epochs >= MIN_EPOCHS_FOR_CHECKPOINT && fold.is_none());
+    let mut save_epoch = |completed_epochs: usize, model: &GraphModel<B>| {
+        let Some(dir) = checkpoint_dir else {
+            return;
+        };
+        let meta = crate::weight_io::EpochCheckpointMeta {
+            genome_id: job.genome_id.clone(),
+            completed_epochs,
+            total_epochs: job.epochs,
+            saved_at_ms: crate::current_unix_ms(),
+        };
+        if let Err(e) = crate::weight_io::save_epoch_checkpoint(model, &meta, dir) {
+            eprintln!("[weight_io] failed to write epoch checkpoint for genome '{}': {}", job.genome_id, e);
+        }
+    };
+
+    if job.epochs > 0 {
+        crate::entities::run_eval_pass(
+            progress,
+            job.index,
+            &mut model_local,
+            &batches.train,
+            job.epochs,
+            LEARNING_RATE,
+            ctx.is_classification,
+            &EVOLUTION_SESSION,
+            ctx.session_snapshot,
+            job.queue_wait_ms,
+            Some(&mut profiler),
+            Some(crate::entities::EpochCheckpointing {
+                start_epoch,
+                on_epoch_end: &mut save_epoch,
+            }),
+        )?;
+    } else {
+        println!(">>> Genome {}: Skipping training (0 epochs requested)", job.index);
+    }
+
+    if ctx.is_cancelled() {
+        println!(
+            ">>> Genome {} cancelled right after training. Skipping validation/test inside worker.",
+            job.index
+        );
+        return Ok((999.0, 0.0, profiler.finalize(), model_local));
+    }
+
+    let (val_loss, val_acc) = if !batches.val.is_empty() {
+        crate::entities::run_validation_pass(
+            &model_local,
+            &batches.val,
+            "Validation",
+            ctx.is_classification,
+            Some(&mut profiler),
+            progress,
+        )?
+    } else {
+        (0.0, 0.0)
+    };
+
+    if ctx.is_cancelled() {
+        println!(
+            ">>> Genome {} cancelled after validation. Skipping test pass inside worker.",
+            job.index
+        );
+        return Ok((999.0, 0.0, profiler.finalize(), model_local));
+    }
+
+    let (loss, acc) = if !batches.test.is_empty() {
+        crate::entities::run_validation_pass(
+            &model_local,
+            &batches.test,
+            "Test",
+            ctx.is_classification,
+            Some(&mut profiler),
+            progress,
+        )?
+    } else if !batches.val.is_empty() {
+        (val_loss, val_acc)
+    } else {
+        crate::entities::run_validation_pass(
+            &model_local,
+            &batches.train,
+            "Train",
+            ctx.is_classification,
+            None,
+            progress,
+        )?
+    };
+
+    Ok((loss, acc, profiler.finalize(), model_local))
+}
+
+/// Zero-cost metrics of every genome from one proxy run on the first
+/// `PROXY_BATCH_SIZE` training samples. Genomes that fail to build or score get `None`,
+/// as do all of them when no batch can be loaded.
+pub fn score_zero_cost<B: AutodiffBackend>(
+    prepared: &PreparedDataset,
+    genomes: &[String],
+    config: &ZeroCostConfig,
+    device: &B::Device,
+    is_cancelled: &dyn Fn() -> bool,
+) -> Result<Vec<Option<ZeroCostMetrics>>, String> {
+    let ids: Vec<String> = prepared.split.train_ids.iter().take(PROXY_BATCH_SIZE).cloned().collect();
+    let Some(batch) = crate::batch_stream::load_batch::<B>(prepared, &ids, device, is_cancelled)? else {
+        eprintln!(">>> Zero-cost scoring skipped: no training batch could be assembled");
+        return Ok(vec![None; genomes.len()]);
+    };
+
+    let mut scores = Vec::with_capacity(genomes.len());
+    for (i, genome_str) in genomes.iter().enumerate() {
+        if is_cancelled() {
+            return Err("Evolution cancelled during zero-cost scoring".to_string());
+        }
+        let scored = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let model = GraphModel::<B>::build(
+                genome_str,
+                device,
+                Some(&prepared.input_overrides),
+                Some(&prepared.output_overrides),
+            )
+            .with_target_specs(prepared.target_specs.clone());
+            compute_proxies(&model, &batch).map(|proxies| ZeroCostMetrics::from_proxies(proxies, config))
+        }));
+        match scored {
+            Ok(Ok(metrics)) => scores.push(Some(metrics)),
+            Ok(Err(e)) => {
+                eprintln!(">>> Genome {}: zero-cost scoring failed: {}", i, e);
+                scores.push(None);
+            }
+            Err(err) => {
+                eprintln!(">>> Genome {}: zero-cost scoring failed: {}", i, panic_message(err.as_ref()));
+                scores.push(None);
+            }
+        }
+    }
+    Ok(scores)
+}
+
+/// Evaluates one genome: consults the persistent evaluation cache, trains with retries on fresh
+/// weights, checkpoints the best weights and records the result in the cache.
+///
+/// Returns `Err` only when the backend state can no longer be trusted (a panic
+/// during training); genomes that fail to compile yield a sentinel result instead.
+pub fn evaluate_genome<B: AutodiffBackend>(
+    ctx: &EvaluationContext<B>,
+    job: &GenomeJob,
+    progress: &dyn ProgressSink,
+) -> Result<GenomeEvaluation, String> {
+    let i = job.index;
+    let cache_key = ctx.cache_key(&job.genome_str, job.epochs);
+
+    // Check cache for identical genome + training params + dataset
+    if let Some(cached) = EVAL_CACHE.get(&cache_key) {
+        eprintln!(
+            ">>> CACHE HIT for Genome {} (key={}): loss={}, acc={}",
+            i,
+            &cache_key[..16],
+            cached.loss,
+            cached.accuracy
+        );
+        progress.attempt_started(i);
+
+        return Ok(GenomeEvaluation {
+            result: EvaluationResult {
+                genome_id: job.genome_id.clone(),
+                ..cached
+            },
+            source: EvaluationSource::Cache,
+        });
+    }
+
+    eprintln!("Genome JSON:\n{}", job.genome_str);
+
+    let build_model = || {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            GraphModel::<B>::build(
+                &job.genome_str,
+                &ctx.device,
+                Some(&ctx.input_overrides),
+                Some(&ctx.output_overrides),
+            )
+            .with_target_specs(ctx.target_specs.clone())
+        }))
+    };
+
+    if let Err(err) = build_model() {
+        println!(
+            ">>> ABORTED: Genome {} failed to compile cleanly: {}",
+            i,
+            panic_message(err.as_ref())
+        );
+        return Ok(GenomeEvaluation {
+            result: failed_result(job.genome_id.clone()),
+            source: EvaluationSource::CompileFailed,
+        });
+    }
+
+    let mut best_loss = 999.0_f32;
+    let mut best_acc = 0.0_f32;
+    let mut best_profiler: Option<TrainingProfiler> = None;
+    let mut best_model: Option<GraphModel<B>> = None;
+
+    for attempt in 0..MAX_RETRIES {
+        if ctx.is_cancelled() {
+            break;
+        }
+
+        let model = match build_model() {
+            Ok(m) => m,
+            Err(_) => {
+                eprintln!(
+                    ">>> Genome {} attempt {} panicked while rebuilding model. Skipping attempt.",
+                    i,
+                    attempt + 1
+                );
+                continue;
+            }
+        };
+        // Only the first attempt continues an interrupted run; retries start from fresh weights
+        let (model, start_epoch) = if attempt == 0 {
+            resume_from_epoch_checkpoint(ctx, job, model)
+        } else {
+            (model, 0)
+        };
+
+        progress.attempt_started(i);
+
+        if attempt > 0 {
+            eprintln!(
+                ">>> RETRY {}/{} for Genome {} (previous acc={:.2}%, threshold={:.0}%)",
+                attempt + 1,
+                MAX_RETRIES,
+                i,
+                best_acc,
+                RANDOM_CHANCE_THRESHOLD
+            );
+        }
+
+        let trained = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            train_and_score(ctx, job, model, start_epoch, None, progress)
+        }));
+
+        let (final_loss, final_acc, profiler_result, trained_model) = match trained {
+            Ok(Ok(tuple)) => tuple,
+            Ok(Err(e)) => return Err(format!("Genome {} attempt {}: {}", i, attempt + 1, e)),
+            Err(_) => {
+                return Err(format!(
+                    "Genome {} attempt {} panicked during training/validation. Aborting evaluate_population to avoid corrupted WGPU state.",
+                    i,
+                    attempt + 1
+                ));
+            }
+        };
+
+        if ctx.is_cancelled() {
+            eprintln!(">>> Cancelled after training for Genome {}. Skipping val/test.", i);
+            break;
+        }
+
+        let attempt_is_better = attempt == 0 || final_acc > best_acc;
+        if attempt_is_better {
+            best_loss = final_loss;
+            best_acc = final_acc;
+            best_profiler = Some(profiler_result);
+            best_model = Some(trained_model);
+        }
+
+        if final_acc > RANDOM_CHANCE_THRESHOLD {
+            break;
+        }
+
+        if attempt < MAX_RETRIES - 1 {
+            eprintln!(
+                ">>> Genome {} attempt {} got acc={:.2}% (below {:.0}% threshold). Will retry with fresh weights.",
+                i,
+                attempt + 1,
+                final_acc,
+                RANDOM_CHANCE_THRESHOLD
+            );
+        }
+    }
+
+    // Walk-forward: the regular split is the last fold, earlier folds train fresh weights
+    if best_model.is_some() && !ctx.walk_forward.is_empty() {
+        let mut scores = Vec::new();
+        for fold in 0..ctx.walk_forward.len() {
+            if ctx.is_cancelled() {
+                break;
+            }
+            let Ok(model) = build_model() else {
+                continue;
+            };
+            let trained = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                train_and_score(ctx, job, model, 0, Some(fold), progress)
+            }));
+            let (loss, acc) = match trained {
+                Ok(Ok((loss, acc, _, _))) => (loss, acc),
+                Ok(Err(e)) => return Err(format!("Genome {} walk-forward fold {}: {}", i, fold + 1, e)),
+                Err(_) => {
+                    return Err(format!(
+                        "Genome {} panicked during walk-forward fold {}. Aborting evaluate_population to avoid corrupted WGPU state.",
+                        i,
+                        fold + 1
+                    ));
+                }
+            };
+            eprintln!(
+                ">>> Genome {} walk-forward fold {}/{}: loss={:.4}, acc={:.2}%",
+                i,
+                fold + 1,
+                ctx.walk_forward.len() + 1,
+                loss,
+                acc
+            );
+            scores.push((loss, acc));
+        }
+
+        if !ctx.is_cancelled() {
+            scores.push((best_loss, best_acc));
+            let n = scores.len() as f32;
+            best_loss = scores.iter().map(|(loss, _)| loss).sum::<f32>() / n;
+            best_acc = scores.iter().map(|(_, acc)| acc).sum::<f32>() / n;
+        }
+    }
+
+    if let Some(model) = best_model.as_ref() {
+        let cache_dir = crate::get_weight_cache_dir();
+        if let Err(e) = std::fs::create_dir_all(&cache_dir).map_err(|err| err.to_string()) {
+            eprintln!("[weight_io] failed to ensure cache dir: {}", e);
+        } else if let Err(e) = crate::weight_io::save_weights(&job.genome_id, Some(model), &cache_dir)
+            .and_then(|_| crate::weight_io::save_preprocessing(&job.genome_id, &ctx.input_preprocessing, &cache_dir))
+        {
+            eprintln!(
+                "[weight_io] failed to checkpoint weights for genome '{}': {}",
+                job.genome_id, e
+            );
+        }
+    }
+
+    if let Some(dir) = ctx.epoch_checkpoint_dir.as_deref()
+        && !ctx.is_cancelled()
+    {
+        crate::weight_io::clear_epoch_checkpoint(&job.genome_id, dir);
+    }
+
+    let result = EvaluationResult {
+        genome_id: job.genome_id.clone(),
+        loss: best_loss,
+        accuracy: best_acc,
+        profiler: best_profiler,
+        budget: None,
+        cost: None,
+        latency: None,
+        device_check: None,
+    };
+
+    // Any completed run with a finite loss is reusable; accuracy is no gate, since
+    // regression scores and hard classification tasks never pass a chance threshold
+    if result.profiler.is_some()
+        && result.loss.is_finite()
+        && !ctx.is_cancelled()
+        && let Err(e) = EVAL_CACHE.insert(&cache_key, &ctx.params.dataset_profile, job.epochs, &result)
+    {
+        eprintln!("[eval_cache] failed to store genome '{}': {}", job.genome_id, e);
+    }
+
+    Ok(GenomeEvaluation {
+        result,
+        source: EvaluationSource::Trained,
+    })
+}
+
+/// Evaluates `jobs` concurrently on the rayon pool, one genome per task. Meant for
+/// the CPU backend, where training a genome only occupies its own thread.
+///
+/// `on_result` fires as soon as each genome finishes; results are returned in job
+/// order. Jobs still queued when the session is cancelled are dropped.
+pub fn evaluate_genomes_parallel<B: AutodiffBackend>(
+    ctx: &EvaluationContext<B>,
+    jobs: Vec<GenomeJob>,
+    progress: &dyn ProgressSink,
+    on_result: &(dyn Fn(&GenomeJob, &GenomeEvaluation) + Sync),
+) -> Vec<(GenomeJob, Result<GenomeEvaluation, String>)> {
+    jobs.into_par_iter()
+        .filter_map(|job| {
+            if ctx.is_cancelled() {
+                return None;
+            }
+            let outcome = evaluate_genome(ctx, &job, progress);
+            if let Ok(evaluation) = &outcome {
+                on_result(&job, evaluation);
+            }
+            Some((job, outcome))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> TrainingParams {
+        TrainingParams {
+            dataset_profile: "profile-a".to_string(),
+            batch_size: 32,
+            dataset_percent: 100,
+            train_split: 80,
+            val_split: 10,
+            test_split: 10,
+        }
+    }
+
+    #[test]
+    fn cache_key_changes_with_any_training_parameter() {
+        let base = params();
+        let key = base.cache_key("genome", 3);
+
+        assert_eq!(key, params().cache_key("genome", 3));
+        assert_ne!(key, base.cache_key("genome", 4));
+        assert_ne!(key, base.cache_key("other genome", 3));
+
+        let mut other = params();
+        other.batch_size = 64;
+        assert_ne!(key, other.cache_key("genome", 3));
+    }
+
+    #[test]
+    fn find_dataset_profile_reports_missing_id() {
+        let json = r#"{"state":{"profiles":[{"id":"p1","name":"P1","sourcePath":null,"streams":[]}]}}"#;
+
+        let found = find_dataset_profile(json, "p1").expect("profile p1 exists");
+        assert_eq!(found.name, "P1");
+
+        let err = find_dataset_profile(json, "missing").expect_err("missing profile errors");
+        assert!(err.contains("missing"));
+    }
+
+    #[test]
+    fn chronological_split_keeps_overlapping_windows_apart() {
+        let root = std::env::temp_dir().join(format!("test_chrono-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).expect("create root");
+        let rows: String = (0..40).map(|i| format!("{},{}\n", i, i * 2)).collect();
+        std::fs::write(root.join("series.csv"), format!("x,y\n{}", rows)).expect("write csv");
+
+        let csv = |mode: &str, features: &str, target: &str, horizon: &str| {
+            format!(
+                r#"{{"type":"CsvDataset","csvPath":"series.csv","hasHeaders":true,"sampleMode":"{}","featureColumns":[{}],"targetColumn":"{}","windowSize":4,"windowStride":2,"horizon":{},"preprocessing":{{"normalization":"none","handleMissing":"skip"}}}}"#,
+                mode, features, target, horizon
+            )
+        };
+        let json = format!(
+            r#"{{"state":{{"profiles":[{{"id":"p","name":"P","sourcePath":{:?},"split":{{"strategy":"chronological","walkForwardFolds":3}},"streams":[
+                {{"id":"w","alias":"W","role":"Input","dataType":"TemporalSequence","tensorShape":[4,1],"locator":{}}},
+                {{"id":"t","alias":"T","role":"Target","dataType":"Vector","tensorShape":[1],"taskType":"regression","locator":{}}}]}}]}}}}"#,
+            root.to_string_lossy(),
+            csv("temporal_window", "\"x\"", "", "null"),
+            csv("row", "", "y", "1")
+        );
+        let profile = find_dataset_profile(&json, "p").expect("profile parses");
+        let prepared = prepare_dataset(profile, None, 100, 60, 20, 20).expect("dataset prepares");
+
+        // 18 windows of 4 rows every 2 rows with one forecast row; a window and its
+        // target span 5 rows, so 2 neighbours on each side share rows with it
+        assert_eq!(prepared.loader.valid_sample_ids.len(), 18);
+        assert_eq!(prepared.loader.sample_overlap(), 2);
+
+        let index = |ids: &[String]| ids.iter().map(|id| id.parse::<usize>().unwrap()).collect::<Vec<_>>();
+        let (train, val, test) = (index(&prepared.split.train_ids), index(&prepared.split.val_ids), index(&prepared.split.test_ids));
+        assert_eq!(train, (0..8).collect::<Vec<_>>());
+        assert_eq!(val, vec![10, 11, 12]);
+        assert_eq!(test, vec![15, 16, 17]);
+
+        // Earlier folds move the test window back and train on everything before it
+        assert_eq!(prepared.split.folds.len(), 2);
+        let last = &prepared.split.folds[1];
+        assert_eq!(index(&last.test_ids), vec![12, 13, 14]);
+        assert_eq!(index(&last.train_ids), (0..10).collect::<Vec<_>>());
+
+        // Each fold standardizes its targets on its own training window
+        let fold = fold_dataset(&prepared, last).unwrap().expect("fold keeps the model widths");
+        let target = &prepared.profile.streams[1];
+        let rows: Vec<Vec<f32>> = last.train_ids.iter().map(|id| prepared.loader.raw_target(target, id).unwrap()).collect();
+        let fold_scaler = fold.loader.target_spec("t").unwrap().scaler.clone();
+        assert_eq!(fold_scaler, crate::targets::TargetScaler::fit(rows.iter().map(Vec::as_slice)));
+        assert_ne!(fold_scaler, prepared.loader.target_spec("t").unwrap().scaler);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn repeated_preparation_gives_the_same_split_and_fingerprint() {
+        let root = std::env::temp_dir().join(format!("test_split_seed-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).expect("create root");
+        let rows: String = (0..30).map(|i| format!("{},{}\n", i, i % 3)).collect();
+        std::fs::write(root.join("table.csv"), format!("x,y\n{}", rows)).expect("write csv");
+
+        let csv = |features: &str, target: &str| {
+            format!(
+                r#"{{"type":"CsvDataset","csvPath":"table.csv","hasHeaders":true,"sampleMode":"row","featureColumns":[{}],"targetColumn":"{}","preprocessing":{{"normalization":"none","handleMissing":"skip"}}}}"#,
+                features, target
+            )
+        };
+        let json = format!(
+            r#"{{"state":{{"profiles":[{{"id":"p","name":"P","sourcePath":{:?},"split":{{"strategy":"random"}},"streams":[
+                {{"id":"x","alias":"X","role":"Input","dataType":"Vector","tensorShape":[1],"locator":{}}},
+                {{"id":"y","alias":"Y","role":"Target","dataType":"Vector","tensorShape":[1],"taskType":"regression","locator":{}}}]}}]}}}}"#,
+            root.to_string_lossy(),
+            csv("\"x\"", ""),
+            csv("", "y")
+        );
+        let prepare = || {
+            let profile = find_dataset_profile(&json, "p").expect("profile parses");
+            prepare_dataset(profile, None, 100, 60, 20, 20).expect("dataset prepares")
+        };
+        let (first, second) = (prepare(), prepare());
+
+        assert_eq!(first.split.train_ids, second.split.train_ids);
+        assert_eq!(first.split.test_ids, second.split.test_ids);
+        assert_eq!(crate::eval_cache::dataset_fingerprint(&first), crate::eval_cache::dataset_fingerprint(&second));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,1009 @@
+/// Headless Evolution Driver
+///
+/// Rust port of the frontend evolution loop (`useEvolutionLoop.ts`) for running
+/// searches without the GUI:
+/// - Genomes are manipulated in their serialized text form (JSON node per line,
+///   `CONNECTIONS`, then `from to` edges), the same format `GraphModel::build` reads
+/// - Each generation is scored through `evaluation::evaluate_genome`
+/// - Selection is elitism + tournament, variation is parameter crossover plus
+///   structural and parameter mutations
+/// - Lineage is tracked in a `GenealogyStore`, termination by `check_stopping_criteria`
+///
+/// Every generation is written to `output_dir` as JSON, together with the best genome.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use burn::tensor::backend::AutodiffBackend;
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::ExecutionBackend;
+use crate::dtos::{GenomeObjectives, MutationType, StoppingCriterion};
+use crate::evaluation::{
+    EvaluationContext, EvaluationSource, GenomeEvaluation, GenomeJob, TrainingParams,
+};
+use crate::genealogy::GenealogyStore;
+use crate::pareto::Objective;
+use crate::pareto_archive::{ParetoArchive, ParetoArchiveConfig};
+use crate::progress::ProgressSink;
+use crate::stopping_criteria::{EvolutionProgressState, check_stopping_criteria, validate_stopping_config};
+
+const DENSE_ACTIVATIONS: [&str; 3] = ["relu", "leaky_relu", "softmax"];
+const CONV_DILATIONS: [u64; 4] = [1, 2, 4, 8];
+/// Node types that keep their input shape and can be inserted or removed freely.
+const SHAPE_PRESERVING_NODES: [&str; 5] = ["Dropout", "Dropout2D", "GaussianNoise", "BatchNorm", "LayerNorm"];
+const BREED_ATTEMPT_MULTIPLIER: usize = 20;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MutationRates {
+    pub add_node: f64,
+    pub remove_node: f64,
+    /// Per-parameter probability used when mutating layer hyperparameters.
+    pub params: f64,
+}
+
+impl Default for MutationRates {
+    fn default() -> Self {
+        MutationRates {
+            add_node: 0.2,
+            remove_node: 0.1,
+            params: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvolutionConfig {
+    pub dataset_profile: String,
+    /// Path to `dataset_profiles.json`; defaults to the one next to the executable.
+    pub profiles_path: Option<PathBuf>,
+    /// Directory used for the dataset image cache.
+    pub app_data_dir: Option<PathBuf>,
+    /// Genome files used to seed the initial population.
+    pub seed_genomes: Vec<PathBuf>,
+    pub population_size: usize,
+    /// Hard cap on generations, applied on top of `stopping_criteria`.
+    pub max_generations: u32,
+    pub batch_size: usize,
+    pub eval_epochs: usize,
+    pub dataset_percent: usize,
+    pub train_split: usize,
+    pub val_split: usize,
+    pub test_split: usize,
+    pub elite_fraction: f32,
+    pub crossover_rate: f64,
+    pub mutation_rates: MutationRates,
+    pub max_nodes: Option<usize>,
+    pub parsimony_alpha: f32,
+    pub stopping_criteria: Vec<StoppingCriterion>,
+    pub stopping_policy: String,
+    pub memory_mode: crate::profiler::MemoryMode,
+    /// `cpu` trains each generation's genomes in parallel on the rayon pool.
+    pub execution_backend: ExecutionBackend,
+    /// Preload vs. streaming of the dataset batches.
+    pub batch_pipeline: crate::batch_stream::BatchPipelineConfig,
+    pub output_dir: PathBuf,
+    pub seed: Option<u64>,
+}
+
+impl Default for EvolutionConfig {
+    fn default() -> Self {
+        EvolutionConfig {
+            dataset_profile: String::new(),
+            profiles_path: None,
+            app_data_dir: None,
+            seed_genomes: Vec::new(),
+            population_size: 10,
+            max_generations: 10,
+            batch_size: 32,
+            eval_epochs: 1,
+            dataset_percent: 100,
+            train_split: 80,
+            val_split: 10,
+            test_split: 10,
+            elite_fraction: 0.1,
+            crossover_rate: 0.5,
+            mutation_rates: MutationRates::default(),
+            max_nodes: None,
+            parsimony_alpha: 0.0,
+            stopping_criteria: Vec::new(),
+            stopping_policy: "any".to_string(),
+            memory_mode: crate::profiler::MemoryMode::Hybrid,
+            execution_backend: ExecutionBackend::Gpu,
+            batch_pipeline: crate::batch_stream::BatchPipelineConfig::default(),
+            output_dir: PathBuf::from("evolution_output"),
+            seed: None,
+        }
+    }
+}
+
+impl EvolutionConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.dataset_profile.is_empty() {
+            return Err("dataset_profile must be set".to_string());
+        }
+        if self.seed_genomes.is_empty() {
+            return Err("At least one seed genome is required".to_string());
+        }
+        if self.population_size < 2 {
+            return Err("population_size must be >= 2".to_string());
+        }
+        if self.max_generations == 0 {
+            return Err("max_generations must be > 0".to_string());
+        }
+        if self.batch_size == 0 {
+            return Err("batch_size must be > 0".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.elite_fraction) {
+            return Err("elite_fraction must be within [0, 1]".to_string());
+        }
+        if !self.stopping_criteria.is_empty() {
+            validate_stopping_config(&self.stopping_criteria, &self.stopping_policy)?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Genome graph operations
+// ---------------------------------------------------------------------------
+
+/// Editable view of a serialized genome. Node parameters are kept as raw JSON so
+/// that every node type round-trips unchanged, including ones the driver never mutates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenomeGraph {
+    pub nodes: Vec<Value>,
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl GenomeGraph {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        let mut parsing_connections = false;
+
+        for line in raw.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            if line == "CONNECTIONS" {
+                parsing_connections = true;
+                continue;
+            }
+            if parsing_connections {
+                let parts: Vec<usize> = line
+                    .split_whitespace()
+                    .map(|s| s.parse::<usize>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| format!("Invalid connection '{}': {}", line, e))?;
+                if parts.len() != 2 {
+                    return Err(format!("Invalid connection '{}': expected 'from to'", line));
+                }
+                edges.push((parts[0], parts[1]));
+            } else {
+                let node: Value =
+                    serde_json::from_str(line).map_err(|e| format!("Invalid node '{}': {}", line, e))?;
+                if node.get("node").and_then(Value::as_str).is_none() {
+                    return Err(format!("Node is missing its 'node' type: {}", line));
+                }
+                nodes.push(node);
+            }
+        }
+
+        let graph = GenomeGraph { nodes, edges };
+        graph.validate()?;
+        Ok(graph)
+    }
+
+    pub fn to_genome_string(&self) -> String {
+        let mut lines: Vec<String> = self.nodes.iter().map(|n| n.to_string()).collect();
+        lines.push("CONNECTIONS".to_string());
+        lines.extend(self.edges.iter().map(|(from, to)| format!("{} {}", from, to)));
+        lines.join("\n")
+    }
+
+    pub fn node_type(&self, idx: usize) -> &str {
+        self.nodes[idx].get("node").and_then(Value::as_str).unwrap_or("")
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let n = self.nodes.len();
+        if !(0..n).any(|i| self.node_type(i) == "Input") {
+            return Err("Genome has no Input node".to_string());
+        }
+        if !(0..n).any(|i| self.node_type(i) == "Output") {
+            return Err("Genome has no Output node".to_string());
+        }
+
+        let mut in_degrees = vec![0usize; n];
+        for &(from, to) in &self.edges {
+            if from >= n || to >= n {
+                return Err(format!("Connection {} -> {} references a missing node", from, to));
+            }
+            in_degrees[to] += 1;
+        }
+
+        // Kahn's algorithm: every node must be reachable in topological order
+        let mut queue: Vec<usize> = (0..n).filter(|&i| in_degrees[i] == 0).collect();
+        let mut visited = 0;
+        while let Some(node) = queue.pop() {
+            visited += 1;
+            for &(from, to) in &self.edges {
+                if from == node {
+                    in_degrees[to] -= 1;
+                    if in_degrees[to] == 0 {
+                        queue.push(to);
+                    }
+                }
+            }
+        }
+        if visited != n {
+            return Err("Genome graph contains a cycle".to_string());
+        }
+        Ok(())
+    }
+
+    fn in_degree(&self, idx: usize) -> usize {
+        self.edges.iter().filter(|&&(_, to)| to == idx).count()
+    }
+
+    fn out_degree(&self, idx: usize) -> usize {
+        self.edges.iter().filter(|&&(from, _)| from == idx).count()
+    }
+
+    /// Nodes whose width reaches an Output unchanged: wired to it directly or through
+    /// shape-preserving layers and Flatten. Their units/filters must stay as they are.
+    pub fn output_feeders(&self) -> Vec<usize> {
+        let mut feeders = Vec::new();
+        let mut pending: Vec<usize> = (0..self.nodes.len()).filter(|&i| self.node_type(i) == "Output").collect();
+        while let Some(node) = pending.pop() {
+            for &(from, to) in &self.edges {
+                if to != node || feeders.contains(&from) {
+                    continue;
+                }
+                feeders.push(from);
+                let node_type = self.node_type(from);
+                if SHAPE_PRESERVING_NODES.contains(&node_type) || node_type == "Flatten" {
+                    pending.push(from);
+                }
+            }
+        }
+        feeders
+    }
+
+    /// Mutates layer hyperparameters, each with probability `rate`, using the same
+    /// value ranges as the frontend node `Mutate` implementations. The width of a
+    /// layer feeding an Output is left alone.
+    /// Returns the number of nodes that changed.
+    pub fn mutate_params(&mut self, rng: &mut StdRng, rate: f64) -> usize {
+        let rate = rate.clamp(0.0, 1.0);
+        let feeders = self.output_feeders();
+        let mut changed = 0;
+
+        for (idx, node) in self.nodes.iter_mut().enumerate() {
+            let keeps_width = feeders.contains(&idx);
+            let node_type = node.get("node").and_then(Value::as_str).unwrap_or("").to_string();
+            let Some(params) = node.get_mut("params").and_then(Value::as_object_mut) else {
+                continue;
+            };
+            let before = params.clone();
+
+            match node_type.as_str() {
+                "Dense" => {
+                    if !keeps_width && rng.random_bool(rate) {
+                        params.insert("units".into(), Value::from(2u64.pow(rng.random_range(4..=12))));
+                    }
+                    if rng.random_bool(rate) {
+                        let activation = DENSE_ACTIVATIONS[rng.random_range(0..DENSE_ACTIVATIONS.len())];
+                        params.insert("activation".into(), Value::from(activation));
+                    }
+                    if rng.random_bool(rate) {
+                        let use_bias = params.get("use_bias").and_then(Value::as_bool).unwrap_or(true);
+                        params.insert("use_bias".into(), Value::from(!use_bias));
+                    }
+                }
+                "Conv2D" | "Conv1D" => {
+                    if !keeps_width && rng.random_bool(rate) {
+                        params.insert("filters".into(), Value::from(4 * rng.random_range(4..=16u64)));
+                    }
+                    if rng.random_bool(rate) {
+                        let kernel = 1 + 2 * rng.random_range(0..=3u64);
+                        let kernel_value = if node_type == "Conv2D" {
+                            serde_json::json!({ "h": kernel, "w": kernel })
+                        } else {
+                            Value::from(kernel)
+                        };
+                        params.insert("kernel_size".into(), kernel_value);
+                    }
+                    if rng.random_bool(rate) {
+                        params.insert("stride".into(), Value::from(rng.random_range(1..=2u64)));
+                    }
+                    if rng.random_bool(rate) {
+                        params.insert("padding".into(), Value::from(rng.random_range(1..=2u64)));
+                    }
+                    if rng.random_bool(rate) {
+                        let dilation = CONV_DILATIONS[rng.random_range(0..CONV_DILATIONS.len())];
+                        params.insert("dilation".into(), Value::from(dilation));
+                    }
+                    if rng.random_bool(rate) {
+                        let use_bias = params.get("use_bias").and_then(Value::as_bool).unwrap_or(true);
+                        params.insert("use_bias".into(), Value::from(!use_bias));
+                    }
+                }
+                "Dropout" | "Dropout2D" if rng.random_bool(rate) => {
+                    let prob = params.get("prob").and_then(Value::as_f64).unwrap_or(0.5);
+                    let nudged = (prob + (rng.random::<f64>() - 0.5) * 0.2).clamp(0.0, 1.0);
+                    params.insert("prob".into(), Value::from(nudged));
+                }
+                "LSTM" | "GRU" if !keeps_width && rng.random_bool(rate) => {
+                    params.insert("hidden_units".into(), Value::from(2u64.pow(rng.random_range(4..=9))));
+                }
+                _ => {}
+            }
+
+            if *params != before {
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+
+    /// Splits a random connection with a new node. A Dense layer is inserted between
+    /// flat layers, a Dropout layer anywhere else; the layer feeding an Output keeps its
+    /// width, so only Dropout goes in front of one. Returns the inserted node type and
+    /// the split connection.
+    pub fn insert_node(&mut self, rng: &mut StdRng, max_nodes: Option<usize>) -> Option<(String, usize, usize)> {
+        if self.edges.is_empty() || max_nodes.is_some_and(|max| self.nodes.len() >= max) {
+            return None;
+        }
+
+        let edge_idx = rng.random_range(0..self.edges.len());
+        let (from, to) = self.edges[edge_idx];
+        let flat_source = matches!(self.node_type(from), "Dense" | "Flatten");
+        let flat_target = self.node_type(to) == "Dense";
+
+        let node = if flat_source && flat_target && rng.random_bool(0.5) {
+            serde_json::json!({
+                "node": "Dense",
+                "params": {
+                    "units": 2u64.pow(rng.random_range(4..=10)),
+                    "activation": "relu",
+                    "use_bias": true
+                }
+            })
+        } else {
+            serde_json::json!({
+                "node": "Dropout",
+                "params": { "prob": rng.random_range(0.1..0.5) }
+            })
+        };
+        let node_type = node["node"].as_str().unwrap_or_default().to_string();
+
+        let new_idx = self.nodes.len();
+        self.nodes.push(node);
+        self.edges[edge_idx] = (from, new_idx);
+        self.edges.push((new_idx, to));
+        Some((node_type, from, to))
+    }
+
+    /// Removes a random shape-preserving pass-through node and reconnects its
+    /// neighbours. Returns the removed node index.
+    pub fn remove_node(&mut self, rng: &mut StdRng) -> Option<usize> {
+        let candidates: Vec<usize> = (0..self.nodes.len())
+            .filter(|&i| SHAPE_PRESERVING_NODES.contains(&self.node_type(i)))
+            .filter(|&i| self.in_degree(i) == 1 && self.out_degree(i) == 1)
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let removed = candidates[rng.random_range(0..candidates.len())];
+        let source = self.edges.iter().find(|&&(_, to)| to == removed).map(|&(from, _)| from)?;
+        let target = self.edges.iter().find(|&&(from, _)| from == removed).map(|&(_, to)| to)?;
+
+        self.nodes.remove(removed);
+        let reindex = |idx: usize| if idx > removed { idx - 1 } else { idx };
+        let mut edges: Vec<(usize, usize)> = self
+            .edges
+            .iter()
+            .filter(|&&(from, to)| from != removed && to != removed)
+            .map(|&(from, to)| (reindex(from), reindex(to)))
+            .collect();
+        let bridge = (reindex(source), reindex(target));
+        if !edges.contains(&bridge) {
+            edges.push(bridge);
+        }
+        self.edges = edges;
+        Some(removed)
+    }
+}
+
+/// Uniform parameter crossover between parents that share a topology.
+/// Returns `None` when the parents are structurally different.
+pub fn crossover_params(a: &GenomeGraph, b: &GenomeGraph, rng: &mut StdRng) -> Option<GenomeGraph> {
+    if a.nodes.len() != b.nodes.len() || a.edges != b.edges {
+        return None;
+    }
+    if (0..a.nodes.len()).any(|i| a.node_type(i) != b.node_type(i)) {
+        return None;
+    }
+
+    let mut child = a.clone();
+    for (i, node) in child.nodes.iter_mut().enumerate() {
+        if rng.random_bool(0.5) {
+            *node = b.nodes[i].clone();
+        }
+    }
+    Some(child)
+}
+
+/// Base fitness mirrors the frontend: accuracy when available, otherwise a
+/// loss-derived score, minus the parsimony penalty.
+pub fn compute_fitness(loss: f32, accuracy: f32, node_count: usize, parsimony_alpha: f32) -> f32 {
+    let base = if accuracy > 0.0 { accuracy } else { 1.0 / (1.0 + loss) };
+    base - parsimony_alpha * node_count as f32
+}
+
+// ---------------------------------------------------------------------------
+// Evolution loop
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Individual {
+    pub genome_id: String,
+    #[serde(skip)]
+    pub genome: GenomeGraph,
+    pub parent_ids: Vec<String>,
+    pub node_count: usize,
+    pub loss: Option<f32>,
+    pub accuracy: Option<f32>,
+    pub fitness: Option<f32>,
+}
+
+impl Individual {
+    fn new(genome: GenomeGraph, parent_ids: Vec<String>) -> Self {
+        Individual {
+            genome_id: uuid::Uuid::new_v4().to_string(),
+            node_count: genome.nodes.len(),
+            genome,
+            parent_ids,
+            loss: None,
+            accuracy: None,
+            fitness: None,
+        }
+    }
+
+    /// Objectives the run's Pareto archive tracks: accuracy against node count.
+    fn objectives(&self) -> Option<GenomeObjectives> {
+        Some(GenomeObjectives {
+            genome_id: self.genome_id.clone(),
+            accuracy: self.accuracy?,
+            inference_latency_ms: 0.0,
+            model_size_mb: 0.0,
+            training_time_ms: 0,
+            is_dominated: false,
+            domination_count: 0,
+            flops: None,
+            parameter_count: None,
+            peak_activation_bytes: None,
+            constraint_violation_score: None,
+            custom_metrics: [("node_count".to_string(), self.node_count as f32)].into_iter().collect(),
+        })
+    }
+}
+
+fn archive_config() -> ParetoArchiveConfig {
+    ParetoArchiveConfig {
+        objectives: vec![Objective::maximize("accuracy"), Objective::minimize("node_count")],
+        reference_point: None,
+        ideal_point: None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationSummary {
+    pub generation: u32,
+    pub best_genome_id: String,
+    pub best_fitness: f32,
+    pub best_accuracy: f32,
+    pub avg_fitness: f32,
+    pub evaluated: usize,
+    pub cache_hits: usize,
+    pub compile_failures: usize,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EvolutionOutcome {
+    pub generations: Vec<GenerationSummary>,
+    pub best: Option<Individual>,
+    pub stop_reason: String,
+    pub output_dir: String,
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn tournament_select<'a>(population: &'a [Individual], rng: &mut StdRng) -> &'a Individual {
+    let a = &population[rng.random_range(0..population.len())];
+    let b = &population[rng.random_range(0..population.len())];
+    if a.fitness.unwrap_or(0.0) > b.fitness.unwrap_or(0.0) { a } else { b }
+}
+
+fn breed_child(
+    population: &[Individual],
+    config: &EvolutionConfig,
+    rng: &mut StdRng,
+) -> (Individual, MutationType) {
+    let parent_a = tournament_select(population, rng);
+    let mut parent_ids = vec![parent_a.genome_id.clone()];
+    let mut mutation = MutationType::Random;
+
+    let mut genome = parent_a.genome.clone();
+    if rng.random_bool(config.crossover_rate.clamp(0.0, 1.0)) {
+        let parent_b = tournament_select(population, rng);
+        if parent_b.genome_id != parent_a.genome_id
+            && let Some(child) = crossover_params(&parent_a.genome, &parent_b.genome, rng)
+        {
+            genome = child;
+            parent_ids.push(parent_b.genome_id.clone());
+            mutation = MutationType::Crossover {
+                parent1: parent_a.genome_id.clone(),
+                parent2: parent_b.genome_id.clone(),
+            };
+        }
+    }
+
+    let rates = &config.mutation_rates;
+    if rng.random_bool(rates.remove_node.clamp(0.0, 1.0))
+        && let Some(removed) = genome.remove_node(rng)
+    {
+        mutation = MutationType::RemoveNode {
+            node_id: removed.to_string(),
+        };
+    }
+    if rng.random_bool(rates.add_node.clamp(0.0, 1.0))
+        && let Some((node_type, from, to)) = genome.insert_node(rng, config.max_nodes)
+    {
+        mutation = MutationType::AddNode {
+            node_type,
+            source: from.to_string(),
+            target: to.to_string(),
+        };
+    }
+    if genome.mutate_params(rng, rates.params) > 0 && matches!(mutation, MutationType::Random) {
+        mutation = MutationType::ParameterMutation {
+            layer_id: "multiple".to_string(),
+            param_name: "params".to_string(),
+        };
+    }
+
+    (Individual::new(genome, parent_ids), mutation)
+}
+
+/// Runs a full evolution according to `config`. `on_generation` is called after
+/// every evaluated generation; `sink` receives per-genome training progress.
+pub fn run_evolution(
+    config: &EvolutionConfig,
+    sink: &dyn ProgressSink,
+    on_generation: &dyn Fn(&GenerationSummary),
+) -> Result<EvolutionOutcome, String> {
+    config.validate()?;
+    fs::create_dir_all(&config.output_dir)
+        .map_err(|e| format!("Failed to create output dir {}: {}", config.output_dir.display(), e))?;
+    write_json(&config.output_dir.join("config.json"), config)?;
+
+    let seeds = config
+        .seed_genomes
+        .iter()
+        .map(|path| {
+            let raw = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read seed genome {}: {}", path.display(), e))?;
+            GenomeGraph::parse(&raw).map_err(|e| format!("Seed genome {}: {}", path.display(), e))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // Dataset is prepared once and shared by every generation
+    let profiles_json = crate::evaluation::read_dataset_profiles(config.profiles_path.as_deref())?;
+    let profile = crate::evaluation::find_dataset_profile(&profiles_json, &config.dataset_profile)?;
+    let prepared = std::sync::Arc::new(crate::evaluation::prepare_dataset(
+        profile,
+        config.app_data_dir.clone(),
+        config.dataset_percent,
+        config.train_split,
+        config.val_split,
+        config.test_split,
+    )?);
+
+    let session_snapshot = crate::EVOLUTION_SESSION.load(Ordering::SeqCst);
+    let params = TrainingParams {
+        dataset_profile: config.dataset_profile.clone(),
+        batch_size: config.batch_size,
+        dataset_percent: config.dataset_percent,
+        train_split: config.train_split,
+        val_split: config.val_split,
+        test_split: config.test_split,
+    };
+
+    match config.execution_backend {
+        ExecutionBackend::Gpu => {
+            let ctx = EvaluationContext::<crate::backend::TrainBackend>::prepare(
+                &prepared,
+                params,
+                &config.batch_pipeline,
+                crate::backend::create_device(),
+                config.memory_mode,
+                session_snapshot,
+                sink,
+            )?;
+            evolve(config, &seeds, &ctx, sink, on_generation)
+        }
+        ExecutionBackend::Cpu => {
+            let ctx = EvaluationContext::<crate::backend::CpuBackend>::prepare(
+                &prepared,
+                params,
+                &config.batch_pipeline,
+                crate::backend::create_cpu_device(),
+                config.memory_mode,
+                session_snapshot,
+                sink,
+            )?;
+            evolve(config, &seeds, &ctx, sink, on_generation)
+        }
+    }
+}
+
+/// Scores every individual without a fitness yet; genomes run in parallel on the CPU backend.
+fn evaluate_pending<B: AutodiffBackend>(
+    config: &EvolutionConfig,
+    ctx: &EvaluationContext<B>,
+    population: &[Individual],
+    sink: &dyn ProgressSink,
+) -> Result<Vec<(GenomeJob, GenomeEvaluation)>, String> {
+    let jobs: Vec<GenomeJob> = population
+        .iter()
+        .enumerate()
+        .filter(|(_, individual)| individual.fitness.is_none())
+        .map(|(index, individual)| GenomeJob {
+            index,
+            genome_id: individual.genome_id.clone(),
+            genome_str: individual.genome.to_genome_string(),
+            epochs: config.eval_epochs,
+            queue_wait_ms: 0,
+        })
+        .collect();
+
+    let outcomes = match config.execution_backend {
+        ExecutionBackend::Cpu => crate::evaluation::evaluate_genomes_parallel(ctx, jobs, sink, &|_, _| {}),
+        ExecutionBackend::Gpu => {
+            let mut outcomes = Vec::with_capacity(jobs.len());
+            for job in jobs {
+                if ctx.is_cancelled() {
+                    break;
+                }
+                let outcome = crate::evaluation::evaluate_genome(ctx, &job, sink);
+                outcomes.push((job, outcome));
+            }
+            outcomes
+        }
+    };
+    if ctx.is_cancelled() {
+        return Err("Evolution cancelled".to_string());
+    }
+
+    outcomes
+        .into_iter()
+        .map(|(job, outcome)| outcome.map(|evaluation| (job, evaluation)))
+        .collect()
+}
+
+fn evolve<B: AutodiffBackend>(
+    config: &EvolutionConfig,
+    seeds: &[GenomeGraph],
+    ctx: &EvaluationContext<B>,
+    sink: &dyn ProgressSink,
+    on_generation: &dyn Fn(&GenerationSummary),
+) -> Result<EvolutionOutcome, String> {
+    let mut rng = StdRng::seed_from_u64(config.seed.unwrap_or_else(|| rand::rng().random()));
+    let mut genealogy = GenealogyStore::new();
+    let mut population: Vec<Individual> = Vec::with_capacity(config.population_size);
+    for seed in seeds {
+        let founder = Individual::new(seed.clone(), Vec::new());
+        genealogy.register_founder_if_missing(&founder.genome_id, 0);
+        population.push(founder);
+    }
+    while population.len() < config.population_size {
+        let parent = &population[rng.random_range(0..seeds.len())];
+        let mut genome = parent.genome.clone();
+        genome.mutate_params(&mut rng, config.mutation_rates.params.max(0.5));
+        let child = Individual::new(genome, vec![parent.genome_id.clone()]);
+        let _ = genealogy.register_mutation(
+            parent.genome_id.clone(),
+            child.genome_id.clone(),
+            MutationType::ParameterMutation {
+                layer_id: "multiple".to_string(),
+                param_name: "params".to_string(),
+            },
+            0,
+        );
+        population.push(child);
+    }
+    population.truncate(config.population_size);
+
+    let mut progress = EvolutionProgressState::new();
+    let mut archive = ParetoArchive::new(archive_config())?;
+    let mut summaries = Vec::new();
+    let mut best_overall: Option<Individual> = None;
+    let mut stop_reason = format!("Reached max_generations ({})", config.max_generations);
+    let started_at = std::time::Instant::now();
+
+    for generation in 0..config.max_generations {
+        let mut cache_hits = 0;
+        let mut compile_failures = 0;
+        let mut evaluated = 0;
+
+        for (job, evaluation) in evaluate_pending(config, ctx, &population, sink)? {
+            match evaluation.source {
+                EvaluationSource::Cache => cache_hits += 1,
+                EvaluationSource::CompileFailed => compile_failures += 1,
+                EvaluationSource::Trained => {}
+            }
+            evaluated += 1;
+
+            let individual = &mut population[job.index];
+            let result = evaluation.result;
+            individual.loss = Some(result.loss);
+            individual.accuracy = Some(result.accuracy);
+            individual.fitness = Some(compute_fitness(
+                result.loss,
+                result.accuracy,
+                individual.node_count,
+                config.parsimony_alpha,
+            ));
+        }
+
+        population.sort_by(|a, b| {
+            b.fitness
+                .unwrap_or(f32::MIN)
+                .total_cmp(&a.fitness.unwrap_or(f32::MIN))
+        });
+
+        let best = population[0].clone();
+        let avg_fitness =
+            population.iter().filter_map(|p| p.fitness).sum::<f32>() / population.len() as f32;
+        let summary = GenerationSummary {
+            generation,
+            best_genome_id: best.genome_id.clone(),
+            best_fitness: best.fitness.unwrap_or(0.0),
+            best_accuracy: best.accuracy.unwrap_or(0.0),
+            avg_fitness,
+            evaluated,
+            cache_hits,
+            compile_failures,
+            elapsed_ms: started_at.elapsed().as_millis() as u64,
+        };
+
+        write_json(
+            &config.output_dir.join(format!("generation_{:04}.json", generation)),
+            &serde_json::json!({ "summary": &summary, "population": &population }),
+        )?;
+        on_generation(&summary);
+        summaries.push(summary);
+
+        if best_overall
+            .as_ref()
+            .is_none_or(|b| best.fitness.unwrap_or(f32::MIN) > b.fitness.unwrap_or(f32::MIN))
+        {
+            fs::write(config.output_dir.join("best_genome.txt"), best.genome.to_genome_string())
+                .map_err(|e| e.to_string())?;
+            best_overall = Some(best.clone());
+        }
+
+        let objectives: Vec<GenomeObjectives> = population.iter().filter_map(Individual::objectives).collect();
+        archive.update(generation, &objectives);
+
+        // Accuracy comes back in percent, stopping criteria expect a fraction
+        progress.on_generation_complete(
+            generation + 1,
+            best.fitness.unwrap_or(0.0),
+            best.accuracy.unwrap_or(0.0) / 100.0,
+            avg_fitness,
+            archive.coverage(),
+        );
+        if !config.stopping_criteria.is_empty() {
+            let decision =
+                check_stopping_criteria(&config.stopping_criteria, &progress, &config.stopping_policy, false);
+            if decision.should_stop {
+                stop_reason = decision.reason_message;
+                break;
+            }
+        }
+        if generation + 1 == config.max_generations {
+            break;
+        }
+
+        // Breed next generation: elitism + tournament selection
+        let elite_count = ((population.len() as f32 * config.elite_fraction) as usize).max(1);
+        let mut next_gen: Vec<Individual> = population.iter().take(elite_count).cloned().collect();
+        let mut attempts = 0;
+        while next_gen.len() < config.population_size
+            && attempts < config.population_size * BREED_ATTEMPT_MULTIPLIER
+        {
+            attempts += 1;
+            let (child, mutation) = breed_child(&population, config, &mut rng);
+            if child.genome.validate().is_err() {
+                continue;
+            }
+
+            let registered = match &mutation {
+                MutationType::Crossover { parent1, parent2 } => genealogy.register_crossover(
+                    parent1.clone(),
+                    parent2.clone(),
+                    child.genome_id.clone(),
+                    generation + 1,
+                ),
+                _ => genealogy.register_mutation(
+                    child.parent_ids[0].clone(),
+                    child.genome_id.clone(),
+                    mutation,
+                    generation + 1,
+                ),
+            };
+            if let Err(e) = registered {
+                eprintln!("[evolution] genealogy registration failed: {}", e);
+            }
+            next_gen.push(child);
+        }
+        population = next_gen;
+    }
+
+    write_json(&config.output_dir.join("genealogy.json"), genealogy.graph())?;
+
+    let outcome = EvolutionOutcome {
+        generations: summaries,
+        best: best_overall,
+        stop_reason,
+        output_dir: config.output_dir.to_string_lossy().to_string(),
+    };
+    write_json(&config.output_dir.join("summary.json"), &outcome)?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENOME: &str = r#"{"node":"Input","params":{"output_shape":[4]}}
+{"node":"Dense","params":{"units":16,"activation":"relu","use_bias":true}}
+{"node":"Dropout","params":{"prob":0.2}}
+{"node":"Output","params":{"input_shape":[3]}}
+CONNECTIONS
+0 1
+1 2
+2 3"#;
+
+    #[test]
+    fn genome_text_round_trips() {
+        let graph = GenomeGraph::parse(GENOME).expect("parse genome");
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.edges, vec![(0, 1), (1, 2), (2, 3)]);
+
+        let reparsed = GenomeGraph::parse(&graph.to_genome_string()).expect("reparse genome");
+        assert_eq!(graph, reparsed);
+    }
+
+    #[test]
+    fn parse_rejects_cycles_and_dangling_edges() {
+        let cyclic = GENOME.replace("2 3", "2 3\n2 1");
+        assert!(GenomeGraph::parse(&cyclic).is_err());
+
+        let dangling = GENOME.replace("2 3", "2 7");
+        assert!(GenomeGraph::parse(&dangling).is_err());
+    }
+
+    #[test]
+    fn insert_and_remove_keep_graph_valid() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut graph = GenomeGraph::parse(GENOME).unwrap();
+
+        let (inserted, from, to) = graph.insert_node(&mut rng, None).expect("insert node");
+        assert!(inserted == "Dense" || inserted == "Dropout");
+        assert_eq!(graph.nodes.len(), 5);
+        assert!(graph.edges.contains(&(from, 4)) && graph.edges.contains(&(4, to)));
+        graph.validate().expect("valid after insert");
+
+        while graph.remove_node(&mut rng).is_some() {
+            graph.validate().expect("valid after remove");
+        }
+        assert!((0..graph.nodes.len()).all(|i| !SHAPE_PRESERVING_NODES.contains(&graph.node_type(i))));
+        assert_eq!(graph.insert_node(&mut rng, Some(graph.nodes.len())), None);
+    }
+
+    #[test]
+    fn inserted_nodes_keep_the_output_width() {
+        let genome = [
+            r#"{"node":"Input","params":{"output_shape":[4]}}"#,
+            r#"{"node":"Dense","params":{"units":16,"activation":"relu","use_bias":true}}"#,
+            r#"{"node":"Dense","params":{"units":3,"activation":"linear","use_bias":true}}"#,
+            r#"{"node":"Output","params":{"input_shape":[3]}}"#,
+            "CONNECTIONS\n0 1\n1 2\n2 3",
+        ]
+        .join("\n");
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut graph = GenomeGraph::parse(&genome).unwrap();
+            for _ in 0..4 {
+                graph.insert_node(&mut rng, None).expect("insert node");
+            }
+            let cost = crate::cost_analysis::analyze_genome(&graph.to_genome_string(), None, None)
+                .expect("mutated genome builds");
+            let output = cost.layers.iter().find(|layer| layer.op == "Output").expect("output layer");
+            assert_eq!(output.output_shape, vec![3], "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn mutation_keeps_the_width_feeding_the_output_through_dropout() {
+        let genome = [
+            r#"{"node":"Input","params":{"output_shape":[4]}}"#,
+            r#"{"node":"Dense","params":{"units":16,"activation":"relu","use_bias":true}}"#,
+            r#"{"node":"Dense","params":{"units":3,"activation":"linear","use_bias":true}}"#,
+            r#"{"node":"Dropout","params":{"prob":0.2}}"#,
+            r#"{"node":"Output","params":{"input_shape":[3]}}"#,
+            "CONNECTIONS\n0 1\n1 2\n2 3\n3 4",
+        ]
+        .join("\n");
+        let mut graph = GenomeGraph::parse(&genome).unwrap();
+        assert_eq!(graph.output_feeders(), vec![3, 2]);
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut hidden_changed = false;
+        for _ in 0..10 {
+            graph.mutate_params(&mut rng, 1.0);
+            assert_eq!(graph.nodes[2]["params"]["units"], 3);
+            hidden_changed |= graph.nodes[1]["params"]["units"] != 16;
+        }
+        assert!(hidden_changed);
+    }
+
+    #[test]
+    fn crossover_requires_matching_topology() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let a = GenomeGraph::parse(GENOME).unwrap();
+        let mut b = a.clone();
+        b.mutate_params(&mut rng, 1.0);
+
+        let child = crossover_params(&a, &b, &mut rng).expect("same topology");
+        assert_eq!(child.edges, a.edges);
+
+        b.insert_node(&mut rng, None);
+        assert!(crossover_params(&a, &b, &mut rng).is_none());
+    }
+
+    #[test]
+    fn fitness_falls_back_to_loss_and_applies_parsimony() {
+        assert_eq!(compute_fitness(0.5, 80.0, 10, 0.0), 80.0);
+        assert_eq!(compute_fitness(1.0, 0.0, 0, 0.0), 0.5);
+        assert!(compute_fitness(0.5, 80.0, 10, 0.1) < 80.0);
+    }
+
+    #[test]
+    fn config_fills_defaults_from_minimal_json() {
+        let config: EvolutionConfig = serde_json::from_str(
+            r#"{"dataset_profile":"p1","seed_genomes":["seed.txt"],"stopping_criteria":[{"type":"GenerationLimit","params":{"max_generations":5}}]}"#,
+        )
+        .expect("parse config");
+        assert_eq!(config.population_size, 10);
+        assert_eq!(config.stopping_policy, "any");
+        config.validate().expect("valid config");
+    }
+}
@@ -0,0 +1,679 @@
+/// Image Cache Format
+///
+/// Decoded, resized images of a dataset live in
+/// `<app data>/datasets_cache/<profile>/<stream>/<sample>.bin`. Each file starts with
+/// `MAGIC`, a little-endian `u32` header length and a JSON `ImageCacheHeader`, followed
+/// by the CHW pixel payload as `f32` (scaled to 0..1) or raw `u8` (4x smaller).
+///
+/// The header records what the payload was built from (shape, dtype, normalization
+/// and the size/mtime/SHA-256 of the source file), so rebuilds only re-decode new or
+/// changed images and stale entries are detected instead of silently misread.
+///
+/// Payloads are only scaled, never normalized: `VisionNormalizer` applies the stream's
+/// normalization mode after loading, so "dataset" statistics can follow the split.
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::dtos::DataStream;
+
+const MAGIC: &[u8; 8] = b"NEVOIMG1";
+const FORMAT_VERSION: u32 = 1;
+/// Guards against reading garbage as a header length.
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheDtype {
+    #[default]
+    F32,
+    U8,
+}
+
+impl CacheDtype {
+    fn bytes_per_value(self) -> usize {
+        match self {
+            CacheDtype::F32 => 4,
+            CacheDtype::U8 => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ImageCacheOptions {
+    pub storage: CacheDtype,
+    /// Re-decode every image even when its cache entry is fresh.
+    pub rebuild: bool,
+    /// Samples "dataset"-normalized streams compute their channel statistics on
+    /// (normally the training split); defaults to every cached sample.
+    pub train_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageCacheHeader {
+    pub version: u32,
+    /// `[channels, height, width]`
+    pub shape: [usize; 3],
+    pub dtype: CacheDtype,
+    pub normalization: String,
+    pub source_size: u64,
+    pub source_mtime_ms: u64,
+    pub source_hash: String,
+}
+
+/// Target geometry of one Image stream, taken from its vision preprocessing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageCacheSpec {
+    pub width: u32,
+    pub height: u32,
+    pub grayscale: bool,
+    pub normalization: String,
+}
+
+impl ImageCacheSpec {
+    pub fn for_stream(stream: &DataStream) -> Self {
+        let mut spec = ImageCacheSpec {
+            width: 64,
+            height: 64,
+            grayscale: false,
+            normalization: "0-1".to_string(),
+        };
+        if let Some(vision) = stream.preprocessing.as_ref().and_then(|prep| prep.vision.as_ref()) {
+            if vision.resize.len() == 2 {
+                spec.width = vision.resize[0];
+                spec.height = vision.resize[1];
+            }
+            spec.grayscale = vision.grayscale;
+            spec.normalization = vision.normalization.clone();
+        }
+        spec
+    }
+
+    pub fn channels(&self) -> usize {
+        if self.grayscale { 1 } else { 3 }
+    }
+
+    pub fn shape(&self) -> [usize; 3] {
+        [self.channels(), self.height as usize, self.width as usize]
+    }
+
+    pub fn pixel_count(&self) -> usize {
+        self.shape().iter().product()
+    }
+}
+
+pub fn stream_cache_dir(app_data: &Path, profile_id: &str, stream_id: &str) -> PathBuf {
+    app_data.join("datasets_cache").join(profile_id).join(stream_id)
+}
+
+pub fn cache_file_path(stream_cache_dir: &Path, sample_id: &str) -> PathBuf {
+    stream_cache_dir.join(format!("{}.bin", sample_id.replace("/", "_")))
+}
+
+/// Strips the quotes scanners leave around locator paths.
+pub fn source_path(locator_val: &str) -> PathBuf {
+    PathBuf::from(locator_val.trim_matches(|c| c == '"' || c == '\''))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceStamp {
+    pub size: u64,
+    pub mtime_ms: u64,
+}
+
+pub fn source_stamp(path: &Path) -> Result<SourceStamp, String> {
+    let meta = fs::metadata(path).map_err(|e| format!("Cannot stat '{}': {}", path.display(), e))?;
+    let mtime_ms = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    Ok(SourceStamp {
+        size: meta.len(),
+        mtime_ms,
+    })
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Decodes and resizes an encoded image into CHW `u8` pixels.
+pub fn decode_image_bytes(bytes: &[u8], spec: &ImageCacheSpec) -> Result<Vec<u8>, String> {
+    let img = image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .decode()
+        .map_err(|e| e.to_string())?
+        .resize_exact(spec.width, spec.height, image::imageops::FilterType::Triangle);
+
+    if spec.grayscale {
+        return Ok(img.to_luma8().into_raw());
+    }
+    let rgb = img.to_rgb8();
+    let mut pixels = Vec::with_capacity(spec.pixel_count());
+    for c in 0..3 {
+        pixels.extend(rgb.pixels().map(|p| p[c]));
+    }
+    Ok(pixels)
+}
+
+pub fn decode_image_file(path: &Path, spec: &ImageCacheSpec) -> Result<Vec<u8>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Cannot read '{}': {}", path.display(), e))?;
+    decode_image_bytes(&bytes, spec)
+}
+
+pub fn pixels_to_f32(pixels: &[u8]) -> Vec<f32> {
+    pixels.iter().map(|&p| p as f32 / 255.0).collect()
+}
+
+/// Writes an entry through a temporary file, so an interrupted build never leaves a
+/// truncated entry behind.
+pub fn write_entry(path: &Path, header: &ImageCacheHeader, pixels: &[u8]) -> Result<(), String> {
+    let header_json = serde_json::to_vec(header).map_err(|e| e.to_string())?;
+    let mut bytes = Vec::with_capacity(12 + header_json.len() + pixels.len() * header.dtype.bytes_per_value());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&(header_json.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&header_json);
+    match header.dtype {
+        CacheDtype::U8 => bytes.extend_from_slice(pixels),
+        CacheDtype::F32 => bytes.extend(pixels_to_f32(pixels).iter().flat_map(|v| v.to_le_bytes())),
+    }
+
+    let tmp = path.with_extension("bin.tmp");
+    fs::write(&tmp, &bytes).map_err(|e| format!("Failed to write '{}': {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to move cache entry into place: {}", e))
+}
+
+fn parse_header(reader: &mut impl Read) -> Result<(ImageCacheHeader, usize), String> {
+    let mut prefix = [0u8; 12];
+    reader
+        .read_exact(&mut prefix)
+        .map_err(|_| "entry is shorter than its header".to_string())?;
+    if &prefix[..8] != MAGIC {
+        return Err("missing header (legacy or foreign cache file)".to_string());
+    }
+    let header_len = u32::from_le_bytes([prefix[8], prefix[9], prefix[10], prefix[11]]) as usize;
+    if header_len > MAX_HEADER_BYTES {
+        return Err(format!("implausible header length {}", header_len));
+    }
+    let mut header_json = vec![0u8; header_len];
+    reader
+        .read_exact(&mut header_json)
+        .map_err(|_| "truncated header".to_string())?;
+    let header: ImageCacheHeader =
+        serde_json::from_slice(&header_json).map_err(|e| format!("unreadable header: {}", e))?;
+    if header.version != FORMAT_VERSION {
+        return Err(format!("unsupported cache format version {}", header.version));
+    }
+    Ok((header, 12 + header_len))
+}
+
+fn payload_len(header: &ImageCacheHeader) -> usize {
+    header.shape.iter().product::<usize>() * header.dtype.bytes_per_value()
+}
+
+/// Reads only the header and checks the file is long enough for its payload.
+pub fn read_header(path: &Path) -> Result<ImageCacheHeader, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let (header, offset) = parse_header(&mut file)?;
+    let file_len = file.metadata().map_err(|e| e.to_string())?.len() as usize;
+    if file_len != offset + payload_len(&header) {
+        return Err(format!(
+            "payload is {} bytes, header expects {}",
+            file_len.saturating_sub(offset),
+            payload_len(&header)
+        ));
+    }
+    Ok(header)
+}
+
+/// Loads an entry as 0..1 scaled CHW floats. `Ok(None)` means there is no entry;
+/// `Err` means the entry exists but does not match `spec` or is corrupt.
+pub fn read_pixels(path: &Path, spec: &ImageCacheSpec) -> Result<Option<Vec<f32>>, String> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let (header, offset) = parse_header(&mut Cursor::new(&bytes))?;
+    if header.shape != spec.shape() {
+        return Err(format!("cached shape {:?} differs from {:?}", header.shape, spec.shape()));
+    }
+    if header.normalization != spec.normalization {
+        return Err(format!(
+            "cached with normalization '{}', profile uses '{}'",
+            header.normalization, spec.normalization
+        ));
+    }
+    let payload = &bytes[offset..];
+    if payload.len() != payload_len(&header) {
+        return Err(format!("payload is {} bytes, header expects {}", payload.len(), payload_len(&header)));
+    }
+    let pixels = match header.dtype {
+        CacheDtype::U8 => pixels_to_f32(payload),
+        CacheDtype::F32 => payload
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    };
+    Ok(Some(pixels))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntryState {
+    Fresh,
+    Missing,
+    /// Built from a different source file or with different settings.
+    Stale(String),
+    Corrupt(String),
+}
+
+/// Checks an entry against the current source file and stream settings.
+///
+/// Size and mtime are compared first; when only the mtime moved (copies, checkouts)
+/// or `deep` is set, the source is hashed and compared with the recorded digest.
+/// `dtype` is only checked when given, so verification accepts either storage.
+pub fn entry_state(
+    cache_path: &Path,
+    source: &Path,
+    spec: &ImageCacheSpec,
+    dtype: Option<CacheDtype>,
+    deep: bool,
+) -> EntryState {
+    if !cache_path.exists() {
+        return EntryState::Missing;
+    }
+    let header = match read_header(cache_path) {
+        Ok(header) => header,
+        Err(e) => return EntryState::Corrupt(e),
+    };
+    if header.shape != spec.shape() {
+        return EntryState::Stale(format!("shape {:?} != {:?}", header.shape, spec.shape()));
+    }
+    if header.normalization != spec.normalization {
+        return EntryState::Stale(format!("normalization '{}' != '{}'", header.normalization, spec.normalization));
+    }
+    if let Some(dtype) = dtype
+        && header.dtype != dtype
+    {
+        return EntryState::Stale(format!("stored as {:?}, requested {:?}", header.dtype, dtype));
+    }
+    let stamp = match source_stamp(source) {
+        Ok(stamp) => stamp,
+        Err(e) => return EntryState::Stale(e),
+    };
+    if stamp.size != header.source_size {
+        return EntryState::Stale("source size changed".to_string());
+    }
+    if stamp.mtime_ms != header.source_mtime_ms || deep {
+        match fs::read(source) {
+            Ok(bytes) if hash_bytes(&bytes) == header.source_hash => {}
+            Ok(_) => return EntryState::Stale("source content changed".to_string()),
+            Err(e) => return EntryState::Stale(e.to_string()),
+        }
+    }
+    EntryState::Fresh
+}
+
+/// Outcome of caching one sample.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheOutcome {
+    Reused,
+    Decoded,
+    Failed(String),
+}
+
+/// Brings the entry for `source` up to date, decoding only when it is missing or stale.
+pub fn cache_image(
+    cache_path: &Path,
+    source: &Path,
+    spec: &ImageCacheSpec,
+    options: &ImageCacheOptions,
+) -> CacheOutcome {
+    if !options.rebuild && entry_state(cache_path, source, spec, Some(options.storage), false) == EntryState::Fresh {
+        return CacheOutcome::Reused;
+    }
+
+    let built = (|| {
+        let stamp = source_stamp(source)?;
+        let bytes = fs::read(source).map_err(|e| e.to_string())?;
+        let pixels = decode_image_bytes(&bytes, spec)?;
+        let header = ImageCacheHeader {
+            version: FORMAT_VERSION,
+            shape: spec.shape(),
+            dtype: options.storage,
+            normalization: spec.normalization.clone(),
+            source_size: stamp.size,
+            source_mtime_ms: stamp.mtime_ms,
+            source_hash: hash_bytes(&bytes),
+        };
+        write_entry(cache_path, &header, &pixels)
+    })();
+
+    match built {
+        Ok(()) => CacheOutcome::Decoded,
+        Err(e) => {
+            // An outdated entry must not outlive a source that no longer decodes
+            let _ = fs::remove_file(cache_path);
+            CacheOutcome::Failed(e)
+        }
+    }
+}
+
+const CHANNEL_STATS_FILE: &str = "channel_stats.json";
+
+const IMAGENET_MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+const IMAGENET_STD: [f32; 3] = [0.229, 0.224, 0.225];
+/// ImageNet statistics averaged over channels, for grayscale streams.
+const IMAGENET_GRAY_MEAN: f32 = 0.449;
+const IMAGENET_GRAY_STD: f32 = 0.226;
+
+/// `VisionSettings.normalization` modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisionNormalization {
+    /// Pixels scaled to 0..1.
+    ZeroOne,
+    /// 0..1, then the ImageNet per-channel mean/std.
+    ImageNet,
+    /// Raw 0..255 values.
+    None,
+    /// 0..1, then per-channel mean/std of the training split.
+    Dataset,
+}
+
+impl VisionNormalization {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "0-1" => Ok(VisionNormalization::ZeroOne),
+            "imagenet" => Ok(VisionNormalization::ImageNet),
+            "none" => Ok(VisionNormalization::None),
+            "dataset" => Ok(VisionNormalization::Dataset),
+            other => Err(format!(
+                "Unknown vision normalization '{}'. Expected '0-1', 'imagenet', 'none' or 'dataset'",
+                other
+            )),
+        }
+    }
+}
+
+/// Per-channel statistics of 0..1 pixels, fitted on the training split.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelStats {
+    pub mean: Vec<f32>,
+    pub std: Vec<f32>,
+    pub samples: usize,
+    /// `tabular::train_fingerprint` of the samples the statistics were computed on.
+    pub train_fingerprint: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChannelAccumulator {
+    sum: Vec<f64>,
+    sum_sq: Vec<f64>,
+    values_per_channel: u64,
+    samples: usize,
+}
+
+impl ChannelAccumulator {
+    /// Adds one CHW sample of 0..1 pixels.
+    pub fn add(&mut self, pixels: &[f32], channels: usize) {
+        let plane = pixels.len() / channels.max(1);
+        if plane == 0 {
+            return;
+        }
+        self.sum.resize(channels, 0.0);
+        self.sum_sq.resize(channels, 0.0);
+        for (c, values) in pixels.chunks_exact(plane).enumerate().take(channels) {
+            for &v in values {
+                self.sum[c] += v as f64;
+                self.sum_sq[c] += (v as f64) * (v as f64);
+            }
+        }
+        self.values_per_channel += plane as u64;
+        self.samples += 1;
+    }
+
+    pub fn merge(mut self, other: ChannelAccumulator) -> Self {
+        if self.samples == 0 {
+            return other;
+        }
+        for (a, b) in self.sum.iter_mut().zip(&other.sum) {
+            *a += b;
+        }
+        for (a, b) in self.sum_sq.iter_mut().zip(&other.sum_sq) {
+            *a += b;
+        }
+        self.values_per_channel += other.values_per_channel;
+        self.samples += other.samples;
+        self
+    }
+
+    pub fn finish(self, train_fingerprint: String) -> ChannelStats {
+        let n = self.values_per_channel.max(1) as f64;
+        let mean: Vec<f64> = self.sum.iter().map(|s| s / n).collect();
+        let std = self
+            .sum_sq
+            .iter()
+            .zip(&mean)
+            .map(|(sq, m)| ((sq / n - m * m).max(0.0)).sqrt() as f32)
+            .collect();
+        ChannelStats {
+            mean: mean.into_iter().map(|m| m as f32).collect(),
+            std,
+            samples: self.samples,
+            train_fingerprint,
+        }
+    }
+}
+
+pub fn channel_stats_path(stream_cache_dir: &Path) -> PathBuf {
+    stream_cache_dir.join(CHANNEL_STATS_FILE)
+}
+
+pub fn load_channel_stats(path: &Path) -> Option<ChannelStats> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+pub fn save_channel_stats(path: &Path, stats: &ChannelStats) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(stats).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Maps 0..1 pixels to model inputs: `x = (p * scale - mean[c]) / std[c]`. The same
+/// three parameters are exported with trained weights so inference can reproduce it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisionNormalizer {
+    pub mode: String,
+    pub scale: f32,
+    pub mean: Vec<f32>,
+    pub std: Vec<f32>,
+}
+
+impl VisionNormalizer {
+    /// `stats` is required for the "dataset" mode; without it the stream falls back
+    /// to plain 0..1 scaling (e.g. a loader used outside of dataset preparation).
+    pub fn new(spec: &ImageCacheSpec, stats: Option<&ChannelStats>) -> Result<Self, String> {
+        let channels = spec.channels();
+        let identity = |mode: &str, scale: f32| VisionNormalizer {
+            mode: mode.to_string(),
+            scale,
+            mean: vec![0.0; channels],
+            std: vec![1.0; channels],
+        };
+        Ok(match VisionNormalization::from_name(&spec.normalization)? {
+            VisionNormalization::ZeroOne => identity("0-1", 1.0),
+            VisionNormalization::None => identity("none", 255.0),
+            VisionNormalization::ImageNet if channels == 1 => VisionNormalizer {
+                mode: "imagenet".to_string(),
+                scale: 1.0,
+                mean: vec![IMAGENET_GRAY_MEAN],
+                std: vec![IMAGENET_GRAY_STD],
+            },
+            VisionNormalization::ImageNet => VisionNormalizer {
+                mode: "imagenet".to_string(),
+                scale: 1.0,
+                mean: IMAGENET_MEAN.to_vec(),
+                std: IMAGENET_STD.to_vec(),
+            },
+            VisionNormalization::Dataset => match stats {
+                Some(stats) if stats.mean.len() == channels => VisionNormalizer {
+                    mode: "dataset".to_string(),
+                    scale: 1.0,
+                    mean: stats.mean.clone(),
+                    std: stats.std.iter().map(|s| s.max(1e-6)).collect(),
+                },
+                _ => identity("0-1", 1.0),
+            },
+        })
+    }
+
+    /// Normalizes CHW 0..1 pixels in place.
+    pub fn apply(&self, pixels: &mut [f32]) {
+        let plane = pixels.len() / self.mean.len().max(1);
+        if plane == 0 {
+            return;
+        }
+        for (c, values) in pixels.chunks_exact_mut(plane).enumerate() {
+            let (mean, std) = (self.mean[c], self.std[c]);
+            for v in values {
+                *v = (*v * self.scale - mean) / std;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}-{}", prefix, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn write_png(path: &Path, side: u32, shade: u8) {
+        image::RgbImage::from_fn(side, side, |x, y| image::Rgb([shade, (x * 40) as u8, (y * 40) as u8]))
+            .save(path)
+            .expect("write png");
+    }
+
+    fn spec() -> ImageCacheSpec {
+        ImageCacheSpec {
+            width: 2,
+            height: 2,
+            grayscale: false,
+            normalization: "0-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn u8_and_f32_entries_read_back_identically() {
+        let dir = temp_dir("image-cache-roundtrip");
+        let source = dir.join("img.png");
+        write_png(&source, 4, 200);
+
+        let mut options = ImageCacheOptions::default();
+        let f32_path = dir.join("f32.bin");
+        assert_eq!(cache_image(&f32_path, &source, &spec(), &options), CacheOutcome::Decoded);
+        options.storage = CacheDtype::U8;
+        let u8_path = dir.join("u8.bin");
+        assert_eq!(cache_image(&u8_path, &source, &spec(), &options), CacheOutcome::Decoded);
+
+        let from_f32 = read_pixels(&f32_path, &spec()).unwrap().unwrap();
+        let from_u8 = read_pixels(&u8_path, &spec()).unwrap().unwrap();
+        assert_eq!(from_f32.len(), 12);
+        assert_eq!(from_f32, from_u8);
+        assert!(fs::metadata(&u8_path).unwrap().len() < fs::metadata(&f32_path).unwrap().len());
+        assert_eq!(read_pixels(&dir.join("absent.bin"), &spec()), Ok(None));
+
+        let mut grayscale = spec();
+        grayscale.grayscale = true;
+        assert!(read_pixels(&u8_path, &grayscale).is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn normalization_modes_map_pixels() {
+        let mut rgb = spec();
+        let pixels = vec![0.5; 12];
+        let normalize = |spec: &ImageCacheSpec, stats: Option<&ChannelStats>| {
+            let mut out = pixels.clone();
+            VisionNormalizer::new(spec, stats).expect("known mode").apply(&mut out);
+            out
+        };
+
+        assert_eq!(normalize(&rgb, None), pixels);
+        rgb.normalization = "none".to_string();
+        assert_eq!(normalize(&rgb, None)[0], 127.5);
+        rgb.normalization = "imagenet".to_string();
+        let imagenet = normalize(&rgb, None);
+        assert!((imagenet[0] - (0.5 - 0.485) / 0.229).abs() < 1e-6);
+        assert!((imagenet[11] - (0.5 - 0.406) / 0.225).abs() < 1e-6);
+
+        let mut accumulator = ChannelAccumulator::default();
+        accumulator.add(&[0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.2, 0.2, 0.2, 0.2], 3);
+        let other = {
+            let mut acc = ChannelAccumulator::default();
+            acc.add(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.2, 0.2, 0.2, 0.2], 3);
+            acc
+        };
+        let stats = accumulator.merge(other).finish("fp".to_string());
+        assert_eq!(stats.samples, 2);
+        assert!((stats.mean[0] - 0.5).abs() < 1e-6 && (stats.std[0] - 0.5).abs() < 1e-6);
+        assert!((stats.mean[1] - 1.0).abs() < 1e-6 && stats.std[1].abs() < 1e-6);
+
+        rgb.normalization = "dataset".to_string();
+        let dataset = normalize(&rgb, Some(&stats));
+        assert!(dataset[0].abs() < 1e-6);
+        // Without fitted statistics the dataset mode degrades to 0..1 scaling
+        assert_eq!(normalize(&rgb, None), pixels);
+        rgb.normalization = "sigmoid".to_string();
+        assert!(VisionNormalizer::new(&rgb, None).is_err());
+    }
+
+    #[test]
+    fn changed_or_damaged_entries_are_detected() {
+        let dir = temp_dir("image-cache-state");
+        let source = dir.join("img.png");
+        write_png(&source, 4, 10);
+        let entry = dir.join("img.bin");
+        let options = ImageCacheOptions::default();
+
+        assert_eq!(entry_state(&entry, &source, &spec(), None, false), EntryState::Missing);
+        assert_eq!(cache_image(&entry, &source, &spec(), &options), CacheOutcome::Decoded);
+        assert_eq!(cache_image(&entry, &source, &spec(), &options), CacheOutcome::Reused);
+        assert_eq!(entry_state(&entry, &source, &spec(), None, true), EntryState::Fresh);
+        assert!(matches!(
+            entry_state(&entry, &source, &spec(), Some(CacheDtype::U8), false),
+            EntryState::Stale(_)
+        ));
+
+        // A larger image changes the source size, so the cheap check catches it
+        write_png(&source, 6, 250);
+        assert!(matches!(entry_state(&entry, &source, &spec(), None, true), EntryState::Stale(_)));
+        assert_eq!(cache_image(&entry, &source, &spec(), &options), CacheOutcome::Decoded);
+
+        let mut bytes = fs::read(&entry).unwrap();
+        bytes.truncate(bytes.len() - 3);
+        fs::write(&entry, &bytes).unwrap();
+        assert!(matches!(entry_state(&entry, &source, &spec(), None, false), EntryState::Corrupt(_)));
+
+        fs::write(&entry, [0u8; 48]).unwrap();
+        assert!(matches!(entry_state(&entry, &source, &spec(), None, false), EntryState::Corrupt(_)));
+
+        fs::write(&source, "not an image").unwrap();
+        assert!(matches!(cache_image(&entry, &source, &spec(), &options), CacheOutcome::Failed(_)));
+        assert!(!entry.exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
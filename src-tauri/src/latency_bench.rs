@@ -0,0 +1,204 @@
+/// Inference Latency Benchmark
+///
+/// `TrainingProfiler.inference_msec_per_sample` is the validation pass's wall time per
+/// sample, data handling and batch pacing included. The benchmark times bare eval-mode
+/// forward passes on random inputs instead:
+/// - `warmup` passes are discarded (kernel compilation, allocator growth)
+/// - `iterations` passes are then timed one by one, each waited on with `Backend::sync`,
+///   at batch 1 and at `batch_size`
+/// - pass times are summarised as nearest-rank percentiles and throughput
+///
+/// Latency does not depend on the weights, so genomes are benchmarked untrained.
+use std::time::Instant;
+
+use burn::module::AutodiffModule;
+use burn::prelude::*;
+use burn::tensor::Distribution;
+use burn::tensor::backend::AutodiffBackend;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{DynamicTensor, GraphModel, Operation};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyBenchConfig {
+    pub warmup: usize,
+    pub iterations: usize,
+    /// Batch size of the throughput measurement
+    pub batch_size: usize,
+}
+
+impl Default for LatencyBenchConfig {
+    fn default() -> Self {
+        LatencyBenchConfig {
+            warmup: 5,
+            iterations: 50,
+            batch_size: 32,
+        }
+    }
+}
+
+/// Timed passes at one batch size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub batch_size: usize,
+    pub iterations: usize,
+    pub mean_ms: f32,
+    pub min_ms: f32,
+    pub max_ms: f32,
+    pub p50_ms: f32,
+    pub p90_ms: f32,
+    pub p99_ms: f32,
+    /// Samples per second at the median pass time
+    pub samples_per_sec: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyBenchmark {
+    pub backend: String,
+    pub single: LatencyStats,
+    pub batched: LatencyStats,
+}
+
+impl LatencyBenchmark {
+    /// Median single-sample latency, the value of `GenomeObjectives.inference_latency_ms`.
+    pub fn latency_ms(&self) -> f32 {
+        self.single.p50_ms
+    }
+}
+
+/// Nearest-rank percentile of ascending values, `q` in 0.0-1.0.
+pub fn percentile(sorted: &[f32], q: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (q.clamp(0.0, 1.0) * sorted.len() as f32).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+pub fn summarize(batch_size: usize, mut times_ms: Vec<f32>) -> LatencyStats {
+    times_ms.sort_by(f32::total_cmp);
+    let iterations = times_ms.len();
+    let p50_ms = percentile(&times_ms, 0.5);
+    LatencyStats {
+        batch_size,
+        iterations,
+        mean_ms: times_ms.iter().sum::<f32>() / iterations.max(1) as f32,
+        min_ms: times_ms.first().copied().unwrap_or(0.0),
+        max_ms: times_ms.last().copied().unwrap_or(0.0),
+        p50_ms,
+        p90_ms: percentile(&times_ms, 0.9),
+        p99_ms: percentile(&times_ms, 0.99),
+        samples_per_sec: if p50_ms > 0.0 { batch_size as f32 * 1000.0 / p50_ms } else { 0.0 },
+    }
+}
+
+/// Standard normal inputs of `batch` samples, in the shapes the Input nodes produce.
+fn random_inputs<B: Backend>(model: &GraphModel<B>, batch: usize, device: &B::Device) -> Result<Vec<DynamicTensor<B>>, String> {
+    let mut inputs: Vec<(usize, DynamicTensor<B>)> = Vec::new();
+    for instr in &model.execution_plan.0 {
+        let Operation::Input(idx) = instr.op else {
+            continue;
+        };
+        let shape = &model.node_output_shapes.0[instr.node_id];
+        let normal = Distribution::Normal(0.0, 1.0);
+        let tensor = match shape.as_slice() {
+            [a] => DynamicTensor::Dim2(Tensor::random([batch, *a], normal, device)),
+            [a, b] => DynamicTensor::Dim3(Tensor::random([batch, *a, *b], normal, device)),
+            [a, b, c] => DynamicTensor::Dim4(Tensor::random([batch, *a, *b, *c], normal, device)),
+            other => return Err(format!("Unsupported input shape {:?} for latency benchmarking", other)),
+        };
+        inputs.push((idx, tensor));
+    }
+    inputs.sort_by_key(|(idx, _)| *idx);
+    Ok(inputs.into_iter().map(|(_, tensor)| tensor).collect())
+}
+
+fn time_passes<B: Backend>(
+    model: &GraphModel<B>,
+    batch: usize,
+    config: &LatencyBenchConfig,
+    device: &B::Device,
+) -> Result<LatencyStats, String> {
+    let inputs = random_inputs(model, batch, device)?;
+    let mut times_ms = Vec::with_capacity(config.iterations);
+    for pass in 0..config.warmup + config.iterations.max(1) {
+        let started = Instant::now();
+        let outputs = model.forward_eval_mode(&inputs);
+        B::sync(device).map_err(|e| format!("Backend sync failed: {:?}", e))?;
+        let elapsed_ms = started.elapsed().as_secs_f32() * 1000.0;
+        drop(outputs);
+        if pass >= config.warmup {
+            times_ms.push(elapsed_ms);
+        }
+    }
+    Ok(summarize(batch, times_ms))
+}
+
+pub fn benchmark_model<B: Backend>(
+    model: &GraphModel<B>,
+    config: &LatencyBenchConfig,
+    backend: &str,
+    device: &B::Device,
+) -> Result<LatencyBenchmark, String> {
+    Ok(LatencyBenchmark {
+        backend: backend.to_string(),
+        single: time_passes(model, 1, config, device)?,
+        batched: time_passes(model, config.batch_size.max(1), config, device)?,
+    })
+}
+
+/// Builds a genome with fresh weights and benchmarks it on the inner (non-autodiff)
+/// backend of `B`.
+pub fn benchmark_genome<B: AutodiffBackend>(
+    genome_str: &str,
+    input_overrides: Option<&[Vec<usize>]>,
+    output_overrides: Option<&[Vec<usize>]>,
+    config: &LatencyBenchConfig,
+    backend: &str,
+    device: &B::Device,
+) -> Result<LatencyBenchmark, String> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let model = GraphModel::<B>::build(genome_str, device, input_overrides, output_overrides).valid();
+        benchmark_model(&model, config, backend, device)
+    }))
+    .map_err(|err| format!("Latency benchmark panicked: {}", crate::evaluation::panic_message(err.as_ref())))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let stats = summarize(8, (1..=100).rev().map(|v| v as f32).collect());
+        assert_eq!((stats.p50_ms, stats.p90_ms, stats.p99_ms), (50.0, 90.0, 99.0));
+        assert_eq!((stats.min_ms, stats.max_ms, stats.mean_ms), (1.0, 100.0, 50.5));
+        assert_eq!(stats.samples_per_sec, 8.0 * 1000.0 / 50.0);
+        assert_eq!(percentile(&[3.0], 0.99), 3.0);
+        assert_eq!(percentile(&[], 0.5), 0.0);
+    }
+
+    #[test]
+    fn benchmark_times_every_iteration_at_both_batch_sizes() {
+        let genome = [
+            r#"{"node":"Input","params":{"output_shape":[6]}}"#,
+            r#"{"node":"Dense","params":{"units":4,"activation":"relu","use_bias":true}}"#,
+            r#"{"node":"Output","params":{"input_shape":[4]}}"#,
+            "CONNECTIONS",
+            "0 1",
+            "1 2",
+        ]
+        .join("\n");
+        let config = LatencyBenchConfig { warmup: 1, iterations: 5, batch_size: 4 };
+        let device = crate::backend::create_cpu_device();
+        let benchmark =
+            benchmark_genome::<crate::backend::CpuBackend>(&genome, None, None, &config, "ndarray", &device).unwrap();
+
+        assert_eq!((benchmark.single.batch_size, benchmark.single.iterations), (1, 5));
+        assert_eq!((benchmark.batched.batch_size, benchmark.batched.iterations), (4, 5));
+        assert!(benchmark.single.p50_ms <= benchmark.single.p90_ms && benchmark.single.p90_ms <= benchmark.single.p99_ms);
+        assert!(benchmark.batched.samples_per_sec > 0.0);
+        assert_eq!(benchmark.latency_ms(), benchmark.single.p50_ms);
+    }
+}
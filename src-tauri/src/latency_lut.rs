@@ -0,0 +1,291 @@
+/// Device Latency Lookup Tables
+///
+/// Host timings (`latency_bench`, the profiler) say nothing about a microcontroller or a
+/// phone. A lookup table holds per-layer latencies measured on the target device, keyed
+/// by the layer type (`cost_analysis::op_name`), its output shape and MACs. A layer of
+/// the analysed model is predicted from, in order:
+/// - "measured": entries of the same type, output shape and MACs (their mean)
+/// - "fitted": a least-squares line over the type's entries, latency against work
+/// - "device_rate": the table's overall milliseconds per unit of work
+///
+/// Work is a layer's MACs, or its output elements for layers without MACs (pooling,
+/// Add, Concat). Input and Output nodes take no time; `overhead_ms` is added once.
+///
+/// Tables are attached to device templates as JSON or built from benchmark CSVs with
+/// the columns `op`, `output_shape` (`4x8x8`), `macs` and `latency_ms`.
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cost_analysis::{LayerCost, ModelCost};
+
+/// One layer benchmarked on the device.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyLutEntry {
+    pub op: String,
+    pub output_shape: Vec<usize>,
+    #[serde(default)]
+    pub macs: u64,
+    pub latency_ms: f32,
+}
+
+impl LatencyLutEntry {
+    /// Whether the entry was measured on this exact layer: type, output shape and MACs.
+    pub fn measures(&self, op: &str, output_shape: &[usize], macs: u64) -> bool {
+        self.op == op && self.output_shape == output_shape && self.macs == macs
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyLut {
+    #[serde(default)]
+    pub entries: Vec<LatencyLutEntry>,
+    /// Fixed cost of one inference (runtime dispatch, input and output copies)
+    #[serde(default)]
+    pub overhead_ms: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerLatency {
+    pub node_id: usize,
+    pub op: String,
+    pub latency_ms: f32,
+    /// "measured", "fitted" or "device_rate"
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyPrediction {
+    pub total_ms: f32,
+    pub layers: Vec<LayerLatency>,
+}
+
+fn work(macs: u64, output_shape: &[usize]) -> f64 {
+    let elements: u64 = output_shape.iter().map(|&d| d as u64).product();
+    macs.max(elements) as f64
+}
+
+/// Intercept and slope of latency against work. Falls back to a line through the origin
+/// when the points cannot give a non-negative fit.
+fn fit(points: &[(f64, f64)]) -> (f64, f64) {
+    let n = points.len() as f64;
+    let (sum_w, sum_ms) = points.iter().fold((0.0, 0.0), |(w, ms), p| (w + p.0, ms + p.1));
+    let mean_w = sum_w / n;
+    let var_w: f64 = points.iter().map(|p| (p.0 - mean_w).powi(2)).sum();
+    if var_w > 0.0 {
+        let cov: f64 = points.iter().map(|p| (p.0 - mean_w) * (p.1 - sum_ms / n)).sum();
+        let slope = cov / var_w;
+        let intercept = sum_ms / n - slope * mean_w;
+        if slope >= 0.0 && intercept >= 0.0 {
+            return (intercept, slope);
+        }
+    }
+    if sum_w > 0.0 { (0.0, sum_ms / sum_w) } else { (sum_ms / n, 0.0) }
+}
+
+impl LatencyLut {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.entries.is_empty() {
+            return Err("Latency table has no entries".to_string());
+        }
+        if let Some(entry) = self.entries.iter().find(|e| !e.latency_ms.is_finite() || e.latency_ms < 0.0) {
+            return Err(format!("Latency of {} {:?} must be >= 0", entry.op, entry.output_shape));
+        }
+        if !self.overhead_ms.is_finite() || self.overhead_ms < 0.0 {
+            return Err("overhead_ms must be >= 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Reads a table from its JSON file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let raw = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let lut: LatencyLut = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+        lut.validate()?;
+        Ok(lut)
+    }
+
+    /// Parses benchmark rows. Columns are found by header name, others are ignored.
+    pub fn parse_csv(reader: impl Read) -> Result<Vec<LatencyLutEntry>, String> {
+        let mut rdr = csv::ReaderBuilder::new().has_headers(true).trim(csv::Trim::All).from_reader(reader);
+        let headers = rdr.headers().map_err(|e| format!("Failed to read headers: {}", e))?.clone();
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("Benchmark CSV has no '{}' column", name))
+        };
+        let (op_col, shape_col, latency_col) = (column("op")?, column("output_shape")?, column("latency_ms")?);
+        let macs_col = column("macs").ok();
+
+        let mut entries = Vec::new();
+        for (row, record) in rdr.records().enumerate() {
+            let record = record.map_err(|e| format!("Failed to read row {}: {}", row + 1, e))?;
+            let field = |col: usize| record.get(col).unwrap_or("");
+            let output_shape = field(shape_col)
+                .split(['x', 'X'])
+                .map(|d| d.trim().parse::<usize>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("Row {}: invalid output_shape '{}'", row + 1, field(shape_col)))?;
+            let macs = match macs_col.map(field) {
+                Some(raw) if !raw.is_empty() => {
+                    raw.parse::<u64>().map_err(|_| format!("Row {}: invalid macs '{}'", row + 1, raw))?
+                }
+                _ => 0,
+            };
+            let latency_ms = field(latency_col)
+                .parse::<f32>()
+                .map_err(|_| format!("Row {}: invalid latency_ms '{}'", row + 1, field(latency_col)))?;
+            entries.push(LatencyLutEntry {
+                op: field(op_col).to_string(),
+                output_shape,
+                macs,
+                latency_ms,
+            });
+        }
+        Ok(entries)
+    }
+
+    pub fn read_csv(path: &Path) -> Result<Vec<LatencyLutEntry>, String> {
+        let file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Self::parse_csv(file)
+    }
+
+    /// Predicted device latency of an analysed model.
+    pub fn predict(&self, cost: &ModelCost) -> Result<LatencyPrediction, String> {
+        self.validate()?;
+        let mut by_op: HashMap<&str, Vec<&LatencyLutEntry>> = HashMap::new();
+        for entry in &self.entries {
+            by_op.entry(entry.op.as_str()).or_default().push(entry);
+        }
+        let (total_work, total_ms) = self
+            .entries
+            .iter()
+            .fold((0.0, 0.0), |(w, ms), e| (w + work(e.macs, &e.output_shape), ms + e.latency_ms as f64));
+        let device_rate = if total_work > 0.0 { total_ms / total_work } else { 0.0 };
+
+        let layers: Vec<LayerLatency> = cost
+            .layers
+            .iter()
+            .filter(|layer| layer.op != "Input" && layer.op != "Output")
+            .map(|layer| predict_layer(layer, by_op.get(layer.op.as_str()), device_rate))
+            .collect();
+        Ok(LatencyPrediction {
+            total_ms: self.overhead_ms + layers.iter().map(|l| l.latency_ms).sum::<f32>(),
+            layers,
+        })
+    }
+}
+
+fn predict_layer(layer: &LayerCost, entries: Option<&Vec<&LatencyLutEntry>>, device_rate: f64) -> LayerLatency {
+    let layer_work = work(layer.macs, &layer.output_shape);
+    let (latency_ms, source) = match entries {
+        Some(entries) => {
+            let measured: Vec<f32> = entries
+                .iter()
+                .filter(|e| e.measures(&layer.op, &layer.output_shape, layer.macs))
+                .map(|e| e.latency_ms)
+                .collect();
+            if measured.is_empty() {
+                let points: Vec<(f64, f64)> =
+                    entries.iter().map(|e| (work(e.macs, &e.output_shape), e.latency_ms as f64)).collect();
+                let (intercept, slope) = fit(&points);
+                ((intercept + slope * layer_work) as f32, "fitted")
+            } else {
+                (measured.iter().sum::<f32>() / measured.len() as f32, "measured")
+            }
+        }
+        None => ((device_rate * layer_work) as f32, "device_rate"),
+    };
+    LayerLatency {
+        node_id: layer.node_id,
+        op: layer.op.clone(),
+        latency_ms,
+        source: source.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(node_id: usize, op: &str, output_shape: &[usize], macs: u64) -> LayerCost {
+        LayerCost {
+            node_id,
+            op: op.to_string(),
+            output_shape: output_shape.to_vec(),
+            macs,
+            params: 0,
+            activation_bytes: 0,
+        }
+    }
+
+    fn entry(op: &str, output_shape: &[usize], macs: u64, latency_ms: f32) -> LatencyLutEntry {
+        LatencyLutEntry { op: op.to_string(), output_shape: output_shape.to_vec(), macs, latency_ms }
+    }
+
+    #[test]
+    fn layers_use_measured_fitted_and_device_rates() {
+        let lut = LatencyLut {
+            entries: vec![
+                entry("Conv2D", &[4, 8, 8], 2304, 2.0),
+                entry("Conv2D", &[8, 8, 8], 4608, 3.0),
+                entry("Dense", &[10], 1000, 0.5),
+            ],
+            overhead_ms: 0.25,
+        };
+        let cost = ModelCost {
+            layers: vec![
+                layer(0, "Input", &[1, 8, 8], 0),
+                layer(1, "Conv2D", &[8, 8, 8], 4608),
+                layer(2, "Conv2D", &[16, 8, 8], 9216),
+                layer(3, "MaxPool", &[16, 4, 4], 0),
+                layer(4, "Output", &[16, 4, 4], 0),
+            ],
+            ..ModelCost::default()
+        };
+        let prediction = lut.predict(&cost).unwrap();
+
+        let sources: Vec<&str> = prediction.layers.iter().map(|l| l.source.as_str()).collect();
+        assert_eq!(sources, ["measured", "fitted", "device_rate"]);
+        assert_eq!(prediction.layers[0].latency_ms, 3.0);
+        // Conv2D line: 1 ms + 1 ms per 2304 MACs
+        assert!((prediction.layers[1].latency_ms - 5.0).abs() < 1e-5);
+        // 5.5 ms over 7912 units of work across the table, for 256 pooled elements
+        assert!((prediction.layers[2].latency_ms - 256.0 * 5.5 / 7912.0).abs() < 1e-5);
+        let expected = 0.25 + 3.0 + 5.0 + prediction.layers[2].latency_ms;
+        assert!((prediction.total_ms - expected).abs() < 1e-5);
+
+        assert!(LatencyLut::default().predict(&cost).is_err());
+    }
+
+    #[test]
+    fn same_shape_with_other_macs_is_fitted() {
+        let lut = LatencyLut {
+            entries: vec![entry("Dense", &[10], 1000, 0.5), entry("Dense", &[10], 3000, 1.5)],
+            overhead_ms: 0.0,
+        };
+        let cost = ModelCost {
+            layers: vec![layer(0, "Input", &[100], 0), layer(1, "Dense", &[10], 2000)],
+            ..ModelCost::default()
+        };
+        let prediction = lut.predict(&cost).unwrap();
+        assert_eq!(prediction.layers[0].source, "fitted");
+        assert!((prediction.layers[0].latency_ms - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn benchmark_csv_rows_become_entries() {
+        let csv = "device,op,output_shape,macs,latency_ms\n\
+                   mcu,Conv2D,4x8x8,2304,2.0\n\
+                   mcu, MaxPool ,4X4X4,,0.1\n";
+        let entries = LatencyLut::parse_csv(csv.as_bytes()).unwrap();
+        assert_eq!(entries, vec![entry("Conv2D", &[4, 8, 8], 2304, 2.0), entry("MaxPool", &[4, 4, 4], 0, 0.1)]);
+
+        let err = LatencyLut::parse_csv("op,output_shape,latency_ms\nDense,10,fast\n".as_bytes()).unwrap_err();
+        assert!(err.contains("latency_ms"));
+        assert!(LatencyLut::parse_csv("op,latency_ms\n".as_bytes()).unwrap_err().contains("output_shape"));
+    }
+}
@@ -1,7 +1,6 @@
 #![recursion_limit = "512"]
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
@@ -23,6 +22,19 @@ pub mod dtos;
 pub mod entities;
 pub mod zero_cost_proxies;
 pub mod csv_loader;
+pub mod tensor_files;
+pub mod image_cache;
+pub mod tabular;
+pub mod targets;
+pub mod sampling;
+pub mod dataset_report;
+pub mod zero_cost_calibration;
+pub mod pareto_archive;
+pub mod cost_analysis;
+pub mod latency_bench;
+pub mod latency_lut;
+pub mod device_filter;
+pub mod companion_loader;
 pub mod shape_inference;
 pub mod orchestrator;
 pub mod profiler;
@@ -32,21 +44,32 @@ pub mod device_library;
 pub mod genealogy;
 pub mod weight_io;
 pub mod stopping_criteria;
+pub mod progress;
+pub mod batch_stream;
+pub mod evaluation;
+pub mod eval_runs;
+pub mod eval_cache;
+pub mod evolution;
+pub mod cli;
 
 /// Global session counter. Incremented by `stop_evolution`.
 /// Each `evaluate_population` call captures a snapshot; if the current value
 /// differs from the snapshot, the evaluation is cancelled.
 static EVOLUTION_SESSION: AtomicU64 = AtomicU64::new(0);
 
-/// Global cache: hash(genome_json + training_params) -> (loss, accuracy).
-/// Persists across generations within the app lifetime. Any parameter difference = different hash.
-static GENOME_EVAL_CACHE: std::sync::LazyLock<Mutex<HashMap<u64, (f32, f32)>>> =
-    std::sync::LazyLock::new(|| Mutex::new(HashMap::new()));
+/// Global disk-backed cache: stable hash(genome + training params + dataset fingerprint) -> result.
+/// Persists across generations and app restarts. Any parameter or data difference = different key.
+static EVAL_CACHE: std::sync::LazyLock<eval_cache::EvalCache> =
+    std::sync::LazyLock::new(|| eval_cache::EvalCache::open(get_genomes_dir().join("eval_cache")));
 
 /// Global in-memory genealogy graph for lineage tracking across evolution operations.
 static GENEALOGY_STORE: std::sync::LazyLock<Mutex<genealogy::GenealogyStore>> =
     std::sync::LazyLock::new(|| Mutex::new(genealogy::GenealogyStore::new()));
 
+/// Pareto archive of the running evolution; `reset_pareto_archive` starts a new one.
+static PARETO_ARCHIVE: std::sync::LazyLock<Mutex<pareto_archive::ParetoArchive>> =
+    std::sync::LazyLock::new(|| Mutex::new(pareto_archive::ParetoArchive::default()));
+
 /// True while evaluate_population is running. Prevent overlapping runs that can
 /// corrupt Burn/WGPU internal stream state.
 static EVALUATION_ACTIVE: AtomicBool = AtomicBool::new(false);
@@ -75,6 +98,41 @@ fn normalize_image_shape_to_internal_chw(shape: &[usize]) -> Vec<usize> {
     }
 }
 
+/// Input and output shape overrides declared by a serialized genome's own Input and
+/// Output nodes, images normalized to CHW.
+fn genome_io_overrides(genome_json: &str) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let mut input_overrides: Vec<Vec<usize>> = Vec::new();
+    let mut output_overrides: Vec<Vec<usize>> = Vec::new();
+
+    for line in genome_json.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        if line == "CONNECTIONS" {
+            break;
+        }
+
+        let parsed: crate::dtos::NodeDtoJSON = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        match parsed {
+            crate::dtos::NodeDtoJSON::Input { output_shape } => {
+                let shape: Vec<usize> = output_shape.iter().map(|&v| v as usize).collect();
+                if shape.len() == 3 {
+                    input_overrides.push(normalize_image_shape_to_internal_chw(&shape));
+                } else {
+                    input_overrides.push(shape);
+                }
+            }
+            crate::dtos::NodeDtoJSON::Output { input_shape } => {
+                output_overrides.push(input_shape.iter().map(|&v| v as usize).collect());
+            }
+            _ => {}
+        }
+    }
+
+    (input_overrides, output_overrides)
+}
+
 #[tauri::command]
 async fn stop_evolution() -> Result<(), String> {
     let prev = EVOLUTION_SESSION.fetch_add(1, Ordering::SeqCst);
@@ -439,12 +497,24 @@ async fn test_train_on_image_folder(genome_str: String) -> Result<(), String> {
     Ok(())
 }
 
-#[derive(Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct EvaluationResult {
     pub genome_id: String,
     pub loss: f32,
     pub accuracy: f32,
     pub profiler: Option<TrainingProfiler>,
+    /// Zero-cost budget the genome was trained with, when scoring was enabled
+    #[serde(default)]
+    pub budget: Option<crate::zero_cost_proxies::TrainingBudget>,
+    /// Static cost of the genome's model at batch 1
+    #[serde(default)]
+    pub cost: Option<crate::cost_analysis::ModelCost>,
+    /// Measured inference latency, when benchmarking was requested
+    #[serde(default)]
+    pub latency: Option<crate::latency_bench::LatencyBenchmark>,
+    /// Pre-training device check, when a device filter was given
+    #[serde(default)]
+    pub device_check: Option<crate::device_filter::DeviceCheck>,
 }
 
 async fn run_worker_job(
@@ -563,6 +633,10 @@ async fn run_worker_job(
         loss: result.loss,
         accuracy: result.accuracy,
         profiler: result.profiler,
+        budget: None,
+        cost: None,
+        latency: None,
+        device_check: None,
     })
 }
 
@@ -583,6 +657,249 @@ async fn evaluate_population(
     max_parallel_jobs: Option<usize>,
     execution_mode: Option<String>,
     memory_safety_margin_mb: Option<u64>,
+    execution_backend: Option<String>,
+    batch_pipeline: Option<crate::batch_stream::BatchPipelineConfig>,
+    zero_cost: Option<crate::dtos::ZeroCostConfigDto>,
+    latency_benchmark: Option<crate::latency_bench::LatencyBenchConfig>,
+    device_filter: Option<crate::device_filter::DeviceFilterConfig>,
+) -> Result<Vec<EvaluationResult>, String> {
+    let config = crate::eval_runs::EvaluationRunConfig {
+        dataset_profile,
+        batch_size,
+        dataset_percent,
+        train_split,
+        val_split,
+        test_split,
+        source_generation,
+        memory_mode: profiling.and_then(|cfg| cfg.memory_mode),
+        max_parallel_jobs,
+        execution_mode,
+        memory_safety_margin_mb,
+        execution_backend: crate::backend::ExecutionBackend::parse(execution_backend.as_deref())?,
+        batch_pipeline: batch_pipeline.unwrap_or_default(),
+        zero_cost: zero_cost.map(ZeroCostConfig::from).filter(|config| config.enabled),
+        latency_benchmark,
+        device_filter,
+    };
+    let entries = genomes
+        .into_iter()
+        .enumerate()
+        .map(|(i, genome_str)| {
+            let genome_id = genome_ids
+                .as_ref()
+                .and_then(|ids| ids.get(i))
+                .cloned()
+                .unwrap_or_else(|| format!("genome_{}", i));
+            let epochs = *per_genome_epochs.get(i).unwrap_or(&0);
+            crate::eval_runs::RunGenomeEntry::pending(i, genome_id, genome_str, epochs)
+        })
+        .collect();
+
+    run_population_evaluation(app_handle, EvaluationLaunch::New { config: Box::new(config), entries }).await
+}
+
+/// Continues an interrupted `evaluate_population` run: finished genomes keep their
+/// persisted results, the rest are evaluated on the run's original split.
+#[tauri::command]
+async fn resume_evaluation(app_handle: tauri::AppHandle, run_id: String) -> Result<Vec<EvaluationResult>, String> {
+    run_population_evaluation(app_handle, EvaluationLaunch::Resume { run_id }).await
+}
+
+#[tauri::command]
+async fn list_evaluation_runs() -> Result<Vec<crate::eval_runs::EvaluationRunSummary>, String> {
+    Ok(crate::eval_runs::list_runs(&get_eval_runs_dir()))
+}
+
+#[tauri::command]
+async fn delete_evaluation_run(run_id: String) -> Result<(), String> {
+    crate::eval_runs::delete_run(&get_eval_runs_dir(), &run_id)
+}
+
+#[tauri::command]
+async fn get_eval_cache_stats() -> Result<crate::eval_cache::EvalCacheStats, String> {
+    Ok(EVAL_CACHE.stats())
+}
+
+/// Drops cached evaluations, either for one dataset profile or all of them.
+/// Returns the number of entries removed.
+#[tauri::command]
+async fn invalidate_eval_cache(dataset_profile: Option<String>) -> Result<usize, String> {
+    Ok(match dataset_profile {
+        Some(profile) => EVAL_CACHE.invalidate_profile(&profile),
+        None => EVAL_CACHE.clear(),
+    })
+}
+
+#[tauri::command]
+async fn set_eval_cache_limits(
+    max_entries: usize,
+    max_bytes: u64,
+) -> Result<crate::eval_cache::EvalCacheStats, String> {
+    if max_entries == 0 || max_bytes == 0 {
+        return Err("Evaluation cache limits must be greater than zero".to_string());
+    }
+    EVAL_CACHE.set_limits(crate::eval_cache::EvalCacheLimits { max_entries, max_bytes })?;
+    Ok(EVAL_CACHE.stats())
+}
+
+enum EvaluationLaunch {
+    New {
+        config: Box<crate::eval_runs::EvaluationRunConfig>,
+        entries: Vec<crate::eval_runs::RunGenomeEntry>,
+    },
+    Resume {
+        run_id: String,
+    },
+}
+
+/// Scores the genomes of a new run on one training batch, decides them against the
+/// profile's calibration (or by rank voting) and applies the budgets to their entries.
+fn plan_zero_cost_budgets(
+    prepared: &crate::evaluation::PreparedDataset,
+    entries: &mut [crate::eval_runs::RunGenomeEntry],
+    config: &ZeroCostConfig,
+    store: Option<&crate::zero_cost_calibration::CalibrationStore>,
+    backend: crate::backend::ExecutionBackend,
+    session_snapshot: u64,
+) -> Result<(), String> {
+    // Genomes the device filter already finished are not scored
+    let pending: Vec<usize> = (0..entries.len()).filter(|&i| !entries[i].status.is_finished()).collect();
+    let genomes: Vec<String> = pending.iter().map(|&i| entries[i].genome_str.clone()).collect();
+    let is_cancelled = || EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot;
+    let scores = match backend {
+        crate::backend::ExecutionBackend::Cpu => crate::evaluation::score_zero_cost::<crate::backend::CpuBackend>(
+            prepared,
+            &genomes,
+            config,
+            &crate::backend::create_cpu_device(),
+            &is_cancelled,
+        )?,
+        crate::backend::ExecutionBackend::Gpu => crate::evaluation::score_zero_cost::<crate::backend::TrainBackend>(
+            prepared,
+            &genomes,
+            config,
+            &crate::backend::create_device(),
+            &is_cancelled,
+        )?,
+    };
+
+    let (indices, mut metrics): (Vec<usize>, Vec<ZeroCostMetrics>) = scores
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, metrics)| metrics.map(|m| (pending[i], m)))
+        .unzip();
+    if metrics.len() > 1 {
+        crate::zero_cost_calibration::calibrate_population(store, &mut metrics, config);
+    }
+    for (i, metrics) in indices.into_iter().zip(metrics) {
+        let budget = crate::zero_cost_proxies::TrainingBudget::plan(metrics, entries[i].epochs, config);
+        eprintln!(
+            ">>> Genome {}: zero-cost score {:.3} -> {} ({} of {} epochs)",
+            i,
+            budget.zero_cost.normalized_score,
+            budget.zero_cost.strategy_decision,
+            budget.epochs,
+            budget.requested_epochs
+        );
+        entries[i].apply_budget(budget);
+    }
+    Ok(())
+}
+
+/// Checks the genomes of a new run against the filter's device before training and
+/// applies the outcome to their entries: a repaired genome, a rejection or a penalty.
+/// Genomes that fail to compile are left to training, which reports them.
+fn check_population_for_device(
+    prepared: &crate::evaluation::PreparedDataset,
+    entries: &mut [crate::eval_runs::RunGenomeEntry],
+    config: &crate::device_filter::DeviceFilterConfig,
+    session_snapshot: u64,
+) -> Result<(), String> {
+    let target = crate::device_filter::DeviceTarget::resolve(config)?;
+    for entry in entries.iter_mut() {
+        if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
+            return Err("Evolution cancelled during device checks".to_string());
+        }
+        match crate::device_filter::filter_genome(
+            &entry.genome_str,
+            Some(&prepared.input_overrides),
+            Some(&prepared.output_overrides),
+            &target,
+            config,
+        ) {
+            Ok((genome_str, check)) => {
+                eprintln!(
+                    ">>> Genome {}: {} check {:?} (violation {:.3}, {} repair steps)",
+                    entry.index, check.device, check.decision, check.validation.violation_score, check.repair_steps
+                );
+                entry.apply_device_check(genome_str, check);
+            }
+            Err(e) => eprintln!(">>> Genome {}: device check failed: {}", entry.index, e),
+        }
+    }
+    Ok(())
+}
+
+/// Benchmarks the inference latency of every unfinished genome on the run's backend
+/// and keeps it on the run entry, so a resumed run reuses it. Genomes that fail to
+/// build or run get `None`.
+fn benchmark_population_latency(
+    prepared: &crate::evaluation::PreparedDataset,
+    entries: &mut [crate::eval_runs::RunGenomeEntry],
+    config: &crate::latency_bench::LatencyBenchConfig,
+    backend: crate::backend::ExecutionBackend,
+    session_snapshot: u64,
+) -> Result<(), String> {
+    for (i, entry) in entries.iter_mut().enumerate() {
+        // Genomes the device filter already finished are not benchmarked
+        if entry.status.is_finished() {
+            continue;
+        }
+        let genome_str = &entry.genome_str;
+        if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
+            return Err("Evolution cancelled during latency benchmarking".to_string());
+        }
+        let benchmark = match backend {
+            crate::backend::ExecutionBackend::Cpu => crate::latency_bench::benchmark_genome::<crate::backend::CpuBackend>(
+                genome_str,
+                Some(&prepared.input_overrides),
+                Some(&prepared.output_overrides),
+                config,
+                backend.name(),
+                &crate::backend::create_cpu_device(),
+            ),
+            crate::backend::ExecutionBackend::Gpu => crate::latency_bench::benchmark_genome::<crate::backend::TrainBackend>(
+                genome_str,
+                Some(&prepared.input_overrides),
+                Some(&prepared.output_overrides),
+                config,
+                backend.name(),
+                &crate::backend::create_device(),
+            ),
+        };
+        match benchmark {
+            Ok(benchmark) => {
+                eprintln!(
+                    ">>> Genome {}: latency p50 {:.3} ms, p99 {:.3} ms, {:.0} samples/s at batch {}",
+                    i,
+                    benchmark.single.p50_ms,
+                    benchmark.single.p99_ms,
+                    benchmark.batched.samples_per_sec,
+                    benchmark.batched.batch_size
+                );
+                entry.latency = Some(benchmark);
+            }
+            Err(e) => {
+                eprintln!(">>> Genome {}: latency benchmark failed: {}", i, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_population_evaluation(
+    app_handle: tauri::AppHandle,
+    launch: EvaluationLaunch,
 ) -> Result<Vec<EvaluationResult>, String> {
     if EVALUATION_ACTIVE
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
@@ -599,6 +916,36 @@ async fn evaluate_population(
     }
     let _evaluation_guard = EvaluationGuard;
 
+    let (config, mut entries, resumed_checkpoint) = match launch {
+        EvaluationLaunch::New { config, entries } => (*config, entries, None),
+        EvaluationLaunch::Resume { run_id } => {
+            let checkpoint = crate::eval_runs::RunCheckpoint::open(&get_eval_runs_dir(), &run_id)?;
+            let manifest = checkpoint.snapshot();
+            (manifest.config, manifest.genomes, Some(checkpoint))
+        }
+    };
+    let crate::eval_runs::EvaluationRunConfig {
+        dataset_profile,
+        batch_size,
+        dataset_percent,
+        train_split,
+        val_split,
+        test_split,
+        source_generation,
+        memory_mode,
+        max_parallel_jobs,
+        execution_mode,
+        memory_safety_margin_mb,
+        execution_backend,
+        batch_pipeline,
+        zero_cost,
+        latency_benchmark,
+        device_filter,
+    } = config.clone();
+    let genomes: Vec<String> = entries.iter().map(|e| e.genome_str.clone()).collect();
+    let mut per_genome_epochs: Vec<usize> = entries.iter().map(|e| e.epochs).collect();
+    let genome_ids: Option<Vec<String>> = Some(entries.iter().map(|e| e.genome_id.clone()).collect());
+
     eprintln!(
         ">>> Entered evaluate_population. Preparing to process dataset profile '{}'...",
         dataset_profile
@@ -610,7 +957,7 @@ async fn evaluate_population(
 
     eprintln!(
         ">>> Evaluation config: backend='{}', mode='{}', requested_parallel_jobs={}, safety_margin_mb={}",
-        crate::backend::backend_name(),
+        execution_backend.name(),
         requested_execution_mode,
         requested_parallel_jobs,
         configured_safety_margin_mb
@@ -631,339 +978,142 @@ async fn evaluate_population(
     // differs from the snapshot (via stop_evolution), we abort.
     let session_snapshot = EVOLUTION_SESSION.load(Ordering::SeqCst);
 
-    type Backend = crate::backend::TrainBackend;
-    let device = crate::backend::create_device();
-    println!(">>> Wgpu device initialized (default)");
     let mut results = Vec::new();
 
     // 1. Read dataset_profiles.json to find the requested profile
     let profiles_json = load_dataset_profiles().await?;
-    let root: crate::dtos::DatasetProfilesRoot = serde_json::from_str(&profiles_json)
-        .map_err(|e| format!("Failed to parse dataset_profiles.json: {}", e))?;
-
-    let profile = root
-        .state
-        .profiles
-        .into_iter()
-        .find(|p| p.id == dataset_profile)
-        .ok_or_else(|| {
-            format!(
-                "Dataset profile '{}' not found in profiles JSON",
-                dataset_profile
-            )
-        })?;
-
-    let source_path_str = profile
-        .source_path
-        .clone()
-        .ok_or_else(|| format!("Profile '{}' has no sourcePath defined", profile.name))?;
-    let source_dir = std::path::Path::new(&source_path_str);
-
-    if !source_dir.exists() {
-        return Err(format!(
-            "Source directory does not exist: {}",
-            source_path_str
-        ));
-    }
+    let profile = crate::evaluation::find_dataset_profile(&profiles_json, &dataset_profile)?;
 
     use tauri::{Emitter, Manager};
     let app_data_dir = app_handle.path().app_data_dir().ok();
 
-    println!(">>> Starting DataLoader creation...");
-    let loader = match crate::data_loader::DataLoader::new(profile.clone(), app_data_dir) {
-        Ok(l) => {
-            println!(">>> DataLoader created successfully!");
-            l
-        },
-        Err(e) => {
-            println!(">>> DataLoader creation FAILED: {}", e);
-            return Err(e);
-        },
-    };
-
     println!(
         "Evaluating population of {} genomes on dataset: {} ({})\nBatch Size: {}, Epochs: {}",
         genomes.len(),
         profile.name,
-        source_path_str,
+        profile.source_path.as_deref().unwrap_or(""),
         batch_size,
         per_genome_epochs.iter().max().unwrap_or(&0)
     );
 
-    let mut valid_ids = loader.valid_sample_ids.clone();
-    if valid_ids.is_empty() {
-        return Err(format!(
-            "No valid samples found matching all streams for dataset: {}",
-            source_path_str
-        ));
-    }
+    let prepared = std::sync::Arc::new(match &resumed_checkpoint {
+        Some(checkpoint) => {
+            crate::evaluation::prepare_dataset_with_split(profile, app_data_dir, checkpoint.load_split()?)?
+        }
+        None => crate::evaluation::prepare_dataset(
+            profile,
+            app_data_dir,
+            dataset_percent,
+            train_split,
+            val_split,
+            test_split,
+        )?,
+    });
 
+    // Device checks, zero-cost decisions and latency benchmarks are made once, when the
+    // run starts, and persisted with it
+    if resumed_checkpoint.is_none()
+        && let Some(device_filter) = device_filter.as_ref()
     {
-        use rand::seq::SliceRandom;
-        let mut rng = rand::rng();
-        valid_ids.shuffle(&mut rng);
-    }
-
-    // Apply pct
-    let pct = dataset_percent.clamp(1, 100);
-    let use_count = (valid_ids.len() * pct) / 100;
-    let use_count = use_count.max(1);
-    valid_ids.truncate(use_count);
-
-    let total_split = (train_split + val_split + test_split).max(1) as f32;
-    let train_ratio = train_split as f32 / total_split;
-    let val_ratio = val_split as f32 / total_split;
-
-    let mut train_ids = Vec::new();
-    let mut val_ids = Vec::new();
-    let mut test_ids = Vec::new();
-
-    // Find a categorical target stream for stratification
-    let strat_stream_idx = profile.streams.iter().position(|s| s.role == "Target" && matches!(s.data_type, crate::dtos::DataType::Categorical));
-
-    if let Some(s_idx) = strat_stream_idx {
-        println!(">>> Stratifying split based on categorical stream '{}'...", profile.streams[s_idx].alias);
-        let stream_id = &profile.streams[s_idx].id;
-        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
-
-        if loader.stream_files.contains_key(stream_id) {
-            for id in &valid_ids {
-                let label = loader.get_class_label(stream_id, id).unwrap_or_else(|| "unknown".to_string());
-                groups.entry(label).or_default().push(id.clone());
-            }
-        }
-
-        for (label, mut members) in groups {
-            {
-                use rand::seq::SliceRandom;
-                members.shuffle(&mut rand::rng());
-            }
-            let n = members.len();
-            let t_count = ((n as f32) * train_ratio).round() as usize;
-            let v_count = ((n as f32) * val_ratio).round() as usize;
-
-            let t_count = t_count.min(n);
-            let v_count = v_count.min(n - t_count);
-
-            train_ids.extend(members.iter().take(t_count).cloned());
-            val_ids.extend(members.iter().skip(t_count).take(v_count).cloned());
-            test_ids.extend(members.iter().skip(t_count + v_count).cloned());
-
-            println!("  Class '{}': Total={}, Train={}, Val={}, Test={}", label, n, t_count, v_count, n - t_count - v_count);
-        }
-
-        // Final shuffle of the split sets
-        {
-            use rand::seq::SliceRandom;
-            let mut local_rng = rand::rng();
-            train_ids.shuffle(&mut local_rng);
-            val_ids.shuffle(&mut local_rng);
-            test_ids.shuffle(&mut local_rng);
-        }
-    } else {
-        // Fallback to random split
-        let train_count = ((valid_ids.len() as f32) * train_ratio).round() as usize;
-        let val_count = ((valid_ids.len() as f32) * val_ratio).round() as usize;
-
-        let train_count = train_count.min(valid_ids.len());
-        let val_count = val_count.min(valid_ids.len() - train_count);
-
-        train_ids = valid_ids.iter().take(train_count).cloned().collect();
-        val_ids = valid_ids.iter().skip(train_count).take(val_count).cloned().collect();
-        test_ids = valid_ids.iter().skip(train_count + val_count).cloned().collect();
+        check_population_for_device(&prepared, &mut entries, device_filter, session_snapshot)?;
     }
-
-    eprintln!(
-        ">>> Split: {} train samples, {} val samples, {} test samples",
-        train_ids.len(),
-        val_ids.len(),
-        test_ids.len()
-    );
-
-    // Filter streams by role
-    let input_stream_indices: Vec<usize> = profile
-        .streams
-        .iter()
-        .enumerate()
-        .filter(|(_, s)| s.role == "Input")
-        .map(|(i, _)| i)
-        .collect();
-    let target_stream_indices: Vec<usize> = profile
-        .streams
+    if resumed_checkpoint.is_none()
+        && let Some(zero_cost) = zero_cost.as_ref()
+    {
+        let store = app_handle
+            .path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| crate::zero_cost_calibration::CalibrationStore::load(&dir, &dataset_profile));
+        plan_zero_cost_budgets(&prepared, &mut entries, zero_cost, store.as_ref(), execution_backend, session_snapshot)?;
+        per_genome_epochs = entries.iter().map(|e| e.epochs).collect();
+    }
+    if resumed_checkpoint.is_none()
+        && let Some(latency_config) = latency_benchmark.as_ref()
+    {
+        benchmark_population_latency(&prepared, &mut entries, latency_config, execution_backend, session_snapshot)?;
+    }
+    // Repair may have changed genomes
+    let genomes: Vec<String> = entries.iter().map(|e| e.genome_str.clone()).collect();
+    let budgets: Vec<Option<crate::zero_cost_proxies::TrainingBudget>> =
+        entries.iter().map(|e| e.budget.clone()).collect();
+    let device_checks: Vec<Option<crate::device_filter::DeviceCheck>> =
+        entries.iter().map(|e| e.device_check.clone()).collect();
+    let costs: Vec<Option<crate::cost_analysis::ModelCost>> = genomes
         .iter()
-        .enumerate()
-        .filter(|(_, s)| s.role == "Target")
-        .map(|(i, _)| i)
+        .map(|genome_str| {
+            crate::cost_analysis::analyze_genome(
+                genome_str,
+                Some(&prepared.input_overrides),
+                Some(&prepared.output_overrides),
+            )
+            .ok()
+        })
         .collect();
+    let latencies: Vec<Option<crate::latency_bench::LatencyBenchmark>> =
+        entries.iter().map(|e| e.latency.clone()).collect();
+    // Results by genome position; genome ids need not be unique
+    let with_budgets = |results: Vec<Option<EvaluationResult>>| -> Vec<EvaluationResult> {
+        results
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, result)| {
+                let mut result = result?;
+                result.budget = budgets.get(i).cloned().flatten();
+                result.cost = costs.get(i).cloned().flatten();
+                result.latency = latencies.get(i).cloned().flatten();
+                result.device_check = device_checks.get(i).cloned().flatten();
+                Some(result)
+            })
+            .collect()
+    };
 
-    if input_stream_indices.is_empty() || target_stream_indices.is_empty() {
-        return Err(
-            "Dataset Profile must define at least one Input and one Target stream".to_string(),
-        );
-    }
-
-    // Determine expected input and output overrides based on the profile
-    let mut input_overrides = Vec::new();
-    for &idx in &input_stream_indices {
-        let stream = &profile.streams[idx];
-        match stream.data_type {
-            crate::dtos::DataType::Image => {
-                let mut h = 64;
-                let mut w = 64;
-                let mut channels = 3;
-                if let Some(prep) = &stream.preprocessing {
-                    if let Some(vision) = &prep.vision {
-                        if vision.resize.len() == 2 {
-                            w = vision.resize[0] as usize;
-                            h = vision.resize[1] as usize;
-                        }
-                        if vision.grayscale {
-                            channels = 1;
-                        }
-                    }
-                }
-
-                let external_shape = if stream.tensor_shape.len() == 3 {
-                    stream.tensor_shape.clone()
-                } else {
-                    vec![h, w, channels]
-                };
-
-                input_overrides.push(normalize_image_shape_to_internal_chw(&external_shape));
-            }
-            crate::dtos::DataType::Vector => {
-                let dim = stream.tensor_shape.get(0).cloned().unwrap_or(1);
-                input_overrides.push(vec![dim]);
-            }
-            crate::dtos::DataType::TemporalSequence => {
-                input_overrides.push(stream.tensor_shape.clone());
+    // Persist the run so it can be resumed after a crash or stop_evolution
+    let resumed = resumed_checkpoint.is_some();
+    let checkpoint = std::sync::Arc::new(match resumed_checkpoint {
+        Some(checkpoint) => checkpoint,
+        None => crate::eval_runs::RunCheckpoint::create(&get_eval_runs_dir(), config, &prepared.split, entries)?,
+    });
+    let run_id = checkpoint.run_id();
+    eprintln!(">>> Evaluation run '{}' (resumed={})", run_id, resumed);
+    let _ = app_handle.emit(
+        "evaluation-run-started",
+        serde_json::json!({ "run_id": run_id, "resumed": resumed }),
+    );
+    if !resumed {
+        for (i, (budget, genome_id)) in budgets.iter().zip(genome_ids.iter().flatten()).enumerate() {
+            if let Some(budget) = budget {
+                let _ = app_handle.emit(
+                    "training-event",
+                    crate::dtos::TrainingEvent::JobZeroCostScored {
+                        run_id: run_id.to_string(),
+                        job_id: i.to_string(),
+                        genome_id: genome_id.clone(),
+                        proxy_score: budget.zero_cost.normalized_score,
+                        strategy_decision: budget.zero_cost.strategy_decision.clone(),
+                    },
+                );
             }
-            _ => input_overrides.push(vec![1]),
-        }
-    }
-
-    let mut output_overrides = Vec::new();
-    let mut is_classification = false;
-    for &idx in &target_stream_indices {
-        let stream = &profile.streams[idx];
-        if let crate::dtos::DataType::Categorical = stream.data_type {
-            is_classification = true;
-            // Get the number of classes discovered by the loader
-            let num_classes = loader.stream_classes.get(&idx).cloned().unwrap_or(1);
-            output_overrides.push(vec![num_classes]);
-        } else {
-            let dim = stream.tensor_shape.get(0).cloned().unwrap_or(1);
-            output_overrides.push(vec![dim]);
         }
     }
 
-    // Helper macro to build batches
-    macro_rules! build_batches {
-        ($ids:expr, $loader:expr, $dev:expr) => {{
-            let mut assembled_batches: Vec<crate::entities::DynamicBatch<Backend>> = Vec::new();
-            for (idx, chunk) in $ids.chunks(batch_size).enumerate() {
-                // Check cancellation every 10 chunks during assembly
-                if idx % 10 == 0 && EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
-                    return Err("Evolution cancelled during batch assembly".to_string());
-                }
-
-                let mut batch_inputs: Vec<Vec<crate::entities::DynamicTensor<Backend>>> = vec![Vec::new(); input_stream_indices.len()];
-                let mut batch_targets: Vec<Vec<crate::entities::DynamicTensor<Backend>>> = vec![Vec::new(); target_stream_indices.len()];
-
-                for id in chunk {
-                    if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
-                        return Err("Evolution cancelled during batch assembly".to_string());
-                    }
-
-                    let load_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                        $loader.load_sample(id, $dev)
-                    }));
-
-                    match load_result {
-                        Ok(Ok(sample)) => {
-                            for (i, &stream_idx) in input_stream_indices.iter().enumerate() {
-                                if let Some(t) = sample.stream_tensors.get(&stream_idx) {
-                                    let t_clone: crate::entities::DynamicTensor<Backend> = t.clone();
-                                    batch_inputs[i].push(t_clone);
-                                }
-                            }
-                            for (i, &stream_idx) in target_stream_indices.iter().enumerate() {
-                                if let Some(t) = sample.stream_tensors.get(&stream_idx) {
-                                    let t_clone: crate::entities::DynamicTensor<Backend> = t.clone();
-                                    batch_targets[i].push(t_clone);
-                                }
-                            }
-                        }
-                        Ok(Err(e)) => {
-                            eprintln!("[ERROR] Sample '{}' dropped during load: {}", id, e);
-                        }
-                        Err(_) => {
-                            eprintln!("[ERROR] Sample '{}' panicked during load; dropping sample", id);
-                            return Err(format!(
-                                "Sample '{}' panicked during load (Burn/WGPU state became invalid). Aborting evaluation.",
-                                id
-                            ));
-                        }
-                    }
-                }
-
-                if !batch_inputs.iter().all(|list| !list.is_empty()) || !batch_targets.iter().all(|list| !list.is_empty()) {
-                    eprintln!("[DEBUG] Skipping batch. Input lens: {:?}, Target lens: {:?}",
-                        batch_inputs.iter().map(|l| l.len()).collect::<Vec<_>>(),
-                        batch_targets.iter().map(|l| l.len()).collect::<Vec<_>>()
-                    );
-                }
-
-                if batch_inputs.iter().all(|list| !list.is_empty()) && batch_targets.iter().all(|list| !list.is_empty()) {
-                    use crate::entities::concat_dynamic_tensors;
-                    let assembled = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                        let inputs: Vec<crate::entities::DynamicTensor<Backend>> = batch_inputs
-                            .into_iter()
-                            .map(|tensors| concat_dynamic_tensors::<Backend>(tensors))
-                            .collect();
-                        let targets: Vec<crate::entities::DynamicTensor<Backend>> = batch_targets
-                            .into_iter()
-                            .map(|tensors| concat_dynamic_tensors::<Backend>(tensors))
-                            .collect();
-                        (inputs, targets)
-                    }));
-
-                    match assembled {
-                        Ok((inputs, targets)) => {
-                            assembled_batches.push(crate::entities::DynamicBatch {
-                                inputs,
-                                targets,
-                            });
-                        }
-                        Err(_) => {
-                            return Err("Batch concatenation panicked (Burn/WGPU state invalid). Aborting evaluation.".to_string());
-                        }
-                    }
-                }
-            }
-            assembled_batches
-        }};
-    }
-
-    // 4. Build batches ONCE (reused for all genomes)
-    if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
-        return Err("Evolution cancelled before batch assembly".to_string());
+    struct RunStatusGuard {
+        checkpoint: std::sync::Arc<crate::eval_runs::RunCheckpoint>,
+        completed: bool,
     }
-    println!(">>> Assembling batches (shared across all genomes)...");
-    let train_batches = build_batches!(train_ids.as_slice(), loader, &device);
-    let val_batches = build_batches!(val_ids.as_slice(), loader, &device);
-    let test_batches = build_batches!(test_ids.as_slice(), loader, &device);
-
-    if train_batches.is_empty() {
-        return Err("No training batches could be assembled. Aborting.".to_string());
+    impl Drop for RunStatusGuard {
+        fn drop(&mut self) {
+            self.checkpoint.finish(if self.completed {
+                crate::eval_runs::RunStatus::Completed
+            } else {
+                crate::eval_runs::RunStatus::Interrupted
+            });
+        }
     }
-
-    println!(
-        ">>> Assembled {} train + {} val + {} test batches. Starting genome evaluation...",
-        train_batches.len(),
-        val_batches.len(),
-        test_batches.len()
-    );
+    let mut run_status_guard = RunStatusGuard {
+        checkpoint: checkpoint.clone(),
+        completed: false,
+    };
 
     let autosave_hidden_archive = |genome_id: &str,
                                    genome_str: &str,
@@ -1008,26 +1158,167 @@ async fn evaluate_population(
         }
     };
 
+    #[derive(serde::Serialize, Clone)]
+    struct GenomeResult {
+        index: usize,
+        loss: f32,
+        accuracy: f32,
+    }
+
+    let emit_genome_result = |index: usize, result: &EvaluationResult| {
+        let _ = app_handle.emit(
+            "evaluating-genome-result",
+            GenomeResult {
+                index,
+                loss: result.loss,
+                accuracy: result.accuracy,
+            },
+        );
+    };
+
+    let report_genome_result = |job: &crate::evaluation::GenomeJob,
+                                evaluation: &crate::evaluation::GenomeEvaluation| {
+        let result = &evaluation.result;
+        emit_genome_result(job.index, result);
+
+        if evaluation.source != crate::evaluation::EvaluationSource::CompileFailed {
+            autosave_hidden_archive(
+                &job.genome_id,
+                &job.genome_str,
+                result.loss,
+                result.accuracy,
+                result.profiler.clone(),
+            );
+        }
+    };
+
+    let selected_memory_mode = memory_mode.unwrap_or(crate::profiler::MemoryMode::Hybrid);
+    let training_params = crate::evaluation::TrainingParams {
+        dataset_profile: dataset_profile.clone(),
+        batch_size,
+        dataset_percent,
+        train_split,
+        val_split,
+        test_split,
+    };
+    let genome_id_at = |i: usize| {
+        genome_ids
+            .as_ref()
+            .and_then(|ids| ids.get(i))
+            .cloned()
+            .unwrap_or_else(|| format!("genome_{}", i))
+    };
+
+    // 4. Build batches ONCE (reused for all genomes)
+    if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
+        return Err("Evolution cancelled before batch assembly".to_string());
+    }
+    println!(">>> Assembling batches (shared across all genomes)...");
+    let progress_sink = std::sync::Arc::new(crate::progress::TauriProgressSink::new(app_handle.clone()));
+
+    // Genomes finished in an earlier session of a resumed run
+    let finished_results: Vec<Option<EvaluationResult>> =
+        (0..genomes.len()).map(|i| checkpoint.finished_result(i)).collect();
+
+    if execution_backend == crate::backend::ExecutionBackend::Cpu {
+        let mut cpu_ctx = crate::evaluation::EvaluationContext::<crate::backend::CpuBackend>::prepare(
+            &prepared,
+            training_params,
+            &batch_pipeline,
+            crate::backend::create_cpu_device(),
+            selected_memory_mode,
+            session_snapshot,
+            progress_sink.as_ref(),
+        )?;
+        cpu_ctx.epoch_checkpoint_dir = Some(checkpoint.epoch_checkpoint_dir());
+
+        let mut indexed_results = finished_results;
+        for (i, result) in indexed_results.iter().enumerate() {
+            if let Some(result) = result {
+                emit_genome_result(i, result);
+            }
+        }
+        let jobs: Vec<crate::evaluation::GenomeJob> = genomes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| indexed_results[*i].is_none())
+            .map(|(i, genome_str)| crate::evaluation::GenomeJob {
+                index: i,
+                genome_id: genome_id_at(i),
+                genome_str: genome_str.clone(),
+                epochs: *per_genome_epochs.get(i).unwrap_or(&0),
+                queue_wait_ms: 0,
+            })
+            .collect();
+        eprintln!(
+            ">>> Running CPU evaluation of {} genomes on the rayon pool ({} threads)",
+            jobs.len(),
+            rayon::current_num_threads()
+        );
+
+        // Training runs on rayon threads; finished genomes are reported from here
+        // so the frontend sees results as they arrive.
+        let (result_tx, mut result_rx) = tokio::sync::mpsc::unbounded_channel();
+        let sink = progress_sink.clone();
+        let run = checkpoint.clone();
+        let handle = tokio::task::spawn_blocking(move || {
+            crate::evaluation::evaluate_genomes_parallel(&cpu_ctx, jobs, sink.as_ref(), &|job, evaluation| {
+                if !cpu_ctx.is_cancelled() {
+                    run.record_result(job.index, &evaluation.result, evaluation.source);
+                }
+                let _ = result_tx.send((job.clone(), evaluation.clone()));
+            })
+        });
+        while let Some((job, evaluation)) = result_rx.recv().await {
+            report_genome_result(&job, &evaluation);
+        }
+
+        let outcomes = handle
+            .await
+            .map_err(|e| format!("CPU evaluation task failed to join: {}", e))?;
+        for (job, outcome) in outcomes {
+            match outcome {
+                Ok(evaluation) => indexed_results[job.index] = Some(evaluation.result),
+                Err(e) => {
+                    checkpoint.record_failure(job.index, &e);
+                    return Err(e);
+                }
+            }
+        }
+        run_status_guard.completed = EVOLUTION_SESSION.load(Ordering::SeqCst) == session_snapshot;
+        return Ok(with_budgets(indexed_results));
+    }
+
+    let mut eval_ctx = crate::evaluation::EvaluationContext::<crate::backend::TrainBackend>::prepare(
+        &prepared,
+        training_params,
+        &batch_pipeline,
+        crate::backend::create_device(),
+        selected_memory_mode,
+        session_snapshot,
+        progress_sink.as_ref(),
+    )?;
+    eval_ctx.epoch_checkpoint_dir = Some(checkpoint.epoch_checkpoint_dir());
+
+    println!(
+        ">>> {} {} train + {} val + {} test batches. Starting genome evaluation...",
+        if eval_ctx.batches.train.is_streaming() { "Streaming" } else { "Assembled" },
+        eval_ctx.batches.train.len(),
+        eval_ctx.batches.val.len(),
+        eval_ctx.batches.test.len()
+    );
+
     // 5. Evaluation Loop over each Genome
-    const MAX_RETRIES: usize = 3;
-    const RANDOM_CHANCE_THRESHOLD: f32 = 55.0;
     let evaluation_started_at = std::time::Instant::now();
-    let selected_memory_mode = profiling
-        .as_ref()
-        .and_then(|cfg| cfg.memory_mode)
-        .unwrap_or(crate::profiler::MemoryMode::Hybrid);
 
     let total_genomes = genomes.len();
     let evaluate_one_genome = |i: usize, genome_str: String, genome_id: String| {
         let app_handle = app_handle.clone();
-        let device = device.clone();
-        let dataset_profile = dataset_profile.clone();
-        let input_overrides = input_overrides.clone();
-        let output_overrides = output_overrides.clone();
-        let train_batches = train_batches.clone();
-        let val_batches = val_batches.clone();
-        let test_batches = test_batches.clone();
-        let per_genome_epochs = per_genome_epochs.clone();
+        let progress_sink = progress_sink.clone();
+        let eval_ctx = eval_ctx.clone();
+        let checkpoint = checkpoint.clone();
+        let finished = finished_results[i].clone();
+        let epochs = *per_genome_epochs.get(i).unwrap_or(&0);
         let requested_execution_mode = requested_execution_mode.clone();
         let requested_parallel_jobs = requested_parallel_jobs;
         let queued_at_ms = evaluation_started_at.elapsed().as_millis() as u64;
@@ -1039,18 +1330,29 @@ async fn evaluate_population(
                 .saturating_sub(queued_at_ms as u128) as u64;
 
             // Check cancellation between genomes
-            if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
-                return Ok((
+            if eval_ctx.is_cancelled() {
+                return Ok::<_, String>((
                     i,
                     EvaluationResult {
                         genome_id,
                         loss: 999.0,
                         accuracy: 0.0,
                         profiler: None,
+                        budget: None,
+                        cost: None,
+                        latency: None,
+                        device_check: None,
                     },
                 ));
             }
 
+            // Already evaluated in an earlier session of this run
+            if let Some(result) = finished {
+                emit_genome_result(i, &result);
+                return Ok((i, result));
+            }
+            checkpoint.mark_running(i);
+
             // Emit current genome index to the frontend for UI synchronization
             app_handle.emit("evaluating-genome", i).unwrap_or_else(|e| {
                 eprintln!("Failed to emit evaluating-genome event: {}", e);
@@ -1070,337 +1372,30 @@ async fn evaluate_population(
                 requested_parallel_jobs
             );
 
-            // Compute a cache key from genome content + all training params
-            let cache_key = {
-                let mut hasher = std::collections::hash_map::DefaultHasher::new();
-                let epochs = *per_genome_epochs.get(i).unwrap_or(&0) as usize;
-                genome_str.hash(&mut hasher);
-                dataset_profile.hash(&mut hasher);
-                batch_size.hash(&mut hasher);
-                epochs.hash(&mut hasher);
-                dataset_percent.hash(&mut hasher);
-                train_split.hash(&mut hasher);
-                val_split.hash(&mut hasher);
-                test_split.hash(&mut hasher);
-                hasher.finish()
+            let job = crate::evaluation::GenomeJob {
+                index: i,
+                genome_id,
+                genome_str,
+                epochs,
+                queue_wait_ms,
             };
 
-            // Check cache for identical genome + training params
-            if let Some(&(cached_loss, cached_acc)) = GENOME_EVAL_CACHE.lock().unwrap().get(&cache_key)
-            {
-                eprintln!(
-                    ">>> CACHE HIT for Genome {} (hash={:#x}): loss={}, acc={}",
-                    i, cache_key, cached_loss, cached_acc
-                );
-
-                let _ = app_handle.emit("evaluating-genome-start", i);
-                #[derive(serde::Serialize, Clone)]
-                struct CachedResult {
-                    index: usize,
-                    loss: f32,
-                    accuracy: f32,
-                }
-                let _ = app_handle.emit(
-                    "evaluating-genome-result",
-                    CachedResult {
-                        index: i,
-                        loss: cached_loss,
-                        accuracy: cached_acc,
-                    },
-                );
-
-                autosave_hidden_archive(&genome_id, &genome_str, cached_loss, cached_acc, None);
+            let (job, outcome) = tokio::task::spawn_blocking(move || {
+                let outcome = crate::evaluation::evaluate_genome(&eval_ctx, &job, progress_sink.as_ref());
+                (job, outcome)
+            })
+            .await
+            .map_err(|e| format!("Genome {} evaluation task failed to join: {}", i, e))?;
 
-                return Ok((
-                    i,
-                    EvaluationResult {
-                        genome_id,
-                        loss: cached_loss,
-                        accuracy: cached_acc,
-                        profiler: None,
-                    },
-                ));
+            let evaluation = outcome.inspect_err(|e| checkpoint.record_failure(i, e))?;
+            if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
+                checkpoint.mark_pending(i);
+            } else {
+                checkpoint.record_result(i, &evaluation.result, evaluation.source);
             }
+            report_genome_result(&job, &evaluation);
 
-            eprintln!("Genome JSON:\n{}", genome_str);
-
-            match std::panic::catch_unwind(|| {
-                crate::entities::GraphModel::<Backend>::build(
-                    &genome_str,
-                    &device,
-                    Some(&input_overrides),
-                    Some(&output_overrides),
-                )
-            }) {
-                Ok(_initial_model) => {
-                    let mut best_loss = 999.0_f32;
-                    let mut best_acc = 0.0_f32;
-                    let mut best_profiler: Option<TrainingProfiler> = None;
-                    let mut best_model: Option<GraphModel<Backend>> = None;
-
-                    for attempt in 0..MAX_RETRIES {
-                        if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
-                            break;
-                        }
-
-                        let model = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                            crate::entities::GraphModel::<Backend>::build(
-                                &genome_str,
-                                &device,
-                                Some(&input_overrides),
-                                Some(&output_overrides),
-                            )
-                        })) {
-                            Ok(m) => m,
-                            Err(_) => {
-                                eprintln!(
-                                    ">>> Genome {} attempt {} panicked while rebuilding model. Skipping attempt.",
-                                    i,
-                                    attempt + 1
-                                );
-                                continue;
-                            }
-                        };
-
-                        let _ = app_handle.emit("evaluating-genome-start", i);
-
-                        if attempt > 0 {
-                            eprintln!(
-                                ">>> RETRY {}/{} for Genome {} (previous acc={:.2}%, threshold={:.0}%)",
-                                attempt + 1,
-                                MAX_RETRIES,
-                                i,
-                                best_acc,
-                                RANDOM_CHANCE_THRESHOLD
-                            );
-                        }
-
-                        let epochs = *per_genome_epochs.get(i).unwrap_or(&0) as usize;
-                        let app_handle_clone = app_handle.clone();
-                        let train_batches_local = train_batches.clone();
-                        let val_batches_local = val_batches.clone();
-                        let test_batches_local = test_batches.clone();
-
-                        let training_task = tokio::task::spawn_blocking(move || {
-                            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                                let mut model_local = model;
-                                let mut profiler = crate::profiler::ProfilerCollector::new();
-                                profiler.set_memory_mode(selected_memory_mode);
-                                profiler.set_queue_wait_ms(queue_wait_ms);
-
-                                if epochs > 0 {
-                                    crate::entities::run_eval_pass(
-                                        &app_handle_clone,
-                                        i,
-                                        &mut model_local,
-                                        &train_batches_local,
-                                        epochs,
-                                        0.001,
-                                        is_classification,
-                                        &EVOLUTION_SESSION,
-                                        session_snapshot,
-                                        queue_wait_ms,
-                                        Some(&mut profiler),
-                                    );
-                                } else {
-                                    println!(">>> Genome {}: Skipping training (0 epochs requested)", i);
-                                }
-
-                                if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
-                                    println!(
-                                        ">>> Genome {} cancelled right after training. Skipping validation/test inside worker.",
-                                        i
-                                    );
-                                    return (999.0, 0.0, profiler.finalize(), model_local);
-                                }
-
-                                let (val_loss, val_acc) = if !val_batches_local.is_empty() {
-                                    crate::entities::run_validation_pass(
-                                        &model_local,
-                                        &val_batches_local,
-                                        "Validation",
-                                        is_classification,
-                                        Some(&mut profiler),
-                                    )
-                                } else {
-                                    (0.0, 0.0)
-                                };
-
-                                if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
-                                    println!(
-                                        ">>> Genome {} cancelled after validation. Skipping test pass inside worker.",
-                                        i
-                                    );
-                                    return (999.0, 0.0, profiler.finalize(), model_local);
-                                }
-
-                                let (loss, acc) = if !test_batches_local.is_empty() {
-                                    crate::entities::run_validation_pass(
-                                        &model_local,
-                                        &test_batches_local,
-                                        "Test",
-                                        is_classification,
-                                        Some(&mut profiler),
-                                    )
-                                } else if !val_batches_local.is_empty() {
-                                    (val_loss, val_acc)
-                                } else {
-                                    crate::entities::run_validation_pass(
-                                        &model_local,
-                                        &train_batches_local,
-                                        "Train",
-                                        is_classification,
-                                        None,
-                                    )
-                                };
-
-                                (loss, acc, profiler.finalize(), model_local)
-                            }))
-                        })
-                        .await;
-
-                        let (final_loss, final_acc, profiler_result, trained_model) = match training_task {
-                            Ok(Ok(tuple)) => tuple,
-                            Ok(Err(_)) => {
-                                return Err(format!(
-                                    "Genome {} attempt {} panicked during training/validation. Aborting evaluate_population to avoid corrupted WGPU state.",
-                                    i,
-                                    attempt + 1
-                                ));
-                            }
-                            Err(e) => {
-                                eprintln!(
-                                    ">>> Genome {} attempt {} failed to join training task: {}",
-                                    i,
-                                    attempt + 1,
-                                    e
-                                );
-                                continue;
-                            }
-                        };
-
-                        if EVOLUTION_SESSION.load(Ordering::SeqCst) != session_snapshot {
-                            eprintln!(
-                                ">>> Cancelled after training for Genome {}. Skipping val/test.",
-                                i
-                            );
-                            break;
-                        }
-
-                        let attempt_is_better = attempt == 0 || final_acc > best_acc;
-                        if attempt_is_better {
-                            best_loss = final_loss;
-                            best_acc = final_acc;
-                            best_profiler = Some(profiler_result);
-                            best_model = Some(trained_model);
-                        }
-
-                        if final_acc > RANDOM_CHANCE_THRESHOLD {
-                            break;
-                        }
-
-                        if attempt < MAX_RETRIES - 1 {
-                            eprintln!(
-                                ">>> Genome {} attempt {} got acc={:.2}% (below {:.0}% threshold). Will retry with fresh weights.",
-                                i,
-                                attempt + 1,
-                                final_acc,
-                                RANDOM_CHANCE_THRESHOLD
-                            );
-                        }
-                    }
-
-                    if let Some(model) = best_model.as_ref() {
-                        let cache_dir = get_weight_cache_dir();
-                        if let Err(e) = fs::create_dir_all(&cache_dir).map_err(|err| err.to_string()) {
-                            eprintln!("[weight_io] failed to ensure cache dir: {}", e);
-                        } else if let Err(e) = crate::weight_io::save_weights(&genome_id, Some(model), &cache_dir) {
-                            eprintln!(
-                                "[weight_io] failed to checkpoint weights for genome '{}': {}",
-                                genome_id, e
-                            );
-                        }
-                    }
-
-                    #[derive(serde::Serialize, Clone)]
-                    struct GenomeResult {
-                        index: usize,
-                        loss: f32,
-                        accuracy: f32,
-                    }
-                    let _ = app_handle.emit(
-                        "evaluating-genome-result",
-                        GenomeResult {
-                            index: i,
-                            loss: best_loss,
-                            accuracy: best_acc,
-                        },
-                    );
-
-                    autosave_hidden_archive(
-                        &genome_id,
-                        &genome_str,
-                        best_loss,
-                        best_acc,
-                        best_profiler.clone(),
-                    );
-
-                    if best_acc > RANDOM_CHANCE_THRESHOLD {
-                        GENOME_EVAL_CACHE
-                            .lock()
-                            .unwrap()
-                            .insert(cache_key, (best_loss, best_acc));
-                    }
-
-                    Ok((
-                        i,
-                        EvaluationResult {
-                            genome_id,
-                            loss: best_loss,
-                            accuracy: best_acc,
-                            profiler: best_profiler,
-                        },
-                    ))
-                }
-                Err(err) => {
-                    let msg = if let Some(s) = err.downcast_ref::<&str>() {
-                        *s
-                    } else if let Some(s) = err.downcast_ref::<String>() {
-                        s.as_str()
-                    } else {
-                        "Unknown panic"
-                    };
-                    println!(
-                        ">>> ABORTED: Genome {} failed to compile cleanly: {}",
-                        i, msg
-                    );
-
-                    #[derive(serde::Serialize, Clone)]
-                    struct GenomeResult2 {
-                        index: usize,
-                        loss: f32,
-                        accuracy: f32,
-                    }
-                    let _ = app_handle.emit(
-                        "evaluating-genome-result",
-                        GenomeResult2 {
-                            index: i,
-                            loss: 999.0,
-                            accuracy: 0.0,
-                        },
-                    );
-
-                    Ok((
-                        i,
-                        EvaluationResult {
-                            genome_id,
-                            loss: 999.0,
-                            accuracy: 0.0,
-                            profiler: None,
-                        },
-                    ))
-                }
-            }
+            Ok((i, evaluation.result))
         }
     };
 
@@ -1412,7 +1407,12 @@ async fn evaluate_population(
             effective_parallel_jobs
         );
 
-        let mut indexed_results: Vec<Option<EvaluationResult>> = vec![None; genomes.len()];
+        let mut indexed_results: Vec<Option<EvaluationResult>> = finished_results.clone();
+        for (i, result) in indexed_results.iter().enumerate() {
+            if let Some(result) = result {
+                emit_genome_result(i, result);
+            }
+        }
 
         let process_worker_mode = requested_execution_mode == "parallel-safe-limited";
         let mut fallback_to_inprocess = false;
@@ -1426,13 +1426,9 @@ async fn evaluate_population(
                 ">>> Worker progress note: per-batch progress events are emitted only when workers output WorkerTrainProgress JSON lines."
             );
 
-            let run_id = format!(
-                "run-{}",
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .map(|d| d.as_millis())
-                    .unwrap_or(0)
-            );
+            let run_id = checkpoint.run_id().to_string();
+            let pending_indices: Vec<usize> =
+                (0..genomes.len()).filter(|&i| indexed_results[i].is_none()).collect();
 
             let mut join_set: tokio::task::JoinSet<(usize, Result<EvaluationResult, String>)> =
                 tokio::task::JoinSet::new();
@@ -1464,13 +1460,17 @@ async fn evaluate_population(
                         .duration_since(std::time::UNIX_EPOCH)
                         .map(|d| d.as_millis() as u64)
                         .unwrap_or(0),
+                    execution_backend,
+                    batch_pipeline: batch_pipeline.clone(),
+                    split: Some(prepared.split.clone()),
+                    epoch_checkpoint_dir: Some(checkpoint.epoch_checkpoint_dir()),
                 };
 
                 join_set.spawn(async move { (idx, run_worker_job(app, idx, request).await) });
             };
 
-            while next_index < genomes.len() && in_flight < effective_parallel_jobs {
-                spawn_next(next_index, &mut join_set);
+            while next_index < pending_indices.len() && in_flight < effective_parallel_jobs {
+                spawn_next(pending_indices[next_index], &mut join_set);
                 next_index += 1;
                 in_flight += 1;
             }
@@ -1505,6 +1505,7 @@ async fn evaluate_population(
                                 accuracy: result.accuracy,
                             },
                         );
+                        checkpoint.record_result(idx, &result, crate::evaluation::EvaluationSource::Trained);
                         indexed_results[idx] = Some(result);
                     }
                     Ok((idx, Err(err))) => {
@@ -1553,8 +1554,8 @@ async fn evaluate_population(
                     break;
                 }
 
-                while next_index < genomes.len() && in_flight < effective_parallel_jobs {
-                    spawn_next(next_index, &mut join_set);
+                while next_index < pending_indices.len() && in_flight < effective_parallel_jobs {
+                    spawn_next(pending_indices[next_index], &mut join_set);
                     next_index += 1;
                     in_flight += 1;
                 }
@@ -1602,9 +1603,7 @@ async fn evaluate_population(
             }
         }
 
-        for result in indexed_results.into_iter().flatten() {
-            results.push(result);
-        }
+        results = indexed_results;
     } else {
         eprintln!(
             ">>> Running sequential evaluation (effective_gpu_workers=1)"
@@ -1621,11 +1620,12 @@ async fn evaluate_population(
                 .cloned()
                 .unwrap_or_else(|| format!("genome_{}", i));
             let (_, result) = evaluate_one_genome(i, genomes[i].clone(), genome_id).await?;
-            results.push(result);
+            results.push(Some(result));
         }
     }
 
-    Ok(results)
+    run_status_guard.completed = EVOLUTION_SESSION.load(Ordering::SeqCst) == session_snapshot;
+    Ok(with_budgets(results))
 }
 
 // --- Scan Dataset ---
@@ -1637,6 +1637,7 @@ pub struct StreamLocatorConfig {
     pub locator_type: String, // "GlobPattern" | "FolderMapping" | "CompanionFile" | "CsvDataset" | "None"
     pub pattern: Option<String>, // for GlobPattern
     pub path_template: Option<String>, // for CompanionFile
+    pub parser: Option<String>, // for CompanionFile: "YOLO" | "Text" | "COCO_Subset"
     
     // CSV Dataset specific
     pub csv_path: Option<String>,
@@ -1645,6 +1646,13 @@ pub struct StreamLocatorConfig {
     pub feature_columns: Option<Vec<String>>,
     pub target_column: Option<String>,
     pub window_size: Option<usize>,
+
+    // NumpyArray / ParquetTable specific
+    pub array_path: Option<String>,
+    pub array_key: Option<String>,        // Array name inside an .npz archive
+    pub columns: Option<Vec<String>>,     // Parquet value columns
+    pub id_column: Option<String>,        // Parquet sample id column
+
     pub stream_role: Option<String>, // "Input" | "Target" | "Ignore"
     pub data_type: Option<String>,   // "Image" | "Vector" | "Categorical" | "Text" | "TemporalSequence"
 }
@@ -1692,6 +1700,23 @@ fn collect_glob_ids(root: &Path, pattern: &str) -> HashMap<String, std::path::Pa
     map
 }
 
+/// The `NumpyArray`/`ParquetTable` locator described by a scan config.
+fn tensor_file_locator(cfg: &StreamLocatorConfig) -> Option<dtos::DataLocatorDef> {
+    let path = cfg.array_path.clone().filter(|p| !p.is_empty())?;
+    match cfg.locator_type.as_str() {
+        "NumpyArray" => Some(dtos::DataLocatorDef::NumpyArray {
+            path,
+            key: cfg.array_key.clone().filter(|k| !k.is_empty()),
+        }),
+        "ParquetTable" => Some(dtos::DataLocatorDef::ParquetTable {
+            path,
+            columns: cfg.columns.clone().unwrap_or_default(),
+            id_column: cfg.id_column.clone().filter(|c| !c.is_empty()),
+        }),
+        _ => None,
+    }
+}
+
 fn collect_folder_mapping_ids(
     anchor_ids: &HashMap<String, std::path::PathBuf>,
 ) -> HashMap<String, String> {
@@ -1706,22 +1731,6 @@ fn collect_folder_mapping_ids(
     map
 }
 
-fn collect_companion_ids(
-    root: &Path,
-    anchor_ids: &HashSet<String>,
-    path_template: &str,
-) -> HashSet<String> {
-    let mut found = HashSet::new();
-    for id in anchor_ids {
-        let relative = path_template.replace("{id}", id);
-        let full_path = root.join(&relative);
-        if full_path.exists() {
-            found.insert(id.clone());
-        }
-    }
-    found
-}
-
 #[tauri::command]
 async fn scan_dataset(
     root_path: String,
@@ -1732,6 +1741,19 @@ async fn scan_dataset(
         return Err(format!("Root path does not exist: {}", root_path));
     }
 
+    // NumPy/Parquet files are read once and shared by anchor discovery and the stream reports
+    let mut tensor_tables: HashMap<String, Result<(dtos::DataLocatorDef, crate::tensor_files::TensorTable), String>> =
+        stream_configs
+            .iter()
+            .filter(|cfg| matches!(cfg.locator_type.as_str(), "NumpyArray" | "ParquetTable"))
+            .map(|cfg| {
+                let loaded = tensor_file_locator(cfg)
+                    .ok_or_else(|| "No array/table path configured".to_string())
+                    .and_then(|locator| crate::tensor_files::load_table(root, &locator).map(|table| (locator, table)));
+                (cfg.stream_id.clone(), loaded)
+            })
+            .collect();
+
     // Step 1a: Check if any stream is CsvDataset вЂ” if so, use it as anchor
     let mut anchor_ids: HashMap<String, std::path::PathBuf> = HashMap::new();
     let mut anchor_from_csv = false;
@@ -1757,6 +1779,7 @@ async fn scan_dataset(
                 target_column: target_column.clone(),
                 window_size: None,
                 window_stride: None,
+                horizon: None,
                 preprocessing: crate::dtos::CsvPreprocessingConfig {
                     normalization: "none".to_string(),
                     handle_missing: "skip".to_string(),
@@ -1804,6 +1827,12 @@ async fn scan_dataset(
                         break;
                     }
                 }
+                "NumpyArray" | "ParquetTable" => {
+                    if let Some(Ok((_, table))) = tensor_tables.get(&cfg.stream_id) {
+                        anchor_ids = table.ids.iter().map(|id| (id.clone(), root.to_path_buf())).collect();
+                        break;
+                    }
+                }
                 _ => {}
             }
         }
@@ -1903,21 +1932,34 @@ async fn scan_dataset(
             }
             "CompanionFile" => {
                 let template = cfg.path_template.as_deref().unwrap_or("{id}.txt");
-                let found = collect_companion_ids(root, &all_sample_ids, template);
+                let parser = crate::companion_loader::CompanionParser::from_name(cfg.parser.as_deref().unwrap_or("Text"))?;
+                let annotations =
+                    crate::companion_loader::load_companion_annotations(root, template, parser, &all_sample_ids);
+                let found: HashSet<String> = annotations.by_sample.keys().cloned().collect();
                 let missing: Vec<String> = all_sample_ids.difference(&found).cloned().collect();
                 valid_ids = valid_ids.intersection(&found).cloned().collect();
-                
-                // For companion files, we can't easily discover classes without parsing them all
+
+                let class_counts = annotations.class_counts();
+                let mut warnings: Vec<String> = annotations.errors.iter().take(10).cloned().collect();
+                if annotations.errors.len() > warnings.len() {
+                    warnings.push(format!(
+                        "... and {} more unparseable companion files",
+                        annotations.errors.len() - warnings.len()
+                    ));
+                }
                 reports.push(StreamScanReport {
                     stream_id: cfg.stream_id.clone(),
                     alias: cfg.alias.clone(),
                     found_count: found.len(),
                     missing_sample_ids: missing,
-                    discovered_classes: None,
+                    num_classes: if class_counts.is_empty() { None } else { Some(class_counts.len()) },
+                    discovered_classes: if class_counts.is_empty() { None } else { Some(class_counts) },
                     input_shape: None,
-                    num_classes: None,
-                    inferred_data_type: "Text".to_string(),
-                    warnings: vec![],
+                    inferred_data_type: match parser {
+                        crate::companion_loader::CompanionParser::Text => "Text".to_string(),
+                        _ => "Categorical".to_string(),
+                    },
+                    warnings,
                 });
             }
             "CsvDataset" => {
@@ -1960,6 +2002,7 @@ async fn scan_dataset(
                         target_column,
                         window_size: None,
                         window_stride: None,
+                        horizon: None,
                         preprocessing: crate::dtos::CsvPreprocessingConfig {
                             normalization: "none".to_string(),
                             handle_missing: "skip".to_string(),
@@ -1975,6 +2018,7 @@ async fn scan_dataset(
                         target_column: String::new(), // Empty for input streams
                         window_size: if effective_sample_mode == "temporal_window" { Some(window_size) } else { None },
                         window_stride: None,
+                        horizon: None,
                         preprocessing: crate::dtos::CsvPreprocessingConfig {
                             normalization: "none".to_string(),
                             handle_missing: "skip".to_string(),
@@ -2075,6 +2119,83 @@ async fn scan_dataset(
                     warnings: vec![],
                 });
             }
+            "NumpyArray" | "ParquetTable" => {
+                let stream_role = cfg.stream_role.as_deref().unwrap_or("Input");
+                let data_type = cfg.data_type.as_deref().unwrap_or("Vector");
+                let loaded = tensor_tables
+                    .remove(&cfg.stream_id)
+                    .unwrap_or_else(|| Err("No array/table path configured".to_string()));
+
+                let (locator, table) = match loaded {
+                    Ok(loaded) => loaded,
+                    Err(e) => {
+                        eprintln!(">>> Tensor file scan failed for stream '{}': {}", cfg.alias, e);
+                        valid_ids.clear();
+                        reports.push(StreamScanReport {
+                            stream_id: cfg.stream_id.clone(),
+                            alias: cfg.alias.clone(),
+                            found_count: 0,
+                            missing_sample_ids: vec![],
+                            discovered_classes: None,
+                            input_shape: None,
+                            num_classes: None,
+                            inferred_data_type: data_type.to_string(),
+                            warnings: vec![e],
+                        });
+                        continue;
+                    }
+                };
+
+                let found: HashSet<String> = table.ids.iter().cloned().collect();
+                let missing: Vec<String> = all_sample_ids.difference(&found).cloned().collect();
+                valid_ids = valid_ids.intersection(&found).cloned().collect();
+
+                let mut warnings = vec![];
+                let mut input_shape = None;
+                let mut inferred_data_type = data_type.to_string();
+                if stream_role == "Input" {
+                    let stream = dtos::DataStream {
+                        id: cfg.stream_id.clone(),
+                        alias: cfg.alias.clone(),
+                        role: "Input".to_string(),
+                        data_type: serde_json::from_value(serde_json::Value::String(data_type.to_string()))
+                            .unwrap_or(dtos::DataType::Vector),
+                        tensor_shape: vec![],
+                        num_classes: None,
+                        locator,
+                        preprocessing: None,
+                        task_type: None,
+                    };
+                    match crate::shape_inference::ShapeInference::tensor_table_shape(&stream, &table, &mut warnings) {
+                        Ok((shape, inferred, _)) => {
+                            input_shape = Some(shape);
+                            inferred_data_type = inferred;
+                        }
+                        Err(e) => warnings.push(e),
+                    }
+                }
+
+                let mut class_counts: HashMap<String, usize> = HashMap::new();
+                if stream_role == "Target" && data_type == "Categorical" {
+                    for row in 0..table.len() {
+                        if let Some(label) = table.label(row) {
+                            *class_counts.entry(label).or_insert(0) += 1;
+                        }
+                    }
+                }
+
+                reports.push(StreamScanReport {
+                    stream_id: cfg.stream_id.clone(),
+                    alias: cfg.alias.clone(),
+                    found_count: found.len(),
+                    missing_sample_ids: missing,
+                    num_classes: if class_counts.is_empty() { None } else { Some(class_counts.len()) },
+                    discovered_classes: if class_counts.is_empty() { None } else { Some(class_counts) },
+                    input_shape,
+                    inferred_data_type,
+                    warnings,
+                });
+            }
             _ => {
                 // "None" or unknown - skip, don't filter
                 reports.push(StreamScanReport {
@@ -2107,25 +2228,108 @@ async fn scan_dataset(
 async fn cache_dataset(
     app_handle: tauri::AppHandle,
     profile_json: String,
+    options: Option<crate::image_cache::ImageCacheOptions>,
 ) -> Result<crate::data_loader::CacheResult, String> {
-    use tauri::Manager;
+    use tauri::{Emitter, Manager};
     let profile: crate::dtos::DatasetProfile = serde_json::from_str(&profile_json)
         .map_err(|e| format!("Failed to parse profile JSON: {}", e))?;
 
     let app_data_dir = app_handle.path().app_data_dir().ok();
-    let loader = crate::data_loader::DataLoader::new(profile, app_data_dir)?;
-    let result = loader.build_image_cache()?;
+    let loader = crate::data_loader::DataLoader::with_all_samples(profile, app_data_dir)?;
+    let options = options.unwrap_or_default();
+    let on_progress = move |progress: &crate::data_loader::CacheBuildProgress| {
+        let _ = app_handle.emit("dataset-cache-progress", progress.clone());
+    };
+    let result = tauri::async_runtime::spawn_blocking(move || loader.build_image_cache(&options, &on_progress))
+        .await
+        .map_err(|e| format!("Cache build task failed: {}", e))??;
     Ok(result)
 }
 
 #[tauri::command]
-async fn validate_dataset_profile(profile_json: String) -> Result<dtos::DatasetValidationReport, String> {
+async fn verify_dataset_cache(
+    app_handle: tauri::AppHandle,
+    profile_json: String,
+    deep: Option<bool>,
+) -> Result<crate::data_loader::CacheVerifyReport, String> {
+    use tauri::Manager;
+    let profile: crate::dtos::DatasetProfile = serde_json::from_str(&profile_json)
+        .map_err(|e| format!("Failed to parse profile JSON: {}", e))?;
+
+    let app_data_dir = app_handle.path().app_data_dir().ok();
+    let loader = crate::data_loader::DataLoader::with_all_samples(profile, app_data_dir)?;
+    tauri::async_runtime::spawn_blocking(move || loader.verify_image_cache(deep.unwrap_or(false)))
+        .await
+        .map_err(|e| format!("Cache verification task failed: {}", e))?
+}
+
+/// Data health report of a profile over a train/val/test split. The cached report is
+/// returned while the profile and its files are unchanged, unless `refresh` is set.
+#[tauri::command]
+async fn dataset_report(
+    app_handle: tauri::AppHandle,
+    profile_json: String,
+    train_split: Option<usize>,
+    val_split: Option<usize>,
+    test_split: Option<usize>,
+    refresh: Option<bool>,
+) -> Result<crate::dataset_report::DatasetReport, String> {
+    use tauri::Manager;
+    let profile: crate::dtos::DatasetProfile = serde_json::from_str(&profile_json)
+        .map_err(|e| format!("Failed to parse profile JSON: {}", e))?;
+    let app_data_dir = app_handle.path().app_data_dir().ok();
+    let splits = (train_split.unwrap_or(80), val_split.unwrap_or(10), test_split.unwrap_or(10));
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let (loader, split) = crate::evaluation::load_and_split(&profile, None, splits.0, splits.1, splits.2)?;
+        let key = crate::dataset_report::report_key(&profile, &loader, splits);
+        let path = app_data_dir.map(|dir| crate::dataset_report::report_path(&dir, &profile.id));
+        if !refresh.unwrap_or(false)
+            && let Some(report) = path.as_deref().and_then(|path| crate::dataset_report::load_cached(path, &key))
+        {
+            return Ok(report);
+        }
+
+        let report = crate::dataset_report::build_report(&profile, &loader, &split, key)?;
+        if let Some(path) = &path
+            && let Err(e) = crate::dataset_report::persist(path, &report)
+        {
+            eprintln!("[dataset_report] failed to cache report for '{}': {}", profile.id, e);
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Dataset report task failed: {}", e))?
+}
+
+/// Tabular preprocessing statistics last fitted for a profile, by stream id.
+#[tauri::command]
+async fn get_tabular_stats(
+    app_handle: tauri::AppHandle,
+    profile_id: String,
+) -> Result<HashMap<String, crate::tabular::TabularStats>, String> {
+    use tauri::Manager;
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("No AppData directory: {}", e))?;
+    Ok(crate::tabular::load_persisted(&crate::tabular::stats_path(&app_data_dir, &profile_id)))
+}
+
+#[tauri::command]
+async fn validate_dataset_profile(
+    profile_json: String,
+    train_split: Option<usize>,
+    val_split: Option<usize>,
+    test_split: Option<usize>,
+) -> Result<dtos::DatasetValidationReport, String> {
     // Validate a dataset profile for evolution readiness.
     // Checks:
     // - All Input streams have valid input shapes
     // - At least one Target stream exists with valid num_classes
     // - Streams have compatible sample counts
     // - Data types are supported by current architecture
+    // - The sampling strategy is known; per-class counts of each split are reported
 
     let profile: dtos::DatasetProfile = serde_json::from_str(&profile_json)
         .map_err(|e| format!("Failed to parse dataset profile: {}", e))?;
@@ -2133,7 +2337,8 @@ async fn validate_dataset_profile(profile_json: String) -> Result<dtos::DatasetV
     let mut issues: Vec<dtos::ValidationIssue> = vec![];
     let mut input_shapes: HashMap<String, Vec<usize>> = HashMap::new();
     let mut output_shape: Option<Vec<usize>> = None;
-    let total_valid_samples: usize = 0;
+    let mut total_valid_samples: usize = 0;
+    let mut class_counts: Vec<dtos::ClassSplitCounts> = vec![];
 
     // Collect input shapes from profile streams
     // After "Scan & Validate", tensor_shape is populated by the scan operation
@@ -2212,6 +2417,62 @@ async fn validate_dataset_profile(profile_json: String) -> Result<dtos::DatasetV
         }
     }
 
+    if let Err(e) = crate::sampling::SamplingStrategy::from_name(profile.sampling.as_deref().unwrap_or("")) {
+        issues.push(dtos::ValidationIssue {
+            severity: dtos::ValidationSeverity::Error,
+            component: "Sampling".to_string(),
+            message: e,
+            suggested_fix: Some("Pick uniform, oversample, undersample or weighted sampling".to_string()),
+        });
+    }
+
+    // Split the samples the way an evaluation run would and count every class.
+    // Labels come straight from the locators, so no image cache is needed.
+    if profile.source_path.is_some() && !issues.iter().any(|i| i.severity == dtos::ValidationSeverity::Error) {
+        let split_profile = profile.clone();
+        let counted = tauri::async_runtime::spawn_blocking(move || {
+            let (loader, split) = crate::evaluation::load_and_split(
+                &split_profile,
+                None,
+                train_split.unwrap_or(80),
+                val_split.unwrap_or(10),
+                test_split.unwrap_or(10),
+            )?;
+            let counts = crate::evaluation::class_split_counts(&split_profile, &loader, &split)?;
+            Ok::<_, String>((loader.valid_sample_ids.len(), counts))
+        })
+        .await
+        .map_err(|e| format!("Split counting task failed: {}", e))?;
+        match counted {
+            Ok((total, counts)) => {
+                total_valid_samples = total;
+                if let (Some(smallest), Some(largest)) = (
+                    counts.iter().map(|c| c.train).min(),
+                    counts.iter().map(|c| c.train).max(),
+                ) && profile.sampling.as_deref().is_none_or(|s| s.is_empty() || s == "uniform")
+                    && largest >= smallest.max(1) * 5
+                {
+                    issues.push(dtos::ValidationIssue {
+                        severity: dtos::ValidationSeverity::Warning,
+                        component: "ClassBalance".to_string(),
+                        message: format!(
+                            "Training classes are imbalanced ({} vs {} samples); genomes may converge to the majority class.",
+                            largest, smallest
+                        ),
+                        suggested_fix: Some("Use oversample, undersample or weighted sampling for this profile".to_string()),
+                    });
+                }
+                class_counts = counts;
+            }
+            Err(e) => issues.push(dtos::ValidationIssue {
+                severity: dtos::ValidationSeverity::Warning,
+                component: "SampleAlignment".to_string(),
+                message: format!("Could not split the dataset to count classes: {}", e),
+                suggested_fix: None,
+            }),
+        }
+    }
+
     // Summary
     let is_valid = !issues.iter().any(|i| i.severity == dtos::ValidationSeverity::Error);
     let can_start_evolution = is_valid && !input_shapes.is_empty() && !target_streams.is_empty();
@@ -2223,6 +2484,7 @@ async fn validate_dataset_profile(profile_json: String) -> Result<dtos::DatasetV
         output_shape,
         total_valid_samples,
         can_start_evolution,
+        class_counts,
     })
 }
 
@@ -2249,6 +2511,10 @@ fn get_weight_cache_dir() -> PathBuf {
     get_genomes_dir().join("weights_cache")
 }
 
+fn get_eval_runs_dir() -> PathBuf {
+    get_genomes_dir().join("eval_runs")
+}
+
 fn current_unix_ms() -> u64 {
     chrono::Utc::now().timestamp_millis().max(0) as u64
 }
@@ -2692,8 +2958,9 @@ async fn export_genome_with_weights(
         lineage,
     };
 
+    let preprocessing = weight_io::load_preprocessing(&genome_id, &cache_dir)?;
     let (_weights_path, metadata_path) =
-        weight_io::export_with_metadata(&genome_id, &output_dir, &objectives, profiler)?;
+        weight_io::export_with_metadata(&genome_id, &output_dir, &objectives, profiler, preprocessing)?;
 
     Ok(WeightExportResponse {
         weights_path: export_weights_path.to_string_lossy().to_string(),
@@ -2790,9 +3057,10 @@ async fn preview_csv(
 
 /// Compute zero-cost proxy metrics for fast architecture evaluation
 /// 
-/// This performs a single forward-backward pass to estimate architecture quality
-/// without full training. Based on SynFlow metric from ICLR 2021 paper:
-/// "Zero-Cost Proxies for Lightweight NAS"
+/// This performs a few forward-backward passes on a small random batch to estimate
+/// architecture quality without full training. Based on the metrics from the ICLR 2021
+/// paper "Zero-Cost Proxies for Lightweight NAS" (SynFlow, GradNorm, SNIP, GraSP,
+/// Fisher, Jacob-cov) plus NASWOT and parameter/FLOP baselines.
 #[tauri::command]
 async fn compute_zero_cost_score(
     genome_json: String,
@@ -2812,44 +3080,13 @@ async fn compute_zero_cost_score(
                         synflow: 5.0,
                         normalized_score: 0.5,
                         strategy_decision: "full_train".to_string(),
+                        proxies: Vec::new(),
                     });
                 }
 
                 // Build model from genome with explicit IO overrides inferred from serialized nodes.
                 // This stabilizes shape semantics between CHW/HWC sources for zero-cost scoring.
-                let mut input_overrides: Vec<Vec<usize>> = Vec::new();
-                let mut output_overrides: Vec<Vec<usize>> = Vec::new();
-
-                let mut parsing_connections = false;
-                for line in genome_json.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
-                    if line == "CONNECTIONS" {
-                        parsing_connections = true;
-                        continue;
-                    }
-                    if parsing_connections {
-                        continue;
-                    }
-
-                    let parsed: crate::dtos::NodeDtoJSON = match serde_json::from_str(line) {
-                        Ok(v) => v,
-                        Err(_) => continue,
-                    };
-
-                    match parsed {
-                        crate::dtos::NodeDtoJSON::Input { output_shape } => {
-                            let shape: Vec<usize> = output_shape.iter().map(|&v| v as usize).collect();
-                            if shape.len() == 3 {
-                                input_overrides.push(normalize_image_shape_to_internal_chw(&shape));
-                            } else {
-                                input_overrides.push(shape);
-                            }
-                        }
-                        crate::dtos::NodeDtoJSON::Output { input_shape } => {
-                            output_overrides.push(input_shape.iter().map(|&v| v as usize).collect());
-                        }
-                        _ => {}
-                    }
-                }
+                let (input_overrides, output_overrides) = genome_io_overrides(&genome_json);
 
                 let input_overrides_ref = if input_overrides.is_empty() {
                     None
@@ -2869,24 +3106,25 @@ async fn compute_zero_cost_score(
                     output_overrides_ref,
                 );
 
-                // Create a minimal sample batch (1 sample with dummy data)
+                // Create a small sample batch with dummy data
+                let n = zero_cost_proxies::PROXY_BATCH_SIZE;
                 let mut inputs = Vec::new();
                 for shape in &model.input_shapes {
                     let tensor = if shape.len() == 1 {
                         DynamicTensor::Dim2(Tensor::<Backend, 2>::random(
-                            [1, shape[0]],
+                            [n, shape[0]],
                             Distribution::Normal(0.0, 1.0),
                             &device,
                         ))
                     } else if shape.len() == 2 {
                         DynamicTensor::Dim3(Tensor::<Backend, 3>::random(
-                            [1, shape[0], shape[1]],
+                            [n, shape[0], shape[1]],
                             Distribution::Normal(0.0, 1.0),
                             &device,
                         ))
                     } else if shape.len() == 3 {
                         DynamicTensor::Dim4(Tensor::<Backend, 4>::random(
-                            [1, shape[0], shape[1], shape[2]],
+                            [n, shape[0], shape[1], shape[2]],
                             Distribution::Normal(0.0, 1.0),
                             &device,
                         ))
@@ -2900,18 +3138,22 @@ async fn compute_zero_cost_score(
                 let mut targets = Vec::new();
                 for shape in &model.output_shapes {
                     let tensor = if shape.len() == 1 {
-                        let num_classes = if shape[0] > 0 { shape[0] as f64 } else { 1.0 };
-                        let class_idx = (rand::random::<f64>() * num_classes).floor();
-                        DynamicTensor::Dim2(Tensor::<Backend, 2>::from_data([[class_idx]], &device))
+                        let num_classes = if shape[0] > 0 { shape[0] as f32 } else { 1.0 };
+                        let class_idx: Vec<f32> =
+                            (0..n).map(|_| (rand::random::<f32>() * num_classes).floor()).collect();
+                        DynamicTensor::Dim2(Tensor::<Backend, 2>::from_data(
+                            burn::tensor::TensorData::new(class_idx, [n, 1]),
+                            &device,
+                        ))
                     } else if shape.len() == 2 {
                         DynamicTensor::Dim3(Tensor::<Backend, 3>::random(
-                            [1, shape[0], shape[1]],
+                            [n, shape[0], shape[1]],
                             Distribution::Normal(0.0, 1.0),
                             &device,
                         ))
                     } else if shape.len() == 3 {
                         DynamicTensor::Dim4(Tensor::<Backend, 4>::random(
-                            [1, shape[0], shape[1], shape[2]],
+                            [n, shape[0], shape[1], shape[2]],
                             Distribution::Normal(0.0, 1.0),
                             &device,
                         ))
@@ -2923,11 +3165,12 @@ async fn compute_zero_cost_score(
 
                 let batch = DynamicBatch { inputs, targets };
 
-                // Compute real SynFlow score
-                let synflow_score = zero_cost_proxies::compute_synflow(&model, &batch);
-                println!(">>> compute_zero_cost_score: score = {}", synflow_score);
+                let proxies = zero_cost_proxies::compute_proxies(&model, &batch)?;
+                for proxy in &proxies {
+                    println!(">>> compute_zero_cost_score: {} = {} ({:.2})", proxy.name, proxy.raw, proxy.normalized);
+                }
 
-                Ok(ZeroCostMetrics::from_synflow(synflow_score, &config))
+                Ok(ZeroCostMetrics::from_proxies(proxies, &config))
             },
         ));
 
@@ -2942,6 +3185,58 @@ async fn compute_zero_cost_score(
     Ok(result)
 }
 
+/// Re-scores a population's zero-cost metrics: with a calibrated store for the dataset
+/// profile the scores come from it, otherwise from rank voting (when `use_voting` is
+/// set). Decisions follow each genome's percentile within the population.
+#[tauri::command]
+fn calibrate_zero_cost_scores(
+    app_handle: tauri::AppHandle,
+    profile_id: Option<String>,
+    metrics: Vec<ZeroCostMetrics>,
+    config_json: String,
+) -> Result<Vec<ZeroCostMetrics>, String> {
+    use tauri::Manager;
+    let config: ZeroCostConfig = serde_json::from_str(&config_json)
+        .map_err(|e| format!("Failed to parse config: {}", e))?;
+    let store = match (profile_id, app_handle.path().app_data_dir()) {
+        (Some(profile_id), Ok(dir)) => Some(crate::zero_cost_calibration::CalibrationStore::load(&dir, &profile_id)),
+        _ => None,
+    };
+    let mut metrics = metrics;
+    crate::zero_cost_calibration::calibrate_population(store.as_ref(), &mut metrics, &config);
+    Ok(metrics)
+}
+
+/// Adds the proxy scores and final accuracies of trained genomes to a profile's
+/// calibration store and returns the updated correlation report.
+#[tauri::command]
+fn record_zero_cost_outcomes(
+    app_handle: tauri::AppHandle,
+    profile_id: String,
+    outcomes: Vec<crate::zero_cost_calibration::CalibrationRecord>,
+) -> Result<crate::zero_cost_calibration::CalibrationReport, String> {
+    use tauri::Manager;
+    let dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let mut store = crate::zero_cost_calibration::CalibrationStore::load(&dir, &profile_id);
+    let now = crate::current_unix_ms();
+    for outcome in outcomes {
+        store.record(outcome.proxies, outcome.accuracy, now);
+    }
+    store.persist(&dir)?;
+    Ok(store.report())
+}
+
+/// Spearman correlation of every proxy with final accuracy on a dataset profile.
+#[tauri::command]
+fn zero_cost_calibration_report(
+    app_handle: tauri::AppHandle,
+    profile_id: String,
+) -> Result<crate::zero_cost_calibration::CalibrationReport, String> {
+    use tauri::Manager;
+    let dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(crate::zero_cost_calibration::CalibrationStore::load(&dir, &profile_id).report())
+}
+
 #[tauri::command]
 async fn compute_pareto_front(
     generation: u32,
@@ -2972,22 +3267,157 @@ async fn compute_pareto_front(
     Ok(pareto::compute_generation_pareto_front(generation, &adjusted))
 }
 
+/// Starts a new Pareto archive for an evolution run.
+#[tauri::command]
+async fn reset_pareto_archive(config: Option<pareto_archive::ParetoArchiveConfig>) -> Result<(), String> {
+    let archive = pareto_archive::ParetoArchive::new(config.unwrap_or_default())?;
+    let mut current = PARETO_ARCHIVE
+        .lock()
+        .map_err(|e| format!("Pareto archive lock poisoned: {}", e))?;
+    *current = archive;
+    Ok(())
+}
+
+/// Merges a generation into the Pareto archive and returns its hypervolume, coverage
+/// and growth.
+#[tauri::command]
+async fn update_pareto_archive(
+    generation: u32,
+    genomes: Vec<dtos::GenomeObjectives>,
+) -> Result<pareto_archive::ArchiveGeneration, String> {
+    let mut archive = PARETO_ARCHIVE
+        .lock()
+        .map_err(|e| format!("Pareto archive lock poisoned: {}", e))?;
+    Ok(archive.update(generation, &genomes))
+}
+
+/// The Pareto archive with its per-generation history.
+#[tauri::command]
+async fn get_pareto_archive_history() -> Result<pareto_archive::ParetoArchive, String> {
+    let archive = PARETO_ARCHIVE
+        .lock()
+        .map_err(|e| format!("Pareto archive lock poisoned: {}", e))?;
+    Ok(archive.clone())
+}
+
+/// NSGA-II ranks and crowding distances of a population on any objectives (the
+/// accuracy/latency/size defaults when omitted), plus the first front's hypervolume
+/// when a reference point is given.
+#[tauri::command]
+async fn rank_pareto_population(
+    genomes: Vec<dtos::GenomeObjectives>,
+    objectives: Option<Vec<pareto::Objective>>,
+    reference_point: Option<Vec<f32>>,
+) -> Result<pareto::ParetoRanking, String> {
+    let objectives = objectives.unwrap_or_else(pareto::default_objectives);
+    pareto::rank_population(&genomes, &objectives, reference_point.as_deref())
+}
+
+/// Static MACs, parameters and activation memory of a genome at batch 1, per node and
+/// in total, with the IO shapes its Input and Output nodes declare.
+#[tauri::command]
+async fn analyze_genome_cost(genome_json: String) -> Result<cost_analysis::ModelCost, String> {
+    tokio::task::spawn_blocking(move || {
+        let (input_overrides, output_overrides) = genome_io_overrides(&genome_json);
+        cost_analysis::analyze_genome(
+            &genome_json,
+            (!input_overrides.is_empty()).then_some(input_overrides.as_slice()),
+            (!output_overrides.is_empty()).then_some(output_overrides.as_slice()),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed to join: {}", e))?
+}
+
+/// Measured inference latency of a genome (fresh weights) on the chosen backend.
+#[tauri::command]
+async fn benchmark_genome_latency(
+    genome_json: String,
+    config: Option<crate::latency_bench::LatencyBenchConfig>,
+    execution_backend: Option<String>,
+) -> Result<crate::latency_bench::LatencyBenchmark, String> {
+    let backend = crate::backend::ExecutionBackend::parse(execution_backend.as_deref())?;
+    let config = config.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        let (input_overrides, output_overrides) = genome_io_overrides(&genome_json);
+        let input_overrides = (!input_overrides.is_empty()).then_some(input_overrides.as_slice());
+        let output_overrides = (!output_overrides.is_empty()).then_some(output_overrides.as_slice());
+        match backend {
+            crate::backend::ExecutionBackend::Cpu => crate::latency_bench::benchmark_genome::<crate::backend::CpuBackend>(
+                &genome_json,
+                input_overrides,
+                output_overrides,
+                &config,
+                backend.name(),
+                &crate::backend::create_cpu_device(),
+            ),
+            crate::backend::ExecutionBackend::Gpu => crate::latency_bench::benchmark_genome::<crate::backend::TrainBackend>(
+                &genome_json,
+                input_overrides,
+                output_overrides,
+                &config,
+                backend.name(),
+                &crate::backend::create_device(),
+            ),
+        }
+    })
+    .await
+    .map_err(|e| format!("Task failed to join: {}", e))?
+}
+
 #[tauri::command]
 async fn get_device_profiles() -> Result<Vec<device_profiles::DeviceProfileDto>, String> {
     Ok(device_profiles::built_in_profiles())
 }
 
+/// Static cost of a genome and the latency table of a device template, if one is given.
+async fn genome_cost_on_template(
+    genome_json: String,
+    template_id: Option<String>,
+) -> Result<(cost_analysis::ModelCost, Option<latency_lut::LatencyLut>), String> {
+    let latency_lut = match template_id {
+        Some(id) => device_library::get_device_template(&id)?.latency_lut,
+        None => None,
+    };
+    let cost = analyze_genome_cost(genome_json).await?;
+    Ok((cost, latency_lut))
+}
+
+/// Checks a genome against device constraints. With `genome_json` the MOPS, flash and
+/// RAM come from the static cost analysis, and with a template that has a latency table
+/// the latency is the table's prediction for the device.
 #[tauri::command]
 async fn validate_genome_for_device(
     genome_objectives: dtos::GenomeObjectives,
     constraints: device_profiles::DeviceResourceConstraints,
+    genome_json: Option<String>,
+    template_id: Option<String>,
 ) -> Result<device_profiles::DeviceValidationResult, String> {
+    let objectives = match genome_json {
+        Some(genome_json) => {
+            let (cost, latency_lut) = genome_cost_on_template(genome_json, template_id).await?;
+            device_profiles::objectives_on_device(&genome_objectives, &cost, latency_lut.as_ref())?
+        }
+        None => genome_objectives,
+    };
     Ok(device_profiles::validate_genome_for_device(
-        &genome_objectives,
+        &objectives,
         &constraints,
     ))
 }
 
+/// Per-layer latency of a genome on a device template, predicted from its latency table.
+#[tauri::command]
+async fn predict_device_latency(
+    genome_json: String,
+    template_id: String,
+) -> Result<latency_lut::LatencyPrediction, String> {
+    let (cost, latency_lut) = genome_cost_on_template(genome_json, Some(template_id.clone())).await?;
+    latency_lut
+        .ok_or_else(|| format!("Template '{}' has no latency table", template_id))?
+        .predict(&cost)
+}
+
 #[tauri::command]
 async fn apply_device_penalty(
     base_fitness: f32,
@@ -3047,6 +3477,20 @@ async fn import_device_library(
     device_library::import_device_library(path, mode)
 }
 
+#[tauri::command]
+async fn attach_device_latency_lut(id: String, path: String) -> Result<dtos::DeviceTemplateDto, String> {
+    device_library::attach_latency_lut(id, path)
+}
+
+#[tauri::command]
+async fn import_device_latency_benchmarks(
+    id: String,
+    path: String,
+    mode: dtos::DeviceLibraryImportMode,
+) -> Result<dtos::DeviceTemplateDto, String> {
+    device_library::import_latency_benchmarks(id, path, mode)
+}
+
 #[cfg(test)]
 static TEST_ENV_LOCK: std::sync::LazyLock<std::sync::Mutex<()>> =
     std::sync::LazyLock::new(|| std::sync::Mutex::new(()));
@@ -3415,417 +3859,94 @@ pub fn run_train_worker() {
     }
 }
 
-fn execute_worker_training(
+fn evaluate_worker_request<B: burn::tensor::backend::AutodiffBackend>(
     request: &crate::dtos::WorkerTrainRequest,
-    device: &crate::backend::TrainDevice,
-) -> (f32, f32, Option<crate::dtos::TrainingProfiler>, Option<String>) {
-    use std::collections::HashMap;
-    use std::panic::AssertUnwindSafe;
-
-    eprintln!("[worker {}] Starting training for genome {}", request.job_id, request.genome_id);
-
-    type Backend = crate::backend::TrainBackend;
-
-    // 1. Load dataset profile
-    let profiles_json = match crate::data_loader::load_dataset_profiles_sync() {
-        Ok(json) => json,
-        Err(e) => {
-            eprintln!("[worker {}] Failed to load profiles: {}", request.job_id, e);
-            return (999.0, 0.0, None, Some(format!("Failed to load dataset profiles: {}", e)));
-        }
-    };
-
-    let root: crate::dtos::DatasetProfilesRoot = match serde_json::from_str(&profiles_json) {
-        Ok(parsed) => parsed,
-        Err(e) => {
-            eprintln!("[worker {}] Failed to parse profiles JSON: {}", request.job_id, e);
-            return (999.0, 0.0, None, Some(format!("Failed to parse dataset profiles: {}", e)));
-        }
-    };
-
-    let profile = match root.state.profiles.into_iter().find(|p| p.id == request.dataset_profile) {
-        Some(p) => p,
-        None => {
-            let msg = format!("Dataset profile '{}' not found", request.dataset_profile);
-            eprintln!("[worker {}] {}", request.job_id, msg);
-            return (999.0, 0.0, None, Some(msg));
-        }
-    };
-
-    let _source_path_str = match profile.source_path.clone() {
-        Some(p) => p,
-        None => {
-            let msg = format!("Profile '{}' has no sourcePath", profile.name);
-            eprintln!("[worker {}] {}", request.job_id, msg);
-            return (999.0, 0.0, None, Some(msg));
-        }
-    };
-
-    // 2. Create DataLoader
-    let loader = match crate::data_loader::DataLoader::new(profile.clone(), None) {
-        Ok(l) => l,
-        Err(e) => {
-            eprintln!("[worker {}] DataLoader creation failed: {}", request.job_id, e);
-            return (999.0, 0.0, None, Some(format!("DataLoader creation failed: {}", e)));
-        }
-    };
-
-    // 3. Build dataset split
-    let mut valid_ids = loader.valid_sample_ids.clone();
-    if valid_ids.is_empty() {
-        let msg = format!("No valid samples found");
-        eprintln!("[worker {}] {}", request.job_id, msg);
-        return (999.0, 0.0, None, Some(msg));
-    }
-
-    {
-        use rand::seq::SliceRandom;
-        let mut rng = rand::rng();
-        valid_ids.shuffle(&mut rng);
-    }
-
-    // Apply dataset percent
-    let pct = request.dataset_percent.clamp(1, 100);
-    let use_count = (valid_ids.len() * pct) / 100;
-    let use_count = use_count.max(1);
-    valid_ids.truncate(use_count);
-
-    let total_split = (request.train_split + request.val_split + request.test_split).max(1) as f32;
-    let train_ratio = request.train_split as f32 / total_split;
-    let val_ratio = request.val_split as f32 / total_split;
-
-    let mut train_ids = Vec::new();
-    let mut val_ids = Vec::new();
-    let mut test_ids = Vec::new();
-
-    // Stratification (same as main path)
-    let strat_stream_idx = profile.streams.iter().position(|s| {
-        s.role == "Target" && matches!(s.data_type, crate::dtos::DataType::Categorical)
+    device: B::Device,
+    queue_wait_ms: u64,
+    progress: &crate::progress::StdoutJsonProgressSink,
+) -> Result<crate::evaluation::GenomeEvaluation, String> {
+    let profiles_json = crate::data_loader::load_dataset_profiles_sync()?;
+    let profile = crate::evaluation::find_dataset_profile(&profiles_json, &request.dataset_profile)?;
+    // Workers reuse the parent's split so resumed runs score on the same samples
+    let prepared = std::sync::Arc::new(match &request.split {
+        Some(split) => crate::evaluation::prepare_dataset_with_split(profile, None, split.clone())?,
+        None => crate::evaluation::prepare_dataset(
+            profile,
+            None,
+            request.dataset_percent,
+            request.train_split,
+            request.val_split,
+            request.test_split,
+        )?,
     });
 
-    if let Some(s_idx) = strat_stream_idx {
-        let stream_id = &profile.streams[s_idx].id;
-        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
-
-        if loader.stream_files.contains_key(stream_id) {
-            for id in &valid_ids {
-                let label = loader.get_class_label(stream_id, id).unwrap_or_else(|| "unknown".to_string());
-                groups.entry(label).or_default().push(id.clone());
-            }
-        }
-
-        for (_, mut members) in groups {
-            {
-                use rand::seq::SliceRandom;
-                members.shuffle(&mut rand::rng());
-            }
-            let n = members.len();
-            let t_count = ((n as f32) * train_ratio).round() as usize;
-            let v_count = ((n as f32) * val_ratio).round() as usize;
-
-            let t_count = t_count.min(n);
-            let v_count = v_count.min(n - t_count);
-
-            train_ids.extend(members.iter().take(t_count).cloned());
-            val_ids.extend(members.iter().skip(t_count).take(v_count).cloned());
-            test_ids.extend(members.iter().skip(t_count + v_count).cloned());
-        }
-
-        {
-            use rand::seq::SliceRandom;
-            let mut local_rng = rand::rng();
-            train_ids.shuffle(&mut local_rng);
-            val_ids.shuffle(&mut local_rng);
-            test_ids.shuffle(&mut local_rng);
-        }
-    } else {
-        let train_count = ((valid_ids.len() as f32) * train_ratio).round() as usize;
-        let val_count = ((valid_ids.len() as f32) * val_ratio).round() as usize;
-
-        let train_count = train_count.min(valid_ids.len());
-        let val_count = val_count.min(valid_ids.len() - train_count);
-
-        train_ids = valid_ids.iter().take(train_count).cloned().collect();
-        val_ids = valid_ids.iter().skip(train_count).take(val_count).cloned().collect();
-        test_ids = valid_ids.iter().skip(train_count + val_count).cloned().collect();
-    }
-
-    eprintln!("[worker {}] Split: {} train, {} val, {} test", request.job_id, train_ids.len(), val_ids.len(), test_ids.len());
-
-    // 4. Get input/output overrides
-    let input_stream_indices: Vec<usize> = profile.streams.iter().enumerate()
-        .filter(|(_, s)| s.role == "Input")
-        .map(|(i, _)| i)
-        .collect();
-    let target_stream_indices: Vec<usize> = profile.streams.iter().enumerate()
-        .filter(|(_, s)| s.role == "Target")
-        .map(|(i, _)| i)
-        .collect();
-
-    if input_stream_indices.is_empty() || target_stream_indices.is_empty() {
-        return (999.0, 0.0, None, Some("No Input/Target streams found".to_string()));
-    }
-
-    let mut input_overrides = Vec::new();
-    for &idx in &input_stream_indices {
-        let stream = &profile.streams[idx];
-        match stream.data_type {
-            crate::dtos::DataType::Image => {
-                let mut h = 64; let mut w = 64; let mut channels = 3;
-                if let Some(prep) = &stream.preprocessing {
-                    if let Some(vision) = &prep.vision {
-                        if vision.resize.len() == 2 {
-                            w = vision.resize[0] as usize;
-                            h = vision.resize[1] as usize;
-                        }
-                        if vision.grayscale { channels = 1; }
-                    }
-                }
-                let external_shape = if stream.tensor_shape.len() == 3 {
-                    stream.tensor_shape.clone()
-                } else {
-                    vec![h, w, channels]
-                };
-                input_overrides.push(normalize_image_shape_to_internal_chw(&external_shape));
-            }
-            crate::dtos::DataType::Vector => {
-                let dim = stream.tensor_shape.get(0).cloned().unwrap_or(1);
-                input_overrides.push(vec![dim]);
-            }
-            crate::dtos::DataType::TemporalSequence => {
-                input_overrides.push(stream.tensor_shape.clone());
-            }
-            _ => input_overrides.push(vec![1]),
-        }
-    }
-
-    let mut output_overrides = Vec::new();
-    let mut is_classification = false;
-    for &idx in &target_stream_indices {
-        let stream = &profile.streams[idx];
-        if let crate::dtos::DataType::Categorical = stream.data_type {
-            is_classification = true;
-            let num_classes = loader.stream_classes.get(&idx).cloned().unwrap_or(1);
-            output_overrides.push(vec![num_classes]);
-        } else {
-            let dim = stream.tensor_shape.get(0).cloned().unwrap_or(1);
-            output_overrides.push(vec![dim]);
-        }
-    }
-
-    // 5. Build batches
-    let mut assemble_batches = |ids: &[String]| -> Result<Vec<crate::entities::DynamicBatch<Backend>>, String> {
-        let mut assembled_batches: Vec<crate::entities::DynamicBatch<Backend>> = Vec::new();
-        for chunk in ids.chunks(request.batch_size) {
-            let mut batch_inputs: Vec<Vec<crate::entities::DynamicTensor<Backend>>> = 
-                vec![Vec::new(); input_stream_indices.len()];
-            let mut batch_targets: Vec<Vec<crate::entities::DynamicTensor<Backend>>> = 
-                vec![Vec::new(); target_stream_indices.len()];
-
-            for id in chunk {
-                let load_result = std::panic::catch_unwind(AssertUnwindSafe(|| {
-                    loader.load_sample(id, device)
-                }));
-
-                match load_result {
-                    Ok(Ok(sample)) => {
-                        for (i, &stream_idx) in input_stream_indices.iter().enumerate() {
-                            if let Some(t) = sample.stream_tensors.get(&stream_idx) {
-                                batch_inputs[i].push(t.clone());
-                            }
-                        }
-                        for (i, &stream_idx) in target_stream_indices.iter().enumerate() {
-                            if let Some(t) = sample.stream_tensors.get(&stream_idx) {
-                                batch_targets[i].push(t.clone());
-                            }
-                        }
-                    }
-                    Ok(Err(_)) => { /* skip sample */ }
-                    Err(_) => {
-                        return Err("Sample panicked during load".to_string());
-                    }
-                }
-            }
-
-            if batch_inputs.iter().all(|list| !list.is_empty()) && 
-               batch_targets.iter().all(|list| !list.is_empty()) {
-                use crate::entities::concat_dynamic_tensors;
-                let assembled = std::panic::catch_unwind(AssertUnwindSafe(|| {
-                    let inputs: Vec<crate::entities::DynamicTensor<Backend>> = batch_inputs
-                        .into_iter()
-                        .map(|tensors| concat_dynamic_tensors::<Backend>(tensors))
-                        .collect();
-                    let targets: Vec<crate::entities::DynamicTensor<Backend>> = batch_targets
-                        .into_iter()
-                        .map(|tensors| concat_dynamic_tensors::<Backend>(tensors))
-                        .collect();
-                    (inputs, targets)
-                }));
-
-                match assembled {
-                    Ok((inputs, targets)) => {
-                        assembled_batches.push(crate::entities::DynamicBatch { inputs, targets });
-                    }
-                    Err(_) => {
-                        return Err("Batch concatenation panicked".to_string());
-                    }
-                }
-            }
-        }
-        Ok(assembled_batches)
-    };
-
-    let train_batches = match assemble_batches(&train_ids) {
-        Ok(b) => b,
-        Err(e) => {
-            return (999.0, 0.0, None, Some(format!("Batch assembly failed: {}", e)));
-        }
+    let mut ctx = crate::evaluation::EvaluationContext::<B>::prepare(
+        &prepared,
+        crate::evaluation::TrainingParams {
+            dataset_profile: request.dataset_profile.clone(),
+            batch_size: request.batch_size,
+            dataset_percent: request.dataset_percent,
+            train_split: request.train_split,
+            val_split: request.val_split,
+            test_split: request.test_split,
+        },
+        &request.batch_pipeline,
+        device,
+        crate::profiler::MemoryMode::Hybrid,
+        EVOLUTION_SESSION.load(Ordering::SeqCst),
+        progress,
+    )?;
+    ctx.epoch_checkpoint_dir = request.epoch_checkpoint_dir.clone();
+    let job = crate::evaluation::GenomeJob {
+        index: 0,
+        genome_id: request.genome_id.clone(),
+        genome_str: request.genome_json.clone(),
+        epochs: request.epochs,
+        queue_wait_ms,
     };
 
-    if train_batches.is_empty() {
-        return (999.0, 0.0, None, Some("No training batches assembled".to_string()));
-    }
+    crate::evaluation::evaluate_genome(&ctx, &job, progress)
+}
 
-    let val_batches = match assemble_batches(&val_ids) {
-        Ok(b) => b,
-        Err(_) => Vec::new(),
-    };
+fn execute_worker_training(
+    request: &crate::dtos::WorkerTrainRequest,
+    device: &crate::backend::TrainDevice,
+) -> (f32, f32, Option<crate::dtos::TrainingProfiler>, Option<String>) {
+    eprintln!("[worker {}] Starting training for genome {}", request.job_id, request.genome_id);
 
-    let test_batches = match assemble_batches(&test_ids) {
-        Ok(b) => b,
-        Err(_) => Vec::new(),
-    };
+    let progress = crate::progress::StdoutJsonProgressSink::new(request.job_id.clone(), request.genome_id.clone());
+    let queue_wait_ms = current_unix_ms().saturating_sub(request.queue_entered_ms);
 
-    // 6. Build model from genome JSON
-    let model = match std::panic::catch_unwind(AssertUnwindSafe(|| {
-        crate::entities::GraphModel::<Backend>::build(
-            &request.genome_json,
-            device,
-            Some(&input_overrides),
-            Some(&output_overrides),
-        )
-    })) {
-        Ok(m) => m,
-        Err(_) => {
-            return (999.0, 0.0, None, Some("Genome compilation failed".to_string()));
+    let evaluation = match request.execution_backend {
+        crate::backend::ExecutionBackend::Gpu => {
+            evaluate_worker_request::<crate::backend::TrainBackend>(request, device.clone(), queue_wait_ms, &progress)
         }
+        crate::backend::ExecutionBackend::Cpu => evaluate_worker_request::<crate::backend::CpuBackend>(
+            request,
+            crate::backend::create_cpu_device(),
+            queue_wait_ms,
+            &progress,
+        ),
     };
 
-    // 7. Run evaluation (simplified worker path - no training yet)
-    let mut profiler = crate::profiler::ProfilerCollector::new();
-    let queue_wait_ms = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0)
-        .saturating_sub(request.queue_entered_ms);
-    profiler.set_queue_wait_ms(queue_wait_ms);
-
-    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
-        let model_local = model;
-        profiler.mark_train_start();
-        profiler.mark_train_end();
-
-        // Validation phase
-        let (val_loss, val_acc) = if !val_batches.is_empty() {
-            profiler.mark_val_start();
-            let mut val_loss_sum = 0.0;
-            let mut val_correct = 0;
-            let mut val_total = 0;
-
-            for batch in &val_batches {
-                let cloned_inputs: Vec<crate::entities::DynamicTensor<Backend>> =
-                    batch.inputs.iter().map(|t| t.clone()).collect();
-                let cloned_targets: Vec<crate::entities::DynamicTensor<Backend>> =
-                    batch.targets.iter().map(|t| t.clone()).collect();
-
-                let predictions = model_local.forward_internal(&cloned_inputs, false, false);
-                let loss = model_local.compute_loss(&predictions, &cloned_targets);
-                let loss_val = loss.into_data().to_vec::<f32>().unwrap_or(vec![0.0])[0];
-                val_loss_sum += loss_val;
-
-                let (correct, count) = crate::entities::compute_accuracy(&predictions, &cloned_targets, is_classification);
-                val_correct += correct;
-                val_total += count;
-
-                profiler.record_inference_samples(count);
-            }
-            profiler.mark_val_end();
-
-            let avg_loss = if !val_batches.is_empty() { val_loss_sum / val_batches.len() as f32 } else { 0.0 };
-            let avg_acc = if val_total > 0 { val_correct as f32 / val_total as f32 } else { 0.0 };
-            (avg_loss, avg_acc)
-        } else {
-            (0.0, 0.0)
-        };
-
-        // Test phase
-        let (loss, acc) = if !test_batches.is_empty() {
-            profiler.mark_test_start();
-            let mut test_loss_sum = 0.0;
-            let mut test_correct = 0;
-            let mut test_total = 0;
-
-            for batch in &test_batches {
-                let cloned_inputs: Vec<crate::entities::DynamicTensor<Backend>> =
-                    batch.inputs.iter().map(|t| t.clone()).collect();
-                let cloned_targets: Vec<crate::entities::DynamicTensor<Backend>> =
-                    batch.targets.iter().map(|t| t.clone()).collect();
-
-                let predictions = model_local.forward_internal(&cloned_inputs, false, false);
-                let loss = model_local.compute_loss(&predictions, &cloned_targets);
-                let loss_val = loss.into_data().to_vec::<f32>().unwrap_or(vec![0.0])[0];
-                test_loss_sum += loss_val;
-
-                let (correct, count) = crate::entities::compute_accuracy(&predictions, &cloned_targets, is_classification);
-                test_correct += correct;
-                test_total += count;
-
-                profiler.record_inference_samples(count);
-            }
-            profiler.mark_test_end();
-
-            let avg_loss = if !test_batches.is_empty() { test_loss_sum / test_batches.len() as f32 } else { 0.0 };
-            let avg_acc = if test_total > 0 { test_correct as f32 / test_total as f32 } else { 0.0 };
-            (avg_loss, avg_acc)
-        } else if !val_batches.is_empty() {
-            (val_loss, val_acc)
-        } else {
-            // Fallback to train loss if no val/test
-            let mut train_loss_sum = 0.0;
-            let mut train_correct = 0;
-            let mut train_total = 0;
-
-            for batch in &train_batches {
-                let cloned_inputs: Vec<crate::entities::DynamicTensor<Backend>> =
-                    batch.inputs.iter().map(|t| t.clone()).collect();
-                let cloned_targets: Vec<crate::entities::DynamicTensor<Backend>> =
-                    batch.targets.iter().map(|t| t.clone()).collect();
-
-                let predictions = model_local.forward_internal(&cloned_inputs, false, false);
-                let loss = model_local.compute_loss(&predictions, &cloned_targets);
-                let loss_val = loss.into_data().to_vec::<f32>().unwrap_or(vec![0.0])[0];
-                train_loss_sum += loss_val;
-
-                let (correct, count) = crate::entities::compute_accuracy(&predictions, &cloned_targets, is_classification);
-                train_correct += correct;
-                train_total += count;
-            }
-
-            let avg_loss = if !train_batches.is_empty() { train_loss_sum / train_batches.len() as f32 } else { 0.0 };
-            let avg_acc = if train_total > 0 { train_correct as f32 / train_total as f32 } else { 0.0 };
-            (avg_loss, avg_acc)
-        };
-
-        (loss, acc, profiler.finalize())
-    }));
-
-    match result {
-        Ok((loss, acc, profiler_result)) => {
-            eprintln!("[worker {}] Evaluation complete: loss={:.4}, acc={:.2}%", request.job_id, loss, acc * 100.0);
-            (loss, acc, Some(profiler_result), None)
+    match evaluation {
+        Ok(evaluation) if evaluation.source == crate::evaluation::EvaluationSource::CompileFailed => (
+            999.0,
+            0.0,
+            None,
+            Some(format!("Genome '{}' failed to compile", request.genome_id)),
+        ),
+        Ok(evaluation) => {
+            let result = evaluation.result;
+            eprintln!(
+                "[worker {}] Evaluation complete: loss={:.4}, acc={:.2}%",
+                request.job_id, result.loss, result.accuracy
+            );
+            (result.loss, result.accuracy, result.profiler, None)
         }
-        Err(_) => {
-            (999.0, 0.0, None, Some("Evaluation panicked".to_string()))
+        Err(e) => {
+            eprintln!("[worker {}] {}", request.job_id, e);
+            (999.0, 0.0, None, Some(e))
         }
     }
 }
@@ -3841,9 +3962,18 @@ pub fn run() {
             test_neural_net_training,
             test_train_on_image_folder,
             evaluate_population,
+            resume_evaluation,
+            list_evaluation_runs,
+            delete_evaluation_run,
+            get_eval_cache_stats,
+            invalidate_eval_cache,
+            set_eval_cache_limits,
             stop_evolution,
             scan_dataset,
             cache_dataset,
+            verify_dataset_cache,
+            dataset_report,
+            get_tabular_stats,
             validate_dataset_profile,
             list_library_genomes,
             list_hidden_library,
@@ -3858,9 +3988,19 @@ pub fn run() {
             load_dataset_profiles,
             preview_csv,
             compute_zero_cost_score,
+            calibrate_zero_cost_scores,
+            record_zero_cost_outcomes,
+            zero_cost_calibration_report,
             compute_pareto_front,
+            rank_pareto_population,
+            reset_pareto_archive,
+            update_pareto_archive,
+            get_pareto_archive_history,
+            analyze_genome_cost,
+            benchmark_genome_latency,
             get_device_profiles,
             validate_genome_for_device,
+            predict_device_latency,
             apply_device_penalty,
             list_device_templates,
             create_device_template,
@@ -3869,6 +4009,8 @@ pub fn run() {
             duplicate_device_template,
             export_device_library,
             import_device_library,
+            attach_device_latency_lut,
+            import_device_latency_benchmarks,
             register_founder,
             register_mutation,
             register_crossover,
@@ -1,48 +1,311 @@
+/// Multi-Objective Ranking (NSGA-II)
+///
+/// Objectives are named metrics of `GenomeObjectives` with a direction. Values are
+/// turned into cost vectors (maximised objectives negated, missing metrics +inf) so
+/// that smaller is always better; on those:
+/// - `non_dominated_sort` splits a population into fronts (rank 0 is non-dominated)
+/// - `crowding_distance` spreads selection along a front
+/// - `hypervolume` measures the space a front dominates up to a reference point
+///
+/// `is_dominated` and `compute_pareto_front` keep the default objectives: accuracy up,
+/// inference latency down, model size down.
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
 use crate::dtos::{GenerationParetoFront, GenomeObjectives};
 
-/// Returns true if `a` is dominated by `b`.
-///
-/// Objectives:
-/// - maximize accuracy
-/// - minimize inference latency
-/// - minimize model size
-pub fn is_dominated(a: &GenomeObjectives, b: &GenomeObjectives) -> bool {
-    let b_no_worse_all = b.accuracy >= a.accuracy
-        && b.inference_latency_ms <= a.inference_latency_ms
-        && b.model_size_mb <= a.model_size_mb;
+/// Crowding distance of a front's boundary genomes; finite so it survives JSON.
+pub const BOUNDARY_CROWDING: f32 = f32::MAX;
 
-    let b_strictly_better_any = b.accuracy > a.accuracy
-        || b.inference_latency_ms < a.inference_latency_ms
-        || b.model_size_mb < a.model_size_mb;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Maximize,
+    Minimize,
+}
 
-    b_no_worse_all && b_strictly_better_any
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Objective {
+    /// `accuracy`, `inference_latency_ms`, `model_size_mb`, `training_time_ms`, `flops`,
+    /// `parameter_count`, `peak_activation_bytes`, `constraint_violation_score` or a key of
+    /// `custom_metrics`
+    pub metric: String,
+    pub direction: Direction,
 }
 
-/// Computes Pareto frontier in O(N^2).
-pub fn compute_pareto_front(genomes: &[GenomeObjectives]) -> Vec<GenomeObjectives> {
-    let mut domination_counts = vec![0u32; genomes.len()];
+impl Objective {
+    pub fn maximize(metric: &str) -> Self {
+        Objective {
+            metric: metric.to_string(),
+            direction: Direction::Maximize,
+        }
+    }
 
-    for i in 0..genomes.len() {
-        for j in 0..genomes.len() {
-            if i == j {
-                continue;
+    pub fn minimize(metric: &str) -> Self {
+        Objective {
+            metric: metric.to_string(),
+            direction: Direction::Minimize,
+        }
+    }
+
+    /// The value as a cost: negated when maximised, +inf when missing.
+    pub fn cost(&self, value: Option<f32>) -> f32 {
+        match value.filter(|v| !v.is_nan()) {
+            Some(v) if self.direction == Direction::Maximize => -v,
+            Some(v) => v,
+            None => f32::INFINITY,
+        }
+    }
+}
+
+pub fn default_objectives() -> Vec<Objective> {
+    vec![
+        Objective::maximize("accuracy"),
+        Objective::minimize("inference_latency_ms"),
+        Objective::minimize("model_size_mb"),
+    ]
+}
+
+pub fn metric_value(genome: &GenomeObjectives, metric: &str) -> Option<f32> {
+    match metric {
+        "accuracy" => Some(genome.accuracy),
+        "inference_latency_ms" => Some(genome.inference_latency_ms),
+        "model_size_mb" => Some(genome.model_size_mb),
+        "training_time_ms" => Some(genome.training_time_ms as f32),
+        "flops" => genome.flops.map(|v| v as f32),
+        "parameter_count" => genome.parameter_count.map(|v| v as f32),
+        "peak_activation_bytes" => genome.peak_activation_bytes.map(|v| v as f32),
+        "constraint_violation_score" => genome.constraint_violation_score,
+        custom => genome.custom_metrics.get(custom).copied(),
+    }
+}
+
+pub fn cost_vector(genome: &GenomeObjectives, objectives: &[Objective]) -> Vec<f32> {
+    objectives
+        .iter()
+        .map(|objective| objective.cost(metric_value(genome, &objective.metric)))
+        .collect()
+}
+
+/// Whether cost vector `a` dominates `b`: no worse in every objective, better in one.
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x <= y) && a.iter().zip(b).any(|(x, y)| x < y)
+}
+
+/// Returns true if `a` is dominated by `b` under the default objectives.
+pub fn is_dominated(a: &GenomeObjectives, b: &GenomeObjectives) -> bool {
+    let objectives = default_objectives();
+    dominates(&cost_vector(b, &objectives), &cost_vector(a, &objectives))
+}
+
+/// Fast non-dominated sort (Deb et al. 2002): indices of every front, best first.
+pub fn non_dominated_sort(costs: &[Vec<f32>]) -> Vec<Vec<usize>> {
+    let n = costs.len();
+    let mut dominated_by = vec![0usize; n];
+    let mut dominating: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if dominates(&costs[i], &costs[j]) {
+                dominating[i].push(j);
+                dominated_by[j] += 1;
+            } else if dominates(&costs[j], &costs[i]) {
+                dominating[j].push(i);
+                dominated_by[i] += 1;
             }
-            if is_dominated(&genomes[i], &genomes[j]) {
-                domination_counts[i] = domination_counts[i].saturating_add(1);
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut current: Vec<usize> = (0..n).filter(|&i| dominated_by[i] == 0).collect();
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        for &i in &current {
+            for &j in &dominating[i] {
+                dominated_by[j] -= 1;
+                if dominated_by[j] == 0 {
+                    next.push(j);
+                }
             }
         }
+        next.sort_unstable();
+        fronts.push(std::mem::replace(&mut current, next));
+    }
+    fronts
+}
+
+/// Crowding distance of every member of `front`, in front order: the sum over
+/// objectives of the normalised gap between each member's neighbours.
+pub fn crowding_distance(costs: &[Vec<f32>], front: &[usize]) -> Vec<f32> {
+    let mut distance = vec![0.0f32; front.len()];
+    if front.len() <= 2 {
+        return vec![BOUNDARY_CROWDING; front.len()];
+    }
+    let columns: Vec<Vec<f32>> = (0..costs[front[0]].len())
+        .map(|m| front.iter().map(|&i| costs[i][m]).collect())
+        .collect();
+    for values in columns {
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        let (first, last) = (order[0], order[order.len() - 1]);
+        distance[first] = BOUNDARY_CROWDING;
+        distance[last] = BOUNDARY_CROWDING;
+        let range = values[last] - values[first];
+        if !range.is_finite() || range <= 0.0 {
+            continue;
+        }
+        for window in order.windows(3) {
+            let gap = (values[window[2]] - values[window[0]]) / range;
+            distance[window[1]] = (distance[window[1]] + gap).min(BOUNDARY_CROWDING);
+        }
+    }
+    distance
+}
+
+/// Exact hypervolume dominated by `costs` up to `reference` (both in cost form), by
+/// slicing along the last objective. Points not strictly better than the reference in
+/// every objective add nothing. Exponential in the number of objectives, which is fine
+/// for the handful an evolution run uses.
+pub fn hypervolume(costs: &[Vec<f32>], reference: &[f32]) -> f64 {
+    let points: Vec<Vec<f64>> = costs
+        .iter()
+        .filter(|p| p.len() == reference.len() && p.iter().zip(reference).all(|(v, r)| v < r))
+        .map(|p| p.iter().map(|&v| v as f64).collect())
+        .collect();
+    let reference: Vec<f64> = reference.iter().map(|&r| r as f64).collect();
+    sliced_volume(points, &reference)
+}
+
+fn sliced_volume(mut points: Vec<Vec<f64>>, reference: &[f64]) -> f64 {
+    let Some(last) = reference.len().checked_sub(1) else {
+        return 0.0;
+    };
+    if points.is_empty() {
+        return 0.0;
+    }
+    if last == 0 {
+        let best = points.iter().map(|p| p[0]).fold(f64::INFINITY, f64::min);
+        return reference[0] - best;
+    }
+
+    points.sort_by(|a, b| a[last].total_cmp(&b[last]));
+    let mut volume = 0.0;
+    for i in 0..points.len() {
+        let upper = points.get(i + 1).map_or(reference[last], |p| p[last]);
+        let depth = upper - points[i][last];
+        if depth > 0.0 {
+            let slice: Vec<Vec<f64>> = points[..=i].iter().map(|p| p[..last].to_vec()).collect();
+            volume += depth * sliced_volume(slice, &reference[..last]);
+        }
     }
+    volume
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedGenome {
+    pub genome_id: String,
+    /// Front index, 0 for the non-dominated front
+    pub rank: usize,
+    pub crowding_distance: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParetoRanking {
+    pub objectives: Vec<Objective>,
+    /// In input order
+    pub genomes: Vec<RankedGenome>,
+    /// Genome ids of every front, best first
+    pub fronts: Vec<Vec<String>>,
+    /// Hypervolume of the first front, when a reference point was given
+    pub hypervolume: Option<f64>,
+}
 
-    genomes
+impl ParetoRanking {
+    /// NSGA-II crowded comparison: lower rank first, then larger crowding distance.
+    pub fn crowded_order(a: &RankedGenome, b: &RankedGenome) -> Ordering {
+        a.rank
+            .cmp(&b.rank)
+            .then_with(|| b.crowding_distance.total_cmp(&a.crowding_distance))
+    }
+
+    /// The `count` genome ids NSGA-II survival keeps: whole fronts while they fit,
+    /// then the least crowded members of the front that does not.
+    pub fn select(&self, count: usize) -> Vec<String> {
+        let mut order: Vec<&RankedGenome> = self.genomes.iter().collect();
+        order.sort_by(|a, b| Self::crowded_order(a, b));
+        order.into_iter().take(count).map(|g| g.genome_id.clone()).collect()
+    }
+}
+
+/// Ranks `genomes` on `objectives`. `reference` is given in metric units, one value
+/// per objective.
+pub fn rank_population(
+    genomes: &[GenomeObjectives],
+    objectives: &[Objective],
+    reference: Option<&[f32]>,
+) -> Result<ParetoRanking, String> {
+    if objectives.is_empty() {
+        return Err("At least one objective is required".to_string());
+    }
+    let costs: Vec<Vec<f32>> = genomes.iter().map(|g| cost_vector(g, objectives)).collect();
+    let fronts = non_dominated_sort(&costs);
+
+    let mut ranked: Vec<RankedGenome> = genomes
         .iter()
-        .enumerate()
-        .map(|(idx, genome)| {
-            let mut updated = genome.clone();
-            updated.domination_count = domination_counts[idx];
-            updated.is_dominated = domination_counts[idx] > 0;
+        .map(|g| RankedGenome {
+            genome_id: g.genome_id.clone(),
+            rank: 0,
+            crowding_distance: 0.0,
+        })
+        .collect();
+    for (rank, front) in fronts.iter().enumerate() {
+        for (&i, distance) in front.iter().zip(crowding_distance(&costs, front)) {
+            ranked[i].rank = rank;
+            ranked[i].crowding_distance = distance;
+        }
+    }
+
+    let hypervolume = match reference {
+        Some(reference) if reference.len() != objectives.len() => {
+            return Err(format!(
+                "Reference point has {} values for {} objectives",
+                reference.len(),
+                objectives.len()
+            ));
+        }
+        Some(reference) => {
+            let reference: Vec<f32> = objectives.iter().zip(reference).map(|(o, &r)| o.cost(Some(r))).collect();
+            let first: Vec<Vec<f32>> = fronts.first().map_or(Vec::new(), |f| f.iter().map(|&i| costs[i].clone()).collect());
+            Some(hypervolume(&first, &reference))
+        }
+        None => None,
+    };
+
+    Ok(ParetoRanking {
+        objectives: objectives.to_vec(),
+        fronts: fronts
+            .iter()
+            .map(|front| front.iter().map(|&i| genomes[i].genome_id.clone()).collect())
+            .collect(),
+        genomes: ranked,
+        hypervolume,
+    })
+}
+
+/// Computes the Pareto frontier (first front) under the default objectives.
+pub fn compute_pareto_front(genomes: &[GenomeObjectives]) -> Vec<GenomeObjectives> {
+    let objectives = default_objectives();
+    let costs: Vec<Vec<f32>> = genomes.iter().map(|g| cost_vector(g, &objectives)).collect();
+    let first = non_dominated_sort(&costs).into_iter().next().unwrap_or_default();
+
+    first
+        .into_iter()
+        .map(|idx| {
+            let mut updated = genomes[idx].clone();
+            updated.domination_count = 0;
+            updated.is_dominated = false;
             updated
         })
-        .filter(|g| !g.is_dominated)
         .collect()
 }
 
@@ -78,6 +341,11 @@ mod tests {
             training_time_ms: 1000,
             is_dominated: false,
             domination_count: 0,
+            flops: None,
+            parameter_count: None,
+            peak_activation_bytes: None,
+            constraint_violation_score: None,
+            custom_metrics: Default::default(),
         }
     }
 
@@ -288,4 +556,58 @@ mod tests {
         assert!(ids.contains("accurate"));
         assert!(ids.contains("balanced"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn non_dominated_sort_splits_population_into_ranked_fronts() {
+        let genomes = vec![
+            obj("a", 0.90, 10.0, 10.0),
+            obj("b", 0.80, 12.0, 12.0),
+            obj("c", 0.70, 14.0, 14.0),
+            obj("d", 0.85, 8.0, 11.0),
+        ];
+        let ranking = rank_population(&genomes, &default_objectives(), None).unwrap();
+        assert_eq!(ranking.fronts, vec![vec!["a", "d"], vec!["b"], vec!["c"]]);
+        assert_eq!(ranking.genomes.iter().map(|g| g.rank).collect::<Vec<_>>(), [0, 1, 2, 0]);
+        assert_eq!(ranking.select(3), ["a", "d", "b"]);
+    }
+
+    #[test]
+    fn crowding_distance_prefers_isolated_members() {
+        let costs = vec![vec![0.0, 4.0], vec![1.0, 3.0], vec![1.2, 2.8], vec![4.0, 0.0]];
+        let distance = crowding_distance(&costs, &[0, 1, 2, 3]);
+        assert_eq!(distance[0], BOUNDARY_CROWDING);
+        assert_eq!(distance[3], BOUNDARY_CROWDING);
+        // Member 2 has a far neighbour on one side, member 1 two close ones
+        assert!(distance[2] > distance[1]);
+        assert!((distance[1] - (1.2 / 4.0 + 1.2 / 4.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hypervolume_matches_union_of_boxes() {
+        // Two 2-D boxes up to (4, 4): 3x1 + 1x3 - 1x1 overlap
+        assert!((hypervolume(&[vec![1.0, 3.0], vec![3.0, 1.0]], &[4.0, 4.0]) - 5.0).abs() < 1e-9);
+        // Dominated and out-of-reference points add nothing
+        let cube = hypervolume(&[vec![0.0, 0.0, 0.0], vec![1.0, 1.0, 1.0], vec![5.0, 0.0, 0.0]], &[2.0, 2.0, 2.0]);
+        assert!((cube - 8.0).abs() < 1e-9);
+        assert_eq!(hypervolume(&[], &[1.0, 1.0]), 0.0);
+    }
+
+    #[test]
+    fn objectives_cover_extra_metrics_and_directions() {
+        let mut small = obj("small", 0.80, 10.0, 10.0);
+        small.parameter_count = Some(1_000);
+        small.custom_metrics.insert("energy_mj".to_string(), 5.0);
+        let mut large = obj("large", 0.80, 10.0, 10.0);
+        large.parameter_count = Some(50_000);
+        large.custom_metrics.insert("energy_mj".to_string(), 9.0);
+        let unknown = obj("unknown", 0.80, 10.0, 10.0);
+
+        let objectives = vec![Objective::minimize("parameter_count"), Objective::minimize("energy_mj")];
+        let ranking = rank_population(&[large, unknown, small], &objectives, Some(&[100_000.0, 10.0])).unwrap();
+        assert_eq!(ranking.fronts, vec![vec!["small"], vec!["large"], vec!["unknown"]]);
+        assert!((ranking.hypervolume.unwrap() - 99_000.0 * 5.0).abs() < 1e-3);
+
+        assert!(rank_population(&[], &objectives, Some(&[1.0])).is_err());
+        assert!(rank_population(&[], &[], None).is_err());
+    }
+}
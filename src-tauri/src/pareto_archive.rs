@@ -0,0 +1,284 @@
+/// Pareto Archive
+///
+/// Keeps the non-dominated genomes of every generation of a run. After each generation
+/// the population is merged into the archive, the archive is cut back to its first
+/// front and measured:
+/// - hypervolume against the reference point (metric units, one value per objective;
+///   by default the first generation's nadir pushed 10% of its range further out)
+/// - coverage: that hypervolume as a share of the box between the reference point and
+///   the ideal point (by default the first generation's best value of every objective,
+///   mirrored as far beyond it as the reference lies behind it), which
+///   `check_stopping_criteria` monitors as "pareto_coverage"
+/// - growth: how many members joined and left the archive
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::GenomeObjectives;
+use crate::pareto::{Objective, cost_vector, default_objectives, hypervolume, non_dominated_sort};
+
+/// Share of the first generation's range the default reference point lies beyond its nadir.
+const REFERENCE_MARGIN: f32 = 0.1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParetoArchiveConfig {
+    #[serde(default = "default_objectives")]
+    pub objectives: Vec<Objective>,
+    #[serde(default)]
+    pub reference_point: Option<Vec<f32>>,
+    #[serde(default)]
+    pub ideal_point: Option<Vec<f32>>,
+}
+
+impl Default for ParetoArchiveConfig {
+    fn default() -> Self {
+        ParetoArchiveConfig {
+            objectives: default_objectives(),
+            reference_point: None,
+            ideal_point: None,
+        }
+    }
+}
+
+/// Archive state after one generation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveGeneration {
+    pub generation: u32,
+    pub archive_size: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub hypervolume: f64,
+    pub coverage: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParetoArchive {
+    pub config: ParetoArchiveConfig,
+    /// Reference point in use, fixed by the first generation when not configured
+    pub reference_point: Option<Vec<f32>>,
+    /// Ideal point in use, fixed by the first generation when not configured
+    #[serde(default)]
+    pub ideal_point: Option<Vec<f32>>,
+    pub members: Vec<GenomeObjectives>,
+    pub history: Vec<ArchiveGeneration>,
+}
+
+impl ParetoArchive {
+    pub fn new(config: ParetoArchiveConfig) -> Result<Self, String> {
+        if config.objectives.is_empty() {
+            return Err("At least one objective is required".to_string());
+        }
+        for (name, point) in [("Reference", &config.reference_point), ("Ideal", &config.ideal_point)] {
+            if let Some(point) = point
+                && point.len() != config.objectives.len()
+            {
+                return Err(format!(
+                    "{} point has {} values for {} objectives",
+                    name,
+                    point.len(),
+                    config.objectives.len()
+                ));
+            }
+        }
+        Ok(ParetoArchive {
+            reference_point: config.reference_point.clone(),
+            ideal_point: config.ideal_point.clone(),
+            config,
+            members: Vec::new(),
+            history: Vec::new(),
+        })
+    }
+
+    /// Merges a generation into the archive. A genome evaluated again replaces its
+    /// archived entry.
+    pub fn update(&mut self, generation: u32, genomes: &[GenomeObjectives]) -> ArchiveGeneration {
+        let objectives = &self.config.objectives;
+        let previous: HashSet<String> = self.members.iter().map(|m| m.genome_id.clone()).collect();
+        let incoming: HashSet<&str> = genomes.iter().map(|g| g.genome_id.as_str()).collect();
+
+        let mut candidates: Vec<GenomeObjectives> = self
+            .members
+            .iter()
+            .filter(|m| !incoming.contains(m.genome_id.as_str()))
+            .cloned()
+            .collect();
+        candidates.extend(genomes.iter().cloned());
+        let costs: Vec<Vec<f32>> = candidates.iter().map(|g| cost_vector(g, objectives)).collect();
+        let front = non_dominated_sort(&costs).into_iter().next().unwrap_or_default();
+
+        let reference = self
+            .reference_point
+            .get_or_insert_with(|| nadir_reference(&costs, objectives))
+            .clone();
+        let reference_cost: Vec<f32> = objectives.iter().zip(&reference).map(|(o, &r)| o.cost(Some(r))).collect();
+        let front_costs: Vec<Vec<f32>> = front.iter().map(|&i| costs[i].clone()).collect();
+        let hypervolume = hypervolume(&front_costs, &reference_cost);
+
+        let ideal = self
+            .ideal_point
+            .get_or_insert_with(|| mirrored_ideal(&front_costs, &reference_cost, objectives))
+            .clone();
+        let ideal_cost: Vec<f32> = objectives.iter().zip(&ideal).map(|(o, &v)| o.cost(Some(v))).collect();
+        let box_volume: f64 = reference_cost
+            .iter()
+            .zip(&ideal_cost)
+            .map(|(r, i)| (r - i) as f64)
+            .product();
+        let coverage = if box_volume.is_finite() && box_volume > 0.0 {
+            (hypervolume / box_volume).clamp(0.0, 1.0) as f32
+        } else if hypervolume > 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+
+        self.members = front
+            .into_iter()
+            .map(|i| {
+                let mut member = candidates[i].clone();
+                member.is_dominated = false;
+                member.domination_count = 0;
+                member
+            })
+            .collect();
+        let current: HashSet<&str> = self.members.iter().map(|m| m.genome_id.as_str()).collect();
+        let entry = ArchiveGeneration {
+            generation,
+            archive_size: self.members.len(),
+            added: current.iter().filter(|id| !previous.contains(**id)).count(),
+            removed: previous.iter().filter(|id| !current.contains(id.as_str())).count(),
+            hypervolume,
+            coverage,
+        };
+        self.history.push(entry.clone());
+        entry
+    }
+
+    /// Coverage after the latest generation, 0 before the first.
+    pub fn coverage(&self) -> f32 {
+        self.history.last().map_or(0.0, |g| g.coverage)
+    }
+}
+
+/// Reference point (metric units) just beyond the worst finite value of every objective.
+fn nadir_reference(costs: &[Vec<f32>], objectives: &[Objective]) -> Vec<f32> {
+    objectives
+        .iter()
+        .enumerate()
+        .map(|(m, objective)| {
+            let values: Vec<f32> = costs.iter().map(|c| c[m]).filter(|v| v.is_finite()).collect();
+            let worst = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let best = values.iter().copied().fold(f32::INFINITY, f32::min);
+            let reference_cost = if values.is_empty() {
+                0.0
+            } else {
+                let spread = worst - best;
+                let margin = if spread > 0.0 { spread } else { worst.abs() } * REFERENCE_MARGIN;
+                worst + margin.max(1e-6)
+            };
+            objective.cost(Some(reference_cost))
+        })
+        .collect()
+}
+
+/// Ideal point (metric units) as far beyond the front's best value of every objective
+/// as the reference lies behind it, so a small first front leaves room to grow.
+fn mirrored_ideal(front_costs: &[Vec<f32>], reference_cost: &[f32], objectives: &[Objective]) -> Vec<f32> {
+    objectives
+        .iter()
+        .enumerate()
+        .map(|(m, objective)| {
+            let best = front_costs.iter().map(|c| c[m]).fold(f32::INFINITY, f32::min);
+            objective.cost(Some(best - (reference_cost[m] - best)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obj(id: &str, acc: f32, lat: f32) -> GenomeObjectives {
+        GenomeObjectives {
+            genome_id: id.to_string(),
+            accuracy: acc,
+            inference_latency_ms: lat,
+            model_size_mb: 1.0,
+            training_time_ms: 0,
+            is_dominated: false,
+            domination_count: 0,
+            flops: None,
+            parameter_count: None,
+            peak_activation_bytes: None,
+            constraint_violation_score: None,
+            custom_metrics: Default::default(),
+        }
+    }
+
+    fn archive() -> ParetoArchive {
+        ParetoArchive::new(ParetoArchiveConfig {
+            objectives: vec![Objective::maximize("accuracy"), Objective::minimize("inference_latency_ms")],
+            reference_point: Some(vec![0.0, 10.0]),
+            ideal_point: Some(vec![1.0, 0.0]),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn archive_keeps_non_dominated_genomes_across_generations() {
+        let mut archive = archive();
+        let first = archive.update(0, &[obj("a", 0.5, 5.0), obj("b", 0.4, 6.0)]);
+        assert_eq!((first.archive_size, first.added, first.removed), (1, 1, 0));
+        assert!((first.hypervolume - 0.5 * 5.0).abs() < 1e-6);
+        assert!((first.coverage - 0.25).abs() < 1e-6);
+
+        // c trades accuracy for latency, d beats a outright
+        let second = archive.update(1, &[obj("c", 0.3, 1.0), obj("d", 0.6, 5.0)]);
+        let ids: Vec<&str> = archive.members.iter().map(|m| m.genome_id.as_str()).collect();
+        assert_eq!(ids, ["c", "d"]);
+        assert_eq!((second.added, second.removed), (2, 1));
+        assert!(second.hypervolume > first.hypervolume);
+
+        // A generation without improvements leaves the archive as it was
+        let third = archive.update(2, &[obj("e", 0.2, 9.0)]);
+        assert_eq!(third.hypervolume, second.hypervolume);
+        assert_eq!(archive.history.len(), 3);
+        assert_eq!(archive.coverage(), third.coverage);
+    }
+
+    #[test]
+    fn default_ideal_is_frozen_so_a_single_first_member_can_still_grow() {
+        let mut archive = ParetoArchive::new(ParetoArchiveConfig {
+            objectives: vec![Objective::maximize("accuracy"), Objective::minimize("inference_latency_ms")],
+            reference_point: None,
+            ideal_point: None,
+        })
+        .unwrap();
+        let first = archive.update(0, &[obj("a", 0.5, 5.0)]);
+        assert!((first.coverage - 0.25).abs() < 1e-4);
+        let ideal = archive.ideal_point.clone().unwrap();
+        assert!((ideal[0] - 0.55).abs() < 1e-6 && (ideal[1] - 4.5).abs() < 1e-6);
+
+        let second = archive.update(1, &[obj("b", 0.52, 4.8)]);
+        assert_eq!(archive.ideal_point, Some(ideal));
+        assert!((second.coverage - 0.49).abs() < 1e-4);
+    }
+
+    #[test]
+    fn default_reference_lies_beyond_the_first_nadir() {
+        let mut archive = ParetoArchive::new(ParetoArchiveConfig::default()).unwrap();
+        let mut slow = obj("slow", 0.9, 20.0);
+        slow.model_size_mb = 3.0;
+        archive.update(0, &[obj("fast", 0.5, 10.0), slow]);
+        let reference = archive.reference_point.clone().unwrap();
+        assert!((reference[0] - 0.46).abs() < 1e-6);
+        assert!((reference[1] - 21.0).abs() < 1e-5);
+        assert!((reference[2] - 3.2).abs() < 1e-5);
+        assert!(archive.history[0].hypervolume > 0.0);
+
+        assert!(
+            ParetoArchive::new(ParetoArchiveConfig { reference_point: Some(vec![1.0]), ..ParetoArchiveConfig::default() })
+                .is_err()
+        );
+    }
+}
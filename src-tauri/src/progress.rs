@@ -0,0 +1,190 @@
+/// Progress Reporting
+///
+/// Training, validation and batch assembly report progress through `ProgressSink`
+/// instead of a `tauri::AppHandle`, so the same code runs in every execution mode:
+/// - `TauriProgressSink`: GUI, emits frontend events
+/// - `StdoutJsonProgressSink`: `--train-worker` processes, one `WorkerTrainProgress` JSON line per update
+/// - `ChannelProgressSink`: forwards `ProgressEvent`s over an mpsc channel (tests, embedding)
+/// - `NoopProgressSink`: CLI / headless runs that only need the final result
+use std::io::Write;
+use std::sync::mpsc::Sender;
+
+use serde::Serialize;
+
+use crate::dtos::WorkerTrainProgress;
+use crate::entities::BatchMetrics;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum ProgressEvent {
+    AttemptStarted { genome_index: usize },
+    BatchMetrics(BatchMetrics),
+    SplitEvaluated { split: String, loss: f32, accuracy: f32 },
+    BatchAssembly { split: String, assembled: usize, total: usize },
+}
+
+/// Receiver of training progress. Every method has a no-op default so sinks only
+/// implement the events they care about.
+pub trait ProgressSink: Send + Sync {
+    /// A training attempt (or a cache hit) for `genome_index` is starting.
+    fn attempt_started(&self, _genome_index: usize) {}
+
+    /// Periodic training metrics from `run_eval_pass`.
+    fn batch_metrics(&self, _metrics: &BatchMetrics) {}
+
+    /// A validation/test pass finished.
+    fn split_evaluated(&self, _split: &str, _loss: f32, _accuracy: f32) {}
+
+    /// `assembled` of `total` batches of `split` have been loaded.
+    fn batch_assembly(&self, _split: &str, _assembled: usize, _total: usize) {}
+}
+
+pub struct NoopProgressSink;
+
+impl ProgressSink for NoopProgressSink {}
+
+pub struct TauriProgressSink {
+    app_handle: tauri::AppHandle,
+}
+
+impl TauriProgressSink {
+    pub fn new(app_handle: tauri::AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl ProgressSink for TauriProgressSink {
+    fn attempt_started(&self, genome_index: usize) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit("evaluating-genome-start", genome_index);
+    }
+
+    fn batch_metrics(&self, metrics: &BatchMetrics) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit("evaluating-batch-metrics", metrics.clone());
+    }
+
+    fn split_evaluated(&self, split: &str, loss: f32, accuracy: f32) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit(
+            "split-evaluated",
+            ProgressEvent::SplitEvaluated {
+                split: split.to_string(),
+                loss,
+                accuracy,
+            },
+        );
+    }
+
+    fn batch_assembly(&self, split: &str, assembled: usize, total: usize) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit(
+            "batch-assembly-progress",
+            ProgressEvent::BatchAssembly {
+                split: split.to_string(),
+                assembled,
+                total,
+            },
+        );
+    }
+}
+
+/// Writes `WorkerTrainProgress` lines to stdout; the parent process forwards them
+/// as `evaluating-batch-metrics` events (see `run_worker_job`).
+pub struct StdoutJsonProgressSink {
+    job_id: String,
+    genome_id: String,
+}
+
+impl StdoutJsonProgressSink {
+    pub fn new(job_id: String, genome_id: String) -> Self {
+        Self { job_id, genome_id }
+    }
+}
+
+impl ProgressSink for StdoutJsonProgressSink {
+    fn batch_metrics(&self, metrics: &BatchMetrics) {
+        let progress = WorkerTrainProgress {
+            job_id: self.job_id.clone(),
+            genome_id: self.genome_id.clone(),
+            epoch: metrics.epoch,
+            batch: metrics.batch,
+            total_batches: metrics.total_batches,
+            step: metrics.step,
+            total_steps: metrics.total_steps,
+            queue_wait_ms: metrics.queue_wait_ms,
+            gpu_active_ms: metrics.gpu_active_ms,
+            step_time_ms: metrics.step_time_ms,
+        };
+        if let Ok(payload) = serde_json::to_string(&progress) {
+            let mut stdout = std::io::stdout().lock();
+            let _ = writeln!(stdout, "{}", payload);
+            let _ = stdout.flush();
+        }
+    }
+}
+
+pub struct ChannelProgressSink {
+    sender: Sender<ProgressEvent>,
+}
+
+impl ChannelProgressSink {
+    pub fn new(sender: Sender<ProgressEvent>) -> Self {
+        Self { sender }
+    }
+}
+
+impl ProgressSink for ChannelProgressSink {
+    fn attempt_started(&self, genome_index: usize) {
+        let _ = self.sender.send(ProgressEvent::AttemptStarted { genome_index });
+    }
+
+    fn batch_metrics(&self, metrics: &BatchMetrics) {
+        let _ = self.sender.send(ProgressEvent::BatchMetrics(metrics.clone()));
+    }
+
+    fn split_evaluated(&self, split: &str, loss: f32, accuracy: f32) {
+        let _ = self.sender.send(ProgressEvent::SplitEvaluated {
+            split: split.to_string(),
+            loss,
+            accuracy,
+        });
+    }
+
+    fn batch_assembly(&self, split: &str, assembled: usize, total: usize) {
+        let _ = self.sender.send(ProgressEvent::BatchAssembly {
+            split: split.to_string(),
+            assembled,
+            total,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_sink_forwards_events_in_order() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let sink = ChannelProgressSink::new(tx);
+
+        sink.attempt_started(3);
+        sink.batch_assembly("train", 1, 4);
+        sink.split_evaluated("Test", 0.5, 87.5);
+
+        let events: Vec<ProgressEvent> = rx.try_iter().collect();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], ProgressEvent::AttemptStarted { genome_index: 3 }));
+        assert!(matches!(&events[1], ProgressEvent::BatchAssembly { split, assembled: 1, total: 4 } if split == "train"));
+        assert!(matches!(&events[2], ProgressEvent::SplitEvaluated { accuracy, .. } if *accuracy == 87.5));
+    }
+
+    #[test]
+    fn noop_sink_accepts_every_event() {
+        let sink: &dyn ProgressSink = &NoopProgressSink;
+        sink.attempt_started(0);
+        sink.split_evaluated("Validation", 1.0, 0.0);
+        sink.batch_assembly("val", 0, 0);
+    }
+}
@@ -0,0 +1,196 @@
+/// Class-Balanced Sampling
+///
+/// A dataset profile's `sampling` strategy decides which training samples the batches
+/// are built from. Classes come from the first classification target stream:
+/// - `uniform`: every training sample once (default)
+/// - `oversample`: every class repeated (with fresh shuffles) up to the largest class
+/// - `undersample`: every class cut down to the smallest class
+/// - `weighted`: as many draws as there are training samples, with replacement, each
+///   sample weighted by the inverse frequency of its class
+///
+/// Oversampled and undersampled classes are interleaved, so every batch sees all of
+/// them. The training set is drawn afresh every epoch from a seed, so a run always sees
+/// the same draws. Validation and test splits are never resampled.
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngExt, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplingStrategy {
+    #[default]
+    Uniform,
+    Oversample,
+    Undersample,
+    Weighted,
+}
+
+impl SamplingStrategy {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "" | "uniform" => Ok(SamplingStrategy::Uniform),
+            "oversample" | "oversampling" => Ok(SamplingStrategy::Oversample),
+            "undersample" | "undersampling" => Ok(SamplingStrategy::Undersample),
+            "weighted" | "inverse_frequency" => Ok(SamplingStrategy::Weighted),
+            other => Err(format!(
+                "Unknown sampling strategy '{}'. Expected 'uniform', 'oversample', 'undersample' or 'weighted'",
+                other
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SamplingStrategy::Uniform => "uniform",
+            SamplingStrategy::Oversample => "oversample",
+            SamplingStrategy::Undersample => "undersample",
+            SamplingStrategy::Weighted => "weighted",
+        }
+    }
+}
+
+/// Training ids under `strategy`, given the training ids grouped by class.
+pub fn resample<R: Rng>(strategy: SamplingStrategy, groups: &[(String, Vec<String>)], rng: &mut R) -> Vec<String> {
+    let groups: Vec<&Vec<String>> = groups.iter().map(|(_, ids)| ids).filter(|ids| !ids.is_empty()).collect();
+    let total: usize = groups.iter().map(|ids| ids.len()).sum();
+    if groups.is_empty() {
+        return Vec::new();
+    }
+
+    match strategy {
+        SamplingStrategy::Uniform => {
+            let mut ids: Vec<String> = groups.into_iter().flatten().cloned().collect();
+            ids.shuffle(rng);
+            ids
+        }
+        SamplingStrategy::Oversample | SamplingStrategy::Undersample => {
+            let per_class = if strategy == SamplingStrategy::Oversample {
+                groups.iter().map(|ids| ids.len()).max().unwrap_or(0)
+            } else {
+                groups.iter().map(|ids| ids.len()).min().unwrap_or(0)
+            };
+            let mut columns: Vec<_> =
+                groups.iter().map(|ids| draw_evenly(ids, per_class, rng).into_iter()).collect();
+
+            // Interleave in a fresh class order every round
+            let mut order: Vec<usize> = (0..columns.len()).collect();
+            let mut ids = Vec::with_capacity(per_class * columns.len());
+            for _ in 0..per_class {
+                order.shuffle(rng);
+                for &class in &order {
+                    ids.extend(columns[class].next());
+                }
+            }
+            ids
+        }
+        SamplingStrategy::Weighted => {
+            // Inverse-frequency weights give every class the same total weight: pick a
+            // class uniformly, then a member of it uniformly.
+            (0..total)
+                .map(|_| {
+                    let members = groups[rng.random_range(0..groups.len())];
+                    members[rng.random_range(0..members.len())].clone()
+                })
+                .collect()
+        }
+    }
+}
+
+/// Training ids of every epoch under a non-uniform strategy. Epoch `e` draws with a
+/// generator seeded by `seed + e`, so resumed and repeated runs see the same batches.
+#[derive(Debug, Clone)]
+pub struct EpochSampler {
+    strategy: SamplingStrategy,
+    groups: Vec<(String, Vec<String>)>,
+    seed: u64,
+}
+
+impl EpochSampler {
+    pub fn new(strategy: SamplingStrategy, groups: Vec<(String, Vec<String>)>, seed: u64) -> Self {
+        EpochSampler { strategy, groups, seed }
+    }
+
+    pub fn strategy(&self) -> SamplingStrategy {
+        self.strategy
+    }
+
+    pub fn epoch_ids(&self, epoch: usize) -> Vec<String> {
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(epoch as u64));
+        resample(self.strategy, &self.groups, &mut rng)
+    }
+}
+
+/// `count` ids of one class: whole shuffled copies of the class, topped up with a
+/// partial shuffled copy, so no sample is repeated more than one extra time.
+fn draw_evenly<R: Rng>(ids: &[String], count: usize, rng: &mut R) -> Vec<String> {
+    let mut drawn = Vec::with_capacity(count);
+    while drawn.len() < count {
+        let mut copy = ids.to_vec();
+        copy.shuffle(rng);
+        copy.truncate(count - drawn.len());
+        drawn.extend(copy);
+    }
+    drawn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups() -> Vec<(String, Vec<String>)> {
+        vec![
+            ("healthy".to_string(), (0..9).map(|i| format!("h{}", i)).collect()),
+            ("sick".to_string(), vec!["s0".to_string(), "s1".to_string(), "s2".to_string()]),
+        ]
+    }
+
+    fn count(ids: &[String], prefix: char) -> usize {
+        ids.iter().filter(|id| id.starts_with(prefix)).count()
+    }
+
+    #[test]
+    fn oversampling_balances_every_batch() {
+        let ids = resample(SamplingStrategy::Oversample, &groups(), &mut StdRng::seed_from_u64(7));
+        assert_eq!(ids.len(), 18);
+        assert_eq!(count(&ids, 's'), 9);
+        // Each sick sample appears exactly three times
+        assert!(ids.iter().filter(|id| *id == "s1").count() == 3);
+        // Batches of two always hold one sample of each class
+        assert!(ids.chunks(2).all(|batch| count(batch, 's') == 1));
+    }
+
+    #[test]
+    fn undersampling_keeps_the_minority_size() {
+        let ids = resample(SamplingStrategy::Undersample, &groups(), &mut StdRng::seed_from_u64(7));
+        assert_eq!(ids.len(), 6);
+        assert_eq!(count(&ids, 'h'), 3);
+        assert_eq!(count(&ids, 's'), 3);
+    }
+
+    #[test]
+    fn weighted_sampling_draws_classes_evenly() {
+        let mut many = groups();
+        many[0].1 = (0..900).map(|i| format!("h{}", i)).collect();
+        let ids = resample(SamplingStrategy::Weighted, &many, &mut StdRng::seed_from_u64(7));
+        assert_eq!(ids.len(), 903);
+        let sick = count(&ids, 's') as f32 / ids.len() as f32;
+        assert!((sick - 0.5).abs() < 0.1, "sick share {}", sick);
+    }
+
+    #[test]
+    fn epochs_draw_afresh_but_reproducibly() {
+        let sampler = EpochSampler::new(SamplingStrategy::Undersample, groups(), 11);
+        let first = sampler.epoch_ids(0);
+        assert_eq!(first, EpochSampler::new(SamplingStrategy::Undersample, groups(), 11).epoch_ids(0));
+        assert_eq!(sampler.epoch_ids(1).len(), first.len());
+        assert!((1..5).any(|epoch| sampler.epoch_ids(epoch) != first));
+    }
+
+    #[test]
+    fn strategy_names_parse() {
+        assert_eq!(SamplingStrategy::from_name("").unwrap(), SamplingStrategy::Uniform);
+        assert_eq!(SamplingStrategy::from_name("inverse-frequency").unwrap(), SamplingStrategy::Weighted);
+        assert!(SamplingStrategy::from_name("smote").is_err());
+    }
+}
@@ -8,6 +8,7 @@ use std::path::Path;
 
 use crate::csv_loader::CsvDatasetLoader;
 use crate::dtos::{CsvDatasetDef, DataLocatorDef, DataStream, DataType};
+use crate::tensor_files::TensorTable;
 
 #[derive(Debug, Clone)]
 pub struct ShapeInference;
@@ -80,6 +81,10 @@ impl ShapeInference {
                 }
             }
 
+            DataLocatorDef::NumpyArray { .. } | DataLocatorDef::ParquetTable { .. } => {
+                ShapeInference::infer_tensor_file_shape(stream, root_path, &mut warnings)
+            }
+
             _ => Err(format!(
                 "Unsupported locator for input shape inference: {:?}",
                 stream.locator
@@ -87,6 +92,48 @@ impl ShapeInference {
         }
     }
 
+    /// Infer the shape of NumPy/Parquet samples from the file itself: the array shape
+    /// without its sample axis, or the number of selected Parquet values per row.
+    ///
+    /// Two-axis samples are sequences `[T, C]`, anything else is a flat vector.
+    fn infer_tensor_file_shape(
+        stream: &DataStream,
+        root_path: &Path,
+        warnings: &mut Vec<String>,
+    ) -> Result<(Vec<usize>, String, Vec<String>), String> {
+        let table = crate::tensor_files::load_table(root_path, &stream.locator)?;
+        ShapeInference::tensor_table_shape(stream, &table, warnings)
+    }
+
+    /// `infer_tensor_file_shape` for a table that is already loaded.
+    pub fn tensor_table_shape(
+        stream: &DataStream,
+        table: &TensorTable,
+        warnings: &mut Vec<String>,
+    ) -> Result<(Vec<usize>, String, Vec<String>), String> {
+        if table.is_empty() {
+            warnings.push("Tensor file contains no samples".to_string());
+        }
+
+        let (shape, data_type) = match table.sample_shape[..] {
+            [steps, channels] => (vec![steps, channels], "TemporalSequence"),
+            _ => (vec![table.sample_len()], "Vector"),
+        };
+        let declared = match stream.data_type {
+            DataType::TemporalSequence => "TemporalSequence",
+            DataType::Vector => "Vector",
+            _ => "",
+        };
+        if declared != data_type {
+            warnings.push(format!(
+                "Samples of shape {:?} look like {} data, but the stream is declared as {:?}",
+                table.sample_shape, data_type, stream.data_type
+            ));
+        }
+
+        Ok((shape, data_type.to_string(), warnings.clone()))
+    }
+
     /// Infer CSV input shape based on data type (which determines sample mode)
     /// 
     /// IMPORTANT: This function resolves the Phase 3 design issue where users had to 
@@ -308,6 +355,7 @@ mod tests {
             target_column: "gesture".to_string(),
             window_size: Some(50),
             window_stride: None,
+            horizon: None,
             preprocessing: crate::dtos::CsvPreprocessingConfig {
                 normalization: "none".to_string(),
                 handle_missing: "skip".to_string(),
@@ -338,6 +386,7 @@ mod tests {
             target_column: "species".to_string(),
             window_size: None,
             window_stride: None,
+            horizon: None,
             preprocessing: crate::dtos::CsvPreprocessingConfig {
                 normalization: "none".to_string(),
                 handle_missing: "skip".to_string(),
@@ -370,12 +419,14 @@ mod tests {
                 target_column: "gesture".to_string(),
                 window_size: None,
                 window_stride: None,
+                horizon: None,
                 preprocessing: crate::dtos::CsvPreprocessingConfig {
                     normalization: "none".to_string(),
                     handle_missing: "skip".to_string(),
                 },
             }),
             preprocessing: None,
+            task_type: None,
         };
 
         let output_shape =
@@ -400,12 +451,14 @@ mod tests {
                 target_column: "gesture".to_string(),
                 window_size: None,
                 window_stride: None,
+                horizon: None,
                 preprocessing: crate::dtos::CsvPreprocessingConfig {
                     normalization: "none".to_string(),
                     handle_missing: "skip".to_string(),
                 },
             }),
             preprocessing: None,
+            task_type: None,
         };
 
         let result = ShapeInference::infer_output_shape(&stream, 0);
@@ -3,6 +3,8 @@
 /// Implements flexible stopping criteria for evolution with any/all policy:
 /// - GenerationLimit: Stop after N generations
 /// - FitnessPlateau: Stop if fitness improvement < threshold for patience generations
+///   (monitors best fitness, best accuracy, population average or the Pareto archive's
+///   hypervolume coverage, see `pareto_archive`)
 /// - TimeLimit: Stop after elapsed time exceeds limit
 /// - TargetAccuracy: Stop if best accuracy reaches threshold
 /// - ManualStop: Triggered via external signal
@@ -37,7 +39,7 @@ pub struct EvolutionProgressState {
     /// Population average fitness
     pub population_avg_fitness: f32,
     
-    /// Pareto front coverage (0.0-1.0)
+    /// Pareto front coverage (0.0-1.0), `ParetoArchive::coverage` of the run's archive
     pub pareto_coverage: f32,
     
     /// Time elapsed since evolution start
@@ -52,6 +54,9 @@ pub struct EvolutionProgressState {
     /// History of best accuracy per generation
     accuracy_history: VecDeque<f32>,
     
+    /// History of Pareto coverage per generation
+    coverage_history: VecDeque<f32>,
+    
     /// Highest fitness improvement threshold for plateau detection
     /// Stores the best delta between consecutive generations
     max_improvement_seen: f32,
@@ -70,6 +75,7 @@ impl EvolutionProgressState {
             start_time: Instant::now(),
             fitness_history: VecDeque::with_capacity(1000),
             accuracy_history: VecDeque::with_capacity(1000),
+            coverage_history: VecDeque::with_capacity(1000),
             max_improvement_seen: 0.0,
         }
     }
@@ -101,11 +107,13 @@ impl EvolutionProgressState {
         // Maintain history for plateau detection
         self.fitness_history.push_back(best_fitness);
         self.accuracy_history.push_back(best_accuracy);
+        self.coverage_history.push_back(self.pareto_coverage);
         
         // Keep history bounded (max 1000 generations)
         if self.fitness_history.len() > 1000 {
             self.fitness_history.pop_front();
             self.accuracy_history.pop_front();
+            self.coverage_history.pop_front();
         }
     }
     
@@ -150,6 +158,19 @@ impl EvolutionProgressState {
         (newest_accuracy - oldest_accuracy).abs() < threshold
     }
     
+    /// Check if Pareto coverage moved less than threshold over the last N generations
+    fn is_coverage_plateaued(&self, patience: u32, threshold: f32) -> bool {
+        let len = self.coverage_history.len();
+        if len < (patience as usize + 1) {
+            return false;
+        }
+        
+        let oldest_coverage = self.coverage_history[len - 1 - patience as usize];
+        let newest_coverage = self.coverage_history[len - 1];
+        
+        (newest_coverage - oldest_coverage).abs() < threshold
+    }
+    
     /// Get population average fitness for plateau detection
     /// (May be used for future monitoring of population-level stopping criteria)
     #[allow(dead_code)]
@@ -244,6 +265,9 @@ pub fn check_stopping_criteria(
                         let improvement = state.get_improvement_last_n(*patience_generations);
                         improvement < *improvement_threshold
                     }
+                    "pareto_coverage" => {
+                        state.is_coverage_plateaued(*patience_generations, *improvement_threshold)
+                    }
                     _ => false,
                 };
                 
@@ -344,7 +368,7 @@ pub fn validate_stopping_config(
                 if *improvement_threshold < 0.0 {
                     return Err("FitnessPlateau threshold must be >= 0".to_string());
                 }
-                let valid_monitors = ["best_fitness", "best_accuracy", "population_avg"];
+                let valid_monitors = ["best_fitness", "best_accuracy", "population_avg", "pareto_coverage"];
                 if !valid_monitors.contains(&monitor.as_str()) {
                     return Err(format!(
                         "Invalid monitor metric: '{}'. Must be one of: {:?}",
@@ -515,6 +539,44 @@ mod tests {
         assert!(decision.triggered_criteria.contains(&"FitnessPlateau".to_string()));
     }
     
+    #[test]
+    fn test_fitness_plateau_pareto_coverage() {
+        let mut state = EvolutionProgressState::new();
+        let criteria = vec![StoppingCriterion::FitnessPlateau {
+            patience_generations: 2,
+            improvement_threshold: 0.01,
+            monitor: "pareto_coverage".to_string(),
+        }];
+        
+        // Coverage keeps growing while fitness stalls
+        state.on_generation_complete(0, 0.80, 0.80, 0.70, 0.40);
+        state.on_generation_complete(1, 0.80, 0.80, 0.70, 0.45);
+        state.on_generation_complete(2, 0.80, 0.80, 0.70, 0.50);
+        assert!(!check_stopping_criteria(&criteria, &state, "any", false).should_stop);
+        
+        state.on_generation_complete(3, 0.80, 0.80, 0.70, 0.505);
+        state.on_generation_complete(4, 0.80, 0.80, 0.70, 0.505);
+        let decision = check_stopping_criteria(&criteria, &state, "any", false);
+        assert!(decision.should_stop);
+        assert!(validate_stopping_config(&criteria, "any").is_ok());
+    }
+    
+    #[test]
+    fn test_fitness_plateau_pareto_coverage_drop_is_not_a_plateau() {
+        let mut state = EvolutionProgressState::new();
+        let criteria = vec![StoppingCriterion::FitnessPlateau {
+            patience_generations: 2,
+            improvement_threshold: 0.01,
+            monitor: "pareto_coverage".to_string(),
+        }];
+        
+        // A re-evaluated archive member came back worse and coverage fell
+        state.on_generation_complete(0, 0.80, 0.80, 0.70, 0.50);
+        state.on_generation_complete(1, 0.80, 0.80, 0.70, 0.45);
+        state.on_generation_complete(2, 0.80, 0.80, 0.70, 0.40);
+        assert!(!check_stopping_criteria(&criteria, &state, "any", false).should_stop);
+    }
+    
     #[test]
     fn test_manual_stop() {
         let state = EvolutionProgressState::new();
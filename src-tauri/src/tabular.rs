@@ -0,0 +1,468 @@
+/// Tabular Preprocessing
+///
+/// Scaling, categorical encoding and missing-value handling for Vector and CSV
+/// streams, configured by the stream's `preprocessing.tabular` block or, for CSV
+/// streams without one, by the locator's own `preprocessing` ("global"
+/// normalization = z-score, `handle_missing`).
+///
+/// Statistics are fitted on the training split only and applied to every split. The
+/// last fitted statistics of a profile are persisted in
+/// `<app data>/datasets_cache/<profile>/tabular_stats.json` (see `get_tabular_stats`).
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::{DataLocatorDef, DataStream};
+use crate::eval_cache::StableHasher;
+
+const STATS_FILE: &str = "tabular_stats.json";
+
+/// Cell values treated as missing (compared case-insensitively after trimming).
+const MISSING_TOKENS: &[&str] = &["", "na", "n/a", "nan", "null", "none", "?"];
+
+pub fn is_missing(cell: &str) -> bool {
+    let cell = cell.trim();
+    MISSING_TOKENS.iter().any(|token| cell.eq_ignore_ascii_case(token))
+}
+
+pub fn row_has_missing(row: &[String]) -> bool {
+    row.iter().any(|cell| is_missing(cell))
+}
+
+fn parse_number(cell: &str) -> Option<f32> {
+    if is_missing(cell) {
+        return None;
+    }
+    cell.trim().parse::<f32>().ok().filter(|v| v.is_finite())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scaling {
+    None,
+    MinMax,
+    ZScore,
+}
+
+impl Scaling {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "none" => Ok(Scaling::None),
+            "min-max" | "minmax" => Ok(Scaling::MinMax),
+            "z-score" | "zscore" | "standard" => Ok(Scaling::ZScore),
+            other => Err(format!("Unknown tabular normalization '{}'. Expected 'min-max', 'z-score' or 'none'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MissingStrategy {
+    /// Samples with any missing feature are removed from the dataset.
+    Drop,
+    Mean,
+    Median,
+    Mode,
+    /// Linear interpolation between neighbouring rows (CSV only); gaps at the edges
+    /// fall back to the column mean.
+    Interpolate,
+}
+
+impl MissingStrategy {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "drop" | "skip" => Ok(MissingStrategy::Drop),
+            "mean" => Ok(MissingStrategy::Mean),
+            "median" => Ok(MissingStrategy::Median),
+            "mode" => Ok(MissingStrategy::Mode),
+            "interpolate" => Ok(MissingStrategy::Interpolate),
+            other => Err(format!(
+                "Unknown missing-value strategy '{}'. Expected 'drop', 'mean', 'median', 'mode' or 'interpolate'",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TabularConfig {
+    pub scaling: Scaling,
+    pub one_hot: bool,
+    pub missing: MissingStrategy,
+}
+
+impl TabularConfig {
+    /// Preprocessing for `stream`, or `None` for streams that are not tabular.
+    pub fn for_stream(stream: &DataStream) -> Result<Option<Self>, String> {
+        if let Some(tabular) = stream.preprocessing.as_ref().and_then(|prep| prep.tabular.as_ref()) {
+            return Ok(Some(TabularConfig {
+                scaling: Scaling::from_name(&tabular.normalization)?,
+                one_hot: tabular.one_hot,
+                missing: MissingStrategy::from_name(&tabular.fill_missing)?,
+            }));
+        }
+        if let DataLocatorDef::CsvDataset(csv_def) = &stream.locator {
+            // "per-sample" / "per-channel" stay with the CSV loader, they need no fitted statistics
+            let scaling = if csv_def.preprocessing.normalization == "global" {
+                Scaling::ZScore
+            } else {
+                Scaling::None
+            };
+            return Ok(Some(TabularConfig {
+                scaling,
+                one_hot: false,
+                missing: MissingStrategy::from_name(&csv_def.preprocessing.handle_missing)?,
+            }));
+        }
+        Ok(None)
+    }
+}
+
+/// Fills interior gaps of numeric columns by linear interpolation between the nearest
+/// known rows. Leading and trailing gaps are left missing.
+pub fn interpolate_columns(rows: &mut [Vec<String>]) {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    for col in 0..width {
+        let mut previous: Option<(usize, f32)> = None;
+        for row in 0..rows.len() {
+            let Some(value) = rows[row].get(col).and_then(|cell| parse_number(cell)) else {
+                continue;
+            };
+            if let Some((prev_row, prev_value)) = previous
+                && row > prev_row + 1
+            {
+                let span = (row - prev_row) as f32;
+                for (offset, gap_row) in rows[prev_row + 1..row].iter_mut().enumerate() {
+                    if let Some(cell) = gap_row.get_mut(col)
+                        && is_missing(cell)
+                    {
+                        let t = (offset + 1) as f32 / span;
+                        *cell = (prev_value + (value - prev_value) * t).to_string();
+                    }
+                }
+            }
+            previous = Some((row, value));
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ColumnEncoding {
+    Numeric {
+        min: f32,
+        max: f32,
+        mean: f32,
+        std: f32,
+        median: f32,
+        mode: f32,
+    },
+    /// Categories in first-seen order of the training rows.
+    Categorical { categories: Vec<String>, mode: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub name: String,
+    #[serde(flatten)]
+    pub encoding: ColumnEncoding,
+    /// Missing cells among the training rows.
+    pub missing: usize,
+}
+
+impl ColumnStats {
+    fn fit(name: &str, cells: &[&str]) -> Self {
+        let present: Vec<&str> = cells.iter().copied().filter(|cell| !is_missing(cell)).collect();
+        let missing = cells.len() - present.len();
+        let numbers: Vec<f32> = present.iter().filter_map(|cell| parse_number(cell)).collect();
+
+        let encoding = if numbers.len() == present.len() {
+            numeric_encoding(numbers)
+        } else {
+            let mut categories: Vec<String> = Vec::new();
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for cell in &present {
+                let cell = cell.trim();
+                if !counts.contains_key(cell) {
+                    categories.push(cell.to_string());
+                }
+                *counts.entry(cell).or_insert(0) += 1;
+            }
+            let mode = categories
+                .iter()
+                .max_by_key(|c| (counts[c.as_str()], std::cmp::Reverse(c.as_str())))
+                .cloned()
+                .unwrap_or_default();
+            ColumnEncoding::Categorical { categories, mode }
+        };
+
+        ColumnStats {
+            name: name.to_string(),
+            encoding,
+            missing,
+        }
+    }
+
+    fn width(&self, one_hot: bool) -> usize {
+        match &self.encoding {
+            ColumnEncoding::Categorical { categories, .. } if one_hot => categories.len(),
+            _ => 1,
+        }
+    }
+}
+
+fn numeric_encoding(mut numbers: Vec<f32>) -> ColumnEncoding {
+    if numbers.is_empty() {
+        return ColumnEncoding::Numeric {
+            min: 0.0,
+            max: 0.0,
+            mean: 0.0,
+            std: 0.0,
+            median: 0.0,
+            mode: 0.0,
+        };
+    }
+    numbers.sort_by(f32::total_cmp);
+    let n = numbers.len() as f32;
+    let mean = numbers.iter().sum::<f32>() / n;
+    let std = (numbers.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n).sqrt();
+    let mid = numbers.len() / 2;
+    let median = if numbers.len().is_multiple_of(2) {
+        (numbers[mid - 1] + numbers[mid]) / 2.0
+    } else {
+        numbers[mid]
+    };
+
+    // Sorted, so runs of equal values are contiguous; the first longest run wins
+    let (mut mode, mut best_run, mut run) = (numbers[0], 0, 0);
+    for (i, value) in numbers.iter().enumerate() {
+        run = if i > 0 && numbers[i - 1] == *value { run + 1 } else { 1 };
+        if run > best_run {
+            best_run = run;
+            mode = *value;
+        }
+    }
+
+    ColumnEncoding::Numeric {
+        min: numbers[0],
+        max: numbers[numbers.len() - 1],
+        mean,
+        std,
+        median,
+        mode,
+    }
+}
+
+/// Fitted preprocessing of one stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TabularStats {
+    pub stream_id: String,
+    pub config: TabularConfig,
+    /// Digest of the training sample ids the statistics were fitted on.
+    pub train_fingerprint: String,
+    pub fitted_rows: usize,
+    pub columns: Vec<ColumnStats>,
+}
+
+impl TabularStats {
+    pub fn fit<'a>(
+        stream_id: &str,
+        column_names: &[String],
+        rows: impl Iterator<Item = &'a [String]>,
+        config: &TabularConfig,
+        train_fingerprint: String,
+    ) -> Self {
+        let mut columns: Vec<Vec<&str>> = vec![Vec::new(); column_names.len()];
+        let mut fitted_rows = 0;
+        for row in rows {
+            fitted_rows += 1;
+            for (col, cells) in columns.iter_mut().enumerate() {
+                cells.push(row.get(col).map(String::as_str).unwrap_or(""));
+            }
+        }
+
+        TabularStats {
+            stream_id: stream_id.to_string(),
+            config: config.clone(),
+            train_fingerprint,
+            fitted_rows,
+            columns: column_names
+                .iter()
+                .zip(&columns)
+                .map(|(name, cells)| ColumnStats::fit(name, cells))
+                .collect(),
+        }
+    }
+
+    /// Number of values `transform` produces per row.
+    pub fn width(&self) -> usize {
+        self.columns.iter().map(|c| c.width(self.config.one_hot)).sum()
+    }
+
+    /// Encodes, fills and scales one row. Cells that are missing or do not match the
+    /// fitted column type are filled; unseen categories one-hot encode as all zeros.
+    pub fn transform(&self, row: &[String]) -> Vec<f32> {
+        let mut out = Vec::with_capacity(self.width());
+        for (col, stats) in self.columns.iter().enumerate() {
+            let cell = row.get(col).map(|c| c.trim()).unwrap_or("");
+            match &stats.encoding {
+                ColumnEncoding::Numeric {
+                    min,
+                    max,
+                    mean,
+                    std,
+                    median,
+                    mode,
+                } => {
+                    let value = parse_number(cell).unwrap_or(match self.config.missing {
+                        MissingStrategy::Median => *median,
+                        MissingStrategy::Mode => *mode,
+                        MissingStrategy::Mean | MissingStrategy::Drop | MissingStrategy::Interpolate => *mean,
+                    });
+                    out.push(match self.config.scaling {
+                        Scaling::None => value,
+                        Scaling::MinMax if max - min > 1e-7 => (value - min) / (max - min),
+                        Scaling::ZScore if *std > 1e-7 => (value - mean) / std,
+                        _ => 0.0,
+                    });
+                }
+                ColumnEncoding::Categorical { categories, mode } => {
+                    let value = if is_missing(cell) { mode.as_str() } else { cell };
+                    let index = categories.iter().position(|c| c == value);
+                    if self.config.one_hot {
+                        out.extend((0..categories.len()).map(|i| if Some(i) == index { 1.0 } else { 0.0 }));
+                    } else {
+                        let fallback = categories.iter().position(|c| c == mode).unwrap_or(0);
+                        out.push(index.unwrap_or(fallback) as f32);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// `(column, missing cells)` for columns with gaps in the training rows.
+    pub fn missing_report(&self) -> Vec<(&str, usize)> {
+        self.columns
+            .iter()
+            .filter(|c| c.missing > 0)
+            .map(|c| (c.name.as_str(), c.missing))
+            .collect()
+    }
+}
+
+/// Order-independent digest of the training sample ids.
+pub fn train_fingerprint(train_ids: &[String]) -> String {
+    let mut ids: Vec<&String> = train_ids.iter().collect();
+    ids.sort();
+    let mut hasher = StableHasher::default();
+    hasher.number(ids.len() as u64);
+    for id in ids {
+        hasher.field(id);
+    }
+    hasher.finish_hex()
+}
+
+pub fn stats_path(app_data: &Path, profile_id: &str) -> PathBuf {
+    app_data.join("datasets_cache").join(profile_id).join(STATS_FILE)
+}
+
+/// Persisted statistics by stream id; empty when none were saved yet.
+pub fn load_persisted(path: &Path) -> HashMap<String, TabularStats> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn persist(path: &Path, stats: &HashMap<String, TabularStats>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(stats).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    fn config(scaling: Scaling, one_hot: bool, missing: MissingStrategy) -> TabularConfig {
+        TabularConfig {
+            scaling,
+            one_hot,
+            missing,
+        }
+    }
+
+    fn fit(rows: &[Vec<String>], config: &TabularConfig) -> TabularStats {
+        let names = vec!["x".to_string(), "color".to_string()];
+        TabularStats::fit("s", &names, rows.iter().map(Vec::as_slice), config, String::new())
+    }
+
+    #[test]
+    fn statistics_come_from_the_fitted_rows_only() {
+        let train = vec![row(&["1", "red"]), row(&["3", "blue"]), row(&["", "red"])];
+        let stats = fit(&train, &config(Scaling::MinMax, true, MissingStrategy::Median));
+
+        assert_eq!(stats.fitted_rows, 3);
+        assert_eq!(stats.width(), 3);
+        assert_eq!(stats.missing_report(), vec![("x", 1)]);
+        assert_eq!(
+            stats.columns[1].encoding,
+            ColumnEncoding::Categorical {
+                categories: vec!["red".to_string(), "blue".to_string()],
+                mode: "red".to_string()
+            }
+        );
+
+        // A test row outside the training range is scaled with the training min/max
+        assert_eq!(stats.transform(&row(&["5", "blue"])), vec![2.0, 0.0, 1.0]);
+        // Missing number -> training median (2), unseen category -> all zeros
+        assert_eq!(stats.transform(&row(&["NaN", "green"])), vec![0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn z_score_and_ordinal_encoding() {
+        let train = vec![row(&["2", "a"]), row(&["4", "b"]), row(&["4", "b"])];
+        let stats = fit(&train, &config(Scaling::ZScore, false, MissingStrategy::Mode));
+        let ColumnEncoding::Numeric { mean, std, mode, .. } = stats.columns[0].encoding else {
+            panic!("numeric column expected");
+        };
+        assert!((mean - 10.0 / 3.0).abs() < 1e-6);
+        assert_eq!(mode, 4.0);
+
+        let encoded = stats.transform(&row(&["?", "a"]));
+        assert!((encoded[0] - (4.0 - mean) / std).abs() < 1e-6);
+        assert_eq!(encoded[1], 0.0);
+        assert_eq!(stats.transform(&row(&["2", ""]))[1], 1.0);
+    }
+
+    #[test]
+    fn interpolation_fills_interior_gaps() {
+        let mut rows = vec![row(&["", "a"]), row(&["0", "b"]), row(&["na", ""]), row(&["", "c"]), row(&["3", "d"])];
+        interpolate_columns(&mut rows);
+        let x: Vec<&str> = rows.iter().map(|r| r[0].as_str()).collect();
+        assert_eq!(x, vec!["", "0", "1", "2", "3"]);
+        assert_eq!(rows[2][1], "");
+        assert!(row_has_missing(&rows[0]));
+        assert!(!row_has_missing(&rows[3]));
+    }
+
+    #[test]
+    fn csv_locator_settings_map_onto_a_config() {
+        assert_eq!(MissingStrategy::from_name("skip"), Ok(MissingStrategy::Drop));
+        assert_eq!(Scaling::from_name("Z-Score"), Ok(Scaling::ZScore));
+        assert!(MissingStrategy::from_name("guess").is_err());
+        assert_ne!(train_fingerprint(&["a".into(), "b".into()]), train_fingerprint(&["a".into()]));
+        assert_eq!(
+            train_fingerprint(&["a".into(), "b".into()]),
+            train_fingerprint(&["b".into(), "a".into()])
+        );
+    }
+}
@@ -0,0 +1,317 @@
+/// Target Tasks
+///
+/// Target streams declare what the model predicts through `DataStream.task_type`:
+/// - `classification`: one class index per sample, cross-entropy loss
+/// - `regression`: one continuous value, MSE loss
+/// - `multi_output`: several continuous values (CSV target columns, Vector streams or a
+///   forecasting horizon), MSE loss
+/// - `multi_label`: independent 0/1 indicators, binary cross-entropy on logits
+///
+/// Continuous targets are standardized with mean/std fitted on the training split, so
+/// the loss is comparable across targets; reported errors are converted back to the
+/// original units. Their "accuracy" is the coefficient of determination (R², in %).
+use burn::tensor::backend::Backend;
+use serde::{Deserialize, Serialize};
+
+use crate::dtos::{DataStream, DataType};
+use crate::entities::DynamicTensor;
+
+/// Samples per quantile bin when stratifying continuous targets.
+const MIN_SAMPLES_PER_BIN: usize = 5;
+const MAX_QUANTILE_BINS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetTask {
+    Classification,
+    Regression,
+    MultiLabel,
+    MultiOutput,
+}
+
+impl TargetTask {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "classification" => Ok(TargetTask::Classification),
+            "regression" => Ok(TargetTask::Regression),
+            "multi_label" | "multilabel" => Ok(TargetTask::MultiLabel),
+            "multi_output" | "multioutput" => Ok(TargetTask::MultiOutput),
+            other => Err(format!(
+                "Unknown target task type '{}'. Expected 'classification', 'regression', 'multi_label' or 'multi_output'",
+                other
+            )),
+        }
+    }
+
+    /// The explicit `taskType` of a stream, otherwise classification for Categorical
+    /// streams and (multi-output) regression for everything else.
+    pub fn for_stream(stream: &DataStream) -> Result<Self, String> {
+        if let Some(name) = stream.task_type.as_deref().filter(|name| !name.trim().is_empty()) {
+            return Self::from_name(name);
+        }
+        Ok(match stream.data_type {
+            DataType::Categorical => TargetTask::Classification,
+            _ if stream.tensor_shape.iter().product::<usize>() > 1 => TargetTask::MultiOutput,
+            _ => TargetTask::Regression,
+        })
+    }
+
+    pub fn is_continuous(self) -> bool {
+        matches!(self, TargetTask::Regression | TargetTask::MultiOutput)
+    }
+}
+
+/// Per-position standardization of continuous target values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetScaler {
+    pub mean: Vec<f32>,
+    pub std: Vec<f32>,
+}
+
+impl TargetScaler {
+    /// Non-finite values are ignored; `None` when no value was seen.
+    pub fn fit<'a>(rows: impl IntoIterator<Item = &'a [f32]>) -> Option<Self> {
+        let mut sums: Vec<(f64, f64, usize)> = Vec::new();
+        for row in rows {
+            if sums.len() < row.len() {
+                sums.resize(row.len(), (0.0, 0.0, 0));
+            }
+            for (acc, &value) in sums.iter_mut().zip(row) {
+                if value.is_finite() {
+                    acc.0 += value as f64;
+                    acc.1 += (value as f64) * (value as f64);
+                    acc.2 += 1;
+                }
+            }
+        }
+        if sums.iter().all(|&(_, _, n)| n == 0) {
+            return None;
+        }
+
+        let (mean, std) = sums
+            .iter()
+            .map(|&(sum, sum_sq, n)| {
+                let n = n.max(1) as f64;
+                let mean = sum / n;
+                let var = (sum_sq / n - mean * mean).max(0.0);
+                let std = if var.sqrt() > 1e-7 { var.sqrt() } else { 1.0 };
+                (mean as f32, std as f32)
+            })
+            .unzip();
+        Some(TargetScaler { mean, std })
+    }
+
+    pub fn transform(&self, values: &mut [f32]) {
+        for (value, (mean, std)) in values.iter_mut().zip(self.mean.iter().zip(&self.std)) {
+            *value = (*value - mean) / std;
+        }
+    }
+
+    pub fn inverse(&self, values: &mut [f32]) {
+        for (value, (mean, std)) in values.iter_mut().zip(self.mean.iter().zip(&self.std)) {
+            *value = *value * std + mean;
+        }
+    }
+}
+
+/// What a target stream predicts, fitted on the training split.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetSpec {
+    pub stream_id: String,
+    pub task: TargetTask,
+    /// Model output units for this target.
+    pub width: usize,
+    pub scaler: Option<TargetScaler>,
+}
+
+/// Splits `values` into at most ten equally populated bins by their rank, keyed
+/// "q0".."q9", for stratified splitting of continuous targets.
+pub fn quantile_bins(mut values: Vec<(String, f32)>) -> Vec<(String, Vec<String>)> {
+    values.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let bins = (values.len() / MIN_SAMPLES_PER_BIN).clamp(1, MAX_QUANTILE_BINS);
+    let mut groups: Vec<(String, Vec<String>)> = (0..bins).map(|bin| (format!("q{}", bin), Vec::new())).collect();
+    let total = values.len().max(1);
+    for (rank, (id, _)) in values.into_iter().enumerate() {
+        groups[rank * bins / total].1.push(id);
+    }
+    groups
+}
+
+/// Metrics accumulated over the batches of one pass.
+#[derive(Debug, Clone, Default)]
+pub struct TargetMetrics {
+    correct: usize,
+    total: usize,
+    // Continuous targets, standardized units
+    values: usize,
+    sse: f64,
+    sum: f64,
+    sum_sq: f64,
+    // Continuous targets, original units
+    abs_error: f64,
+    sq_error: f64,
+}
+
+impl TargetMetrics {
+    /// Adds classification counts computed elsewhere (models without target specs).
+    pub fn add_counts(&mut self, correct: usize, total: usize) {
+        self.correct += correct;
+        self.total += total;
+    }
+
+    pub fn add_batch<B: Backend>(
+        &mut self,
+        specs: &[TargetSpec],
+        predictions: &[DynamicTensor<B>],
+        targets: &[DynamicTensor<B>],
+    ) {
+        for ((spec, prediction), target) in specs.iter().zip(predictions).zip(targets) {
+            let (DynamicTensor::Dim2(p), DynamicTensor::Dim2(t)) = (prediction, target) else {
+                continue;
+            };
+            let [batch_size, out_features] = p.dims();
+            let preds = p.clone().into_data().to_vec::<f32>().unwrap_or_default();
+            let truth = t.clone().into_data().to_vec::<f32>().unwrap_or_default();
+
+            match spec.task {
+                TargetTask::Classification if out_features > 1 => {
+                    for (row, &label) in preds.chunks(out_features).zip(&truth).take(batch_size) {
+                        let predicted = row
+                            .iter()
+                            .enumerate()
+                            .max_by(|a, b| a.1.total_cmp(b.1))
+                            .map_or(0, |(class, _)| class);
+                        self.correct += usize::from(predicted == label as usize);
+                        self.total += 1;
+                    }
+                }
+                TargetTask::Classification => {
+                    for (&p, &t) in preds.iter().zip(&truth) {
+                        let class = if p > 0.5 { 1.0 } else { 0.0 };
+                        self.correct += usize::from((class - t).abs() < 0.1);
+                        self.total += 1;
+                    }
+                }
+                TargetTask::MultiLabel => {
+                    for (&logit, &t) in preds.iter().zip(&truth) {
+                        self.correct += usize::from((logit > 0.0) == (t > 0.5));
+                        self.total += 1;
+                    }
+                }
+                TargetTask::Regression | TargetTask::MultiOutput => {
+                    for (&p, &t) in preds.iter().zip(&truth) {
+                        let error = (p - t) as f64;
+                        self.values += 1;
+                        self.sse += error * error;
+                        self.sum += t as f64;
+                        self.sum_sq += (t as f64) * (t as f64);
+                    }
+                    let width = out_features.max(1);
+                    for (p_row, t_row) in preds.chunks(width).zip(truth.chunks(width)) {
+                        let (mut p_row, mut t_row) = (p_row.to_vec(), t_row.to_vec());
+                        if let Some(scaler) = &spec.scaler {
+                            scaler.inverse(&mut p_row);
+                            scaler.inverse(&mut t_row);
+                        }
+                        for (p, t) in p_row.iter().zip(&t_row) {
+                            let error = (p - t).abs() as f64;
+                            self.abs_error += error;
+                            self.sq_error += error * error;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Classification/multi-label accuracy, R² of continuous targets (clamped at 0), or
+    /// their mean when a model predicts both, in percent.
+    pub fn accuracy(&self) -> f32 {
+        let classification = (self.total > 0).then(|| self.correct as f64 / self.total as f64);
+        let r2 = self.r2().map(|r2| r2.max(0.0));
+        let score = match (classification, r2) {
+            (Some(c), Some(r)) => (c + r) / 2.0,
+            (Some(c), None) => c,
+            (None, Some(r)) => r,
+            (None, None) => 0.0,
+        };
+        (score * 100.0) as f32
+    }
+
+    /// Coefficient of determination over all continuous target values.
+    pub fn r2(&self) -> Option<f64> {
+        if self.values == 0 {
+            return None;
+        }
+        let n = self.values as f64;
+        let total = self.sum_sq - self.sum * self.sum / n;
+        Some(if total > 1e-12 { 1.0 - self.sse / total } else { 0.0 })
+    }
+
+    /// Mean absolute error in the original units of the targets.
+    pub fn mae(&self) -> Option<f32> {
+        (self.values > 0).then(|| (self.abs_error / self.values as f64) as f32)
+    }
+
+    /// Root mean squared error in the original units of the targets.
+    pub fn rmse(&self) -> Option<f32> {
+        (self.values > 0).then(|| (self.sq_error / self.values as f64).sqrt() as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn::tensor::{Tensor, TensorData};
+
+    type TestBackend = crate::backend::CpuBackend;
+
+    #[test]
+    fn scaler_roundtrips_and_ignores_missing_values() {
+        let rows = [vec![1.0, 10.0], vec![3.0, f32::NAN], vec![5.0, 30.0]];
+        let scaler = TargetScaler::fit(rows.iter().map(Vec::as_slice)).expect("fitted");
+        assert_eq!(scaler.mean, vec![3.0, 20.0]);
+        assert!((scaler.std[1] - 10.0).abs() < 1e-5);
+
+        let mut values = vec![5.0, 30.0];
+        scaler.transform(&mut values);
+        assert!((values[1] - 1.0).abs() < 1e-5);
+        scaler.inverse(&mut values);
+        assert_eq!(values, vec![5.0, 30.0]);
+    }
+
+    #[test]
+    fn quantile_bins_are_balanced() {
+        let values: Vec<(String, f32)> = (0..40).map(|i| (i.to_string(), (39 - i) as f32)).collect();
+        let bins = quantile_bins(values);
+        assert_eq!(bins.len(), 8);
+        assert!(bins.iter().all(|(_, ids)| ids.len() == 5));
+        // Lowest values land in the first bin
+        assert!(bins[0].1.contains(&"39".to_string()));
+    }
+
+    #[test]
+    fn regression_metrics_report_original_units() {
+        let device = crate::backend::create_cpu_device();
+        let spec = TargetSpec {
+            stream_id: "price".to_string(),
+            task: TargetTask::Regression,
+            width: 1,
+            scaler: Some(TargetScaler {
+                mean: vec![100.0],
+                std: vec![10.0],
+            }),
+        };
+        let tensor = |values: Vec<f32>| {
+            let rows = values.len();
+            DynamicTensor::Dim2(Tensor::<TestBackend, 2>::from_data(TensorData::new(values, [rows, 1]), &device))
+        };
+
+        let mut metrics = TargetMetrics::default();
+        metrics.add_batch(&[spec], &[tensor(vec![-1.0, 0.5, 1.0])], &[tensor(vec![-1.0, 0.0, 1.0])]);
+
+        assert!((metrics.mae().unwrap() - 5.0 / 3.0).abs() < 1e-4);
+        assert!((metrics.r2().unwrap() - (1.0 - 0.25 / 2.0)).abs() < 1e-6);
+        assert!((metrics.accuracy() - 87.5).abs() < 1e-3);
+    }
+}
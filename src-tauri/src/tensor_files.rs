@@ -0,0 +1,439 @@
+/// Tensor Files
+///
+/// Numeric samples read directly from binary files instead of CSV text:
+/// - `NumpyArray`: a `.npy` array, or one array of an `.npz` archive, indexed by its
+///   first axis. The remaining axes are the sample shape (`[N, F]` vectors, `[N, T, C]`
+///   sequences). Float, integer and bool dtypes of either byte order are converted to
+///   f32; Fortran-ordered arrays are rejected. Sample IDs are the indices "0", "1", ...
+/// - `ParquetTable`: one row per sample. The selected numeric columns, with list
+///   columns flattened, make up a sample's values; an optional id column provides the
+///   sample IDs (otherwise the row index). String cells of a single selected column
+///   are kept as labels for Categorical streams.
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+
+use crate::dtos::DataLocatorDef;
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Samples of one stream, stored row-major.
+#[derive(Debug, Clone)]
+pub struct TensorTable {
+    pub ids: Vec<String>,
+    /// Shape of one sample, without the sample axis.
+    pub sample_shape: Vec<usize>,
+    values: Vec<f32>,
+    /// Parquet string cells per row (empty for NumPy arrays and numeric columns).
+    labels: Vec<String>,
+}
+
+impl TensorTable {
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Values per sample.
+    pub fn sample_len(&self) -> usize {
+        self.sample_shape.iter().product()
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[f32]> {
+        let len = self.sample_len();
+        self.values.get(row * len..(row + 1) * len)
+    }
+
+    /// Class label of a row: its string cell, or its single value ("3" for 3.0).
+    pub fn label(&self, row: usize) -> Option<String> {
+        if let Some(label) = self.labels.get(row).filter(|label| !label.is_empty()) {
+            return Some(label.clone());
+        }
+        match self.row(row)? {
+            [value] if value.is_finite() && value.fract() == 0.0 => Some(format!("{}", *value as i64)),
+            [value] if value.is_finite() => Some(value.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Loads every sample of a `NumpyArray` or `ParquetTable` locator.
+pub fn load_table(root: &Path, locator: &DataLocatorDef) -> Result<TensorTable, String> {
+    match locator {
+        DataLocatorDef::NumpyArray { path, key } => load_numpy(&root.join(path), key.as_deref()),
+        DataLocatorDef::ParquetTable { path, columns, id_column } => {
+            load_parquet(&root.join(path), columns, id_column.as_deref())
+        }
+        other => Err(format!("Not a tensor file locator: {:?}", other)),
+    }
+}
+
+/// Reads a `.npy` file, or the array `key` (default: the first one) of an `.npz` archive.
+pub fn load_numpy(path: &Path, key: Option<&str>) -> Result<TensorTable, String> {
+    let is_npz = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("npz"));
+    let bytes = if is_npz {
+        read_npz_entry(path, key)?
+    } else {
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+    };
+
+    let (shape, values) = read_npy(&bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let samples = shape.first().copied().unwrap_or(1);
+    let sample_shape = match &shape[..] {
+        [] | [_] => vec![1],
+        [_, rest @ ..] => rest.to_vec(),
+    };
+    Ok(TensorTable {
+        ids: (0..samples).map(|i| i.to_string()).collect(),
+        sample_shape,
+        values,
+        labels: Vec::new(),
+    })
+}
+
+fn read_npz_entry(path: &Path, key: Option<&str>) -> Result<Vec<u8>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    let name = match key {
+        Some(key) => names
+            .iter()
+            .find(|name| name.as_str() == key || name.strip_suffix(".npy") == Some(key))
+            .cloned()
+            .ok_or_else(|| format!("Array '{}' not found in {}. Arrays: {:?}", key, path.display(), names))?,
+        None => names
+            .iter()
+            .find(|name| name.ends_with(".npy"))
+            .cloned()
+            .ok_or_else(|| format!("No .npy array in {}", path.display()))?,
+    };
+
+    let mut entry = archive
+        .by_name(&name)
+        .map_err(|e| format!("Failed to open '{}' in {}: {}", name, path.display(), e))?;
+    let mut bytes = Vec::new();
+    entry
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read '{}' in {}: {}", name, path.display(), e))?;
+    Ok(bytes)
+}
+
+/// Parses NPY bytes (format versions 1-3) into the array shape and its values.
+pub fn read_npy(bytes: &[u8]) -> Result<(Vec<usize>, Vec<f32>), String> {
+    if bytes.len() < 10 || !bytes.starts_with(NPY_MAGIC) {
+        return Err("Not a NumPy .npy file".to_string());
+    }
+    let (header_len, header_start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize, 12),
+        version => return Err(format!("Unsupported .npy format version {}", version)),
+    };
+    let data_start = header_start + header_len;
+    let header = bytes
+        .get(header_start..data_start)
+        .and_then(|h| std::str::from_utf8(h).ok())
+        .ok_or("Truncated .npy header")?;
+
+    let descr = header_value(header, "descr")
+        .and_then(|v| v.split(['\'', '"']).nth(1))
+        .ok_or_else(|| format!("No dtype in .npy header: {}", header))?;
+    if header_value(header, "fortran_order").is_some_and(|v| v.starts_with("True")) {
+        return Err("Fortran-ordered arrays are not supported; save with np.ascontiguousarray".to_string());
+    }
+    let shape_text = header_value(header, "shape")
+        .and_then(|v| v.strip_prefix('('))
+        .and_then(|v| v.split(')').next())
+        .ok_or_else(|| format!("No shape in .npy header: {}", header))?;
+    let shape = shape_text
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse::<usize>().map_err(|_| format!("Invalid dimension '{}' in .npy shape", dim)))
+        .collect::<Result<Vec<usize>, String>>()?;
+
+    let count = shape.iter().product::<usize>();
+    let values = decode_values(descr, &bytes[data_start.min(bytes.len())..], count)?;
+    Ok((shape, values))
+}
+
+/// Text following `'<key>':` in an NPY header dict.
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header
+        .find(&format!("'{}'", key))
+        .or_else(|| header.find(&format!("\"{}\"", key)))?;
+    let rest = &header[start + key.len() + 2..];
+    Some(rest.trim_start().strip_prefix(':')?.trim_start())
+}
+
+fn decode_values(descr: &str, data: &[u8], count: usize) -> Result<Vec<f32>, String> {
+    let (order, kind) = descr.split_at(descr.len().min(1));
+    let big_endian = order == ">";
+
+    macro_rules! decode {
+        ($ty:ty) => {{
+            const SIZE: usize = std::mem::size_of::<$ty>();
+            if data.len() < count * SIZE {
+                return Err(format!("Array data is truncated: expected {} bytes, found {}", count * SIZE, data.len()));
+            }
+            data.chunks_exact(SIZE)
+                .take(count)
+                .map(|chunk| {
+                    let bytes: [u8; SIZE] = chunk.try_into().expect("chunk of SIZE bytes");
+                    let value = if big_endian { <$ty>::from_be_bytes(bytes) } else { <$ty>::from_le_bytes(bytes) };
+                    value as f32
+                })
+                .collect()
+        }};
+    }
+
+    Ok(match kind {
+        "f4" => decode!(f32),
+        "f8" => decode!(f64),
+        "i1" => decode!(i8),
+        "i2" => decode!(i16),
+        "i4" => decode!(i32),
+        "i8" => decode!(i64),
+        "u1" | "b1" => decode!(u8),
+        "u2" => decode!(u16),
+        "u4" => decode!(u32),
+        "u8" => decode!(u64),
+        _ => return Err(format!("Unsupported .npy dtype '{}'", descr)),
+    })
+}
+
+/// Reads the rows of a Parquet file. `columns` selects and orders the value columns
+/// (empty: every numeric column but the id column).
+pub fn load_parquet(path: &Path, columns: &[String], id_column: Option<&str>) -> Result<TensorTable, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let reader =
+        SerializedFileReader::new(file).map_err(|e| format!("Failed to read Parquet {}: {}", path.display(), e))?;
+    let rows = reader
+        .get_row_iter(None)
+        .map_err(|e| format!("Failed to read Parquet rows of {}: {}", path.display(), e))?;
+
+    let mut ids = Vec::new();
+    let mut values = Vec::new();
+    let mut labels = Vec::new();
+    let mut selected: Vec<String> = columns.to_vec();
+    let mut sample_len = None;
+
+    for (row_idx, row) in rows.enumerate() {
+        let row = row.map_err(|e| format!("Failed to read Parquet row {}: {}", row_idx, e))?;
+        if selected.is_empty() && row_idx == 0 {
+            selected = row
+                .get_column_iter()
+                .filter(|(name, field)| Some(name.as_str()) != id_column && is_numeric(field))
+                .map(|(name, _)| name.clone())
+                .collect();
+        }
+
+        let mut row_values = Vec::new();
+        let mut row_labels = Vec::new();
+        for column in &selected {
+            let field = row
+                .get_column_iter()
+                .find(|(name, _)| name == &column)
+                .map(|(_, field)| field)
+                .ok_or_else(|| format!("Column '{}' not found in {}", column, path.display()))?;
+            match field {
+                Field::Str(text) => {
+                    row_values.push(f32::NAN);
+                    row_labels.push(text.clone());
+                }
+                field if push_numeric(field, &mut row_values) => {}
+                other => return Err(format!("Column '{}' has non-numeric value {:?}", column, other)),
+            }
+        }
+
+        let expected = *sample_len.get_or_insert(row_values.len());
+        if row_values.len() != expected {
+            return Err(format!(
+                "Parquet row {} has {} values but earlier rows have {}",
+                row_idx,
+                row_values.len(),
+                expected
+            ));
+        }
+
+        let id = match id_column {
+            Some(column) => row
+                .get_column_iter()
+                .find(|(name, _)| name.as_str() == column)
+                .map(|(_, field)| match field {
+                    Field::Str(text) => text.clone(),
+                    other => other.to_string(),
+                })
+                .ok_or_else(|| format!("Id column '{}' not found in {}", column, path.display()))?,
+            None => row_idx.to_string(),
+        };
+        ids.push(id);
+        values.extend(row_values);
+        labels.push(row_labels.join(","));
+    }
+
+    if selected.is_empty() {
+        return Err(format!("No numeric columns to read in {}", path.display()));
+    }
+    if labels.iter().all(String::is_empty) {
+        labels.clear();
+    }
+    Ok(TensorTable {
+        ids,
+        sample_shape: vec![sample_len.unwrap_or(selected.len())],
+        values,
+        labels,
+    })
+}
+
+fn is_numeric(field: &Field) -> bool {
+    push_numeric(field, &mut Vec::new())
+}
+
+/// Appends the numeric value(s) of a cell; false for non-numeric cells.
+fn push_numeric(field: &Field, out: &mut Vec<f32>) -> bool {
+    let value = match field {
+        Field::Null => f32::NAN,
+        Field::Bool(v) => f32::from(u8::from(*v)),
+        Field::Byte(v) => *v as f32,
+        Field::Short(v) => *v as f32,
+        Field::Int(v) => *v as f32,
+        Field::Long(v) => *v as f32,
+        Field::UByte(v) => *v as f32,
+        Field::UShort(v) => *v as f32,
+        Field::UInt(v) => *v as f32,
+        Field::ULong(v) => *v as f32,
+        Field::Float16(v) => v.to_f32(),
+        Field::Float(v) => *v,
+        Field::Double(v) => *v as f32,
+        Field::ListInternal(list) => return list.elements().iter().all(|element| push_numeric(element, out)),
+        _ => return false,
+    };
+    out.push(value);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    fn npy_bytes(descr: &str, shape: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+        let mut bytes = NPY_MAGIC.to_vec();
+        bytes.extend([1, 0]);
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn npy_arrays_decode_any_byte_order() {
+        let data: Vec<u8> = [1.5f32, -2.0, 3.0, 4.25, 5.0, 6.0].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let (shape, values) = read_npy(&npy_bytes("<f4", "(3, 2)", &data)).expect("f4 array");
+        assert_eq!(shape, vec![3, 2]);
+        assert_eq!(values, vec![1.5, -2.0, 3.0, 4.25, 5.0, 6.0]);
+
+        let data: Vec<u8> = [7i16, -8].iter().flat_map(|v| v.to_be_bytes()).collect();
+        let (shape, values) = read_npy(&npy_bytes(">i2", "(2,)", &data)).expect("big-endian i2 array");
+        assert_eq!(shape, vec![2]);
+        assert_eq!(values, vec![7.0, -8.0]);
+
+        assert!(read_npy(&npy_bytes("<f4", "(3, 2)", &data)).is_err(), "truncated data is rejected");
+    }
+
+    #[test]
+    fn npz_arrays_are_indexed_by_their_first_axis() {
+        let dir = std::env::temp_dir().join(format!("test_npz-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("data.npz");
+
+        let data: Vec<u8> = (0..12).flat_map(|v| (v as f64).to_le_bytes()).collect();
+        let mut writer = zip::ZipWriter::new(File::create(&path).expect("create npz"));
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        writer.start_file("labels.npy", options).expect("start labels");
+        writer.write_all(&npy_bytes("|u1", "(2,)", &[0, 1])).expect("write labels");
+        writer.start_file("x.npy", options).expect("start x");
+        writer.write_all(&npy_bytes("<f8", "(2, 3, 2)", &data)).expect("write x");
+        writer.finish().expect("finish npz");
+
+        let table = load_numpy(&path, Some("x")).expect("x loads");
+        assert_eq!(table.ids, vec!["0", "1"]);
+        assert_eq!(table.sample_shape, vec![3, 2]);
+        assert_eq!(table.row(1).expect("second sample"), &[6.0, 7.0, 8.0, 9.0, 10.0, 11.0]);
+
+        let labels = load_numpy(&path, None).expect("first array loads");
+        assert_eq!(labels.label(1).as_deref(), Some("1"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parquet_columns_are_selected_in_order() {
+        use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int32Type};
+        use parquet::file::properties::WriterProperties;
+        use parquet::file::writer::SerializedFileWriter;
+
+        let dir = std::env::temp_dir().join(format!("test_parquet-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("table.parquet");
+
+        let schema = Arc::new(
+            parquet::schema::parser::parse_message_type(
+                "message sample { REQUIRED BYTE_ARRAY id (UTF8); REQUIRED DOUBLE a; REQUIRED INT32 b; REQUIRED BYTE_ARRAY kind (UTF8); }",
+            )
+            .expect("schema"),
+        );
+        let mut writer = SerializedFileWriter::new(
+            File::create(&path).expect("create parquet"),
+            schema,
+            Arc::new(WriterProperties::builder().build()),
+        )
+        .expect("writer");
+        let mut group = writer.next_row_group().expect("row group");
+        let ids: Vec<ByteArray> = ["s1", "s2"].iter().map(|s| ByteArray::from(*s)).collect();
+        let kinds: Vec<ByteArray> = ["cat", "dog"].iter().map(|s| ByteArray::from(*s)).collect();
+        let mut column = group.next_column().expect("id column").expect("id");
+        column.typed::<ByteArrayType>().write_batch(&ids, None, None).expect("write id");
+        column.close().expect("close id");
+        let mut column = group.next_column().expect("a column").expect("a");
+        column.typed::<DoubleType>().write_batch(&[0.5, 1.5], None, None).expect("write a");
+        column.close().expect("close a");
+        let mut column = group.next_column().expect("b column").expect("b");
+        column.typed::<Int32Type>().write_batch(&[10, 20], None, None).expect("write b");
+        column.close().expect("close b");
+        let mut column = group.next_column().expect("kind column").expect("kind");
+        column.typed::<ByteArrayType>().write_batch(&kinds, None, None).expect("write kind");
+        column.close().expect("close kind");
+        group.close().expect("close group");
+        writer.close().expect("close writer");
+
+        let table = load_parquet(&path, &["b".to_string(), "a".to_string()], Some("id")).expect("table loads");
+        assert_eq!(table.ids, vec!["s1", "s2"]);
+        assert_eq!(table.sample_shape, vec![2]);
+        assert_eq!(table.row(1).expect("second row"), &[20.0, 1.5]);
+
+        // Every numeric column by default; string columns serve as labels
+        let table = load_parquet(&path, &[], Some("id")).expect("numeric columns load");
+        assert_eq!(table.row(0).expect("first row"), &[0.5, 10.0]);
+        let labels = load_parquet(&path, &["kind".to_string()], None).expect("labels load");
+        assert_eq!(labels.label(1).as_deref(), Some("dog"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
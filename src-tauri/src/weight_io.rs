@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::dtos::TrainingProfiler;
 use crate::entities::GraphModel;
+use crate::image_cache::VisionNormalizer;
+use crate::tabular::TabularStats;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ExportObjectives {
@@ -29,6 +31,30 @@ pub struct WeightExportMetadata {
     pub train_duration_ms: Option<u64>,
     pub device_profile_id: Option<String>,
     pub lineage: Vec<String>,
+    /// Input preprocessing of the training run, in model input order.
+    #[serde(default)]
+    pub preprocessing: Vec<InputPreprocessing>,
+}
+
+/// Image resizing and normalization of an input stream. Inputs are NCHW tensors of
+/// pixels scaled to 0..1 and then normalized by `normalizer`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisionPreprocessing {
+    pub width: u32,
+    pub height: u32,
+    pub grayscale: bool,
+    pub normalizer: VisionNormalizer,
+}
+
+/// How one model input was preprocessed while training, so inference can reproduce it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputPreprocessing {
+    pub stream_id: String,
+    pub data_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vision: Option<VisionPreprocessing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tabular: Option<TabularStats>,
 }
 
 fn weight_file_path(genome_id: &str, dir: &Path) -> PathBuf {
@@ -39,6 +65,27 @@ fn metadata_file_path(dir: &Path) -> PathBuf {
     dir.join("metadata.json")
 }
 
+fn preprocessing_file_path(genome_id: &str, dir: &Path) -> PathBuf {
+    dir.join(format!("{}.preprocessing.json", genome_id))
+}
+
+/// Stores the input preprocessing next to the cached weights of a genome.
+pub fn save_preprocessing(genome_id: &str, preprocessing: &[InputPreprocessing], dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(preprocessing).map_err(|e| e.to_string())?;
+    fs::write(preprocessing_file_path(genome_id, dir), json).map_err(|e| e.to_string())
+}
+
+/// Preprocessing stored with the weights; empty for weights cached before it was recorded.
+pub fn load_preprocessing(genome_id: &str, dir: &Path) -> Result<Vec<InputPreprocessing>, String> {
+    let path = preprocessing_file_path(genome_id, dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| format!("Invalid preprocessing file {}: {}", path.display(), e))
+}
+
 pub fn save_weights<B: Backend>(
     genome_id: &str,
     model: Option<&GraphModel<B>>,
@@ -89,6 +136,7 @@ pub fn export_with_metadata(
     output_dir: &Path,
     objectives: &ExportObjectives,
     profiler: Option<&TrainingProfiler>,
+    preprocessing: Vec<InputPreprocessing>,
 ) -> Result<(PathBuf, PathBuf), String> {
     fs::create_dir_all(output_dir).map_err(|e| e.to_string())?;
 
@@ -113,6 +161,7 @@ pub fn export_with_metadata(
             .or_else(|| profiler.map(|p| p.total_train_duration_ms)),
         device_profile_id: objectives.device_profile_id.clone(),
         lineage: objectives.lineage.clone(),
+        preprocessing,
     };
 
     let metadata_path = metadata_file_path(output_dir);
@@ -122,6 +171,66 @@ pub fn export_with_metadata(
     Ok((weights_path, metadata_path))
 }
 
+/// Progress of a partially trained genome, stored next to its `.mpk` epoch checkpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpochCheckpointMeta {
+    pub genome_id: String,
+    pub completed_epochs: usize,
+    pub total_epochs: usize,
+    pub saved_at_ms: u64,
+}
+
+fn epoch_meta_path(genome_id: &str, dir: &Path) -> PathBuf {
+    dir.join(format!("{}.epoch.json", genome_id))
+}
+
+/// Overwrites the epoch checkpoint of `genome_id`. Weights are written before the
+/// metadata, so a checkpoint with metadata always has complete weights.
+pub fn save_epoch_checkpoint<B: Backend>(
+    model: &GraphModel<B>,
+    meta: &EpochCheckpointMeta,
+    dir: &Path,
+) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let recorder = NamedMpkFileRecorder::<FullPrecisionSettings>::new();
+    model
+        .clone()
+        .save_file(dir.join(&meta.genome_id), &recorder)
+        .map_err(|e| e.to_string())?;
+
+    let meta_json = serde_json::to_string(meta).map_err(|e| e.to_string())?;
+    fs::write(epoch_meta_path(&meta.genome_id, dir), meta_json).map_err(|e| e.to_string())
+}
+
+/// Loads the epoch checkpoint of `genome_id` into `model`, if one exists.
+pub fn load_epoch_checkpoint<B: Backend>(
+    genome_id: &str,
+    model: GraphModel<B>,
+    dir: &Path,
+    device: &B::Device,
+) -> Result<Option<(GraphModel<B>, EpochCheckpointMeta)>, String> {
+    let meta_path = epoch_meta_path(genome_id, dir);
+    let weights_path = weight_file_path(genome_id, dir);
+    if !meta_path.exists() || !weights_path.exists() {
+        return Ok(None);
+    }
+
+    let meta: EpochCheckpointMeta = serde_json::from_str(&fs::read_to_string(&meta_path).map_err(|e| e.to_string())?)
+        .map_err(|e| format!("Invalid epoch checkpoint metadata {}: {}", meta_path.display(), e))?;
+    let recorder = NamedMpkFileRecorder::<FullPrecisionSettings>::new();
+    let model = model
+        .load_file(dir.join(genome_id), &recorder, device)
+        .map_err(|e| format!("Failed to load epoch checkpoint for genome '{}': {}", genome_id, e))?;
+
+    Ok(Some((model, meta)))
+}
+
+pub fn clear_epoch_checkpoint(genome_id: &str, dir: &Path) {
+    let _ = fs::remove_file(epoch_meta_path(genome_id, dir));
+    let _ = fs::remove_file(weight_file_path(genome_id, dir));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,8 +294,28 @@ mod tests {
             lineage: vec!["parent-1".to_string(), "parent-2".to_string()],
         };
 
+        let preprocessing = vec![InputPreprocessing {
+            stream_id: "image".to_string(),
+            data_type: "Image".to_string(),
+            vision: Some(VisionPreprocessing {
+                width: 32,
+                height: 32,
+                grayscale: false,
+                normalizer: VisionNormalizer {
+                    mode: "imagenet".to_string(),
+                    scale: 1.0,
+                    mean: vec![0.485, 0.456, 0.406],
+                    std: vec![0.229, 0.224, 0.225],
+                },
+            }),
+            tabular: None,
+        }];
+        save_preprocessing("genome-b", &preprocessing, &dir).expect("save preprocessing");
+        let stored = load_preprocessing("genome-b", &dir).expect("load preprocessing");
+        assert_eq!(stored, preprocessing);
+
         let (_weights_path, metadata_path) =
-            export_with_metadata("genome-b", &dir, &objectives, None).expect("export with metadata");
+            export_with_metadata("genome-b", &dir, &objectives, None, stored).expect("export with metadata");
 
         let json = fs::read_to_string(metadata_path).expect("read metadata");
         let val: serde_json::Value = serde_json::from_str(&json).expect("parse metadata json");
@@ -199,6 +328,48 @@ mod tests {
         assert_eq!(val["train_duration_ms"], 5200);
         assert_eq!(val["device_profile_id"], "edge-device-a");
         assert!(val["lineage"].is_array());
+        assert_eq!(val["preprocessing"][0]["vision"]["normalizer"]["mode"], "imagenet");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn epoch_checkpoint_roundtrip_and_clear() {
+        let dir = temp_dir("epoch-checkpoint");
+
+        type TestBackend = crate::backend::CpuBackend;
+        let device = crate::backend::create_cpu_device();
+        let genome = [
+            r#"{"node":"Input","params":{"output_shape":[4]}}"#,
+            r#"{"node":"Dense","params":{"units":3,"activation":"relu","use_bias":true}}"#,
+            r#"{"node":"Output","params":{"input_shape":[3]}}"#,
+            "CONNECTIONS",
+            "0 1",
+            "1 2",
+        ]
+        .join("\n");
+        let model = GraphModel::<TestBackend>::build(&genome, &device, None, None);
+        let fresh = GraphModel::<TestBackend>::build(&genome, &device, None, None);
+        assert!(load_epoch_checkpoint("genome-c", fresh, &dir, &device).unwrap().is_none());
+
+        let meta = EpochCheckpointMeta {
+            genome_id: "genome-c".to_string(),
+            completed_epochs: 2,
+            total_epochs: 5,
+            saved_at_ms: 1,
+        };
+        save_epoch_checkpoint(&model, &meta, &dir).expect("save epoch checkpoint");
+
+        let fresh = GraphModel::<TestBackend>::build(&genome, &device, None, None);
+        let (_, loaded) = load_epoch_checkpoint("genome-c", fresh, &dir, &device)
+            .expect("load epoch checkpoint")
+            .expect("checkpoint exists");
+        assert_eq!(loaded.completed_epochs, 2);
+        assert_eq!(loaded.total_epochs, 5);
+
+        clear_epoch_checkpoint("genome-c", &dir);
+        let fresh = GraphModel::<TestBackend>::build(&genome, &device, None, None);
+        assert!(load_epoch_checkpoint("genome-c", fresh, &dir, &device).unwrap().is_none());
 
         let _ = fs::remove_dir_all(dir);
     }
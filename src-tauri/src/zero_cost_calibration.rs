@@ -0,0 +1,307 @@
+/// Zero-Cost Proxy Calibration
+///
+/// A fixed log scale says little about how a proxy behaves on a particular dataset. The
+/// calibration store keeps, per dataset profile, the proxy scores of every fully trained
+/// genome next to the accuracy it reached. Once it holds `MIN_RECORDS` of them:
+/// - each proxy's raw score is mapped to its percentile among the recorded genomes (a
+///   rank-based normaliser)
+/// - the calibrated score averages those percentiles, weighted by each proxy's Spearman
+///   correlation with accuracy (proxies that do not correlate positively get no weight)
+///
+/// Skip/partial/full decisions for a population use percentile thresholds within that
+/// population: `fast_pass_threshold` 0.6 sends the top 40% to full training. Stores
+/// live in `zero_cost_calibration/<profile>.json`.
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::zero_cost_proxies::{ProxyScore, ZeroCostConfig, ZeroCostMetrics, decide, vote_by_rank};
+
+const CALIBRATION_DIR: &str = "zero_cost_calibration";
+/// Records needed before calibrated scores replace the fixed normalization.
+pub const MIN_RECORDS: usize = 5;
+/// Oldest records are dropped beyond this many.
+const MAX_RECORDS: usize = 500;
+
+/// Proxy scores of one trained genome and the accuracy it reached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationRecord {
+    pub proxies: Vec<ProxyScore>,
+    pub accuracy: f32,
+    #[serde(default)]
+    pub recorded_at_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationStore {
+    pub profile_id: String,
+    pub records: Vec<CalibrationRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyCorrelation {
+    pub name: String,
+    /// Spearman rank correlation with final accuracy, when it is defined.
+    pub spearman: Option<f32>,
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationReport {
+    pub profile_id: String,
+    pub records: usize,
+    /// Whether population scores use the calibrated normaliser.
+    pub calibrated: bool,
+    pub proxies: Vec<ProxyCorrelation>,
+    /// Proxy with the highest correlation.
+    pub best_proxy: Option<String>,
+}
+
+impl CalibrationStore {
+    pub fn path(app_data: &Path, profile_id: &str) -> PathBuf {
+        app_data.join(CALIBRATION_DIR).join(format!("{}.json", profile_id))
+    }
+
+    /// The stored calibration of a profile; empty when there is none yet.
+    pub fn load(app_data: &Path, profile_id: &str) -> Self {
+        std::fs::read_to_string(Self::path(app_data, profile_id))
+            .ok()
+            .and_then(|raw| serde_json::from_str::<CalibrationStore>(&raw).ok())
+            .unwrap_or_else(|| CalibrationStore {
+                profile_id: profile_id.to_string(),
+                records: Vec::new(),
+            })
+    }
+
+    pub fn persist(&self, app_data: &Path) -> Result<(), String> {
+        let path = Self::path(app_data, &self.profile_id);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn record(&mut self, proxies: Vec<ProxyScore>, accuracy: f32, recorded_at_ms: u64) {
+        if proxies.is_empty() || !accuracy.is_finite() {
+            return;
+        }
+        self.records.push(CalibrationRecord {
+            proxies,
+            accuracy,
+            recorded_at_ms,
+        });
+        if self.records.len() > MAX_RECORDS {
+            let excess = self.records.len() - MAX_RECORDS;
+            self.records.drain(..excess);
+        }
+    }
+
+    pub fn is_calibrated(&self) -> bool {
+        self.records.len() >= MIN_RECORDS
+    }
+
+    /// Recorded raw scores of one proxy, with the accuracy of the same genomes.
+    fn history(&self, proxy: &str) -> (Vec<f32>, Vec<f32>) {
+        self.records
+            .iter()
+            .filter_map(|r| r.proxies.iter().find(|p| p.name == proxy).map(|p| (p.raw, r.accuracy)))
+            .unzip()
+    }
+
+    fn proxy_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for proxy in self.records.iter().flat_map(|r| &r.proxies) {
+            if !names.contains(&proxy.name) {
+                names.push(proxy.name.clone());
+            }
+        }
+        names
+    }
+
+    pub fn report(&self) -> CalibrationReport {
+        let proxies: Vec<ProxyCorrelation> = self
+            .proxy_names()
+            .into_iter()
+            .map(|name| {
+                let (scores, accuracies) = self.history(&name);
+                ProxyCorrelation {
+                    spearman: spearman(&scores, &accuracies),
+                    samples: scores.len(),
+                    name,
+                }
+            })
+            .collect();
+        let best_proxy = proxies
+            .iter()
+            .filter_map(|p| p.spearman.map(|rho| (p, rho)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(p, _)| p.name.clone());
+        CalibrationReport {
+            profile_id: self.profile_id.clone(),
+            records: self.records.len(),
+            calibrated: self.is_calibrated(),
+            proxies,
+            best_proxy,
+        }
+    }
+
+    /// Correlation-weighted mean of the genome's per-proxy percentiles among recorded
+    /// genomes. `None` before calibration or when no proxy correlates positively.
+    pub fn calibrated_score(&self, proxies: &[ProxyScore]) -> Option<f32> {
+        if !self.is_calibrated() {
+            return None;
+        }
+        let mut weighted = 0.0f32;
+        let mut total_weight = 0.0f32;
+        for proxy in proxies {
+            let (scores, accuracies) = self.history(&proxy.name);
+            let weight = spearman(&scores, &accuracies).unwrap_or(0.0).max(0.0);
+            if weight > 0.0 {
+                weighted += weight * percentile(&scores, proxy.raw);
+                total_weight += weight;
+            }
+        }
+        (total_weight > 0.0).then(|| weighted / total_weight)
+    }
+}
+
+/// Fraction of `history` below `value`, counting ties as half.
+pub fn percentile(history: &[f32], value: f32) -> f32 {
+    if history.is_empty() {
+        return 0.5;
+    }
+    let below = history.iter().filter(|h| **h < value).count() as f32;
+    let equal = history.iter().filter(|h| **h == value).count() as f32;
+    (below + 0.5 * equal) / history.len() as f32
+}
+
+/// Ranks starting at 0, ties sharing their mean rank.
+fn ranks(values: &[f32]) -> Vec<f32> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start;
+        while end + 1 < order.len() && values[order[end + 1]] == values[order[start]] {
+            end += 1;
+        }
+        for &i in &order[start..=end] {
+            ranks[i] = (start + end) as f32 / 2.0;
+        }
+        start = end + 1;
+    }
+    ranks
+}
+
+/// Spearman rank correlation: Pearson correlation of the ranks. Undefined for fewer
+/// than three pairs or when either side is constant.
+pub fn spearman(xs: &[f32], ys: &[f32]) -> Option<f32> {
+    if xs.len() != ys.len() || xs.len() < 3 {
+        return None;
+    }
+    let (rx, ry) = (ranks(xs), ranks(ys));
+    let mean = (xs.len() - 1) as f32 / 2.0;
+    let (mut cov, mut var_x, mut var_y) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in rx.iter().zip(&ry) {
+        cov += (x - mean) * (y - mean);
+        var_x += (x - mean).powi(2);
+        var_y += (y - mean).powi(2);
+    }
+    (var_x > 0.0 && var_y > 0.0).then(|| cov / (var_x * var_y).sqrt())
+}
+
+/// Scores and decides a scored population. With a calibrated store the scores come from
+/// the calibrated normaliser, otherwise from rank voting (when enabled) or as they are.
+/// Decisions then follow each genome's percentile within the population.
+pub fn calibrate_population(
+    store: Option<&CalibrationStore>,
+    metrics: &mut [ZeroCostMetrics],
+    config: &ZeroCostConfig,
+) {
+    let calibrated: Vec<Option<f32>> = metrics
+        .iter()
+        .map(|m| store.and_then(|store| store.calibrated_score(&m.proxies)))
+        .collect();
+    if calibrated.iter().any(Option::is_some) {
+        for (m, score) in metrics.iter_mut().zip(calibrated) {
+            if let Some(score) = score {
+                m.normalized_score = score;
+            }
+        }
+    } else {
+        vote_by_rank(metrics, config);
+    }
+    decide_by_percentile(metrics, config);
+}
+
+/// Decides every genome from the percentile of its score within the population.
+pub fn decide_by_percentile(metrics: &mut [ZeroCostMetrics], config: &ZeroCostConfig) {
+    if metrics.len() < 2 {
+        return;
+    }
+    let scores: Vec<f32> = metrics.iter().map(|m| m.normalized_score).collect();
+    let last = (scores.len() - 1) as f32;
+    for (m, rank) in metrics.iter_mut().zip(ranks(&scores)) {
+        m.strategy_decision = decide(rank / last, config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxies(synflow: f32, params: f32) -> Vec<ProxyScore> {
+        vec![
+            ProxyScore { name: "synflow".to_string(), raw: synflow, normalized: 0.5 },
+            ProxyScore { name: "params".to_string(), raw: params, normalized: 0.5 },
+        ]
+    }
+
+    fn store() -> CalibrationStore {
+        let mut store = CalibrationStore { profile_id: "p".to_string(), records: Vec::new() };
+        // SynFlow tracks accuracy, the parameter count runs against it
+        for i in 0..6 {
+            store.record(proxies(i as f32, 10.0 - i as f32), 0.5 + i as f32 * 0.05, 0);
+        }
+        store
+    }
+
+    #[test]
+    fn spearman_follows_rank_order() {
+        assert_eq!(spearman(&[1.0, 2.0, 3.0, 4.0], &[10.0, 20.0, 25.0, 100.0]), Some(1.0));
+        assert_eq!(spearman(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]), Some(-1.0));
+        assert_eq!(spearman(&[1.0, 1.0, 1.0], &[3.0, 2.0, 1.0]), None);
+    }
+
+    #[test]
+    fn report_ranks_proxies_by_correlation() {
+        let report = store().report();
+        assert!(report.calibrated);
+        assert_eq!(report.best_proxy.as_deref(), Some("synflow"));
+        let params = report.proxies.iter().find(|p| p.name == "params").unwrap();
+        assert_eq!(params.spearman, Some(-1.0));
+    }
+
+    #[test]
+    fn calibrated_scores_use_correlating_proxies_and_population_percentiles() {
+        let store = store();
+        // Only SynFlow has positive weight: 4.5 sits above five of six recorded genomes
+        let score = store.calibrated_score(&proxies(4.5, 0.0)).unwrap();
+        assert!((score - 5.0 / 6.0).abs() < 1e-6);
+
+        let config = ZeroCostConfig { enabled: true, fast_pass_threshold: 0.6, ..ZeroCostConfig::default() };
+        let mut population: Vec<ZeroCostMetrics> = [0.5, 2.5, 4.5, 6.0]
+            .iter()
+            .map(|s| ZeroCostMetrics::from_proxies(proxies(*s, 1.0), &config))
+            .collect();
+        calibrate_population(Some(&store), &mut population, &config);
+        let decisions: Vec<&str> = population.iter().map(|m| m.strategy_decision.as_str()).collect();
+        assert_eq!(decisions, ["skip", "partial_train", "full_train", "full_train"]);
+    }
+}
@@ -1,75 +1,409 @@
+use burn::module::{ModuleMapper, ModuleVisitor, Param, ParamId};
 use burn::prelude::*;
 use burn::tensor::backend::AutodiffBackend;
 use crate::entities::{GraphModel, DynamicBatch, DynamicTensor};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-/// helper to calculate |grad * weight|.sum() for a parameter
-fn calc_synflow_param<B: AutodiffBackend, const D: usize>(
-    param: &burn::module::Param<Tensor<B, D>>,
-    grads: &B::Gradients,
-) -> f32 {
-    if let Some(grad) = param.grad(grads) {
-        let weight_inner: Tensor<B::InnerBackend, D> = param.val().inner();
-        let product: Tensor<B::InnerBackend, D> = grad.mul(weight_inner);
-        let sum_tensor: Tensor<B::InnerBackend, 1> = product.abs().sum().reshape([1]);
-        sum_tensor.into_data().to_vec::<f32>().unwrap()[0]
-    } else {
-        0.0
+/// Sums |θ ⊙ ∂R/∂θ| over every float parameter a module holds, whatever the layer type.
+struct SynflowVisitor<'a, B: AutodiffBackend> {
+    grads: &'a B::Gradients,
+    score: f64,
+}
+
+impl<B: AutodiffBackend> ModuleVisitor<B> for SynflowVisitor<'_, B> {
+    fn visit_float<const D: usize>(&mut self, param: &Param<Tensor<B, D>>) {
+        if let Some(grad) = param.grad(self.grads) {
+            self.score += grad.mul(param.val().inner()).abs().sum().into_scalar().elem::<f64>();
+        }
     }
 }
 
-/// Compute the SynFlow metric for a model: Σ |∇w ⊙ w|
-/// This measures the "signal flow" through the network without training.
-pub fn compute_synflow<B: AutodiffBackend>(
-    model: &GraphModel<B>,
-    batch: &DynamicBatch<B>,
-) -> f32 {
-    // 1. Forward pass
-    let outputs = model.forward(&batch.inputs);
-    
-    // 2. Compute the "SynFlow loss" - simply the sum of all components in the output
-    let mut total_output = Tensor::<B, 1>::zeros([1], &B::Device::default());
+/// Replaces every parameter by its absolute value, as SynFlow's linearization does.
+struct AbsParams;
+
+impl<B: AutodiffBackend> ModuleMapper<B> for AbsParams {
+    fn map_float<const D: usize>(&mut self, param: Param<Tensor<B, D>>) -> Param<Tensor<B, D>> {
+        param.map(|tensor| Tensor::from_inner(tensor.inner().abs()).require_grad())
+    }
+}
+
+/// Sum of every component of every output, as one scalar to back-propagate.
+fn summed_outputs<B: Backend>(outputs: Vec<DynamicTensor<B>>) -> Tensor<B, 1> {
+    let mut total = Tensor::<B, 1>::zeros([1], &B::Device::default());
     for out in outputs {
         let sum = match out {
             DynamicTensor::Dim2(t) => t.sum(),
             DynamicTensor::Dim3(t) => t.sum(),
             DynamicTensor::Dim4(t) => t.sum(),
         };
-        total_output = total_output + sum.reshape([1]);
+        total = total + sum.reshape([1]);
     }
-    
-    // 3. Backward pass to compute gradients
-    let grads = total_output.backward();
-    
-    // 4. Calculate Σ |∇w ⊙ w| for all parameters
-    let mut synflow_score = 0.0;
-    
-    // Manual iteration over layer types in GraphModel to avoid polymorphic type inference issues
-    for layer in &model.conv1ds {
-        synflow_score += calc_synflow_param(&layer.weight, &grads);
-        if let Some(bias) = &layer.bias { synflow_score += calc_synflow_param(bias, &grads); }
+    total
+}
+
+/// Compute the SynFlow metric for a model: Σ |∇w ⊙ w|
+/// This measures the "signal flow" through the network without training.
+///
+/// Follows the paper (Tanaka et al., 2020): the weights are replaced by |w|, a single
+/// all-ones sample goes through the network in inference mode (BatchNorm on running
+/// statistics, no Dropout) and R is the sum of all outputs. The data never enters the
+/// score, and every parameter the model holds — convolutions, dense, LSTM/GRU gates,
+/// attention projections and norms — contributes. Inputs of more than three
+/// dimensions cannot be scored.
+pub fn compute_synflow<B: AutodiffBackend>(model: &GraphModel<B>) -> Result<f32, String> {
+    let device = B::Device::default();
+    let linearized = model.clone().map(&mut AbsParams);
+
+    let inputs: Vec<DynamicTensor<B>> = model
+        .input_shapes
+        .iter()
+        .map(|shape| match shape.as_slice() {
+            [f] => Ok(DynamicTensor::Dim2(Tensor::ones([1, *f], &device))),
+            [s, f] => Ok(DynamicTensor::Dim3(Tensor::ones([1, *s, *f], &device))),
+            [c, h, w] => Ok(DynamicTensor::Dim4(Tensor::ones([1, *c, *h, *w], &device))),
+            other => Err(format!("Unsupported input shape {:?} for SynFlow", other)),
+        })
+        .collect::<Result<_, String>>()?;
+
+    let grads = summed_outputs(linearized.forward_eval_mode(&inputs)).backward();
+    let mut visitor = SynflowVisitor::<B> { grads: &grads, score: 0.0 };
+    linearized.visit(&mut visitor);
+
+    // Deep networks can exceed f32; keep the largest finite value instead of inf
+    Ok(visitor.score.min(f32::MAX as f64) as f32)
+}
+
+// ---------------------------------------------------------------------------
+// Additional zero-cost proxies
+// ---------------------------------------------------------------------------
+
+/// Random samples per scoring batch. NASWOT and Jacob-cov compare samples with each
+/// other, so they need more than one.
+pub const PROXY_BATCH_SIZE: usize = 8;
+
+/// Proxies that take part in voting. The parameter and FLOP counts are baselines that
+/// are reported next to them but never vote.
+pub const VOTING_PROXIES: [&str; 7] = ["synflow", "naswot", "grad_norm", "snip", "grasp", "fisher", "jacob_cov"];
+
+/// One proxy's result: `raw` is the metric itself, `normalized` maps it into 0.0-1.0
+/// (higher means a more promising architecture).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProxyScore {
+    pub name: String,
+    pub raw: f32,
+    pub normalized: f32,
+}
+
+impl ProxyScore {
+    fn new(name: &str, raw: f32, normalized: f32) -> Self {
+        ProxyScore {
+            name: name.to_string(),
+            raw: if raw.is_finite() { raw } else { 0.0 },
+            normalized: if normalized.is_finite() { normalized.clamp(0.0, 1.0) } else { 0.0 },
+        }
     }
-    for layer in &model.conv2ds {
-        synflow_score += calc_synflow_param(&layer.weight, &grads);
-        if let Some(bias) = &layer.bias { synflow_score += calc_synflow_param(bias, &grads); }
+
+    /// Non-negative proxy on a log scale; `reference` is the raw value that maps to
+    /// the same point SynFlow's 1.0 does.
+    fn log_scaled(name: &str, raw: f32, reference: f32) -> Self {
+        ProxyScore::new(name, raw, (raw.max(0.0) / reference).ln_1p() / 6.0)
     }
-    for layer in &model.denses {
-        synflow_score += calc_synflow_param(&layer.weight, &grads);
-        if let Some(bias) = &layer.bias { synflow_score += calc_synflow_param(bias, &grads); }
+}
+
+/// Computes every proxy for `model` on `batch` (inputs and targets of a few samples).
+pub fn compute_proxies<B: AutodiffBackend>(
+    model: &GraphModel<B>,
+    batch: &DynamicBatch<B>,
+) -> Result<Vec<ProxyScore>, String> {
+    let synflow = compute_synflow(model)?;
+    let mut scores = vec![ProxyScore::new("synflow", synflow, normalize_synflow(synflow))];
+
+    let (_, activations) = model.forward_with_activations(&batch.inputs);
+    scores.push(naswot(&activation_codes(&activations)));
+
+    // Loss gradients at θ and after a small step along them (for GraSP)
+    let predictions = model.forward(&batch.inputs);
+    let grads = model.compute_loss(&predictions, &batch.targets).backward();
+    let first = collect_gradients(model, &grads);
+
+    let norm = grad_norm(&first);
+    let step = GRASP_STEP / (norm + 1e-12);
+    let stepped = model.clone().map(&mut GradientStep::<B> { grads: &grads, step });
+    let stepped_predictions = stepped.forward(&batch.inputs);
+    let stepped_grads = stepped.compute_loss(&stepped_predictions, &batch.targets).backward();
+    let second = collect_gradients(&stepped, &stepped_grads);
+
+    scores.push(ProxyScore::log_scaled("grad_norm", norm, 0.1));
+    scores.push(ProxyScore::log_scaled("snip", snip(&first), 1.0));
+    let grasp = grasp(&first, &second, step);
+    scores.push(ProxyScore::new("grasp", grasp, 0.5 + 0.5 * grasp.tanh()));
+    scores.push(ProxyScore::log_scaled("fisher", fisher(&first), 1e-3));
+
+    scores.push(jacob_cov(&input_jacobian(model, &batch.inputs)));
+
+    scores.push(ProxyScore::log_scaled("params", model.num_params() as f32, 1e3));
+    scores.push(ProxyScore::log_scaled("flops", crate::cost_analysis::analyze(model).flops() as f32, 1e4));
+    Ok(scores)
+}
+
+/// Length of the finite-difference step GraSP takes along the gradient.
+const GRASP_STEP: f32 = 1e-3;
+
+/// Flattened weights and loss gradients of one parameter tensor.
+struct ParamGradient {
+    id: ParamId,
+    weights: Vec<f32>,
+    grads: Vec<f32>,
+}
+
+/// Visits every float parameter of a module and records its gradient.
+struct GradientCollector<'a, B: AutodiffBackend> {
+    grads: &'a B::Gradients,
+    params: Vec<ParamGradient>,
+}
+
+impl<B: AutodiffBackend> ModuleVisitor<B> for GradientCollector<'_, B> {
+    fn visit_float<const D: usize>(&mut self, param: &Param<Tensor<B, D>>) {
+        if let Some(grad) = param.grad(self.grads) {
+            self.params.push(ParamGradient {
+                id: param.id,
+                weights: param.val().inner().into_data().to_vec::<f32>().unwrap_or_default(),
+                grads: grad.into_data().to_vec::<f32>().unwrap_or_default(),
+            });
+        }
+    }
+}
+
+fn collect_gradients<B: AutodiffBackend>(model: &GraphModel<B>, grads: &B::Gradients) -> Vec<ParamGradient> {
+    let mut collector = GradientCollector::<B> { grads, params: Vec::new() };
+    model.visit(&mut collector);
+    collector.params
+}
+
+/// Moves every parameter by `step` times its gradient, keeping the parameter ids.
+struct GradientStep<'a, B: AutodiffBackend> {
+    grads: &'a B::Gradients,
+    step: f32,
+}
+
+impl<B: AutodiffBackend> ModuleMapper<B> for GradientStep<'_, B> {
+    fn map_float<const D: usize>(&mut self, param: Param<Tensor<B, D>>) -> Param<Tensor<B, D>> {
+        let Some(grad) = param.grad(self.grads) else {
+            return param;
+        };
+        let step = self.step;
+        param.map(|tensor| Tensor::from_inner(tensor.inner() + grad.mul_scalar(step)).require_grad())
+    }
+}
+
+/// GradNorm: ‖∇L‖₂ over all parameters.
+fn grad_norm(params: &[ParamGradient]) -> f32 {
+    params.iter().flat_map(|p| &p.grads).map(|g| g * g).sum::<f32>().sqrt()
+}
+
+/// SNIP: Σ |∇L ⊙ w|, the loss sensitivity to removing each weight.
+fn snip(params: &[ParamGradient]) -> f32 {
+    params.iter().flat_map(|p| p.grads.iter().zip(&p.weights)).map(|(g, w)| (g * w).abs()).sum()
+}
+
+/// Fisher: ½ Σ (∇L ⊙ w)², the diagonal empirical Fisher information taken at the
+/// parameters rather than at the activations.
+fn fisher(params: &[ParamGradient]) -> f32 {
+    0.5 * params.iter().flat_map(|p| p.grads.iter().zip(&p.weights)).map(|(g, w)| (g * w).powi(2)).sum::<f32>()
+}
+
+/// GraSP: -Σ w ⊙ (H ∇L). The Hessian-gradient product is the finite difference of the
+/// gradients at θ (`first`) and at θ + step·∇L (`second`).
+fn grasp(first: &[ParamGradient], second: &[ParamGradient], step: f32) -> f32 {
+    let stepped: HashMap<ParamId, &Vec<f32>> = second.iter().map(|p| (p.id, &p.grads)).collect();
+    let mut score = 0.0;
+    for param in first {
+        let Some(grads) = stepped.get(&param.id) else { continue };
+        for ((w, g), g_stepped) in param.weights.iter().zip(&param.grads).zip(grads.iter()) {
+            score -= w * (g_stepped - g) / step;
+        }
+    }
+    score
+}
+
+/// Splits a batch-major tensor into one flat row per sample.
+fn sample_rows<B2: Backend, const D: usize>(tensor: Tensor<B2, D>) -> Vec<Vec<f32>> {
+    let samples = tensor.dims()[0].max(1);
+    let values = tensor.into_data().to_vec::<f32>().unwrap_or_default();
+    let width = values.len() / samples;
+    if width == 0 {
+        return vec![Vec::new(); samples];
     }
-    for _layer in &model.lstms {
-        // LSTM has many internal parameters
-        // We'll just skip complex params for now or handle them if they are public
-        // Actually, Burn's LSTM params might not be easily accessible individually without more logic
+    values.chunks(width).map(|row| row.to_vec()).collect()
+}
+
+fn dynamic_rows<B2: Backend>(tensor: DynamicTensor<B2>) -> Vec<Vec<f32>> {
+    match tensor {
+        DynamicTensor::Dim2(t) => sample_rows(t),
+        DynamicTensor::Dim3(t) => sample_rows(t),
+        DynamicTensor::Dim4(t) => sample_rows(t),
     }
-    for _layer in &model.grus {
-        // Similar for GRU
+}
+
+/// Binary activation codes: per sample, which units of every layer output are positive
+/// (the units a ReLU would let through).
+fn activation_codes<B: Backend>(activations: &[DynamicTensor<B>]) -> Vec<Vec<bool>> {
+    let mut codes: Vec<Vec<bool>> = Vec::new();
+    for activation in activations {
+        for (sample, row) in dynamic_rows(activation.clone()).into_iter().enumerate() {
+            if codes.len() <= sample {
+                codes.resize(sample + 1, Vec::new());
+            }
+            codes[sample].extend(row.iter().map(|v| *v > 0.0));
+        }
     }
-    for _layer in &model.mha_layers {
-        // Similar for MHA
+    codes
+}
+
+/// NASWOT: log|K| where K[i][j] counts the units on which samples i and j share the same
+/// code. Networks that tell the samples apart have a better conditioned K. Normalized
+/// against Hadamard's bound n·ln(units).
+fn naswot(codes: &[Vec<bool>]) -> ProxyScore {
+    let units = codes.iter().map(|c| c.len()).min().unwrap_or(0);
+    if codes.len() < 2 || units == 0 {
+        return ProxyScore::new("naswot", 0.0, 0.0);
     }
-    
-    synflow_score
+    let kernel: Vec<Vec<f64>> = codes
+        .iter()
+        .map(|a| codes.iter().map(|b| a.iter().zip(b).filter(|(x, y)| x == y).count() as f64).collect())
+        .collect();
+    let log_det = log_determinant(kernel);
+    let bound = codes.len() as f64 * (units as f64).ln();
+    let normalized = if bound > 0.0 { log_det / bound } else { 0.0 };
+    ProxyScore::new("naswot", log_det as f32, normalized as f32)
+}
+
+/// ln|det A| by Gaussian elimination with partial pivoting. Pivots are floored at 1e-12
+/// so a singular matrix gives a very low, finite value.
+fn log_determinant(mut a: Vec<Vec<f64>>) -> f64 {
+    let n = a.len();
+    let mut log_det = 0.0;
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap_or(col);
+        a.swap(col, pivot);
+        let p = a[col][col];
+        log_det += p.abs().max(1e-12).ln();
+        if p.abs() < 1e-12 {
+            continue;
+        }
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for row in lower.iter_mut() {
+            let factor = row[col] / p;
+            for (x, y) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= factor * y;
+            }
+        }
+    }
+    log_det
+}
+
+/// Gradient of the summed outputs with respect to every input, one row per sample.
+fn input_jacobian<B: AutodiffBackend>(model: &GraphModel<B>, inputs: &[DynamicTensor<B>]) -> Vec<Vec<f32>> {
+    let inputs: Vec<DynamicTensor<B>> = inputs
+        .iter()
+        .map(|input| match input.clone() {
+            DynamicTensor::Dim2(t) => DynamicTensor::Dim2(t.require_grad()),
+            DynamicTensor::Dim3(t) => DynamicTensor::Dim3(t.require_grad()),
+            DynamicTensor::Dim4(t) => DynamicTensor::Dim4(t.require_grad()),
+        })
+        .collect();
+
+    let grads = summed_outputs(model.forward(&inputs)).backward();
+
+    let mut rows: Vec<Vec<f32>> = Vec::new();
+    for input in &inputs {
+        let input_rows = match input {
+            DynamicTensor::Dim2(t) => t.grad(&grads).map(sample_rows),
+            DynamicTensor::Dim3(t) => t.grad(&grads).map(sample_rows),
+            DynamicTensor::Dim4(t) => t.grad(&grads).map(sample_rows),
+        };
+        for (sample, row) in input_rows.unwrap_or_default().into_iter().enumerate() {
+            if rows.len() <= sample {
+                rows.resize(sample + 1, Vec::new());
+            }
+            rows[sample].extend(row);
+        }
+    }
+    rows
+}
+
+/// Jacob-cov: -Σ (ln(λ + k) + 1/(λ + k)) over the eigenvalues λ of the correlation
+/// matrix of per-sample input Jacobians, k = 1e-5. Each term is at least 1, so the
+/// ideal (uncorrelated) score is -n and `n / -raw` normalizes it.
+fn jacob_cov(jacobian: &[Vec<f32>]) -> ProxyScore {
+    let n = jacobian.len();
+    if n < 2 || jacobian.iter().any(|row| row.is_empty()) {
+        return ProxyScore::new("jacob_cov", 0.0, 0.0);
+    }
+    let centered: Vec<Vec<f64>> = jacobian
+        .iter()
+        .map(|row| {
+            let mean = row.iter().map(|v| *v as f64).sum::<f64>() / row.len() as f64;
+            let centered: Vec<f64> = row.iter().map(|v| *v as f64 - mean).collect();
+            let norm = centered.iter().map(|v| v * v).sum::<f64>().sqrt().max(1e-12);
+            centered.into_iter().map(|v| v / norm).collect()
+        })
+        .collect();
+    let correlation: Vec<Vec<f64>> = centered
+        .iter()
+        .map(|a| centered.iter().map(|b| a.iter().zip(b).map(|(x, y)| x * y).sum()).collect())
+        .collect();
+
+    const K: f64 = 1e-5;
+    let raw: f64 = -symmetric_eigenvalues(correlation)
+        .into_iter()
+        .map(|l| {
+            let l = l.max(0.0) + K;
+            l.ln() + 1.0 / l
+        })
+        .sum::<f64>();
+    ProxyScore::new("jacob_cov", raw as f32, (n as f64 / -raw) as f32)
+}
+
+/// Eigenvalues of a small symmetric matrix by cyclic Jacobi rotations.
+fn symmetric_eigenvalues(mut a: Vec<Vec<f64>>) -> Vec<f64> {
+    let n = a.len();
+    for _sweep in 0..50 {
+        let off_diagonal: f64 = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off_diagonal < 1e-18 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (upper, lower) = a.split_at_mut(q);
+                for (apk, aqk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    let (x, y) = (*apk, *aqk);
+                    *apk = c * x - s * y;
+                    *aqk = s * x + c * y;
+                }
+            }
+        }
+    }
+    (0..n).map(|i| a[i][i]).collect()
 }
 
 /// Configuration for zero-cost proxy evaluation
@@ -83,7 +417,7 @@ pub struct ZeroCostConfig {
     pub fast_pass_threshold: f32,
     /// Epochs for architectures scoring between 0.3-0.7
     pub partial_training_epochs: u32,
-    /// Whether to compute multiple metrics or just SynFlow
+    /// Decide on the rank vote of all proxies instead of SynFlow alone
     pub use_voting: bool,
 }
 
@@ -108,6 +442,71 @@ pub struct ZeroCostMetrics {
     pub normalized_score: f32,
     /// Strategy decision
     pub strategy_decision: String, // serialized as snake_case string
+    /// Every proxy's raw and normalized score
+    #[serde(default)]
+    pub proxies: Vec<ProxyScore>,
+}
+
+/// Logarithmic normalization to handle wide range of scores (especially for sequences)
+/// ln(1 + 400) is approx 6.0, so this maps a wide range to [0, 1]
+fn normalize_synflow(synflow: f32) -> f32 {
+    (synflow.ln_1p() / 6.0).clamp(0.0, 1.0)
+}
+
+/// Skip/partial/full decision for a score in 0.0-1.0.
+pub fn decide(normalized: f32, config: &ZeroCostConfig) -> String {
+    if !config.enabled || normalized >= config.fast_pass_threshold {
+        "full_train".to_string()
+    } else if normalized >= config.fast_pass_threshold * 0.4 { // Lower bound for partial
+        "partial_train".to_string()
+    } else {
+        "skip".to_string()
+    }
+}
+
+/// Rank voting over a population: for every voting proxy the genomes are ranked by raw
+/// score (ties share the mean rank) and mapped to 0.0-1.0; a genome's score is its mean
+/// over the proxies it has. Decisions are recomputed from the voted scores.
+pub fn vote_by_rank(metrics: &mut [ZeroCostMetrics], config: &ZeroCostConfig) {
+    if !config.use_voting || metrics.len() < 2 {
+        return;
+    }
+    let mut rank_sums = vec![0.0f32; metrics.len()];
+    let mut votes = vec![0usize; metrics.len()];
+
+    for proxy in VOTING_PROXIES {
+        let mut entries: Vec<(usize, f32)> = metrics
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| m.proxies.iter().find(|p| p.name == proxy).map(|p| (i, p.raw)))
+            .collect();
+        if entries.len() < 2 {
+            continue;
+        }
+        entries.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let last = (entries.len() - 1) as f32;
+        let mut start = 0;
+        while start < entries.len() {
+            let mut end = start;
+            while end + 1 < entries.len() && entries[end + 1].1 == entries[start].1 {
+                end += 1;
+            }
+            let rank = (start + end) as f32 / 2.0 / last;
+            for &(i, _) in &entries[start..=end] {
+                rank_sums[i] += rank;
+                votes[i] += 1;
+            }
+            start = end + 1;
+        }
+    }
+
+    for (i, m) in metrics.iter_mut().enumerate() {
+        if votes[i] > 0 {
+            m.normalized_score = rank_sums[i] / votes[i] as f32;
+            m.strategy_decision = decide(m.normalized_score, config);
+        }
+    }
 }
 
 /// Decision on how to handle the architecture based on zero-cost score
@@ -125,24 +524,35 @@ pub enum StrategyDecision {
 impl ZeroCostMetrics {
     /// Create new metrics from SynFlow score
     pub fn from_synflow(synflow: f32, config: &ZeroCostConfig) -> Self {
-        // Logarithmic normalization to handle wide range of scores (especially for sequences)
-        // ln(1 + 400) is approx 6.0, so this maps a wide range to [0, 1]
-        let normalized = (synflow.ln_1p() / 6.0).min(1.0).max(0.0);
-        
-        let strategy_decision = if !config.enabled {
-            "full_train".to_string()
-        } else if normalized >= config.fast_pass_threshold {
-            "full_train".to_string()
-        } else if normalized >= config.fast_pass_threshold * 0.4 { // Lower bound for partial
-            "partial_train".to_string()
+        let normalized = normalize_synflow(synflow);
+        ZeroCostMetrics {
+            synflow,
+            normalized_score: normalized,
+            strategy_decision: decide(normalized, config),
+            proxies: Vec::new(),
+        }
+    }
+
+    /// Create metrics from a full proxy breakdown. With `use_voting` the score is the
+    /// mean normalized score of the voting proxies, until `vote_by_rank` ranks them
+    /// against the rest of the population.
+    pub fn from_proxies(proxies: Vec<ProxyScore>, config: &ZeroCostConfig) -> Self {
+        let synflow = proxies.iter().find(|p| p.name == "synflow").map(|p| p.raw).unwrap_or(0.0);
+        let normalized = if config.use_voting {
+            let voters: Vec<f32> = proxies
+                .iter()
+                .filter(|p| VOTING_PROXIES.contains(&p.name.as_str()))
+                .map(|p| p.normalized)
+                .collect();
+            voters.iter().sum::<f32>() / voters.len().max(1) as f32
         } else {
-            "skip".to_string()
+            normalize_synflow(synflow)
         };
-
         ZeroCostMetrics {
             synflow,
             normalized_score: normalized,
-            strategy_decision,
+            strategy_decision: decide(normalized, config),
+            proxies,
         }
     }
     
@@ -157,6 +567,46 @@ impl ZeroCostMetrics {
     }
 }
 
+impl From<crate::dtos::ZeroCostConfigDto> for ZeroCostConfig {
+    fn from(dto: crate::dtos::ZeroCostConfigDto) -> Self {
+        ZeroCostConfig {
+            enabled: dto.enabled,
+            strategy: dto.strategy,
+            fast_pass_threshold: dto.fast_pass_threshold,
+            partial_training_epochs: dto.partial_training_epochs,
+            use_voting: dto.use_voting,
+        }
+    }
+}
+
+/// Training budget `evaluate_population` gave a genome after scoring it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrainingBudget {
+    /// Epochs the genome is trained for (0 when skipped)
+    pub epochs: usize,
+    /// Epochs requested for the genome before its decision
+    pub requested_epochs: usize,
+    pub zero_cost: ZeroCostMetrics,
+}
+
+impl TrainingBudget {
+    /// Partial training is capped at the requested epochs, skipped genomes get none.
+    pub fn plan(zero_cost: ZeroCostMetrics, requested_epochs: usize, config: &ZeroCostConfig) -> Self {
+        let epochs = zero_cost
+            .recommended_epochs(requested_epochs as u32, config.partial_training_epochs)
+            .map_or(0, |epochs| (epochs as usize).min(requested_epochs));
+        TrainingBudget {
+            epochs,
+            requested_epochs,
+            zero_cost,
+        }
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        self.zero_cost.strategy_decision == "skip"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +663,7 @@ mod tests {
             synflow: 1.0,
             normalized_score: 0.1,
             strategy_decision: "skip".to_string(),
+            proxies: Vec::new(),
         };
         assert_eq!(skip.recommended_epochs(50, 20), None);
         
@@ -220,6 +671,7 @@ mod tests {
             synflow: 4.0,
             normalized_score: 0.4,
             strategy_decision: "partial_train".to_string(),
+            proxies: Vec::new(),
         };
         assert_eq!(partial.recommended_epochs(50, 20), Some(20));
         
@@ -227,7 +679,99 @@ mod tests {
             synflow: 8.0,
             normalized_score: 0.8,
             strategy_decision: "full_train".to_string(),
+            proxies: Vec::new(),
         };
         assert_eq!(full.recommended_epochs(50, 20), Some(50));
     }
+
+    #[test]
+    fn synflow_covers_recurrent_and_norm_layers() {
+        type TestBackend = crate::backend::CpuBackend;
+        let device = crate::backend::create_cpu_device();
+        let synflow = |layers: &[&str]| {
+            let mut lines = vec![r#"{"node":"Input","params":{"output_shape":[6,3]}}"#];
+            lines.extend_from_slice(layers);
+            lines.push(r#"{"node":"Dense","params":{"units":2,"activation":"linear","use_bias":true}}"#);
+            lines.push(r#"{"node":"Output","params":{"input_shape":[2]}}"#);
+            let connections: Vec<String> = (1..lines.len()).map(|i| format!("{} {}", i - 1, i)).collect();
+            let genome = format!("{}\nCONNECTIONS\n{}", lines.join("\n"), connections.join("\n"));
+            let model = GraphModel::<TestBackend>::build(&genome, &device, None, None);
+            compute_synflow(&model).expect("synflow")
+        };
+        let lstm = r#"{"node":"LSTM","params":{"hidden_units":4,"gate_activation":"sigmoid","cell_activation":"tanh","hidden_activation":"tanh","use_bias":true}}"#;
+        let flatten = r#"{"node":"Flatten","params":{}}"#;
+        let batch_norm = r#"{"node":"BatchNorm","params":{"epsilon":0.00001,"momentum":0.1}}"#;
+
+        let score = synflow(&[lstm, flatten, batch_norm]);
+        assert!(score.is_finite() && score > 0.0);
+        // All-ones input and inference-mode norms make the score deterministic
+        assert_eq!(score, synflow(&[lstm, flatten, batch_norm]));
+        // The LSTM gates and the BatchNorm scale and shift each take part in the score
+        assert_ne!(score, synflow(&[lstm, flatten]));
+        assert_ne!(score, synflow(&[flatten, batch_norm]));
+    }
+
+    fn metrics_with(raws: &[(&str, f32)], config: &ZeroCostConfig) -> ZeroCostMetrics {
+        let proxies = raws.iter().map(|(name, raw)| ProxyScore::new(name, *raw, 0.5)).collect();
+        ZeroCostMetrics::from_proxies(proxies, config)
+    }
+
+    #[test]
+    fn rank_voting_orders_the_population() {
+        let config = ZeroCostConfig { enabled: true, use_voting: true, ..ZeroCostConfig::default() };
+        let mut population = vec![
+            metrics_with(&[("synflow", 1.0), ("snip", 0.2), ("params", 9e6)], &config),
+            metrics_with(&[("synflow", 50.0), ("snip", 3.0), ("params", 1.0)], &config),
+            metrics_with(&[("synflow", 50.0), ("snip", 1.0), ("params", 5.0)], &config),
+        ];
+        vote_by_rank(&mut population, &config);
+
+        // Baselines never vote, SynFlow ties share their rank
+        assert_eq!(population[0].normalized_score, 0.0);
+        assert_eq!(population[0].strategy_decision, "skip");
+        assert!((population[1].normalized_score - 0.875).abs() < 1e-6);
+        assert!((population[2].normalized_score - 0.625).abs() < 1e-6);
+        assert_eq!(population[1].strategy_decision, "full_train");
+    }
+
+    #[test]
+    fn naswot_prefers_distinct_codes() {
+        let distinct = vec![
+            vec![true, false, false, true],
+            vec![false, true, true, false],
+            vec![true, true, false, false],
+        ];
+        let collapsed = vec![vec![true, true, true, false]; 3];
+        let good = naswot(&distinct);
+        let bad = naswot(&collapsed);
+        assert!(good.raw > bad.raw);
+        assert!(good.normalized > bad.normalized);
+    }
+
+    #[test]
+    fn jacob_cov_rewards_uncorrelated_jacobians() {
+        let uncorrelated = vec![vec![1.0, -1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0, -1.0]];
+        let correlated = vec![vec![1.0, 2.0, 3.0, 4.0], vec![2.0, 4.0, 6.0, 8.1]];
+        let good = jacob_cov(&uncorrelated);
+        assert!((good.normalized - 1.0).abs() < 1e-3, "{:?}", good);
+        assert!(jacob_cov(&correlated).normalized < 0.1);
+    }
+
+    #[test]
+    fn budgets_follow_decisions() {
+        let config = ZeroCostConfig { enabled: true, partial_training_epochs: 5, ..ZeroCostConfig::default() };
+        let decided = |decision: &str| ZeroCostMetrics {
+            synflow: 1.0,
+            normalized_score: 0.5,
+            strategy_decision: decision.to_string(),
+            proxies: Vec::new(),
+        };
+        let budget = |decision: &str, requested: usize| TrainingBudget::plan(decided(decision), requested, &config);
+        assert_eq!(budget("full_train", 10).epochs, 10);
+        assert_eq!(budget("partial_train", 10).epochs, 5);
+        assert_eq!(budget("partial_train", 3).epochs, 3);
+        let skipped = budget("skip", 10);
+        assert!(skipped.is_skipped());
+        assert_eq!((skipped.epochs, skipped.requested_epochs), (0, 10));
+    }
 }
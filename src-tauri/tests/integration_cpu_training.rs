@@ -0,0 +1,242 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Once};
+
+use neural_evo_tauri_app_lib::backend::{CpuBackend, ExecutionBackend, create_cpu_device};
+use neural_evo_tauri_app_lib::batch_stream::{BatchLoading, BatchPipelineConfig};
+use neural_evo_tauri_app_lib::evaluation::{
+    EvaluationContext, EvaluationSource, GenomeJob, TrainingParams, evaluate_genomes_parallel, find_dataset_profile,
+    prepare_dataset,
+};
+use neural_evo_tauri_app_lib::evolution::{EvolutionConfig, run_evolution};
+use neural_evo_tauri_app_lib::profiler::MemoryMode;
+use neural_evo_tauri_app_lib::progress::NoopProgressSink;
+
+static WEIGHTS_DIR: Once = Once::new();
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", prefix, uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).expect("create temp dir");
+
+    // evaluate_genome checkpoints weights into the genome library
+    WEIGHTS_DIR.call_once(|| {
+        let weights = std::env::temp_dir().join(format!("cpu-training-genomes-{}", uuid::Uuid::new_v4()));
+        unsafe {
+            std::env::set_var("NEURAL_EVO_GENOMES_DIR", weights.to_string_lossy().to_string());
+        }
+    });
+    dir
+}
+
+/// Two-feature, two-class CSV dataset: label is `a` when x1 > x2.
+fn write_dataset(root: &Path) -> String {
+    let mut csv = String::from("x1,x2,label\n");
+    for i in 0..60 {
+        let x1 = (i % 10) as f32 / 10.0;
+        let x2 = ((i * 7) % 10) as f32 / 10.0;
+        let label = if x1 > x2 { "a" } else { "b" };
+        csv.push_str(&format!("{},{},{}\n", x1, x2, label));
+    }
+    fs::write(root.join("data.csv"), csv).expect("write csv");
+
+    let locator = |features: &[&str], target: &str| {
+        serde_json::json!({
+            "type": "CsvDataset",
+            "csvPath": "data.csv",
+            "hasHeaders": true,
+            "sampleMode": "row",
+            "featureColumns": features,
+            "targetColumn": target,
+            "windowSize": null,
+            "windowStride": null,
+            "preprocessing": { "normalization": "none", "handleMissing": "skip" }
+        })
+    };
+    serde_json::json!({
+        "state": {
+            "profiles": [{
+                "id": "csv-toy",
+                "name": "CSV toy",
+                "sourcePath": root.to_string_lossy(),
+                "streams": [
+                    {
+                        "id": "features",
+                        "alias": "features",
+                        "role": "Input",
+                        "dataType": "Vector",
+                        "tensorShape": [2],
+                        "locator": locator(&["x1", "x2"], ""),
+                        "preprocessing": null
+                    },
+                    {
+                        "id": "label",
+                        "alias": "label",
+                        "role": "Target",
+                        "dataType": "Categorical",
+                        "tensorShape": [1],
+                        "locator": locator(&[], "label"),
+                        "preprocessing": null
+                    }
+                ]
+            }]
+        }
+    })
+    .to_string()
+}
+
+fn genome(hidden_units: Option<usize>) -> String {
+    let mut lines = vec![r#"{"node":"Input","params":{"output_shape":[2]}}"#.to_string()];
+    if let Some(units) = hidden_units {
+        lines.push(format!(
+            r#"{{"node":"Dense","params":{{"units":{},"activation":"relu","use_bias":true}}}}"#,
+            units
+        ));
+    }
+    lines.push(r#"{"node":"Dense","params":{"units":2,"activation":"softmax","use_bias":true}}"#.to_string());
+    lines.push(r#"{"node":"Output","params":{"input_shape":[2]}}"#.to_string());
+    lines.push("CONNECTIONS".to_string());
+    let node_count = lines.len() - 1;
+    for i in 0..node_count - 1 {
+        lines.push(format!("{} {}", i, i + 1));
+    }
+    lines.join("\n")
+}
+
+fn params(batch_size: usize) -> TrainingParams {
+    TrainingParams {
+        dataset_profile: "csv-toy".to_string(),
+        batch_size,
+        dataset_percent: 100,
+        train_split: 80,
+        val_split: 10,
+        test_split: 10,
+    }
+}
+
+#[test]
+fn integration_cpu_backend_trains_genomes_in_parallel() {
+    let root = temp_dir("cpu-training-dataset");
+    let profiles_json = write_dataset(&root);
+    let profile = find_dataset_profile(&profiles_json, "csv-toy").expect("profile exists");
+    let prepared = Arc::new(prepare_dataset(profile, None, 100, 80, 10, 10).expect("dataset prepares"));
+    assert!(prepared.is_classification);
+    assert_eq!(prepared.output_overrides, vec![vec![2]]);
+
+    let ctx = EvaluationContext::<CpuBackend>::prepare(
+        &prepared,
+        params(8),
+        &BatchPipelineConfig::default(),
+        create_cpu_device(),
+        MemoryMode::Hybrid,
+        0,
+        &NoopProgressSink,
+    )
+    .expect("batches assemble on the CPU backend");
+    assert!(!ctx.batches.train.is_empty());
+
+    let jobs: Vec<GenomeJob> = [None, Some(4), Some(8)]
+        .into_iter()
+        .enumerate()
+        .map(|(index, hidden)| GenomeJob {
+            index,
+            genome_id: format!("cpu-genome-{}", index),
+            genome_str: genome(hidden),
+            epochs: 2,
+            queue_wait_ms: 0,
+        })
+        .collect();
+
+    let reported = std::sync::Mutex::new(Vec::new());
+    let outcomes = evaluate_genomes_parallel(&ctx, jobs, &NoopProgressSink, &|job, _| {
+        reported.lock().unwrap().push(job.index);
+    });
+
+    assert_eq!(outcomes.iter().map(|(job, _)| job.index).collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(reported.lock().unwrap().len(), 3);
+    for (job, outcome) in outcomes {
+        let evaluation = outcome.expect("training does not panic");
+        assert_ne!(evaluation.source, EvaluationSource::CompileFailed, "genome {} compiles", job.index);
+        assert!(evaluation.result.loss.is_finite());
+        assert!((0.0..=100.0).contains(&evaluation.result.accuracy));
+    }
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn integration_cpu_backend_trains_from_streamed_batches() {
+    let root = temp_dir("cpu-streaming-dataset");
+    let profiles_json = write_dataset(&root);
+    let profile = find_dataset_profile(&profiles_json, "csv-toy").expect("profile exists");
+    let prepared = Arc::new(prepare_dataset(profile, None, 100, 80, 10, 10).expect("dataset prepares"));
+
+    let pipeline = BatchPipelineConfig {
+        loading: BatchLoading::Stream,
+        decode_workers: 2,
+        prefetch_batches: 1,
+        ..BatchPipelineConfig::default()
+    };
+    let ctx = EvaluationContext::<CpuBackend>::prepare(
+        &prepared,
+        params(4),
+        &pipeline,
+        create_cpu_device(),
+        MemoryMode::Hybrid,
+        0,
+        &NoopProgressSink,
+    )
+    .expect("streaming context prepares");
+    assert!(ctx.batches.train.is_streaming());
+    assert!(ctx.batches.train.len() > 1);
+
+    // Two passes over the same stream each see every chunk
+    let first_pass = ctx.batches.train.iter().count();
+    let second_pass = ctx.batches.train.iter().count();
+    assert_eq!(first_pass, ctx.batches.train.len());
+    assert_eq!(second_pass, first_pass);
+
+    let jobs = vec![GenomeJob {
+        index: 0,
+        genome_id: "cpu-streamed-genome".to_string(),
+        genome_str: genome(Some(6)),
+        epochs: 2,
+        queue_wait_ms: 0,
+    }];
+    let outcomes = evaluate_genomes_parallel(&ctx, jobs, &NoopProgressSink, &|_, _| {});
+    let evaluation = outcomes.into_iter().next().expect("one outcome").1.expect("streamed training completes");
+    assert_ne!(evaluation.source, EvaluationSource::CompileFailed);
+    assert!(evaluation.result.loss.is_finite());
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn integration_cpu_backend_runs_headless_evolution() {
+    let root = temp_dir("cpu-evolution-dataset");
+    let profiles_path = root.join("dataset_profiles.json");
+    fs::write(&profiles_path, write_dataset(&root)).expect("write profiles");
+    let seed_path = root.join("seed.txt");
+    fs::write(&seed_path, genome(Some(4))).expect("write seed genome");
+
+    let config = EvolutionConfig {
+        dataset_profile: "csv-toy".to_string(),
+        profiles_path: Some(profiles_path),
+        seed_genomes: vec![seed_path],
+        population_size: 3,
+        max_generations: 2,
+        batch_size: 8,
+        eval_epochs: 1,
+        execution_backend: ExecutionBackend::Cpu,
+        output_dir: root.join("output"),
+        seed: Some(42),
+        ..EvolutionConfig::default()
+    };
+
+    let outcome = run_evolution(&config, &NoopProgressSink, &|_| {}).expect("evolution completes");
+    assert_eq!(outcome.generations.len(), 2);
+    assert!(outcome.best.is_some());
+    assert!(root.join("output").join("best_genome.txt").exists());
+    assert!(root.join("output").join("summary.json").exists());
+
+    let _ = fs::remove_dir_all(root);
+}
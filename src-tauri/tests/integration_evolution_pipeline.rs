@@ -18,6 +18,11 @@ fn obj(id: &str, acc: f32, latency: f32, size: f32) -> GenomeObjectives {
         training_time_ms: 1200,
         is_dominated: false,
         domination_count: 0,
+        flops: None,
+        parameter_count: None,
+        peak_activation_bytes: None,
+        constraint_violation_score: None,
+        custom_metrics: Default::default(),
     }
 }
 
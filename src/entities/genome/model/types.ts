@@ -2,14 +2,23 @@ import type { BaseNode, Genome } from '../../canvas-genome';
 import type {
     GenomeGenealogy,
     GenomeObjectives,
+    LatencyBenchmark,
+    ModelCost,
     MutationType,
     TrainingProfiler,
 } from '../../../shared/lib';
 
+export interface ZeroCostProxyScore {
+    name: string;
+    raw: number;
+    normalized: number;
+}
+
 export interface ZeroCostMetricsView {
     synflow: number;
     normalized_score: number;
     strategy_decision: 'skip' | 'partial_train' | 'full_train';
+    proxies?: ZeroCostProxyScore[];
 }
 
 export interface BatchMetrics {
@@ -38,6 +47,8 @@ export interface PopulatedGenome {
     resources?: { totalFlash: number; totalRam: number; totalMacs: number; totalNodes: number };
     zeroCostMetric?: ZeroCostMetricsView;
     profiler?: TrainingProfiler;
+    cost?: ModelCost;
+    latency?: LatencyBenchmark;
     objectives?: GenomeObjectives;
     is_dominated?: boolean;
     generation?: number;
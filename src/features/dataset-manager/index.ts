@@ -10,10 +10,17 @@ export type {
     CsvPreprocessingConfig,
     DataStream,
     DataType,
+    DatasetHealthReport,
     DatasetProfile,
+    ClassSplitCounts,
     DatasetSourceType,
+    DatasetSplitSettings,
     DatasetValidationReport,
+    HistogramBin,
+    SamplingStrategy,
     ScanResult,
+    SplitStrategy,
     TabularSettings,
+    TargetTaskType,
     VisionSettings,
 } from './model/store';
@@ -12,7 +12,7 @@ export type DataType =
 export interface VisionSettings {
     resize: [number, number];
     grayscale: boolean;
-    normalization: '0-1' | 'imagenet' | 'none';
+    normalization: '0-1' | 'imagenet' | 'none' | 'dataset';
 }
 
 export interface TabularSettings {
@@ -37,9 +37,13 @@ export type DataLocatorDef =
     | { type: 'FolderMapping' } // Uses parent folder name
     | { type: 'CompanionFile'; pathTemplate: string; parser: 'YOLO' | 'Text' | 'COCO_Subset' }
     | { type: 'MasterIndex'; indexPath: string; keyField: string; valueField: string; hasHeaders: boolean }
-    | { type: 'CsvDataset'; csvPath: string; hasHeaders: boolean; sampleMode: 'row' | 'temporal_window'; featureColumns: string[]; targetColumn: string; windowSize?: number; windowStride?: number; preprocessing: CsvPreprocessingConfig }
+    | { type: 'CsvDataset'; csvPath: string; hasHeaders: boolean; sampleMode: 'row' | 'temporal_window'; featureColumns: string[]; targetColumn: string; windowSize?: number; windowStride?: number; horizon?: number; preprocessing: CsvPreprocessingConfig }
+    | { type: 'NumpyArray'; path: string; key?: string } // .npy, or one array of an .npz; first axis = samples
+    | { type: 'ParquetTable'; path: string; columns: string[]; idColumn?: string } // one row per sample
     | { type: 'None' }; // Fallback
 
+export type TargetTaskType = 'classification' | 'regression' | 'multi_label' | 'multi_output';
+
 export interface DataStream {
     id: string;
     alias: string;        // E.g., "Image Input", "Price Output"
@@ -47,6 +51,7 @@ export interface DataStream {
     dataType: DataType;
     tensorShape: number[]; // Explicit shape expected by the model
     numClasses?: number;   // Number of output classes (for Target/classification streams)
+    taskType?: TargetTaskType; // Target streams; inferred from dataType when unset
 
     // How the data is found and aligned by SampleID
     locator: DataLocatorDef;
@@ -90,6 +95,72 @@ export interface DatasetValidationReport {
     output_shape?: number[];                               // Output unit count (from num_classes)
     total_valid_samples: number;                           // After alignment
     can_start_evolution: boolean;                          // Ready for evolution pipeline
+    class_counts?: ClassSplitCounts[];                     // First classification target only
+}
+
+export interface ClassSplitCounts {
+    className: string;
+    train: number;
+    val: number;
+    test: number;
+    trainSampled: number;                                  // Training samples after the sampling strategy
+}
+
+export type SplitStrategy = 'stratified' | 'random' | 'chronological';
+
+// Data health report (`dataset_report` command), cached by the backend per profile
+export interface HistogramBin {
+    label: string;
+    count: number;
+}
+
+export interface DatasetHealthReport {
+    profileId: string;
+    generatedAtMs: number;
+    totalSamples: number;
+    trainSamples: number;
+    valSamples: number;
+    testSamples: number;
+    classCounts: ClassSplitCounts[];
+    images: {
+        streamId: string;
+        decoded: number;
+        minWidth: number;
+        maxWidth: number;
+        minHeight: number;
+        maxHeight: number;
+        sizeHistogram: HistogramBin[];   // by the longer side, before resize
+        aspectHistogram: HistogramBin[]; // width / height
+    }[];
+    unreadableFiles: number;
+    unreadable: { streamId: string; sampleId: string; path: string; error: string }[];
+    duplicateSamples: number;
+    duplicates: { sampleIds: string[]; splits: string[]; labels: string[] }[];
+    columns: {
+        file: string;
+        column: string;
+        rows: number;
+        missingPct: number;
+        numeric: boolean;
+        min?: number;
+        max?: number;
+        mean?: number;
+        distinct: number;
+        constant: boolean;
+    }[];
+    labelHints: { kind: 'conflicting_duplicates' | 'similar_labels' | 'rare_class'; message: string; sampleIds: string[] }[];
+}
+
+// How training batches are drawn from the train split (classification targets only)
+export type SamplingStrategy = 'uniform' | 'oversample' | 'undersample' | 'weighted';
+
+export interface DatasetSplitSettings {
+    train: number;
+    val: number;
+    test: number;
+    strategy?: SplitStrategy;     // default: stratified
+    gap?: number;                 // chronological: samples left out between segments (default: window overlap)
+    walkForwardFolds?: number;    // chronological: 0/1 = off
 }
 
 export interface DatasetProfile {
@@ -102,7 +173,8 @@ export interface DatasetProfile {
     streams: DataStream[];
 
     totalSamples?: number;
-    split: { train: number, val: number, test: number };
+    split: DatasetSplitSettings;
+    sampling?: SamplingStrategy;  // default: uniform
 
     // Global Augmentations (applied mostly to vision streams if present)
     augmentation: AugmentationSettings;
@@ -12,6 +12,8 @@ export type SecondaryObjective = 'latency' | 'model_size' | 'train_time' | 'trai
 export type ObjectiveWeightKey = 'accuracy' | 'latency' | 'model_size' | 'train_time';
 export type MemoryMode = 'estimate' | 'runtime' | 'hybrid';
 export type ExecutionMode = 'sequential' | 'parallel-cpu' | 'parallel-safe-limited';
+export type ExecutionBackend = 'gpu' | 'cpu';
+export type BatchLoading = 'auto' | 'preload' | 'stream';
 
 export interface GenerationProfilingStats {
     generation: number;
@@ -105,10 +107,14 @@ export interface EvolutionSettingsState {
     setFastPassThreshold: (val: number) => void;
     partialTrainingEpochs: number;
     setPartialTrainingEpochs: (val: number) => void;
+    zeroCostVoting: boolean; // rank-vote all proxies instead of SynFlow alone
+    setZeroCostVoting: (val: boolean) => void;
 
     // Performance & Profiling
     profilingEnabled: boolean;
     setProfilingEnabled: (val: boolean) => void;
+    benchmarkLatency: boolean; // warmup + timed forward passes per genome
+    setBenchmarkLatency: (val: boolean) => void;
     memorySafetyMarginMb: number;
     setMemorySafetyMarginMb: (val: number) => void;
     estimatorSafetyFactor: number;
@@ -117,6 +123,10 @@ export interface EvolutionSettingsState {
     setMemoryMode: (val: MemoryMode) => void;
     executionMode: ExecutionMode;
     setExecutionMode: (val: ExecutionMode) => void;
+    executionBackend: ExecutionBackend;
+    setExecutionBackend: (val: ExecutionBackend) => void;
+    batchLoading: BatchLoading;
+    setBatchLoading: (val: BatchLoading) => void;
     maxParallelJobs: number;
     setMaxParallelJobs: (val: number) => void;
 
@@ -150,6 +160,10 @@ export interface EvolutionSettingsState {
     setSelectedDeviceProfile: (val?: DeviceProfile) => void;
         showOnlyFeasible: boolean;
         setShowOnlyFeasible: (val: boolean) => void;
+    deviceFilterAction: 'off' | 'penalize' | 'reject'; // static device check before training
+    setDeviceFilterAction: (val: 'off' | 'penalize' | 'reject') => void;
+    repairForDevice: boolean; // shrink filters/units until the genome fits
+    setRepairForDevice: (val: boolean) => void;
 
     // Stopping Criteria
     stoppingPolicy: StoppingPolicy;
@@ -266,9 +280,13 @@ export const useEvolutionSettingsStore = create<EvolutionSettingsState>((set) =>
     setFastPassThreshold: (val) => set({ fastPassThreshold: Math.max(0, Math.min(1, val)) }),
     partialTrainingEpochs: 20,
     setPartialTrainingEpochs: (val) => set({ partialTrainingEpochs: Math.max(1, Math.min(100, val)) }),
+    zeroCostVoting: false,
+    setZeroCostVoting: (val) => set({ zeroCostVoting: val }),
 
     profilingEnabled: false,
     setProfilingEnabled: (val) => set({ profilingEnabled: val }),
+    benchmarkLatency: false,
+    setBenchmarkLatency: (val) => set({ benchmarkLatency: val }),
     memorySafetyMarginMb: 128,
     setMemorySafetyMarginMb: (val) => set({ memorySafetyMarginMb: Math.max(0, val) }),
     estimatorSafetyFactor: 1.1,
@@ -277,6 +295,10 @@ export const useEvolutionSettingsStore = create<EvolutionSettingsState>((set) =>
     setMemoryMode: (val) => set({ memoryMode: val }),
     executionMode: 'sequential',
     setExecutionMode: (val) => set({ executionMode: val }),
+    executionBackend: 'gpu',
+    setExecutionBackend: (val) => set({ executionBackend: val }),
+    batchLoading: 'auto',
+    setBatchLoading: (val) => set({ batchLoading: val }),
     maxParallelJobs: 1,
     setMaxParallelJobs: (val) => set({ maxParallelJobs: Math.max(1, Math.min(64, Math.floor(val || 1))) }),
 
@@ -334,6 +356,10 @@ export const useEvolutionSettingsStore = create<EvolutionSettingsState>((set) =>
     setSelectedDeviceProfile: (val) => set({ selectedDeviceProfile: val }),
         showOnlyFeasible: false,
         setShowOnlyFeasible: (val) => set({ showOnlyFeasible: val }),
+    deviceFilterAction: 'off',
+    setDeviceFilterAction: (val) => set({ deviceFilterAction: val }),
+    repairForDevice: false,
+    setRepairForDevice: (val) => set({ repairForDevice: val }),
 
     stoppingPolicy: {
         criteria: [{ type: 'ManualStop' }],
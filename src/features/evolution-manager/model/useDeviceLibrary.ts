@@ -130,6 +130,35 @@ export function useDeviceLibrary() {
         }
     }, []);
 
+    const replaceTemplate = useCallback(async (command: string, args: Record<string, unknown>) => {
+        setIsMutating(true);
+        setError(null);
+        try {
+            const updated = await invoke<DeviceTemplateDto>(command, args);
+            setTemplates((prev) => sortByUpdatedAtDesc(
+                prev.map((template) => (template.id === updated.id ? updated : template)),
+            ));
+            return updated;
+        } catch (err) {
+            const message = toErrorMessage(err);
+            setError(message);
+            throw new Error(message);
+        } finally {
+            setIsMutating(false);
+        }
+    }, []);
+
+    const attachLatencyTable = useCallback(
+        (id: string, path: string) => replaceTemplate('attach_device_latency_lut', { id, path }),
+        [replaceTemplate],
+    );
+
+    const importLatencyBenchmarks = useCallback(
+        (id: string, path: string, mode: DeviceLibraryImportMode) =>
+            replaceTemplate('import_device_latency_benchmarks', { id, path, mode }),
+        [replaceTemplate],
+    );
+
     const importLibrary = useCallback(async (path: string, mode: DeviceLibraryImportMode) => {
         setIsMutating(true);
         setError(null);
@@ -162,5 +191,7 @@ export function useDeviceLibrary() {
         duplicateTemplate,
         exportLibrary,
         importLibrary,
+        attachLatencyTable,
+        importLatencyBenchmarks,
     };
 }
@@ -286,6 +286,30 @@ export function DeviceProfileSelector({
                         aria-label="Show only feasible"
                     />
                 </label>
+                <label className={styles.row}>
+                    <span className={styles.label}>Before training</span>
+                    <select
+                        className={styles.select}
+                        value={settings.deviceFilterAction}
+                        onChange={(event) => settings.setDeviceFilterAction(event.target.value as 'off' | 'penalize' | 'reject')}
+                        disabled={disabled}
+                        aria-label="Before training"
+                    >
+                        <option value="off">Train every genome</option>
+                        <option value="penalize">Penalize infeasible</option>
+                        <option value="reject">Reject infeasible</option>
+                    </select>
+                </label>
+                <label className={styles.row}>
+                    <span className={styles.label}>Repair to fit</span>
+                    <input
+                        type="checkbox"
+                        checked={settings.repairForDevice}
+                        onChange={(event) => settings.setRepairForDevice(event.target.checked)}
+                        disabled={disabled || settings.deviceFilterAction === 'off'}
+                        aria-label="Repair to fit"
+                    />
+                </label>
             </div>
 
             <div className={styles.templates}>
@@ -34,6 +34,16 @@ export function AdvancedPerformanceSection({ disabled = false }: AdvancedPerform
                         <span>Profiling enabled</span>
                     </label>
 
+                    <label className={styles.checkboxLabel}>
+                        <input
+                            type="checkbox"
+                            checked={settings.benchmarkLatency}
+                            onChange={(event) => settings.setBenchmarkLatency(event.target.checked)}
+                            disabled={disabled}
+                        />
+                        <span>Benchmark inference latency</span>
+                    </label>
+
                     <div className={styles.row}>
                         <span className={styles.label}>Safety margin (MB)</span>
                         <input
@@ -88,6 +98,33 @@ export function AdvancedPerformanceSection({ disabled = false }: AdvancedPerform
                         </select>
                     </div>
 
+                    <div className={styles.row}>
+                        <span className={styles.label}>Training backend</span>
+                        <select
+                            className={styles.select}
+                            value={settings.executionBackend}
+                            onChange={(event) => settings.setExecutionBackend(event.target.value as 'gpu' | 'cpu')}
+                            disabled={disabled}
+                        >
+                            <option value="gpu">gpu</option>
+                            <option value="cpu">cpu (ndarray, parallel)</option>
+                        </select>
+                    </div>
+
+                    <div className={styles.row}>
+                        <span className={styles.label}>Batch loading</span>
+                        <select
+                            className={styles.select}
+                            value={settings.batchLoading}
+                            onChange={(event) => settings.setBatchLoading(event.target.value as 'auto' | 'preload' | 'stream')}
+                            disabled={disabled}
+                        >
+                            <option value="auto">auto</option>
+                            <option value="preload">preload</option>
+                            <option value="stream">stream</option>
+                        </select>
+                    </div>
+
                     <div className={styles.row}>
                         <span className={styles.label}>Max parallel jobs</span>
                         <input
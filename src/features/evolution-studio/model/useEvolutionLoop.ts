@@ -4,12 +4,16 @@ import { Genome, serializeGenome, deserializeGenome, generateRandomArchitecture,
 import type { BatchMetrics, GenerationSnapshot, PopulatedGenome } from '../../../entities/genome';
 import type {
     AdaptiveMutationSettings,
+    DeviceCheck,
     GenomeGenealogy,
+    LatencyBenchmark,
+    ModelCost,
     MutationType,
+    ParetoArchiveGeneration,
     TrainingProfiler,
     UseEvolutionLoopParams,
 } from '../../../shared/lib';
-import { computeZeroCostScore, ZeroCostMetrics } from './useZeroCostEvaluation';
+import { recordZeroCostOutcomes, ZeroCostMetrics } from './useZeroCostEvaluation';
 
 const DEFAULT_MAX_SINGLE_TENSOR_MB = 192;
 const DEFAULT_MAX_WORKING_SET_MB = 2048;
@@ -104,6 +108,20 @@ export interface EvaluationResult {
     loss: number;
     accuracy: number;
     profiler?: TrainingProfiler;
+    /** Zero-cost training budget, when proxies were enabled */
+    budget?: TrainingBudget;
+    /** Static MACs, parameters and activation memory at batch 1 */
+    cost?: ModelCost;
+    /** Warmed-up forward pass timings, when latency benchmarking was enabled */
+    latency?: LatencyBenchmark;
+    /** Static device check made before training, when a device filter was set */
+    device_check?: DeviceCheck;
+}
+
+export interface TrainingBudget {
+    epochs: number;
+    requested_epochs: number;
+    zero_cost: ZeroCostMetrics;
 }
 
 export interface LogEntry {
@@ -797,93 +815,41 @@ export const useEvolutionLoop = ({ datasetProfileId, settings, datasetProfiles }
 
             addLog(`Sending ${serializedGenomes.length} genomes to Rust for evaluation...`);
 
-            // 1b. Zero-Cost Proxy Scoring (if enabled)
-            let zeroCostScores: ZeroCostMetrics[] = [];
-            let evalEpochsAdjustments: Map<number, number> = new Map();
-            
-            if (settings.useZeroCostProxies) {
-                addLog(`Computing zero-cost proxy scores for architecture evaluation...`);
-                
-                for (let i = 0; i < serializedGenomes.length; i++) {
-                    if (!isRunningRef.current || isPausedRef.current) {
-                        addLog('Zero-cost scoring interrupted by stop/pause request.', 'warn');
-                        break;
-                    }
-
-                    try {
-                        const score = await computeZeroCostScore(serializedGenomes[i], {
-                            enabled: true,
-                            strategy: settings.zeroCostStrategy,
-                            fastPassThreshold: settings.fastPassThreshold,
-                            partialTrainingEpochs: settings.partialTrainingEpochs,
-                            useVoting: false,
-                        });
-                        
-                        zeroCostScores.push(score);
-                        
-                        // Determine epochs for this genome based on strategy decision
-                        const recommended = score.strategy_decision === 'skip' 
-                            ? 0 
-                            : score.strategy_decision === 'partial_train'
-                                ? settings.partialTrainingEpochs
-                                : settings.evalEpochs;
-                        
-                        evalEpochsAdjustments.set(i, recommended);
-                        
-                        addLog(
-                            `Genome ${i}: SynFlow=${score.synflow.toFixed(2)} ` +
-                            `(${(score.normalized_score * 100).toFixed(0)}%) → ` +
-                            `${score.strategy_decision === 'skip' ? 'SKIP' : recommended + ' epochs'}`,
-                            "info"
-                        );
-                    } catch (e) {
-                        const errMsg = String(e);
-                        if (/cancel|stopp|aborted/i.test(errMsg)) {
-                            addLog('Zero-cost scoring cancelled.', 'warn');
-                            break;
-                        }
-
-                        // Fallback: full training if zero-cost fails
-                        zeroCostScores.push({
-                            synflow: 5.0,
-                            normalized_score: 0.5,
-                            strategy_decision: 'full_train',
-                        });
-                        evalEpochsAdjustments.set(i, settings.evalEpochs);
-                        addLog(`Genome ${i}: Zero-cost scoring failed, using full training fallback`);
-                    }
-                }
-                
-                // Count statistics
-                const skipped = zeroCostScores.filter(s => s.strategy_decision === 'skip').length;
-                const partial = zeroCostScores.filter(s => s.strategy_decision === 'partial_train').length;
-                const full = zeroCostScores.filter(s => s.strategy_decision === 'full_train').length;
-                const avgSynFlow = zeroCostScores.reduce((sum, s) => sum + s.synflow, 0) / zeroCostScores.length;
-                
-                addLog(
-                    `Zero-Cost Summary: ${full} full + ${partial} partial + ${skipped} skipped | ` +
-                    `Avg SynFlow: ${avgSynFlow.toFixed(2)}`,
-                    "success"
-                );
-            } else {
-                // If zero-cost disabled, use standard epochs for all
-                for (let i = 0; i < serializedGenomes.length; i++) {
-                    evalEpochsAdjustments.set(i, settings.evalEpochs);
-                }
+            // 1b. Zero-cost scoring runs inside evaluate_population: every genome is scored
+            // on one training batch and trained for the budget its decision allows
+            const zeroCostConfig = settings.useZeroCostProxies ? {
+                enabled: true,
+                strategy: settings.zeroCostStrategy,
+                fastPassThreshold: settings.fastPassThreshold,
+                partialTrainingEpochs: settings.partialTrainingEpochs,
+                useVoting: settings.zeroCostVoting,
+            } : undefined;
+            if (zeroCostConfig) {
+                addLog(`Zero-cost proxies will score genomes before training...`);
             }
 
+            // 1c. Static device check: infeasible genomes are penalized or rejected before training
+            const customDevice = settings.isCustomDevice ? settings.customDeviceParams : undefined;
+            const deviceFilter = settings.deviceFilterAction && settings.deviceFilterAction !== 'off' && settings.deviceProfileId ? {
+                device: settings.deviceProfileId,
+                constraints: customDevice?.mops_budget && customDevice.flash_mb ? {
+                    mops_budget: customDevice.mops_budget,
+                    ram_budget_mb: customDevice.ram_mb,
+                    flash_budget_mb: customDevice.flash_mb,
+                    max_latency_ms: customDevice.latency_budget_ms,
+                } : null,
+                action: settings.deviceFilterAction,
+                repair: settings.repairForDevice ?? false,
+            } : null;
+
             // Look up dataset split percentages from the dataset manager
             const currentProfile = datasetProfiles.find(p => p.id === datasetProfileId);
             const trainSplit = currentProfile?.split?.train ?? 80;
             const valSplit = currentProfile?.split?.val ?? 10;
             const testSplit = currentProfile?.split?.test ?? 10;
 
-            // 2. Call Rust Evaluator
-            // For now, we use a global evalEpochs value
-            // In a more advanced implementation, we'd modify evaluate_population to accept per-genome epochs
-            const perGenomeEpochs = Array.from({ length: serializedGenomes.length }, (_, i) => 
-                evalEpochsAdjustments.get(i) ?? settings.evalEpochs
-            );
+            // 2. Call Rust Evaluator (zero-cost decisions shorten these budgets in Rust)
+            const perGenomeEpochs = Array.from({ length: serializedGenomes.length }, () => settings.evalEpochs);
 
             const configuredRamMb = settings.customDeviceParams?.ram_mb
                 ?? Math.max(1, settings.resourceTargets.ram / MB_TO_BYTES);
@@ -928,17 +894,56 @@ export const useEvolutionLoop = ({ datasetProfileId, settings, datasetProfiles }
                 maxParallelJobs: requestedMaxParallelJobs,
                 executionMode: requestedExecutionMode,
                 memorySafetyMarginMb: safetyMarginMb,
+                executionBackend: settings.executionBackend ?? 'gpu',
+                batchPipeline: { loading: settings.batchLoading ?? 'auto' },
+                zeroCost: zeroCostConfig,
+                latencyBenchmark: settings.benchmarkLatency
+                    ? { warmup: 5, iterations: 50, batch_size: settings.batchSize || 32 }
+                    : null,
+                deviceFilter,
             });
 
+            const zeroCostScores: (ZeroCostMetrics | undefined)[] = results.map(res => res?.budget?.zero_cost);
+            const budgets = results.flatMap(res => res?.budget ? [res.budget] : []);
+            if (budgets.length > 0) {
+                const count = (decision: string) => budgets.filter(b => b.zero_cost.strategy_decision === decision).length;
+                const avgSynFlow = budgets.reduce((sum, b) => sum + b.zero_cost.synflow, 0) / budgets.length;
+                addLog(
+                    `Zero-Cost Summary: ${count('full_train')} full + ${count('partial_train')} partial + ${count('skip')} skipped | ` +
+                    `Avg SynFlow: ${avgSynFlow.toFixed(2)}`,
+                    "success"
+                );
+            }
+
+            const deviceChecks = results.flatMap(res => res?.device_check ? [res.device_check] : []);
+            if (deviceChecks.length > 0) {
+                const count = (decision: DeviceCheck['decision']) => deviceChecks.filter(c => c.decision === decision).length;
+                const repaired = deviceChecks.filter(c => c.repair_steps > 0).length;
+                addLog(
+                    `Device Check (${deviceChecks[0].device}): ${count('train')} trained (${repaired} repaired) + ` +
+                    `${count('penalized')} penalized + ${count('rejected')} rejected`,
+                    "success"
+                );
+            }
+            // Repaired genomes replace the ones they were shrunk from
+            const repairedGenomes = await Promise.all(results.map(async res => {
+                const repaired = res?.device_check?.repaired_genome;
+                return repaired ? (await deserializeGenome(repaired)).genome : undefined;
+            }));
+
             // 3. Map Results & Apply Fitness (Parsimony + Resource-Aware + Zero-Cost)
             const alpha = settings.useParsimonyPressure ? settings.parsimonyAlpha : 0;
             const evaluatedPop = population.map((p, index) => {
                 const res = results[index];
-                const nodeCount = p.genome.getAllNodes().length;
-                const resources = p.genome.GetGenomeResources();
-                const zeroCostMetric = zeroCostScores.length > index ? zeroCostScores[index] : undefined;
+                const genome = repairedGenomes[index] ?? p.genome;
+                const nodeCount = genome.getAllNodes().length;
+                const resources = genome.GetGenomeResources();
+                const zeroCostMetric = zeroCostScores[index];
 
                 let baseFitness = res.accuracy > 0 ? res.accuracy : (1 / (1 + res.loss));
+                if (res.device_check?.decision === 'penalized') {
+                    baseFitness = res.device_check.penalty_fitness ?? 0;
+                }
 
                 // Combine with zero-cost proxy if available
                 if (settings.useZeroCostProxies && zeroCostMetric) {
@@ -966,6 +971,7 @@ export const useEvolutionLoop = ({ datasetProfileId, settings, datasetProfiles }
 
                 return {
                     ...p,
+                    genome,
                     loss: res.loss,
                     accuracy: res.accuracy,
                     adjustedFitness: baseFitness - (alpha * nodeCount),
@@ -973,12 +979,64 @@ export const useEvolutionLoop = ({ datasetProfileId, settings, datasetProfiles }
                     resources,
                     zeroCostMetric,
                     profiler: res.profiler,
+                    cost: res.cost,
+                    latency: res.latency,
                 } as PopulatedGenome;
             });
 
+            // Fully trained genomes calibrate the proxies for this dataset profile
+            if (settings.useZeroCostProxies && datasetProfileId) {
+                // Only a real trained accuracy counts: failed or cancelled genomes come back
+                // as a sentinel without a profiler, rejected and penalized ones never train
+                const outcomes = zeroCostScores.flatMap((score, index) => {
+                    const res = results[index];
+                    if (!res || !score || score.strategy_decision !== 'full_train' || !score.proxies?.length) return [];
+                    if (!res.profiler || !Number.isFinite(res.accuracy)) return [];
+                    if (res.device_check && res.device_check.decision !== 'train') return [];
+                    return [{ proxies: score.proxies, accuracy: res.accuracy }];
+                });
+                const report = outcomes.length > 0 ? await recordZeroCostOutcomes(datasetProfileId, outcomes) : null;
+                if (report) {
+                    const correlations = report.proxies
+                        .filter(p => p.spearman !== null)
+                        .map(p => `${p.name} ${p.spearman!.toFixed(2)}`)
+                        .join(', ');
+                    addLog(
+                        `Zero-cost calibration: ${report.records} records${report.calibrated ? '' : ' (not calibrated yet)'}` +
+                        (correlations ? ` | Spearman: ${correlations}` : ''),
+                        "info"
+                    );
+                }
+            }
+
             // 4. Sort by Adjusted Fitness (descending)
             evaluatedPop.sort((a, b) => (b.adjustedFitness || 0) - (a.adjustedFitness || 0));
 
+            // Merge the generation into the run's Pareto archive
+            try {
+                const archive = await invoke<ParetoArchiveGeneration>('update_pareto_archive', {
+                    generation,
+                    genomes: evaluatedPop.map(p => ({
+                        genome_id: p.id,
+                        accuracy: p.accuracy || 0,
+                        inference_latency_ms: p.latency?.single.p50_ms ?? p.profiler?.inference_msec_per_sample ?? 0,
+                        model_size_mb: (p.cost?.parameter_bytes ?? p.resources?.totalFlash ?? 0) / (1024 * 1024),
+                        training_time_ms: p.profiler?.total_train_duration_ms ?? 0,
+                        is_dominated: false,
+                        domination_count: 0,
+                        flops: p.cost ? p.cost.total_macs * 2 : undefined,
+                        parameter_count: p.cost?.total_params,
+                        peak_activation_bytes: p.cost?.peak_activation_bytes,
+                    })),
+                });
+                addLog(
+                    `Pareto archive: ${archive.archive_size} genomes (+${archive.added}/-${archive.removed}), ` +
+                    `HV ${archive.hypervolume.toPrecision(4)}, coverage ${(archive.coverage * 100).toFixed(1)}%`,
+                );
+            } catch (e) {
+                addLog(`Pareto archive update failed: ${String(e)}`, 'warn');
+            }
+
             const best = evaluatedPop[0];
             addLog(`Generation ${generation} complete. Best Fitness: ${best.adjustedFitness?.toFixed(4)} (Nodes: ${best.nodes.length})`, "success");
 
@@ -1543,6 +1601,9 @@ export const useEvolutionLoop = ({ datasetProfileId, settings, datasetProfiles }
                 }
             }
 
+            invoke('reset_pareto_archive', { config: null })
+                .catch(e => addLog(`Pareto archive reset failed: ${String(e)}`, 'warn'));
+
             setIsRunning(true);
             setIsPaused(false);
             isRunningRef.current = true;
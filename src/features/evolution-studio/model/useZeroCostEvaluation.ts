@@ -7,10 +7,36 @@
 
 import { invoke } from '@tauri-apps/api/core';
 
+export interface ProxyScore {
+  name: string;
+  raw: number;
+  normalized: number;
+}
+
 export interface ZeroCostMetrics {
   synflow: number;
   normalized_score: number;
   strategy_decision: 'skip' | 'partial_train' | 'full_train';
+  proxies?: ProxyScore[];
+}
+
+export interface ProxyCorrelation {
+  name: string;
+  spearman: number | null;
+  samples: number;
+}
+
+export interface ZeroCostCalibrationReport {
+  profileId: string;
+  records: number;
+  calibrated: boolean;
+  proxies: ProxyCorrelation[];
+  bestProxy: string | null;
+}
+
+export interface ZeroCostOutcome {
+  proxies: ProxyScore[];
+  accuracy: number;
 }
 
 export interface ZeroCostConfig {
@@ -21,10 +47,19 @@ export interface ZeroCostConfig {
   useVoting: boolean;
 }
 
+const serializeConfig = (config: ZeroCostConfig): string =>
+  JSON.stringify({
+    enabled: config.enabled,
+    strategy: config.strategy,
+    fast_pass_threshold: config.fastPassThreshold,
+    partial_training_epochs: config.partialTrainingEpochs,
+    use_voting: config.useVoting,
+  });
+
 /**
  * Compute zero-cost proxy score for an architecture
  * 
- * This performs a single forward-backward pass on a sample batch
+ * This performs a few forward-backward passes on a small sample batch
  * to estimate architecture quality without training.
  */
 export const computeZeroCostScore = async (
@@ -32,18 +67,9 @@ export const computeZeroCostScore = async (
   config: ZeroCostConfig
 ): Promise<ZeroCostMetrics> => {
   try {
-    // Prepare config for serialization
-    const configDto = {
-      enabled: config.enabled,
-      strategy: config.strategy,
-      fast_pass_threshold: config.fastPassThreshold,
-      partial_training_epochs: config.partialTrainingEpochs,
-      use_voting: config.useVoting,
-    };
-
     const result = await invoke<any>('compute_zero_cost_score', {
       genomeJson: genomeJSON,
-      configJson: JSON.stringify(configDto),
+      configJson: serializeConfig(config),
     });
 
     // Normalize the result
@@ -51,6 +77,7 @@ export const computeZeroCostScore = async (
       synflow: result.synflow ?? 5.0,
       normalized_score: result.normalized_score ?? 0.5,
       strategy_decision: (result.strategy_decision?.replace(/_/g, '_') ?? 'full_train') as 'skip' | 'partial_train' | 'full_train',
+      proxies: result.proxies ?? [],
     };
   } catch (error) {
     console.error('Error computing zero-cost score:', error);
@@ -63,6 +90,46 @@ export const computeZeroCostScore = async (
   }
 };
 
+/**
+ * Re-score a whole population: calibrated against past results on the dataset profile
+ * when there are enough, otherwise by rank voting (if enabled). Decisions then follow
+ * each genome's percentile within the population.
+ * Returns the metrics unchanged when the call fails.
+ */
+export const calibrateZeroCostScores = async (
+  metrics: ZeroCostMetrics[],
+  config: ZeroCostConfig,
+  profileId: string | null
+): Promise<ZeroCostMetrics[]> => {
+  if (metrics.length < 2) return metrics;
+  try {
+    return await invoke<ZeroCostMetrics[]>('calibrate_zero_cost_scores', {
+      profileId,
+      metrics,
+      configJson: serializeConfig(config),
+    });
+  } catch (error) {
+    console.error('Error calibrating zero-cost scores:', error);
+    return metrics;
+  }
+};
+
+/**
+ * Record the proxy scores and final accuracy of fully trained genomes, returning the
+ * updated per-proxy Spearman correlations for the profile.
+ */
+export const recordZeroCostOutcomes = async (
+  profileId: string,
+  outcomes: ZeroCostOutcome[]
+): Promise<ZeroCostCalibrationReport | null> => {
+  try {
+    return await invoke<ZeroCostCalibrationReport>('record_zero_cost_outcomes', { profileId, outcomes });
+  } catch (error) {
+    console.error('Error recording zero-cost outcomes:', error);
+    return null;
+  }
+};
+
 /**
  * Get training epochs recommendation based on zero-cost score
  */
@@ -12,6 +12,7 @@ interface CsvDatasetLocator {
     targetColumn: string;
     windowSize?: number;
     windowStride?: number;
+    horizon?: number;
     preprocessing: CsvPreprocessingConfig;
 }
 
@@ -314,8 +315,19 @@ export const CsvDatasetConfigPanel: React.FC<Props> = ({
                         placeholder={locator.hasHeaders ? 'e.g. gesture, class, label' : 'e.g. column index'}
                     />
                     <small style={{ color: 'var(--color-text-muted)', display: 'block', marginTop: '0.25rem' }}>
-                        Column containing class labels for classification tasks
+                        Column containing class labels, or comma-separated numeric columns for regression targets
                     </small>
+                    <label style={{ marginTop: '0.5rem' }}>Forecast Horizon (rows ahead, 0 = same row)</label>
+                    <input
+                        type="number"
+                        min="0"
+                        value={locator.horizon ?? 0}
+                        onChange={(e) => {
+                            const horizon = parseInt(e.target.value);
+                            onChange({ ...locator, horizon: horizon > 0 ? horizon : undefined });
+                        }}
+                        style={{ width: '100px' }}
+                    />
                 </div>
             ) : (
                 <div style={{ 
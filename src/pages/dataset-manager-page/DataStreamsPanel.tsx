@@ -176,6 +176,21 @@ export const DataStreamsPanel: React.FC<Props> = ({ profile }) => {
                                             <option value="TemporalSequence">Temporal Sequence (Time Series)</option>
                                         </select>
                                     </div>
+                                    {stream.role === 'Target' && (
+                                        <div className={styles.inputGroup} style={{ flex: 1 }}>
+                                            <label>Task</label>
+                                            <select
+                                                value={stream.taskType ?? ''}
+                                                onChange={(e) => updateStream(stream.id, { taskType: (e.target.value || undefined) as any })}
+                                            >
+                                                <option value="">Auto (from Data Type)</option>
+                                                <option value="classification">Classification</option>
+                                                <option value="regression">Regression</option>
+                                                <option value="multi_output">Multi-Output Regression</option>
+                                                <option value="multi_label">Multi-Label</option>
+                                            </select>
+                                        </div>
+                                    )}
                                     <div className={styles.inputGroup} style={{ flex: 2 }}>
                                         <label>Location Rule (Locator)</label>
                                         <select
@@ -187,6 +202,8 @@ export const DataStreamsPanel: React.FC<Props> = ({ profile }) => {
                                                 if (type === 'FolderMapping') newLocator = { type: 'FolderMapping' };
                                                 if (type === 'CompanionFile') newLocator = { type: 'CompanionFile', pathTemplate: '../labels/{id}.txt', parser: 'YOLO' };
                                                 if (type === 'MasterIndex') newLocator = { type: 'MasterIndex', indexPath: 'data.csv', keyField: '', valueField: '*', hasHeaders: true };
+                                                if (type === 'NumpyArray') newLocator = { type: 'NumpyArray', path: 'data.npy' };
+                                                if (type === 'ParquetTable') newLocator = { type: 'ParquetTable', path: 'data.parquet', columns: [] };
                                                 if (type === 'CsvDataset') {
                                                     const defaultCsvPreprocessing: CsvPreprocessingConfig = {
                                                         normalization: 'per-channel',
@@ -213,6 +230,8 @@ export const DataStreamsPanel: React.FC<Props> = ({ profile }) => {
                                             <option value="CompanionFile">Companion File (Neighbor file)</option>
                                             <option value="MasterIndex">CSV Column Mapping (MasterIndex)</option>
                                             <option value="CsvDataset">CSV Dataset (Complete Configuration)</option>
+                                            <option value="NumpyArray">NumPy Array (.npy / .npz)</option>
+                                            <option value="ParquetTable">Parquet Table</option>
                                             <option value="None">None / Manual</option>
                                         </select>
                                     </div>
@@ -313,6 +332,60 @@ export const DataStreamsPanel: React.FC<Props> = ({ profile }) => {
                                     </div>
                                 )}
 
+                                {stream.locator.type === 'NumpyArray' && (
+                                    <div className={styles.configRow}>
+                                        <div className={styles.inputGroup} style={{ flex: 2 }}>
+                                            <label>Array File (Rel. to Root)</label>
+                                            <input
+                                                type="text"
+                                                value={stream.locator.path}
+                                                onChange={(e) => updateStream(stream.id, { locator: { ...stream.locator, path: e.target.value } as any })}
+                                                placeholder="e.g. features.npy or data.npz"
+                                            />
+                                        </div>
+                                        <div className={styles.inputGroup}>
+                                            <label>Array Name (.npz only)</label>
+                                            <input
+                                                type="text"
+                                                value={stream.locator.key ?? ''}
+                                                onChange={(e) => updateStream(stream.id, { locator: { ...stream.locator, key: e.target.value || undefined } as any })}
+                                                placeholder="first array"
+                                            />
+                                        </div>
+                                    </div>
+                                )}
+                                {stream.locator.type === 'ParquetTable' && (
+                                    <div className={styles.configRow}>
+                                        <div className={styles.inputGroup} style={{ flex: 2 }}>
+                                            <label>Parquet File (Rel. to Root)</label>
+                                            <input
+                                                type="text"
+                                                value={stream.locator.path}
+                                                onChange={(e) => updateStream(stream.id, { locator: { ...stream.locator, path: e.target.value } as any })}
+                                                placeholder="e.g. features.parquet"
+                                            />
+                                        </div>
+                                        <div className={styles.inputGroup} style={{ flex: 2 }}>
+                                            <label>Columns (empty = all numeric)</label>
+                                            <input
+                                                type="text"
+                                                value={stream.locator.columns.join(',')}
+                                                onChange={(e) => updateStream(stream.id, { locator: { ...stream.locator, columns: e.target.value.split(',').map(c => c.trim()).filter(Boolean) } as any })}
+                                                placeholder="e.g. f0,f1,embedding"
+                                            />
+                                        </div>
+                                        <div className={styles.inputGroup}>
+                                            <label>ID Column (empty = row num)</label>
+                                            <input
+                                                type="text"
+                                                value={stream.locator.idColumn ?? ''}
+                                                onChange={(e) => updateStream(stream.id, { locator: { ...stream.locator, idColumn: e.target.value || undefined } as any })}
+                                                placeholder="e.g. sample_id"
+                                            />
+                                        </div>
+                                    </div>
+                                )}
+
                                 {/* CSV Dataset Configuration Panel */}
                                 {stream.locator.type === 'CsvDataset' && (
                                     <CsvDatasetConfigPanel
@@ -354,6 +427,7 @@ export const DataStreamsPanel: React.FC<Props> = ({ profile }) => {
                                                         <option value="0-1">0..1 (MinMax)</option>
                                                         <option value="imagenet">ImageNet</option>
                                                         <option value="none">None</option>
+                                                        <option value="dataset">Dataset mean/std</option>
                                                     </select>
                                                 </div>
                                                 <div className={`${styles.inputGroup} ${styles.checkboxRow}`}>
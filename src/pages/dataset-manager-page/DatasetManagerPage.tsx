@@ -1,20 +1,25 @@
 import React from 'react';
 import styles from './DatasetManagerPage.module.css';
 import { TitleBar } from '../../widgets/title-bar/TitleBar';
-import { BsDatabaseAdd, BsFolder2Open, BsFiletypeCsv, BsSearch, BsCheckCircle, BsExclamationTriangle, BsLightningCharge } from 'react-icons/bs';
-import { useDatasetManagerStore, type DatasetSourceType, type ScanResult } from '../../features/dataset-manager';
+import { BsDatabaseAdd, BsFolder2Open, BsFiletypeCsv, BsSearch, BsCheckCircle, BsExclamationTriangle, BsLightningCharge, BsClipboardData } from 'react-icons/bs';
+import { useDatasetManagerStore, type DatasetHealthReport, type DatasetSourceType, type SamplingStrategy, type ScanResult, type SplitStrategy } from '../../features/dataset-manager';
 import { CreateDatasetModal } from './CreateDatasetModal';
 import { DataStreamsPanel } from './DataStreamsPanel';
 import { DatasetValidationPanel } from './DatasetValidationPanel';
+import { DatasetReportPanel } from './DatasetReportPanel';
 import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 export const DatasetManagerPage: React.FC = () => {
     const { profiles, selectedProfileId, setSelectedProfileId, removeProfile } = useDatasetManagerStore();
     const [isCreateModalOpen, setIsCreateModalOpen] = useState(false);
     const [isScanning, setIsScanning] = useState(false);
     const [isCaching, setIsCaching] = useState(false);
+    const [cacheProgress, setCacheProgress] = useState<{ processed: number; total: number } | null>(null);
     const [isValidating, setIsValidating] = useState(false);
+    const [isReporting, setIsReporting] = useState(false);
+    const [healthReports, setHealthReports] = useState<Record<string, DatasetHealthReport>>({});
 
     const normalizeImageShapeToHwc = (shape: number[]): number[] => {
         if (shape.length !== 3) {
@@ -53,6 +58,7 @@ export const DatasetManagerPage: React.FC = () => {
                     locator_type: s.locator.type,
                     pattern: s.locator.type === 'GlobPattern' ? s.locator.pattern : null,
                     path_template: s.locator.type === 'CompanionFile' ? s.locator.pathTemplate : null,
+                    parser: s.locator.type === 'CompanionFile' ? s.locator.parser : null,
                     stream_role: s.role, // ← Send the role
                     data_type: s.dataType,
                 };
@@ -68,6 +74,15 @@ export const DatasetManagerPage: React.FC = () => {
                     config.target_column = s.role === 'Target' ? s.locator.targetColumn : '';
                     config.window_size = s.locator.windowSize ?? null;
                 }
+                if (s.locator.type === 'NumpyArray') {
+                    config.array_path = s.locator.path;
+                    config.array_key = s.locator.key ?? null;
+                }
+                if (s.locator.type === 'ParquetTable') {
+                    config.array_path = s.locator.path;
+                    config.columns = s.locator.columns;
+                    config.id_column = s.locator.idColumn ?? null;
+                }
                 
                 return config;
             });
@@ -161,6 +176,9 @@ export const DatasetManagerPage: React.FC = () => {
         }
 
         setIsCaching(true);
+        const unlistenProgress = await listen<{ processed: number; total: number }>('dataset-cache-progress', (event) => {
+            setCacheProgress({ processed: event.payload.processed, total: event.payload.total });
+        });
         try {
             const profileJson = JSON.stringify(profile);
             const result = await invoke<{
@@ -168,6 +186,8 @@ export const DatasetManagerPage: React.FC = () => {
                 total_dropped: number;
                 dropped_sample_ids: string[];
                 class_counts: Record<string, number>;
+                decoded: number;
+                reused: number;
             }>('cache_dataset', { profileJson });
 
             // We update the ScanResult with cached values to reflect the successfully cached samples.
@@ -196,14 +216,40 @@ export const DatasetManagerPage: React.FC = () => {
                     totalSamples: result.total_cached,
                 });
             }
-            console.log(`AoT cache built: cached=${result.total_cached}, dropped=${result.total_dropped}`);
+            console.log(
+                `AoT cache built: cached=${result.total_cached}, dropped=${result.total_dropped}, ` +
+                `decoded=${result.decoded}, reused=${result.reused}`
+            );
         } catch (err) {
             console.error('Caching failed:', err);
         } finally {
+            unlistenProgress();
+            setCacheProgress(null);
             setIsCaching(false);
         }
     };
 
+    const handleReport = async (profileId: string, refresh = false) => {
+        const profile = useDatasetManagerStore.getState().profiles.find(p => p.id === profileId);
+        if (!profile) return;
+
+        setIsReporting(true);
+        try {
+            const report = await invoke<DatasetHealthReport>('dataset_report', {
+                profileJson: JSON.stringify(profile),
+                trainSplit: profile.split.train,
+                valSplit: profile.split.val,
+                testSplit: profile.split.test,
+                refresh,
+            });
+            setHealthReports(prev => ({ ...prev, [profileId]: report }));
+        } catch (err) {
+            console.error('Dataset report failed:', err);
+        } finally {
+            setIsReporting(false);
+        }
+    };
+
     const handleValidate = async (profileId: string) => {
         const profile = useDatasetManagerStore.getState().profiles.find(p => p.id === profileId);
         if (!profile) return;
@@ -213,6 +259,9 @@ export const DatasetManagerPage: React.FC = () => {
             const profileJson = JSON.stringify(profile);
             const validationReportRaw = await invoke<any>('validate_dataset_profile', {
                 profileJson,
+                trainSplit: profile.split.train,
+                valSplit: profile.split.val,
+                testSplit: profile.split.test,
             });
 
             // Rust serializes validation report using camelCase via serde(rename_all="camelCase")
@@ -236,6 +285,7 @@ export const DatasetManagerPage: React.FC = () => {
                     : undefined,
                 total_valid_samples: Number(validationReportRaw?.totalValidSamples ?? validationReportRaw?.total_valid_samples ?? 0),
                 can_start_evolution: Boolean(validationReportRaw?.canStartEvolution ?? validationReportRaw?.can_start_evolution),
+                class_counts: Array.isArray(validationReportRaw?.classCounts) ? validationReportRaw.classCounts : [],
             };
 
             useDatasetManagerStore.getState().updateProfile(profileId, {
@@ -319,7 +369,22 @@ export const DatasetManagerPage: React.FC = () => {
                                                 title={!profile.streams.some(s => s.role === 'Input' && s.dataType === 'Image') ? "AoT Cache is only available for Image datasets" : "Build static dataset cache"}
                                             >
                                                 <span style={{ display: 'flex', alignItems: 'center', gap: '0.4rem' }}>
-                                                    <BsLightningCharge /> {isCaching ? 'Caching...' : 'Build AoT Cache'}
+                                                    <BsLightningCharge /> {isCaching
+                                                        ? cacheProgress
+                                                            ? `Caching ${cacheProgress.processed}/${cacheProgress.total}`
+                                                            : 'Caching...'
+                                                        : 'Build AoT Cache'}
+                                                </span>
+                                            </button>
+                                            <button
+                                                className={styles.saveBtn}
+                                                style={{ padding: '0.5rem 1rem', fontSize: '0.85rem' }}
+                                                onClick={() => handleReport(profile.id)}
+                                                disabled={isScanning || isReporting || !profile.isScanned}
+                                                title="Class balance, image sizes, unreadable files, duplicates and CSV column statistics"
+                                            >
+                                                <span style={{ display: 'flex', alignItems: 'center', gap: '0.4rem' }}>
+                                                    <BsClipboardData /> {isReporting ? 'Analyzing...' : 'Health Report'}
                                                 </span>
                                             </button>
                                             <button
@@ -426,6 +491,15 @@ export const DatasetManagerPage: React.FC = () => {
                                         <DatasetValidationPanel validationReport={profile.validationReport} />
                                     )}
 
+                                    {/* Data Health Report */}
+                                    {healthReports[profile.id] && (
+                                        <DatasetReportPanel
+                                            report={healthReports[profile.id]}
+                                            onRefresh={() => handleReport(profile.id, true)}
+                                            isRefreshing={isReporting}
+                                        />
+                                    )}
+
                                     {/* Data Streams */}
                                     <DataStreamsPanel profile={profile} />
 
@@ -433,7 +507,7 @@ export const DatasetManagerPage: React.FC = () => {
                                     <div className={styles.configSection}>
                                         <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', marginBottom: '1rem' }}>
                                             <h3 style={{ margin: 0 }}>Dataset Splits</h3>
-                                            {profile.streams.some(s => s.role === 'Target' && s.dataType === 'Categorical') && (
+                                            {(profile.split.strategy ?? 'stratified') === 'stratified' && profile.streams.some(s => s.role === 'Target' && s.dataType === 'Categorical') && (
                                                 <span style={{
                                                     fontSize: '0.75rem',
                                                     color: 'var(--color-success)',
@@ -472,6 +546,52 @@ export const DatasetManagerPage: React.FC = () => {
                                                 />
                                             </div>
                                         </div>
+                                        <div className={styles.configRow}>
+                                            <div className={styles.inputGroup}>
+                                                <label>Strategy</label>
+                                                <select
+                                                    value={profile.split.strategy ?? 'stratified'}
+                                                    onChange={e => handleUpdate({ split: { ...profile.split, strategy: e.target.value as SplitStrategy } })}
+                                                >
+                                                    <option value="stratified">Stratified</option>
+                                                    <option value="random">Random</option>
+                                                    <option value="chronological">Chronological (time series)</option>
+                                                </select>
+                                            </div>
+                                            <div className={styles.inputGroup}>
+                                                <label>Training Sampling</label>
+                                                <select
+                                                    value={profile.sampling ?? 'uniform'}
+                                                    onChange={e => handleUpdate({ sampling: e.target.value as SamplingStrategy })}
+                                                >
+                                                    <option value="uniform">Uniform</option>
+                                                    <option value="oversample">Oversample minority classes</option>
+                                                    <option value="undersample">Undersample majority classes</option>
+                                                    <option value="weighted">Inverse-frequency weighted</option>
+                                                </select>
+                                            </div>
+                                            {profile.split.strategy === 'chronological' && (
+                                                <>
+                                                    <div className={styles.inputGroup}>
+                                                        <label>Gap (samples)</label>
+                                                        <input
+                                                            type="number" min="0"
+                                                            placeholder="auto"
+                                                            value={profile.split.gap ?? ''}
+                                                            onChange={e => handleUpdate({ split: { ...profile.split, gap: e.target.value === '' ? undefined : Number(e.target.value) } })}
+                                                        />
+                                                    </div>
+                                                    <div className={styles.inputGroup}>
+                                                        <label>Walk-forward folds</label>
+                                                        <input
+                                                            type="number" min="0" max="10"
+                                                            value={profile.split.walkForwardFolds ?? 0}
+                                                            onChange={e => handleUpdate({ split: { ...profile.split, walkForwardFolds: Number(e.target.value) } })}
+                                                        />
+                                                    </div>
+                                                </>
+                                            )}
+                                        </div>
                                     </div>
 
                                 </div>
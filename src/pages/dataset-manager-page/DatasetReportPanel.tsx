@@ -0,0 +1,176 @@
+import React from 'react';
+import type { DatasetHealthReport, HistogramBin } from '../../features/dataset-manager';
+
+interface Props {
+    report: DatasetHealthReport;
+    onRefresh: () => void;
+    isRefreshing: boolean;
+}
+
+const sectionTitle: React.CSSProperties = { margin: '1.25rem 0 0.5rem 0', color: 'var(--color-text-secondary)', fontSize: '0.9rem' };
+const cell: React.CSSProperties = { padding: '0.25rem 0.5rem', textAlign: 'right' };
+const firstCell: React.CSSProperties = { ...cell, textAlign: 'left' };
+
+const Histogram: React.FC<{ title: string; bins: HistogramBin[] }> = ({ title, bins }) => {
+    const maxCount = Math.max(1, ...bins.map(b => b.count));
+    return (
+        <div style={{ flex: 1 }}>
+            <div style={{ fontSize: '0.8rem', color: 'var(--color-text-muted)', marginBottom: '0.25rem' }}>{title}</div>
+            {bins.map(bin => (
+                <div key={bin.label} style={{ display: 'flex', alignItems: 'center', gap: '8px', fontSize: '0.75rem' }}>
+                    <div style={{ width: '80px', fontFamily: 'monospace' }}>{bin.label}</div>
+                    <div style={{ flex: 1, height: '8px', background: 'var(--color-bg-primary)', borderRadius: '4px', overflow: 'hidden' }}>
+                        <div style={{ width: `${(bin.count / maxCount) * 100}%`, height: '100%', background: 'var(--color-accent-primary)' }} />
+                    </div>
+                    <div style={{ width: '50px', textAlign: 'right' }}>{bin.count}</div>
+                </div>
+            ))}
+        </div>
+    );
+};
+
+export const DatasetReportPanel: React.FC<Props> = ({ report, onRefresh, isRefreshing }) => {
+    const constantColumns = report.columns.filter(c => c.constant);
+    const leakingDuplicates = report.duplicates.filter(d => d.splits.length > 1);
+
+    return (
+        <div style={{
+            background: 'var(--color-bg-primary)',
+            border: '1px solid var(--color-border)',
+            borderRadius: '8px',
+            padding: '1.5rem',
+            marginTop: '1rem'
+        }}>
+            <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center' }}>
+                <h3 style={{ margin: 0 }}>Data Health Report</h3>
+                <div style={{ display: 'flex', alignItems: 'center', gap: '1rem', fontSize: '0.8rem', color: 'var(--color-text-muted)' }}>
+                    <span>Generated {new Date(report.generatedAtMs).toLocaleString()}</span>
+                    <button onClick={onRefresh} disabled={isRefreshing}>{isRefreshing ? 'Rebuilding...' : 'Rebuild'}</button>
+                </div>
+            </div>
+
+            <div style={{ display: 'flex', gap: '2rem', marginTop: '0.75rem', fontSize: '0.9rem' }}>
+                <span><strong>Samples:</strong> {report.totalSamples}</span>
+                <span><strong>Train/Val/Test:</strong> {report.trainSamples} / {report.valSamples} / {report.testSamples}</span>
+                <span style={{ color: report.unreadableFiles > 0 ? 'var(--color-danger)' : undefined }}>
+                    <strong>Unreadable files:</strong> {report.unreadableFiles}
+                </span>
+                <span style={{ color: report.duplicateSamples > 0 ? 'var(--color-warning)' : undefined }}>
+                    <strong>Duplicates:</strong> {report.duplicateSamples}
+                </span>
+            </div>
+
+            {report.classCounts.length > 0 && (
+                <>
+                    <h4 style={sectionTitle}>Classes per Split</h4>
+                    <table style={{ width: '100%', borderCollapse: 'collapse', fontSize: '0.85rem' }}>
+                        <thead>
+                            <tr style={{ color: 'var(--color-text-muted)' }}>
+                                <th style={firstCell}>Class</th>
+                                <th style={cell}>Train</th>
+                                <th style={cell}>Val</th>
+                                <th style={cell}>Test</th>
+                                <th style={cell}>Train (sampled)</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {report.classCounts.map(c => (
+                                <tr key={c.className} style={{ fontFamily: 'monospace' }}>
+                                    <td style={firstCell}>{c.className}</td>
+                                    <td style={cell}>{c.train}</td>
+                                    <td style={cell}>{c.val}</td>
+                                    <td style={cell}>{c.test}</td>
+                                    <td style={cell}>{c.trainSampled}</td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </>
+            )}
+
+            {report.images.map(stream => (
+                <div key={stream.streamId}>
+                    <h4 style={sectionTitle}>
+                        Images '{stream.streamId}' — {stream.decoded} decoded, {stream.minWidth}–{stream.maxWidth} × {stream.minHeight}–{stream.maxHeight} px
+                    </h4>
+                    <div style={{ display: 'flex', gap: '2rem' }}>
+                        <Histogram title="Longer side (px, before resize)" bins={stream.sizeHistogram} />
+                        <Histogram title="Aspect ratio (width / height)" bins={stream.aspectHistogram} />
+                    </div>
+                </div>
+            ))}
+
+            {report.unreadable.length > 0 && (
+                <>
+                    <h4 style={sectionTitle}>Unreadable Files ({report.unreadableFiles})</h4>
+                    <div style={{ fontSize: '0.8rem', fontFamily: 'monospace', maxHeight: '160px', overflowY: 'auto' }}>
+                        {report.unreadable.map(f => (
+                            <div key={`${f.streamId}/${f.sampleId}`} title={f.path}>{f.sampleId}: {f.error}</div>
+                        ))}
+                    </div>
+                </>
+            )}
+
+            {report.duplicates.length > 0 && (
+                <>
+                    <h4 style={sectionTitle}>
+                        Duplicate Samples ({report.duplicateSamples}{leakingDuplicates.length > 0 ? `, ${leakingDuplicates.length} groups span several splits` : ''})
+                    </h4>
+                    <div style={{ fontSize: '0.8rem', fontFamily: 'monospace', maxHeight: '160px', overflowY: 'auto' }}>
+                        {report.duplicates.map(d => (
+                            <div key={d.sampleIds.join('|')} style={{ color: d.splits.length > 1 ? 'var(--color-warning)' : undefined }}>
+                                {d.sampleIds.join(', ')} [{d.splits.join('/')}]{d.labels.length > 1 ? ` labels: ${d.labels.join(', ')}` : ''}
+                            </div>
+                        ))}
+                    </div>
+                </>
+            )}
+
+            {report.columns.length > 0 && (
+                <>
+                    <h4 style={sectionTitle}>CSV Columns{constantColumns.length > 0 ? ` (${constantColumns.length} constant)` : ''}</h4>
+                    <table style={{ width: '100%', borderCollapse: 'collapse', fontSize: '0.8rem' }}>
+                        <thead>
+                            <tr style={{ color: 'var(--color-text-muted)' }}>
+                                <th style={firstCell}>Column</th>
+                                <th style={cell}>Missing %</th>
+                                <th style={cell}>Min</th>
+                                <th style={cell}>Max</th>
+                                <th style={cell}>Mean</th>
+                                <th style={cell}>Distinct</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {report.columns.map(c => (
+                                <tr key={`${c.file}/${c.column}`} style={{
+                                    fontFamily: 'monospace',
+                                    color: c.constant ? 'var(--color-text-muted)' : c.missingPct > 20 ? 'var(--color-warning)' : undefined
+                                }}>
+                                    <td style={firstCell} title={c.file}>{c.column}{c.constant ? ' (constant)' : ''}</td>
+                                    <td style={cell}>{c.missingPct.toFixed(1)}</td>
+                                    <td style={cell}>{c.min?.toPrecision(4) ?? '—'}</td>
+                                    <td style={cell}>{c.max?.toPrecision(4) ?? '—'}</td>
+                                    <td style={cell}>{c.mean?.toPrecision(4) ?? '—'}</td>
+                                    <td style={cell}>{c.distinct >= 1000 ? '1000+' : c.distinct}</td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </>
+            )}
+
+            {report.labelHints.length > 0 && (
+                <>
+                    <h4 style={sectionTitle}>Label Noise Hints</h4>
+                    <div style={{ display: 'flex', flexDirection: 'column', gap: '0.4rem', fontSize: '0.85rem' }}>
+                        {report.labelHints.map((hint, idx) => (
+                            <div key={idx} style={{ color: 'var(--color-warning)' }} title={hint.sampleIds.join(', ')}>
+                                ⚠ {hint.message}
+                            </div>
+                        ))}
+                    </div>
+                </>
+            )}
+        </div>
+    );
+};
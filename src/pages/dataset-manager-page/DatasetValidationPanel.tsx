@@ -13,6 +13,7 @@ export const DatasetValidationPanel: React.FC<Props> = ({ validationReport }) =>
     const inputShapes = validationReport?.input_shapes ?? {};
     const outputShape = validationReport?.output_shape;
     const totalValidSamples = validationReport?.total_valid_samples ?? 0;
+    const classCounts = validationReport?.class_counts ?? [];
     const canStartEvolution = validationReport?.can_start_evolution ?? false;
     const isValid = validationReport?.is_valid ?? false;
 
@@ -141,6 +142,37 @@ export const DatasetValidationPanel: React.FC<Props> = ({ validationReport }) =>
                 </div>
             </div>
 
+            {/* Per-class split counts */}
+            {classCounts.length > 0 && (
+                <div style={{ marginBottom: '1.5rem' }}>
+                    <h4 style={{ margin: '0 0 0.75rem 0', color: 'var(--color-text-secondary)', fontSize: '0.9rem' }}>
+                        Class Distribution
+                    </h4>
+                    <table style={{ width: '100%', borderCollapse: 'collapse', fontSize: '0.85rem' }}>
+                        <thead>
+                            <tr style={{ color: 'var(--color-text-muted)', textAlign: 'right' }}>
+                                <th style={{ textAlign: 'left', padding: '0.25rem 0.5rem' }}>Class</th>
+                                <th style={{ padding: '0.25rem 0.5rem' }}>Train</th>
+                                <th style={{ padding: '0.25rem 0.5rem' }}>Val</th>
+                                <th style={{ padding: '0.25rem 0.5rem' }}>Test</th>
+                                <th style={{ padding: '0.25rem 0.5rem' }}>Train (sampled)</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {classCounts.map(c => (
+                                <tr key={c.className} style={{ color: 'var(--color-text-primary)', textAlign: 'right', fontFamily: 'monospace' }}>
+                                    <td style={{ textAlign: 'left', padding: '0.25rem 0.5rem' }}>{c.className}</td>
+                                    <td style={{ padding: '0.25rem 0.5rem' }}>{c.train}</td>
+                                    <td style={{ padding: '0.25rem 0.5rem' }}>{c.val}</td>
+                                    <td style={{ padding: '0.25rem 0.5rem' }}>{c.test}</td>
+                                    <td style={{ padding: '0.25rem 0.5rem' }}>{c.trainSampled}</td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                </div>
+            )}
+
             {/* Issues List */}
             {issues.length > 0 && (
                 <div>
@@ -106,6 +106,8 @@ function resetSettingsMock() {
         setFastPassThreshold: setFastPassThresholdMock,
         partialTrainingEpochs: 20,
         setPartialTrainingEpochs: setPartialTrainingEpochsMock,
+        zeroCostVoting: false,
+        setZeroCostVoting: vi.fn(),
         normalizeObjectiveWeights: normalizeObjectiveWeightsMock,
     };
 }
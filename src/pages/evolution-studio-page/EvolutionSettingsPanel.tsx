@@ -484,6 +484,19 @@ export const EvolutionSettingsPanel: React.FC<EvolutionSettingsPanelProps> = ({
                                 For medium-scored architectures (scoring 0.3-0.7 range)
                             </p>
                         </div>
+
+                        <label className={styles.checkboxLabel}>
+                            <input
+                                type="checkbox"
+                                checked={settings.zeroCostVoting}
+                                onChange={e => settings.setZeroCostVoting(e.target.checked)}
+                                disabled={disabled}
+                            />
+                            <span>Proxy Voting</span>
+                        </label>
+                        <p className={styles.helpText}>
+                            Rank the population on NASWOT, GradNorm, SNIP, GraSP, Fisher, Jacob-cov and SynFlow together
+                        </p>
                     </>
                 )}
             </div>
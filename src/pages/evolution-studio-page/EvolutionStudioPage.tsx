@@ -49,17 +49,20 @@ function computeParetoMembers(objectives: GenomeObjectives[]): GenomeObjectives[
 }
 
 function mapToObjectives(genome: PopulatedGenome): GenomeObjectives {
-    const totalFlashBytes = genome.resources?.totalFlash ?? 0;
+    const totalFlashBytes = genome.cost?.parameter_bytes ?? genome.resources?.totalFlash ?? 0;
     const modelSizeMb = totalFlashBytes / (1024 * 1024);
 
     return {
         genome_id: genome.id,
         accuracy: genome.accuracy ?? 0,
-        inference_latency_ms: genome.profiler?.inference_msec_per_sample ?? 0,
+        inference_latency_ms: genome.latency?.single.p50_ms ?? genome.profiler?.inference_msec_per_sample ?? 0,
         model_size_mb: modelSizeMb,
         training_time_ms: genome.profiler?.total_train_duration_ms ?? 0,
         is_dominated: false,
         domination_count: 0,
+        flops: genome.cost ? genome.cost.total_macs * 2 : undefined,
+        parameter_count: genome.cost?.total_params,
+        peak_activation_bytes: genome.cost?.peak_activation_bytes,
     };
 }
 
@@ -34,6 +34,85 @@ export interface GenomeObjectives {
     domination_count: number;
     device_feasible?: boolean;
     constraint_violation_score?: number;
+    flops?: number;
+    parameter_count?: number;
+    peak_activation_bytes?: number;
+    custom_metrics?: Record<string, number>;
+}
+
+export interface LayerCost {
+    node_id: number;
+    op: string;
+    output_shape: number[];
+    macs: number;
+    params: number;
+    activation_bytes: number;
+}
+
+/** Static cost of a genome's model at batch 1 (`analyze_genome_cost`). */
+export interface ModelCost {
+    layers: LayerCost[];
+    total_macs: number;
+    total_params: number;
+    parameter_bytes: number;
+    activation_bytes: number;
+    peak_activation_bytes: number;
+}
+
+export interface LatencyStats {
+    batch_size: number;
+    iterations: number;
+    mean_ms: number;
+    min_ms: number;
+    max_ms: number;
+    p50_ms: number;
+    p90_ms: number;
+    p99_ms: number;
+    samples_per_sec: number;
+}
+
+export interface LatencyBenchmark {
+    backend: string;
+    single: LatencyStats;
+    batched: LatencyStats;
+}
+
+export interface ParetoObjective {
+    metric: string;
+    direction: 'maximize' | 'minimize';
+}
+
+export interface RankedGenome {
+    genome_id: string;
+    rank: number;
+    crowding_distance: number;
+}
+
+export interface ParetoRanking {
+    objectives: ParetoObjective[];
+    genomes: RankedGenome[];
+    fronts: string[][];
+    hypervolume: number | null;
+}
+
+export interface ParetoArchiveGeneration {
+    generation: number;
+    archive_size: number;
+    added: number;
+    removed: number;
+    hypervolume: number;
+    coverage: number;
+}
+
+export interface ParetoArchive {
+    config: {
+        objectives: ParetoObjective[];
+        reference_point: number[] | null;
+        ideal_point: number[] | null;
+    };
+    reference_point: number[] | null;
+    members: GenomeObjectives[];
+    history: ParetoArchiveGeneration[];
 }
 
 export interface GenerationParetoFront {
@@ -75,6 +154,51 @@ export interface DeviceTemplateDto {
     tags: string[];
     created_at_unix_ms: number;
     updated_at_unix_ms: number;
+    /** Per-layer latencies measured on the device */
+    latency_lut?: LatencyLut;
+}
+
+export interface LatencyLutEntry {
+    op: string;
+    output_shape: number[];
+    macs: number;
+    latency_ms: number;
+}
+
+export interface LatencyLut {
+    entries: LatencyLutEntry[];
+    overhead_ms: number;
+}
+
+export interface LayerLatency {
+    node_id: number;
+    op: string;
+    latency_ms: number;
+    source: 'measured' | 'fitted' | 'device_rate';
+}
+
+export interface LatencyPrediction {
+    total_ms: number;
+    layers: LayerLatency[];
+}
+
+export interface DeviceValidationResult {
+    is_feasible: boolean;
+    violation_score: number;
+    mops_ratio: number;
+    ram_ratio: number;
+    flash_ratio: number;
+    latency_ratio: number;
+}
+
+export interface DeviceCheck {
+    device: string;
+    validation: DeviceValidationResult;
+    predicted_latency_ms?: number | null;
+    repair_steps: number;
+    repaired_genome?: string | null;
+    decision: 'train' | 'rejected' | 'penalized';
+    penalty_fitness?: number | null;
 }
 
 export interface CreateDeviceTemplateInput {
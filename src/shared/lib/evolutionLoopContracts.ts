@@ -26,6 +26,7 @@ export type EvolutionLoopSettings = AdaptiveMutationSettings & {
     zeroCostStrategy: 'two-stage' | 'early-stopping';
     fastPassThreshold: number;
     partialTrainingEpochs: number;
+    zeroCostVoting: boolean;
     evalEpochs: number;
     batchSize: number;
     datasetPercent: number;
@@ -46,9 +47,16 @@ export type EvolutionLoopSettings = AdaptiveMutationSettings & {
         max_model_size_mb?: number;
     };
     executionMode?: 'sequential' | 'parallel-cpu' | 'parallel-safe-limited';
+    executionBackend?: 'gpu' | 'cpu';
+    batchLoading?: 'auto' | 'preload' | 'stream';
     maxParallelJobs?: number;
     memorySafetyMarginMb?: number;
     estimatorSafetyFactor?: number;
+    benchmarkLatency?: boolean;
+    deviceProfileId?: string;
+    isCustomDevice?: boolean;
+    deviceFilterAction?: 'off' | 'penalize' | 'reject';
+    repairForDevice?: boolean;
     useMaxGenerations: boolean;
     maxGenerations: number;
     genealogyTrackingEnabled: boolean;